@@ -6,17 +6,18 @@
 
 use js_sys::Float64Array;
 use oxmpl::base::{
+    error::PlanningError,
     error::StateSamplingError,
     goal::{Goal, GoalRegion, GoalSampleableRegion},
-    planner::{Path, Planner},
+    planner::{Path, Planner, Solution},
     problem_definition::ProblemDefinition,
     space::{RealVectorStateSpace, StateSpace},
     state::RealVectorState,
-    validity::StateValidityChecker,
+    validity::{GridOccupancyChecker, StateValidityChecker},
 };
 use oxmpl::geometric::{RRTConnect, RRTStar, PRM, RRT};
 use rand::rng;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use wasm_bindgen::prelude::*;
 use web_sys::console;
@@ -38,10 +39,50 @@ fn js_array_to_state(array: &Float64Array) -> RealVectorState {
     RealVectorState::new(values)
 }
 
-// Set panic hook to get better error messages
+/// Forwards the planners' `log` events to the browser console.
+struct ConsoleLogger;
+
+impl log::Log for ConsoleLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = JsValue::from(record.args().to_string());
+        match record.level() {
+            log::Level::Error => console::error_1(&message),
+            log::Level::Warn => console::warn_1(&message),
+            log::Level::Info => console::info_1(&message),
+            log::Level::Debug | log::Level::Trace => console::debug_1(&message),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static CONSOLE_LOGGER: ConsoleLogger = ConsoleLogger;
+
+// Set panic hook to get better error messages, and route planner events to the console
 #[wasm_bindgen(start)]
 pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
+    if log::set_logger(&CONSOLE_LOGGER).is_ok() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+}
+
+/// Sets the most verbose planner events written to the console: one of `"off"`, `"error"`,
+/// `"warn"`, `"info"` (the default), `"debug"` or `"trace"`.
+#[wasm_bindgen(js_name = setLogLevel)]
+pub fn set_log_level(level: &str) -> Result<(), String> {
+    let level = level
+        .parse::<log::LevelFilter>()
+        .map_err(|_| format!("Unknown log level: {level}"))?;
+    log::set_max_level(level);
+    Ok(())
 }
 
 #[wasm_bindgen]
@@ -50,10 +91,32 @@ extern "C" {
     fn log(s: &str);
 }
 
-#[wasm_bindgen(js_name = StateValidityChecker)]
 #[derive(Clone)]
+enum ValidityCheckerKind {
+    Callback(js_sys::Function),
+    OccupancyGrid(Arc<GridOccupancyChecker>),
+}
+
+/// A validity checker backed by a JavaScript callback or an occupancy grid.
+///
+/// If the callback throws, the state is treated as invalid. In strict mode the exception is also
+/// recorded, so that the planner aborts with `PlanningError::ValidityCheckFailed` and the
+/// exception surfaces from `solve` instead of silently shaping the search.
+#[wasm_bindgen(js_name = StateValidityChecker)]
 pub struct JsStateValidityChecker {
-    callback: js_sys::Function,
+    kind: ValidityCheckerKind,
+    strict: bool,
+    error: Mutex<Option<String>>,
+}
+
+impl Clone for JsStateValidityChecker {
+    fn clone(&self) -> Self {
+        Self {
+            kind: self.kind.clone(),
+            strict: self.strict,
+            error: Mutex::new(None),
+        }
+    }
 }
 
 #[wasm_bindgen]
@@ -67,19 +130,58 @@ extern "C" {
 
 #[wasm_bindgen(js_class = StateValidityChecker)]
 impl JsStateValidityChecker {
+    /// Creates a checker that calls `callback` for every state.
+    ///
+    /// By default an exception thrown by the callback marks the state as invalid. With `strict`
+    /// set, it instead aborts planning and is reported by `solve`.
     #[wasm_bindgen(constructor)]
-    pub fn new(callback: StateValidityCallback) -> Self {
+    pub fn new(callback: StateValidityCallback, strict: Option<bool>) -> Self {
         Self {
-            callback: JsValue::from(callback).into(),
+            kind: ValidityCheckerKind::Callback(JsValue::from(callback).into()),
+            strict: strict.unwrap_or(false),
+            error: Mutex::new(None),
         }
     }
+
+    /// Creates a checker backed by a 2D occupancy grid instead of a JavaScript callback.
+    ///
+    /// `occupancy` holds `width * height` cells in row-major order with row 0 at the bottom of
+    /// the map; any non-zero value marks an occupied cell. States outside the grid are invalid.
+    #[wasm_bindgen(js_name = fromOccupancyGrid)]
+    pub fn from_occupancy_grid(
+        width: usize,
+        height: usize,
+        cell_size: f64,
+        origin_x: f64,
+        origin_y: f64,
+        occupancy: Vec<u8>,
+    ) -> Result<JsStateValidityChecker, String> {
+        let occupancy = occupancy.into_iter().map(|cell| cell != 0).collect();
+        match GridOccupancyChecker::new(width, height, cell_size, (origin_x, origin_y), occupancy) {
+            Ok(checker) => Ok(Self {
+                kind: ValidityCheckerKind::OccupancyGrid(Arc::new(checker)),
+                strict: false,
+                error: Mutex::new(None),
+            }),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    #[wasm_bindgen(js_name = isValid)]
+    pub fn is_valid_js(&self, state: Vec<f64>) -> bool {
+        self.is_valid(&RealVectorState::new(state))
+    }
 }
 
 impl StateValidityChecker<RealVectorState> for JsStateValidityChecker {
     fn is_valid(&self, state: &RealVectorState) -> bool {
+        let callback = match &self.kind {
+            ValidityCheckerKind::Callback(callback) => callback,
+            ValidityCheckerKind::OccupancyGrid(grid) => return grid.is_valid(state),
+        };
         let array = state_to_js_array(state);
 
-        match self.callback.call1(&JsValue::NULL, &array) {
+        match callback.call1(&JsValue::NULL, &array) {
             Ok(result) => match result.as_bool() {
                 Some(is_valid) => is_valid,
                 None => {
@@ -88,11 +190,25 @@ impl StateValidityChecker<RealVectorState> for JsStateValidityChecker {
                 }
             },
             Err(e) => {
-                console::error_2(&"State validity checker callback failed:".into(), &e);
+                if self.strict {
+                    let mut error = self.error.lock().unwrap();
+                    if error.is_none() {
+                        *error = Some(match e.dyn_ref::<js_sys::Error>() {
+                            Some(js_error) => String::from(js_error.to_string()),
+                            None => format!("{e:?}"),
+                        });
+                    }
+                } else {
+                    console::error_2(&"State validity checker callback failed:".into(), &e);
+                }
                 false
             }
         }
     }
+
+    fn take_error(&self) -> Option<String> {
+        self.error.lock().unwrap().take()
+    }
 }
 
 #[wasm_bindgen(js_name = Goal)]
@@ -255,11 +371,8 @@ impl JsProblemDefinition {
     #[wasm_bindgen(constructor)]
     pub fn new(space: &JsRealVectorStateSpace, start: Vec<f64>, goal: JsGoal) -> Self {
         let start_state = RealVectorState::new(start);
-        let problem_def = ProblemDefinition {
-            space: space.inner.clone(),
-            start_states: vec![start_state],
-            goal: Arc::new(goal),
-        };
+        let problem_def =
+            ProblemDefinition::new(space.inner.clone(), vec![start_state], Arc::new(goal));
         Self {
             inner: Arc::new(problem_def),
         }
@@ -280,10 +393,14 @@ impl From<&JsProblemDefinition>
     for ProblemDefinition<RealVectorState, RealVectorStateSpace, JsGoal>
 {
     fn from(js_problem: &JsProblemDefinition) -> Self {
-        ProblemDefinition {
-            space: js_problem.inner.space.clone(),
-            start_states: js_problem.inner.start_states.clone(),
-            goal: js_problem.inner.goal.clone(),
+        let problem_def = ProblemDefinition::new(
+            js_problem.inner.space.clone(),
+            js_problem.inner.start_states.clone(),
+            js_problem.inner.goal.clone(),
+        );
+        match &js_problem.inner.start_sampler {
+            Some(sampler) => problem_def.with_start_sampler(sampler.clone()),
+            None => problem_def,
         }
     }
 }
@@ -291,6 +408,25 @@ impl From<&JsProblemDefinition>
 #[wasm_bindgen(js_name = Path)]
 pub struct JsPath {
     states: Path<RealVectorState>,
+    space: Arc<RealVectorStateSpace>,
+    cost: f64,
+}
+
+impl JsPath {
+    /// Wraps the result of a planner's `solve_detailed`, keeping the problem's `space` to measure
+    /// the path with.
+    fn from_solution(
+        solution: Result<Solution<RealVectorState>, PlanningError>,
+        space: Option<&Arc<RealVectorStateSpace>>,
+    ) -> Result<JsPath, String> {
+        let space = space.ok_or(PlanningError::PlannerUninitialised.to_string())?;
+        let solution = solution.map_err(|e| e.to_string())?;
+        Ok(JsPath {
+            states: solution.path,
+            space: space.clone(),
+            cost: solution.cost,
+        })
+    }
 }
 
 #[wasm_bindgen(js_class = Path)]
@@ -303,11 +439,25 @@ impl JsPath {
     pub fn length(&self) -> usize {
         self.states.0.len()
     }
+
+    /// The geometric length of the path, as the sum of the space's distances between consecutive
+    /// states.
+    #[wasm_bindgen(js_name = totalLength)]
+    pub fn total_length(&self) -> f64 {
+        self.states.length(&*self.space)
+    }
+
+    /// The cost of the path under the planner's optimisation objective, which is its length
+    /// unless the planner optimises something else.
+    pub fn cost(&self) -> f64 {
+        self.cost
+    }
 }
 
 #[wasm_bindgen(js_name = RRT)]
 pub struct JsRRT {
     planner: RRT<RealVectorState, RealVectorStateSpace, JsGoal>,
+    space: Option<Arc<RealVectorStateSpace>>,
 }
 
 #[wasm_bindgen(js_class = RRT)]
@@ -316,6 +466,7 @@ impl JsRRT {
     pub fn new(max_distance: f32, goal_bias: f32) -> Self {
         Self {
             planner: RRT::new(max_distance as f64, goal_bias as f64),
+            space: None,
         }
     }
 
@@ -326,21 +477,20 @@ impl JsRRT {
     ) {
         let problem = Arc::new(problem_def.into());
         let checker = Arc::new(validity_checker.clone());
+        self.space = Some(problem_def.inner.space.clone());
         self.planner.setup(problem, checker);
     }
 
     pub fn solve(&mut self, timeout_secs: f32) -> Result<JsPath, String> {
         let timeout = Duration::from_secs_f32(timeout_secs);
-        match self.planner.solve(timeout) {
-            Ok(path) => Ok(JsPath { states: path }),
-            Err(e) => Err(e.to_string()),
-        }
+        JsPath::from_solution(self.planner.solve_detailed(timeout), self.space.as_ref())
     }
 }
 
 #[wasm_bindgen(js_name = RRTConnect)]
 pub struct JsRRTConnect {
     planner: RRTConnect<RealVectorState, RealVectorStateSpace, JsGoal>,
+    space: Option<Arc<RealVectorStateSpace>>,
 }
 
 #[wasm_bindgen(js_class = RRTConnect)]
@@ -349,6 +499,7 @@ impl JsRRTConnect {
     pub fn new(max_distance: f32, goal_bias: f32) -> Self {
         Self {
             planner: RRTConnect::new(max_distance as f64, goal_bias as f64),
+            space: None,
         }
     }
 
@@ -359,21 +510,20 @@ impl JsRRTConnect {
     ) {
         let problem = Arc::new(problem_def.into());
         let checker = Arc::new(validity_checker.clone());
+        self.space = Some(problem_def.inner.space.clone());
         self.planner.setup(problem, checker);
     }
 
     pub fn solve(&mut self, timeout_secs: f32) -> Result<JsPath, String> {
         let timeout = Duration::from_secs_f32(timeout_secs);
-        match self.planner.solve(timeout) {
-            Ok(path) => Ok(JsPath { states: path }),
-            Err(e) => Err(e.to_string()),
-        }
+        JsPath::from_solution(self.planner.solve_detailed(timeout), self.space.as_ref())
     }
 }
 
 #[wasm_bindgen(js_name = RRTStar)]
 pub struct JsRRTStar {
     planner: RRTStar<RealVectorState, RealVectorStateSpace, JsGoal>,
+    space: Option<Arc<RealVectorStateSpace>>,
 }
 
 #[wasm_bindgen(js_class = RRTStar)]
@@ -382,6 +532,7 @@ impl JsRRTStar {
     pub fn new(max_distance: f32, goal_bias: f32, search_radius: f32) -> Self {
         Self {
             planner: RRTStar::new(max_distance as f64, goal_bias as f64, search_radius as f64),
+            space: None,
         }
     }
 
@@ -392,21 +543,20 @@ impl JsRRTStar {
     ) {
         let problem = Arc::new(problem_def.into());
         let checker = Arc::new(validity_checker.clone());
+        self.space = Some(problem_def.inner.space.clone());
         self.planner.setup(problem, checker);
     }
 
     pub fn solve(&mut self, timeout_secs: f32) -> Result<JsPath, String> {
         let timeout = Duration::from_secs_f32(timeout_secs);
-        match self.planner.solve(timeout) {
-            Ok(path) => Ok(JsPath { states: path }),
-            Err(e) => Err(e.to_string()),
-        }
+        JsPath::from_solution(self.planner.solve_detailed(timeout), self.space.as_ref())
     }
 }
 
 #[wasm_bindgen(js_name = PRM)]
 pub struct JsPRM {
     planner: PRM<RealVectorState, RealVectorStateSpace, JsGoal>,
+    space: Option<Arc<RealVectorStateSpace>>,
 }
 
 #[wasm_bindgen(js_class = PRM)]
@@ -415,6 +565,7 @@ impl JsPRM {
     pub fn new(timeout_secs: f32, connection_radius: f32) -> Self {
         Self {
             planner: PRM::new(timeout_secs.into(), connection_radius as f64),
+            space: None,
         }
     }
 
@@ -425,6 +576,7 @@ impl JsPRM {
     ) {
         let problem = Arc::new(problem_def.into());
         let checker = Arc::new(validity_checker.clone());
+        self.space = Some(problem_def.inner.space.clone());
         self.planner.setup(problem, checker);
     }
 
@@ -438,9 +590,6 @@ impl JsPRM {
 
     pub fn solve(&mut self, timeout_secs: f32) -> Result<JsPath, String> {
         let timeout = Duration::from_secs_f32(timeout_secs);
-        match self.planner.solve(timeout) {
-            Ok(path) => Ok(JsPath { states: path }),
-            Err(e) => Err(e.to_string()),
-        }
+        JsPath::from_solution(self.planner.solve_detailed(timeout), self.space.as_ref())
     }
 }
@@ -0,0 +1,62 @@
+import * as oxmpl from 'oxmpl';
+import { describe, expect, test } from 'vitest';
+
+// A 10x10 map of unit cells with a vertical wall in column 5 spanning rows 2..7.
+function wallGrid() {
+  const width = 10;
+  const height = 10;
+  const occupancy = new Uint8Array(width * height);
+  for (let row = 2; row < 8; row++) {
+    occupancy[row * width + 5] = 1;
+  }
+  return oxmpl.StateValidityChecker.fromOccupancyGrid(width, height, 1.0, 0.0, 0.0, occupancy);
+}
+
+describe('GridOccupancyChecker Tests', () => {
+  test('free and occupied cells', () => {
+    const checker = wallGrid();
+
+    expect(checker.isValid([1.5, 5.5])).toBe(true);
+    expect(checker.isValid([5.5, 5.5])).toBe(false);
+    expect(checker.isValid([5.5, 9.5])).toBe(true);
+  });
+
+  test('states outside the grid are invalid', () => {
+    const checker = wallGrid();
+
+    expect(checker.isValid([-0.5, 5.0])).toBe(false);
+    expect(checker.isValid([5.0, 10.5])).toBe(false);
+  });
+
+  test('mismatched occupancy data is rejected', () => {
+    expect(() =>
+      oxmpl.StateValidityChecker.fromOccupancyGrid(2, 2, 1.0, 0.0, 0.0, new Uint8Array(3))
+    ).toThrow();
+  });
+
+  test('RRT plans against an occupancy grid', () => {
+    const space = new oxmpl.RealVectorStateSpace(2, [0.0, 10.0, 0.0, 10.0]);
+    const target = [9.0, 5.0];
+    const radius = 0.5;
+
+    const goal = new oxmpl.Goal(
+      (state) => Math.hypot(state[0] - target[0], state[1] - target[1]) <= radius,
+      (state) => Math.max(0, Math.hypot(state[0] - target[0], state[1] - target[1]) - radius),
+      () => new Float64Array(target)
+    );
+
+    const problemDef = new oxmpl.ProblemDefinition(space, [1.0, 5.0], goal);
+    const checker = wallGrid();
+
+    const planner = new oxmpl.RRT(0.5, 0.05);
+    planner.setup(problemDef, checker);
+
+    const path = planner.solve(5.0);
+    const states = path.getStates();
+
+    expect(states.length).toBeGreaterThan(1);
+    for (const state of states) {
+      expect(checker.isValid(Array.from(state))).toBe(true);
+    }
+  });
+});
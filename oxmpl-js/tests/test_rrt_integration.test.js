@@ -1,5 +1,5 @@
 import * as oxmpl from 'oxmpl';
-import { describe, expect, test } from 'vitest';
+import { describe, expect, test, vi } from 'vitest';
 
 class CircularGoal {
   constructor(space, x, y, radius) {
@@ -112,4 +112,67 @@ describe('RRT Integration Tests', () => {
 
     console.log('Path validation successful!');
   });
+
+  test('RRT path reports its total length and cost', () => {
+    const space = new oxmpl.RealVectorStateSpace(2, [0.0, 10.0, 0.0, 10.0]);
+    const startState = [1.0, 5.0];
+    const goalRegion = new CircularGoal(space, 9.0, 5.0, 0.5);
+    const goal = new oxmpl.Goal(
+      goalRegion.isSatisfied.bind(goalRegion),
+      goalRegion.distanceToGoal.bind(goalRegion),
+      goalRegion.sampleGoal.bind(goalRegion)
+    );
+    const problemDef = new oxmpl.ProblemDefinition(space, startState, goal);
+    const validityChecker = new oxmpl.StateValidityChecker(isStateValid);
+
+    const planner = new oxmpl.RRT(0.5, 0.05);
+    planner.setup(problemDef, validityChecker);
+    const path = planner.solve(5.0);
+
+    // The total length is the Euclidean length of every segment, summed.
+    const states = path.getStates();
+    let expectedLength = 0.0;
+    for (let i = 1; i < states.length; i++) {
+      const dx = states[i][0] - states[i - 1][0];
+      const dy = states[i][1] - states[i - 1][1];
+      expectedLength += Math.sqrt(dx * dx + dy * dy);
+    }
+    expect(path.totalLength()).toBeCloseTo(expectedLength, 9);
+
+    // RRT does not optimise an objective, so the cost is the length.
+    expect(path.cost()).toBeCloseTo(path.totalLength(), 9);
+  });
+
+  test('RRT reports the solution to the console once, unless silenced', () => {
+    const space = new oxmpl.RealVectorStateSpace(2, [0.0, 10.0, 0.0, 10.0]);
+    const goalRegion = new CircularGoal(space, 9.0, 5.0, 0.5);
+    const goal = new oxmpl.Goal(
+      goalRegion.isSatisfied.bind(goalRegion),
+      goalRegion.distanceToGoal.bind(goalRegion),
+      goalRegion.sampleGoal.bind(goalRegion)
+    );
+    const problemDef = new oxmpl.ProblemDefinition(space, [1.0, 5.0], goal);
+    const validityChecker = new oxmpl.StateValidityChecker(isStateValid);
+    const solutionMessages = (spy) =>
+      spy.mock.calls.filter(([message]) => String(message).includes('solution found'));
+
+    const info = vi.spyOn(console, 'info').mockImplementation(() => {});
+    try {
+      const planner = new oxmpl.RRT(0.5, 0.05);
+      planner.setup(problemDef, validityChecker);
+      planner.solve(5.0);
+      expect(solutionMessages(info)).toHaveLength(1);
+
+      info.mockClear();
+      oxmpl.setLogLevel('off');
+      planner.setup(problemDef, validityChecker);
+      planner.solve(5.0);
+      expect(solutionMessages(info)).toHaveLength(0);
+    } finally {
+      oxmpl.setLogLevel('info');
+      info.mockRestore();
+    }
+
+    expect(() => oxmpl.setLogLevel('loud')).toThrow();
+  });
 });
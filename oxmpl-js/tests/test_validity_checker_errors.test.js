@@ -0,0 +1,47 @@
+import * as oxmpl from 'oxmpl';
+import { describe, expect, test, vi } from 'vitest';
+
+// Throws for every state right of x = 5, and accepts the rest.
+function throwingChecker(state) {
+  if (state[0] > 5.0) {
+    throw new Error('collision model unavailable');
+  }
+  return true;
+}
+
+function makeProblem() {
+  const space = new oxmpl.RealVectorStateSpace(2, [0.0, 10.0, 0.0, 10.0]);
+  const target = [9.0, 5.0];
+  const radius = 0.5;
+
+  const goal = new oxmpl.Goal(
+    (state) => Math.hypot(state[0] - target[0], state[1] - target[1]) <= radius,
+    (state) => Math.max(0, Math.hypot(state[0] - target[0], state[1] - target[1]) - radius),
+    () => new Float64Array(target)
+  );
+
+  return new oxmpl.ProblemDefinition(space, [1.0, 5.0], goal);
+}
+
+describe('StateValidityChecker Error Tests', () => {
+  test('strict mode surfaces the callback exception', () => {
+    const planner = new oxmpl.RRT(0.5, 0.05);
+    planner.setup(makeProblem(), new oxmpl.StateValidityChecker(throwingChecker, true));
+
+    expect(() => planner.solve(5.0)).toThrow(/collision model unavailable/);
+  });
+
+  test('default mode treats the callback exception as invalid', () => {
+    const error = vi.spyOn(console, 'error').mockImplementation(() => {});
+    try {
+      const planner = new oxmpl.RRT(0.5, 0.05);
+      planner.setup(makeProblem(), new oxmpl.StateValidityChecker(throwingChecker));
+
+      // Every state right of x = 5 counts as invalid, so the goal can never be reached.
+      expect(() => planner.solve(0.5)).toThrow(/No solution found within timeout/);
+      expect(error).toHaveBeenCalled();
+    } finally {
+      error.mockRestore();
+    }
+  });
+});
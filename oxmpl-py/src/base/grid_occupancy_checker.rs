@@ -0,0 +1,68 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+use std::sync::Arc;
+
+use oxmpl::base::{
+    state::RealVectorState,
+    validity::{GridOccupancyChecker as OxmplGridOccupancyChecker, StateValidityChecker},
+};
+
+use super::real_vector_state::PyRealVectorState;
+
+/// A validity checker backed by a 2D occupancy grid.
+///
+/// The grid covers an axis-aligned rectangle starting at `origin` (its lower-left corner). The
+/// occupancy list is row-major with row 0 at the bottom of the map. States outside the grid are
+/// treated as invalid.
+///
+/// Instances are callable, so they can be passed directly to a planner's `setup` in place of a
+/// Python validity function.
+///
+/// Args:
+///     width (int): Number of cells along the x-axis.
+///     height (int): Number of cells along the y-axis.
+///     cell_size (float): Side length of a single cell.
+///     origin (Tuple[float, float]): World coordinates of the lower-left corner.
+///     occupancy (List[bool]): `width * height` flags, `True` marking an occupied cell.
+///
+/// Raises:
+///     ValueError: If the occupancy length or cell size is invalid.
+#[pyclass(name = "GridOccupancyChecker", unsendable)]
+#[derive(Clone)]
+pub struct PyGridOccupancyChecker(pub Arc<OxmplGridOccupancyChecker>);
+
+#[pymethods]
+impl PyGridOccupancyChecker {
+    #[new]
+    fn new(
+        width: usize,
+        height: usize,
+        cell_size: f64,
+        origin: (f64, f64),
+        occupancy: Vec<bool>,
+    ) -> PyResult<Self> {
+        match OxmplGridOccupancyChecker::new(width, height, cell_size, origin, occupancy) {
+            Ok(checker) => Ok(Self(Arc::new(checker))),
+            Err(e) => Err(PyValueError::new_err(e.to_string())),
+        }
+    }
+
+    /// Returns `True` if the state lies in a free cell of the grid.
+    fn is_valid(&self, state: &PyRealVectorState) -> bool {
+        StateValidityChecker::<RealVectorState>::is_valid(&*self.0, &state.0)
+    }
+
+    fn __call__(&self, state: &PyRealVectorState) -> bool {
+        self.is_valid(state)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<GridOccupancyChecker {}x{} cell_size={}>",
+            self.0.width, self.0.height, self.0.cell_size
+        )
+    }
+}
@@ -5,6 +5,7 @@
 use pyo3::prelude::*;
 
 mod goal;
+mod grid_occupancy_checker;
 mod path;
 mod problem_definition;
 mod py_state_convert;
@@ -17,6 +18,7 @@ mod so3_state_space;
 mod state_validity_checker;
 
 pub use goal::PyGoal;
+pub use grid_occupancy_checker::PyGridOccupancyChecker;
 pub use path::PyPath;
 pub use problem_definition::ProblemDefinitionVariant;
 pub use problem_definition::PyProblemDefinition;
@@ -38,5 +40,6 @@ pub fn create_module(_py: Python<'_>) -> PyResult<Bound<'_, PyModule>> {
     base_module.add_class::<PySO3StateSpace>()?;
     base_module.add_class::<PyPath>()?;
     base_module.add_class::<PyProblemDefinition>()?;
+    base_module.add_class::<PyGridOccupancyChecker>()?;
     Ok(base_module)
 }
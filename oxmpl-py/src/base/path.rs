@@ -44,6 +44,13 @@ impl PyPath {
         Self(PathVariant::SO2(OxmplPath(rust_states)))
     }
 
+    /// Creates a new Path from a list of `SO3State` objects.
+    #[staticmethod]
+    fn from_so3_states(states: Vec<PySO3State>) -> Self {
+        let rust_states = states.into_iter().map(|s| (*s.0).clone()).collect();
+        Self(PathVariant::SO3(OxmplPath(rust_states)))
+    }
+
     /// list[RealVectorState]: The sequence of states that make up the path.
     #[getter]
     fn get_states(&self, py: Python<'_>) -> PyResult<PyObject> {
@@ -64,11 +64,11 @@ impl PyProblemDefinition {
         // Create a snapshot of the space's configuration
         let cloned_inner_space = space.0.lock().unwrap().clone();
 
-        let pd = ProblemDefinition {
-            space: Arc::new(cloned_inner_space),
-            start_states: vec![(*start_state.0).clone()],
-            goal: Arc::new(goal_wrapper),
-        };
+        let pd = ProblemDefinition::new(
+            Arc::new(cloned_inner_space),
+            vec![(*start_state.0).clone()],
+            Arc::new(goal_wrapper),
+        );
 
         // Wrap the result in the correct enum variant
         Self(ProblemDefinitionVariant::RealVector(Arc::new(pd)))
@@ -92,11 +92,11 @@ impl PyProblemDefinition {
         // Create a snapshot of the space's configuration
         let cloned_inner_space = space.0.lock().unwrap().clone();
 
-        let pd = ProblemDefinition {
-            space: Arc::new(cloned_inner_space),
-            start_states: vec![(*start_state.0).clone()],
-            goal: Arc::new(goal_wrapper),
-        };
+        let pd = ProblemDefinition::new(
+            Arc::new(cloned_inner_space),
+            vec![(*start_state.0).clone()],
+            Arc::new(goal_wrapper),
+        );
 
         // Wrap the result in the correct enum variant
         Self(ProblemDefinitionVariant::SO2(Arc::new(pd)))
@@ -120,11 +120,11 @@ impl PyProblemDefinition {
         // Create a snapshot of the space's configuration
         let cloned_inner_space = space.0.lock().unwrap().clone();
 
-        let pd = ProblemDefinition {
-            space: Arc::new(cloned_inner_space),
-            start_states: vec![(*start_state.0).clone()],
-            goal: Arc::new(goal_wrapper),
-        };
+        let pd = ProblemDefinition::new(
+            Arc::new(cloned_inner_space),
+            vec![(*start_state.0).clone()],
+            Arc::new(goal_wrapper),
+        );
 
         // Wrap the result in the correct enum variant
         Self(ProblemDefinitionVariant::SO3(Arc::new(pd)))
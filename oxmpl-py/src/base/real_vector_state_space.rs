@@ -5,7 +5,10 @@
 use pyo3::{exceptions::PyValueError, prelude::*};
 use std::sync::{Arc, Mutex};
 
-use oxmpl::base::space::{RealVectorStateSpace as OxmplRealVectorStateSpace, StateSpace as _};
+use oxmpl::base::{
+    space::{RealVectorStateSpace as OxmplRealVectorStateSpace, StateSpace as _},
+    state::RealVectorState as OxmplRealVectorState,
+};
 
 use super::real_vector_state::PyRealVectorState;
 
@@ -42,12 +45,72 @@ impl PyRealVectorStateSpace {
         self.0.lock().unwrap().distance(&state1.0, &state2.0)
     }
 
+    /// Returns the number of dimensions of the space.
+    fn get_dimension(&self) -> usize {
+        self.0.lock().unwrap().dimension
+    }
+
+    /// Returns the `(min, max)` bounds of each dimension, infinite where it is unbounded.
+    fn get_bounds(&self) -> Vec<(f64, f64)> {
+        self.0.lock().unwrap().bounds.clone()
+    }
+
+    /// Returns the state a fraction `t` of the way from `state1` to `state2`.
+    ///
+    /// Raises:
+    ///     ValueError: If either state does not have the dimension of the space.
+    fn interpolate(
+        &self,
+        state1: &PyRealVectorState,
+        state2: &PyRealVectorState,
+        t: f64,
+    ) -> PyResult<PyRealVectorState> {
+        let space = self.0.lock().unwrap();
+        if state1.0.values.len() != space.dimension || state2.0.values.len() != space.dimension {
+            return Err(PyValueError::new_err(format!(
+                "states must have dimension {}",
+                space.dimension
+            )));
+        }
+        let mut out_state = OxmplRealVectorState::zeros(space.dimension);
+        space.interpolate(&state1.0, &state2.0, t, &mut out_state);
+        Ok(PyRealVectorState(Arc::new(out_state)))
+    }
+
     /// Returns the maximum possible distance in this space.
     fn get_maximum_extent(&self) -> f64 {
         self.0.lock().unwrap().get_maximum_extent()
     }
 
-    /// Sets the fraction used to determine motion checking resolution.
+    /// Samples a state uniformly from within the bounds of the space.
+    ///
+    /// Raises:
+    ///     ValueError: If a dimension of the space is unbounded.
+    fn sample_uniform(&self) -> PyResult<PyRealVectorState> {
+        match self.0.lock().unwrap().sample_uniform(&mut rand::rng()) {
+            Ok(state) => Ok(PyRealVectorState(Arc::new(state))),
+            Err(e) => Err(PyValueError::new_err(e.to_string())),
+        }
+    }
+
+    /// Returns a copy of `state` brought within the bounds of the space, by clamping each value to the bounds of its dimension.
+    fn enforce_bounds(&self, state: &PyRealVectorState) -> PyRealVectorState {
+        let mut state = (*state.0).clone();
+        self.0.lock().unwrap().enforce_bounds(&mut state);
+        PyRealVectorState(Arc::new(state))
+    }
+
+    /// Returns whether `state` lies within the bounds of the space.
+    fn satisfies_bounds(&self, state: &PyRealVectorState) -> bool {
+        self.0.lock().unwrap().satisfies_bounds(&state.0)
+    }
+
+    /// Sets the fraction of the maximum extent used as the motion checking resolution.
+    ///
+    /// Motions are checked at steps of `fraction * get_maximum_extent()`. Defaults to 0.005.
+    ///
+    /// Earlier releases checked motions at a tenth of this step and defaulted the fraction to 0.05.
+    /// Divide a fraction written for those releases by 10 to keep its old resolution.
     fn set_longest_valid_segment_fraction(&mut self, fraction: f64) {
         self.0
             .lock()
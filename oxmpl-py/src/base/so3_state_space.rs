@@ -54,7 +54,35 @@ impl PySO3StateSpace {
         self.0.lock().unwrap().get_maximum_extent()
     }
 
-    /// Sets the fraction used to determine motion checking resolution.
+    /// Samples a state uniformly from within the bounds of the space.
+    ///
+    /// Raises:
+    ///     ValueError: If the space cannot be sampled.
+    fn sample_uniform(&self) -> PyResult<PySO3State> {
+        match self.0.lock().unwrap().sample_uniform(&mut rand::rng()) {
+            Ok(state) => Ok(PySO3State(Arc::new(state))),
+            Err(e) => Err(PyValueError::new_err(e.to_string())),
+        }
+    }
+
+    /// Returns a copy of `state` brought within the bounds of the space, by clamping it onto the boundary of the bounded region.
+    fn enforce_bounds(&self, state: &PySO3State) -> PySO3State {
+        let mut state = (*state.0).clone();
+        self.0.lock().unwrap().enforce_bounds(&mut state);
+        PySO3State(Arc::new(state))
+    }
+
+    /// Returns whether `state` lies within the bounds of the space.
+    fn satisfies_bounds(&self, state: &PySO3State) -> bool {
+        self.0.lock().unwrap().satisfies_bounds(&state.0)
+    }
+
+    /// Sets the fraction of the maximum extent used as the motion checking resolution.
+    ///
+    /// Motions are checked at steps of `fraction * get_maximum_extent()`. Defaults to 0.005.
+    ///
+    /// Earlier releases checked motions at a tenth of this step and defaulted the fraction to 0.05.
+    /// Divide a fraction written for those releases by 10 to keep its old resolution.
     fn set_longest_valid_segment_fraction(&mut self, fraction: f64) {
         self.0
             .lock()
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: BSD-3-Clause
 
 use pyo3::prelude::*;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use oxmpl::base::{
     state::RealVectorState as OxmplRealVectorState, state::SO2State as OxmplSO2State,
@@ -16,14 +16,47 @@ use super::so3_state::PySO3State;
 
 /// An internal Rust struct that implements the `StateValidityChecker` trait by calling a
 /// user-provided Python function.
+///
+/// If the callback raises, the state is treated as invalid. In strict mode the exception is also
+/// recorded, so that the planner aborts with `PlanningError::ValidityCheckFailed` and the
+/// exception surfaces from `solve` instead of silently shaping the search.
 pub struct PyStateValidityChecker {
     pub callback: PyObject,
+    pub strict: bool,
+    error: Mutex<Option<String>>,
 }
+
+impl PyStateValidityChecker {
+    pub fn new(callback: PyObject, strict: bool) -> Self {
+        Self {
+            callback,
+            strict,
+            error: Mutex::new(None),
+        }
+    }
+
+    /// Converts the outcome of a callback into a validity, handling a raised exception.
+    fn handle_result(&self, py: Python<'_>, result: PyResult<bool>) -> bool {
+        match result {
+            Ok(is_valid) => is_valid,
+            Err(e) => {
+                if self.strict {
+                    let mut error = self.error.lock().unwrap();
+                    if error.is_none() {
+                        *error = Some(e.to_string());
+                    }
+                } else {
+                    e.print(py);
+                }
+                false
+            }
+        }
+    }
+}
+
 impl Clone for PyStateValidityChecker {
     fn clone(&self) -> Self {
-        Python::with_gil(|py| Self {
-            callback: self.callback.clone_ref(py),
-        })
+        Python::with_gil(|py| Self::new(self.callback.clone_ref(py), self.strict))
     }
 }
 
@@ -36,15 +69,13 @@ impl StateValidityChecker<OxmplRealVectorState> for PyStateValidityChecker {
                 let result = self.callback.call1(py, args)?;
                 result.extract(py)
             })();
-            match result {
-                Ok(is_valid) => is_valid,
-                Err(e) => {
-                    e.print(py);
-                    false
-                }
-            }
+            self.handle_result(py, result)
         })
     }
+
+    fn take_error(&self) -> Option<String> {
+        self.error.lock().unwrap().take()
+    }
 }
 
 impl StateValidityChecker<OxmplSO2State> for PyStateValidityChecker {
@@ -56,15 +87,13 @@ impl StateValidityChecker<OxmplSO2State> for PyStateValidityChecker {
                 let result = self.callback.call1(py, args)?;
                 result.extract(py)
             })();
-            match result {
-                Ok(is_valid) => is_valid,
-                Err(e) => {
-                    e.print(py);
-                    false
-                }
-            }
+            self.handle_result(py, result)
         })
     }
+
+    fn take_error(&self) -> Option<String> {
+        self.error.lock().unwrap().take()
+    }
 }
 
 impl StateValidityChecker<OxmplSO3State> for PyStateValidityChecker {
@@ -76,13 +105,11 @@ impl StateValidityChecker<OxmplSO3State> for PyStateValidityChecker {
                 let result = self.callback.call1(py, args)?;
                 result.extract(py)
             })();
-            match result {
-                Ok(is_valid) => is_valid,
-                Err(e) => {
-                    e.print(py);
-                    false
-                }
-            }
+            self.handle_result(py, result)
         })
     }
+
+    fn take_error(&self) -> Option<String> {
+        self.error.lock().unwrap().take()
+    }
 }
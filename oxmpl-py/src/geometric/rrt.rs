@@ -71,12 +71,15 @@ impl PyRrt {
         Ok(Self { planner, pd })
     }
 
-    fn setup(&mut self, validity_callback: PyObject) -> PyResult<()> {
+    /// Configures the planner with a Python validity callback.
+    ///
+    /// By default an exception raised by the callback marks the state as invalid. With
+    /// `strict=True`, it instead aborts planning and is raised from `solve`.
+    #[pyo3(signature = (validity_callback, strict = false))]
+    fn setup(&mut self, validity_callback: PyObject, strict: bool) -> PyResult<()> {
         match &mut self.planner {
             PlannerVariant::RealVector(planner_variant) => {
-                let checker = Arc::new(PyStateValidityChecker {
-                    callback: validity_callback,
-                });
+                let checker = Arc::new(PyStateValidityChecker::new(validity_callback, strict));
                 if let ProblemDefinitionVariant::RealVector(problem_def) = &self.pd {
                     planner_variant
                         .borrow_mut()
@@ -84,9 +87,7 @@ impl PyRrt {
                 }
             }
             PlannerVariant::SO2(planner_variant) => {
-                let checker = Arc::new(PyStateValidityChecker {
-                    callback: validity_callback,
-                });
+                let checker = Arc::new(PyStateValidityChecker::new(validity_callback, strict));
                 if let ProblemDefinitionVariant::SO2(problem_def) = &self.pd {
                     planner_variant
                         .borrow_mut()
@@ -94,9 +95,7 @@ impl PyRrt {
                 }
             }
             PlannerVariant::SO3(planner_variant) => {
-                let checker = Arc::new(PyStateValidityChecker {
-                    callback: validity_callback,
-                });
+                let checker = Arc::new(PyStateValidityChecker::new(validity_callback, strict));
                 if let ProblemDefinitionVariant::SO3(problem_def) = &self.pd {
                     planner_variant
                         .borrow_mut()
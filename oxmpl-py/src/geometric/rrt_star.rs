@@ -14,13 +14,25 @@ use oxmpl::{
         space::{RealVectorStateSpace, SO2StateSpace, SO3StateSpace},
         state::{RealVectorState, SO2State, SO3State},
     },
-    geometric::RRTStar,
+    geometric::{ProgressCallback, RRTStar},
 };
 
 type RrtStarForRealVector = RRTStar<RealVectorState, RealVectorStateSpace, PyGoal<RealVectorState>>;
 type RrtStarForSO2 = RRTStar<SO2State, SO2StateSpace, PyGoal<SO2State>>;
 type RrtStarForSO3 = RRTStar<SO3State, SO3StateSpace, PyGoal<SO3State>>;
 
+/// Wraps a Python callable as a `ProgressCallback`. Exceptions it raises are printed and
+/// otherwise ignored, so they never interrupt planning.
+fn py_progress_callback(callback: PyObject) -> ProgressCallback {
+    Arc::new(move |cost| {
+        Python::with_gil(|py| {
+            if let Err(e) = callback.call1(py, (cost,)) {
+                e.print(py);
+            }
+        })
+    })
+}
+
 enum PlannerVariant {
     RealVector(Rc<RefCell<RrtStarForRealVector>>),
     SO2(Rc<RefCell<RrtStarForSO2>>),
@@ -73,12 +85,15 @@ impl PyRrtStar {
         Ok(Self { planner, pd })
     }
 
-    fn setup(&mut self, validity_callback: PyObject) -> PyResult<()> {
+    /// Configures the planner with a Python validity callback.
+    ///
+    /// By default an exception raised by the callback marks the state as invalid. With
+    /// `strict=True`, it instead aborts planning and is raised from `solve`.
+    #[pyo3(signature = (validity_callback, strict = false))]
+    fn setup(&mut self, validity_callback: PyObject, strict: bool) -> PyResult<()> {
         match &mut self.planner {
             PlannerVariant::RealVector(planner_variant) => {
-                let checker = Arc::new(PyStateValidityChecker {
-                    callback: validity_callback,
-                });
+                let checker = Arc::new(PyStateValidityChecker::new(validity_callback, strict));
                 if let ProblemDefinitionVariant::RealVector(problem_def) = &self.pd {
                     planner_variant
                         .borrow_mut()
@@ -86,9 +101,7 @@ impl PyRrtStar {
                 }
             }
             PlannerVariant::SO2(planner_variant) => {
-                let checker = Arc::new(PyStateValidityChecker {
-                    callback: validity_callback,
-                });
+                let checker = Arc::new(PyStateValidityChecker::new(validity_callback, strict));
                 if let ProblemDefinitionVariant::SO2(problem_def) = &self.pd {
                     planner_variant
                         .borrow_mut()
@@ -96,9 +109,7 @@ impl PyRrtStar {
                 }
             }
             PlannerVariant::SO3(planner_variant) => {
-                let checker = Arc::new(PyStateValidityChecker {
-                    callback: validity_callback,
-                });
+                let checker = Arc::new(PyStateValidityChecker::new(validity_callback, strict));
                 if let ProblemDefinitionVariant::SO3(problem_def) = &self.pd {
                     planner_variant
                         .borrow_mut()
@@ -109,25 +120,47 @@ impl PyRrtStar {
         Ok(())
     }
 
-    fn solve(&mut self, timeout_secs: f32) -> PyResult<PyPath> {
+    /// Solves the planning problem within `timeout_secs`.
+    ///
+    /// The planner keeps refining the solution until the timeout and returns the best one. With a
+    /// `progress_callback`, it calls `progress_callback(cost)` each time the cost of the best
+    /// solution improves. Exceptions raised by the callback are printed and planning continues.
+    #[pyo3(signature = (timeout_secs, progress_callback = None))]
+    fn solve(
+        &mut self,
+        py: Python<'_>,
+        timeout_secs: f32,
+        progress_callback: Option<PyObject>,
+    ) -> PyResult<PyPath> {
         let timeout = Duration::from_secs_f32(timeout_secs);
-        match &mut self.planner {
+        let callback = || {
+            progress_callback
+                .as_ref()
+                .map(|callback| py_progress_callback(callback.clone_ref(py)))
+        };
+        match &self.planner {
             PlannerVariant::RealVector(p) => {
-                let result = p.borrow_mut().solve(timeout);
+                let mut planner = p.borrow_mut();
+                planner.set_progress_callback(callback());
+                let result = planner.solve(timeout);
                 match result {
                     Ok(path) => Ok(PyPath::from(path)),
                     Err(e) => Err(pyo3::exceptions::PyException::new_err(e.to_string())),
                 }
             }
             PlannerVariant::SO2(p) => {
-                let result = p.borrow_mut().solve(timeout);
+                let mut planner = p.borrow_mut();
+                planner.set_progress_callback(callback());
+                let result = planner.solve(timeout);
                 match result {
                     Ok(path) => Ok(PyPath::from(path)),
                     Err(e) => Err(pyo3::exceptions::PyException::new_err(e.to_string())),
                 }
             }
             PlannerVariant::SO3(p) => {
-                let result = p.borrow_mut().solve(timeout);
+                let mut planner = p.borrow_mut();
+                planner.set_progress_callback(callback());
+                let result = planner.solve(timeout);
                 match result {
                     Ok(path) => Ok(PyPath::from(path)),
                     Err(e) => Err(pyo3::exceptions::PyException::new_err(e.to_string())),
@@ -0,0 +1,77 @@
+import math
+import random
+
+import pytest
+
+from oxmpl_py.base import (
+    GridOccupancyChecker,
+    ProblemDefinition,
+    RealVectorState,
+    RealVectorStateSpace,
+)
+from oxmpl_py.geometric import RRT
+
+
+class CircularGoal:
+    def __init__(self, space: RealVectorStateSpace, x: float, y: float, radius: float):
+        self.space = space
+        self.target = RealVectorState([x, y])
+        self.radius = radius
+        self.rng = random.Random(123)
+
+    def is_satisfied(self, state: RealVectorState) -> bool:
+        return self.space.distance(self.target, state) <= self.radius
+
+    def sample_goal(self) -> RealVectorState:
+        angle = self.rng.uniform(0, 2 * math.pi)
+        radius = self.radius * math.sqrt(self.rng.uniform(0, 1))
+
+        x = self.target.values[0] + radius * math.cos(angle)
+        y = self.target.values[1] + radius * math.sin(angle)
+        return RealVectorState([x, y])
+
+
+def wall_grid() -> GridOccupancyChecker:
+    # A 10x10 map of unit cells with a vertical wall in column 5 spanning rows 2..7.
+    width, height = 10, 10
+    occupancy = [False] * (width * height)
+    for row in range(2, 8):
+        occupancy[row * width + 5] = True
+    return GridOccupancyChecker(width, height, 1.0, (0.0, 0.0), occupancy)
+
+
+def test_grid_occupancy_checker_cells():
+    checker = wall_grid()
+
+    assert checker.is_valid(RealVectorState([1.5, 5.5]))
+    assert not checker.is_valid(RealVectorState([5.5, 5.5]))
+    assert checker(RealVectorState([5.5, 9.5]))
+
+
+def test_grid_occupancy_checker_out_of_grid():
+    checker = wall_grid()
+
+    assert not checker.is_valid(RealVectorState([-0.5, 5.0]))
+    assert not checker.is_valid(RealVectorState([5.0, 10.5]))
+
+
+def test_grid_occupancy_checker_invalid_data():
+    with pytest.raises(ValueError):
+        GridOccupancyChecker(2, 2, 1.0, (0.0, 0.0), [False] * 3)
+
+
+def test_rrt_plans_against_grid_occupancy_checker():
+    space = RealVectorStateSpace(dimension=2, bounds=[(0.0, 10.0), (0.0, 10.0)])
+    start_state = RealVectorState([1.0, 5.0])
+    goal_region = CircularGoal(space, x=9.0, y=5.0, radius=0.5)
+    problem_def = ProblemDefinition.from_real_vector(space, start_state, goal_region)
+
+    checker = wall_grid()
+    planner = RRT(max_distance=0.5, goal_bias=0.05, problem_definition=problem_def)
+    planner.setup(checker)
+
+    path = planner.solve(timeout_secs=5.0)
+
+    assert len(path.states) > 1
+    for state in path.states:
+        assert checker.is_valid(state), f"Path contains an occupied state: {state.values}"
@@ -0,0 +1,26 @@
+import math
+
+from oxmpl_py.base import Path, SO3State
+
+
+def test_path_from_so3_states_reads_back_states():
+    half_angle = math.pi / 4.0
+    states = [
+        SO3State.identity(),
+        SO3State(x=0.0, y=0.0, z=math.sin(half_angle), w=math.cos(half_angle)),
+        SO3State(x=0.0, y=0.0, z=1.0, w=0.0),
+    ]
+
+    path = Path.from_so3_states(states)
+
+    assert len(path) == 3
+    assert repr(path) == "<Path of 3 SO3States>"
+
+    read_back = path.states
+    assert len(read_back) == len(states)
+    for expected, actual in zip(states, read_back):
+        assert isinstance(actual, SO3State)
+        assert math.isclose(actual.x, expected.x, abs_tol=1e-12)
+        assert math.isclose(actual.y, expected.y, abs_tol=1e-12)
+        assert math.isclose(actual.z, expected.z, abs_tol=1e-12)
+        assert math.isclose(actual.w, expected.w, abs_tol=1e-12)
@@ -80,3 +80,52 @@ def test_rrt_connect_finds_path_in_so3ss():
     print("Path validation successful!")
 
 
+
+
+FORBIDDEN_CONE_AXIS = (1.0, 0.0, 0.0)
+FORBIDDEN_CONE_HALF_ANGLE = math.radians(30.0)
+
+
+def body_x_axis(state: SO3State) -> tuple[float, float, float]:
+    """Returns the body x-axis rotated into the world frame."""
+    x, y, z, w = state.x, state.y, state.z, state.w
+    return (
+        1.0 - 2.0 * (y * y + z * z),
+        2.0 * (x * y + w * z),
+        2.0 * (x * z - w * y),
+    )
+
+
+def is_outside_forbidden_cone(state: SO3State) -> bool:
+    """Rejects rotations pointing the body x-axis within the cone around the world x-axis."""
+    axis = body_x_axis(state)
+    cos_angle = sum(a * b for a, b in zip(axis, FORBIDDEN_CONE_AXIS))
+    return cos_angle < math.cos(FORBIDDEN_CONE_HALF_ANGLE)
+
+
+def test_rrt_connect_avoids_forbidden_cone_in_so3ss():
+    space = SO3StateSpace()
+
+    # Turning about y from the start to the goal sweeps the body x-axis from -z through +x to +z,
+    # so the direct rotation passes through the cone and the planner has to go around it.
+    start_state = quaternion_from_axis_angle(axis=(0.0, 1.0, 0.0), angle=math.pi / 2.0)
+    goal_target = quaternion_from_axis_angle(axis=(0.0, 1.0, 0.0), angle=-math.pi / 2.0)
+    goal_region = QuaternionGoalRegion(space, target=goal_target, radius=math.radians(10.0))
+
+    problem_def = ProblemDefinition.from_so3(space, start_state, goal_region)
+
+    planner = RRTConnect(max_distance=0.2, goal_bias=0.05, problem_definition=problem_def)
+    planner.setup(is_outside_forbidden_cone)
+
+    try:
+        path = planner.solve(timeout_secs=10.0)
+    except Exception as e:
+        pytest.fail(
+            f"Planner failed to find a solution when one should exist. Error: {e}"
+        )
+
+    assert space.distance(path.states[0], start_state) < 1e-9, "Path must start at the start state."
+    assert goal_region.is_satisfied(path.states[-1]), "Path must end inside the goal region."
+
+    for i, state in enumerate(path.states):
+        assert is_outside_forbidden_cone(state), f"Path enters the forbidden cone at index {i}: {state}"
@@ -83,3 +83,38 @@ def test_rrt_star_finds_path_in_rvss():
         assert is_state_valid(state), f"Path contains an invalid state: {state.values}"
 
     print("Path validation successful!")
+
+
+def test_rrt_star_reports_non_increasing_costs_in_rvss():
+    space = RealVectorStateSpace(dimension=2, bounds=[(0.0, 10.0), (0.0, 10.0)])
+
+    start_state = RealVectorState([1.0, 5.0])
+    goal_region = CircularGoal(space, x=9.0, y=5.0, radius=0.5)
+
+    problem_def = ProblemDefinition.from_real_vector(space, start_state, goal_region)
+
+    planner = RRTStar(
+        max_distance=0.5,
+        goal_bias=0.05,
+        search_radius=1.0,
+        problem_definition=problem_def,
+    )
+
+    planner.setup(is_state_valid)
+
+    costs = []
+
+    def on_progress(cost: float):
+        costs.append(cost)
+        if len(costs) == 1:
+            raise RuntimeError("exceptions from the callback must not stop planning")
+
+    path = planner.solve(timeout_secs=2.0, progress_callback=on_progress)
+
+    assert len(costs) > 0, "The callback should be told the cost of the first solution."
+    for previous, current in zip(costs, costs[1:]):
+        assert current <= previous, f"Costs must not increase: {costs}"
+
+    assert goal_region.is_satisfied(path.states[-1]), "Path must end inside the goal region."
+    for state in path.states:
+        assert is_state_valid(state), f"Path contains an invalid state: {state.values}"
@@ -0,0 +1,89 @@
+import math
+
+import pytest
+
+from oxmpl_py.base import (
+    RealVectorState,
+    RealVectorStateSpace,
+    SO2State,
+    SO2StateSpace,
+    SO3State,
+    SO3StateSpace,
+)
+
+
+def test_real_vector_space_samples_within_bounds():
+    space = RealVectorStateSpace(dimension=2, bounds=[(0.0, 10.0), (-1.0, 1.0)])
+
+    for _ in range(100):
+        state = space.sample_uniform()
+        assert space.satisfies_bounds(state)
+        x, y = state.values
+        assert 0.0 <= x <= 10.0
+        assert -1.0 <= y <= 1.0
+
+
+def test_real_vector_space_sampling_unbounded_dimension_raises():
+    space = RealVectorStateSpace(dimension=2)
+
+    with pytest.raises(ValueError):
+        space.sample_uniform()
+
+
+def test_real_vector_space_enforce_bounds_returns_clamped_copy():
+    space = RealVectorStateSpace(dimension=2, bounds=[(0.0, 10.0), (0.0, 10.0)])
+    state = RealVectorState([-3.0, 12.0])
+
+    assert not space.satisfies_bounds(state)
+    clamped = space.enforce_bounds(state)
+
+    assert clamped.values == [0.0, 10.0]
+    assert space.satisfies_bounds(clamped)
+    assert state.values == [-3.0, 12.0], "The original state must not be modified."
+
+
+def test_so2_space_sample_and_enforce_bounds():
+    space = SO2StateSpace(bounds=(0.0, math.pi / 2.0))
+
+    for _ in range(100):
+        assert space.satisfies_bounds(space.sample_uniform())
+
+    clamped = space.enforce_bounds(SO2State(-0.2))
+    assert clamped.value == 0.0
+    assert space.satisfies_bounds(clamped)
+
+
+def test_so3_space_samples_within_bounds():
+    space = SO3StateSpace(bounds=(SO3State.identity(), math.pi / 4.0))
+
+    for _ in range(100):
+        state = space.sample_uniform()
+        assert space.satisfies_bounds(state)
+        assert space.distance(SO3State.identity(), state) <= math.pi / 4.0 + 1e-9
+
+
+def test_real_vector_space_reports_construction_parameters():
+    bounds = [(0.0, 10.0), (-1.0, 1.0), (2.5, 3.5)]
+    space = RealVectorStateSpace(dimension=3, bounds=bounds)
+
+    assert space.get_dimension() == 3
+    assert space.get_bounds() == bounds
+
+
+def test_real_vector_space_interpolates_midpoint():
+    space = RealVectorStateSpace(dimension=2, bounds=[(0.0, 10.0), (0.0, 10.0)])
+    start = RealVectorState([1.0, 2.0])
+    end = RealVectorState([5.0, 8.0])
+
+    midpoint = space.interpolate(start, end, 0.5)
+
+    assert midpoint.values == pytest.approx([3.0, 5.0])
+    assert space.interpolate(start, end, 0.0).values == pytest.approx(start.values)
+    assert space.interpolate(start, end, 1.0).values == pytest.approx(end.values)
+
+
+def test_real_vector_space_interpolate_rejects_wrong_dimension():
+    space = RealVectorStateSpace(dimension=2, bounds=[(0.0, 10.0), (0.0, 10.0)])
+
+    with pytest.raises(ValueError):
+        space.interpolate(RealVectorState([1.0, 2.0]), RealVectorState([1.0, 2.0, 3.0]), 0.5)
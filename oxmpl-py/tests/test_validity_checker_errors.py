@@ -0,0 +1,58 @@
+import pytest
+import math
+import random
+
+from oxmpl_py.base import RealVectorState, RealVectorStateSpace, ProblemDefinition
+from oxmpl_py.geometric import RRT
+
+
+class CircularGoal:
+    def __init__(self, space: RealVectorStateSpace, x: float, y: float, radius: float):
+        self.space = space
+        self.target = RealVectorState([x, y])
+        self.radius = radius
+        self.rng = random.Random(123)
+
+    def is_satisfied(self, state: RealVectorState) -> bool:
+        return self.space.distance(self.target, state) <= self.radius
+
+    def sample_goal(self) -> RealVectorState:
+        angle = self.rng.uniform(0, 2 * math.pi)
+        radius = self.radius * math.sqrt(self.rng.uniform(0, 1))
+
+        x = self.target.values[0] + radius * math.cos(angle)
+        y = self.target.values[1] + radius * math.sin(angle)
+        return RealVectorState([x, y])
+
+
+def raising_checker(state: RealVectorState) -> bool:
+    """Raises for every state right of x = 5, and accepts the rest."""
+    x, _ = state.values
+    if x > 5.0:
+        raise ValueError("collision model unavailable")
+    return True
+
+
+def make_planner() -> RRT:
+    space = RealVectorStateSpace(dimension=2, bounds=[(0.0, 10.0), (0.0, 10.0)])
+    start_state = RealVectorState([1.0, 5.0])
+    goal_region = CircularGoal(space, x=9.0, y=5.0, radius=0.5)
+    problem_def = ProblemDefinition.from_real_vector(space, start_state, goal_region)
+    return RRT(max_distance=0.5, goal_bias=0.05, problem_definition=problem_def)
+
+
+def test_strict_mode_surfaces_checker_exception():
+    planner = make_planner()
+    planner.setup(raising_checker, strict=True)
+
+    with pytest.raises(Exception, match="collision model unavailable"):
+        planner.solve(timeout_secs=5.0)
+
+
+def test_default_mode_treats_checker_exception_as_invalid():
+    planner = make_planner()
+    planner.setup(raising_checker)
+
+    # Every state right of x = 5 counts as invalid, so the goal can never be reached.
+    with pytest.raises(Exception, match="No solution found within timeout"):
+        planner.solve(timeout_secs=0.5)
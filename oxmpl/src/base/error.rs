@@ -28,6 +28,16 @@ pub enum StateSpaceError {
     ZeroDimensionUnbounded,
     /// Below the least angular bound
     InvalidAngularDistance { lower: f64 },
+    /// The space's `distance` or `interpolate` violates a basic axiom.
+    AxiomViolation { axiom: &'static str },
+    /// A subspace or dimension weight is not positive and finite.
+    InvalidWeight { weight: f64 },
+    /// The order `p` of an L-p norm is not at least 1, so the distance would not be a metric.
+    InvalidNormOrder { p: f64 },
+    /// The turning radius of a car-like space is not positive and finite.
+    InvalidTurningRadius { radius: f64 },
+    /// The threshold of a goal around a state is negative or not finite.
+    InvalidThreshold { threshold: f64 },
 }
 impl fmt::Display for StateSpaceError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -51,6 +61,27 @@ impl fmt::Display for StateSpaceError {
                     "Maximum angle cannot be negative or less than zero. Provided: {lower}."
                 )
             }
+            Self::AxiomViolation { axiom } => {
+                write!(f, "StateSpace violates the {axiom} axiom.")
+            }
+            Self::InvalidWeight { weight } => {
+                write!(f, "Weight must be positive and finite. Provided: {weight}.")
+            }
+            Self::InvalidNormOrder { p } => {
+                write!(f, "Norm order must be at least 1. Provided: {p}.")
+            }
+            Self::InvalidTurningRadius { radius } => {
+                write!(
+                    f,
+                    "Turning radius must be positive and finite. Provided: {radius}."
+                )
+            }
+            Self::InvalidThreshold { threshold } => {
+                write!(
+                    f,
+                    "Goal threshold must be non-negative and finite. Provided: {threshold}."
+                )
+            }
         }
     }
 }
@@ -93,10 +124,86 @@ impl fmt::Display for StateSamplingError {
 }
 impl error::Error for StateSamplingError {}
 
+#[derive(Debug, PartialEq)]
+pub enum ValidityCheckerError {
+    /// The length of the occupancy data does not match the number of grid cells.
+    OccupancyMismatch { expected: usize, found: usize },
+    /// The cell size of a grid must be strictly positive and finite.
+    InvalidCellSize { cell_size: f64 },
+}
+impl fmt::Display for ValidityCheckerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OccupancyMismatch { expected, found } => write!(
+                f,
+                "Occupancy data length ({found}) does not match the number of grid cells ({expected})."
+            ),
+            Self::InvalidCellSize { cell_size } => {
+                write!(f, "Cell size must be positive and finite. Provided: {cell_size}.")
+            }
+        }
+    }
+}
+impl error::Error for ValidityCheckerError {}
+
+#[derive(Debug, PartialEq)]
+pub enum PathValidationError {
+    /// The state at `index` in the path is invalid.
+    InvalidState { index: usize },
+    /// The motion from the state at `segment` to the next one passes through an invalid state,
+    /// first at interpolation parameter `t`.
+    InvalidMotion { segment: usize, t: f64 },
+}
+impl fmt::Display for PathValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidState { index } => write!(f, "State {index} of the path is invalid."),
+            Self::InvalidMotion { segment, t } => write!(
+                f,
+                "Motion {segment} of the path is invalid at interpolation parameter {t}."
+            ),
+        }
+    }
+}
+impl error::Error for PathValidationError {}
+
+#[derive(Debug, PartialEq)]
+pub enum ProblemError {
+    /// The problem definition has no start states.
+    NoStartStates,
+    /// The start state at `index` is not compatible with the space, e.g. has the wrong dimension.
+    IncompatibleStart { index: usize },
+    /// The start state at `index` is outside the bounds of the space.
+    OutOfBounds { index: usize },
+    /// The start state at `index` is invalid according to the validity checker.
+    InvalidStart { index: usize },
+}
+impl fmt::Display for ProblemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoStartStates => write!(f, "The problem definition has no start states."),
+            Self::IncompatibleStart { index } => write!(
+                f,
+                "Start state {index} does not match the dimension of the space."
+            ),
+            Self::OutOfBounds { index } => {
+                write!(f, "Start state {index} is outside the bounds of the space.")
+            }
+            Self::InvalidStart { index } => write!(f, "Start state {index} is not valid."),
+        }
+    }
+}
+impl error::Error for ProblemError {}
+
 #[derive(Debug, PartialEq)]
 pub enum PlanningError {
-    /// Solution search timed-out.
-    Timeout,
+    /// Solution search timed-out. Carries the smallest distance to the goal reached by any state
+    /// the planner explored, and the number of nodes it had grown, so callers can tell how close
+    /// it got.
+    Timeout {
+        best_goal_distance: f64,
+        nodes: usize,
+    },
     /// No solution found.
     NoSolutionFound,
     // Planner was not setup first.
@@ -105,12 +212,39 @@ pub enum PlanningError {
     InvalidStartState,
     // State space hasn't been sampled.
     UnsampledStateSpace,
+    // Motion between two states is not valid.
+    InvalidMotion,
+    /// The planner could not sample a state, even after falling back to or retrying uniform
+    /// sampling. Carries the underlying sampling error.
+    SamplingFailed(StateSamplingError),
+    /// The validity checker failed to check a state, e.g. because a callback into another
+    /// language raised an exception. Carries the checker's description of the failure.
+    ValidityCheckFailed(String),
+    /// The problem definition is malformed, e.g. it has no start states or a start state does not
+    /// fit the space. Carries a description of the problem.
+    InvalidProblemDefinition(String),
+    /// The planner needed more validity checks than the budget set with
+    /// `set_max_validity_checks`. Carries the budget.
+    CheckBudgetExhausted {
+        max_checks: usize,
+    },
+    /// A planner parameter is out of its valid range. Carries the parameter's name and value.
+    InvalidParameter {
+        name: &'static str,
+        value: f64,
+    },
 }
 impl fmt::Display for PlanningError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Timeout => {
-                write!(f, "No solution found within timeout.")
+            Self::Timeout {
+                best_goal_distance,
+                nodes,
+            } => {
+                write!(
+                    f,
+                    "No solution found within timeout. Explored {nodes} nodes, the closest of which was {best_goal_distance} from the goal."
+                )
             }
             Self::NoSolutionFound => {
                 write!(f, "No solution found.")
@@ -130,6 +264,27 @@ impl fmt::Display for PlanningError {
                     "StateSpace is not sampled. Either Tree or Roadmap is empty."
                 )
             }
+            Self::InvalidMotion => {
+                write!(f, "Motion between two states is not valid.")
+            }
+            Self::SamplingFailed(err) => {
+                write!(f, "Failed to sample a state: {err}")
+            }
+            Self::ValidityCheckFailed(message) => {
+                write!(f, "State validity check failed: {message}")
+            }
+            Self::InvalidProblemDefinition(message) => {
+                write!(f, "Invalid problem definition: {message}")
+            }
+            Self::CheckBudgetExhausted { max_checks } => {
+                write!(
+                    f,
+                    "No solution found within the budget of {max_checks} validity checks."
+                )
+            }
+            Self::InvalidParameter { name, value } => {
+                write!(f, "Invalid value {value} for planner parameter `{name}`.")
+            }
         }
     }
 }
@@ -2,7 +2,15 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
-use crate::base::{error::StateSamplingError, state::State};
+use std::sync::Arc;
+
+use crate::base::{
+    error::{StateSamplingError, StateSpaceError},
+    planner::MAX_SAMPLING_ATTEMPTS,
+    space::StateSpace,
+    state::{RealVectorState, State},
+    validity::StateValidityChecker,
+};
 use rand::Rng;
 
 /// The base trait for all goal definitions.
@@ -36,6 +44,368 @@ pub trait GoalSampleableRegion<S: State>: GoalRegion<S> {
     /// be valid goal states.
     ///
     /// # Errors
-    /// Can return an error if sampling is not possible.
+    /// Can return an error if sampling is not possible. Implementations that draw states until
+    /// one lies in the region should bound the number of draws with `sample_goal_with_budget`,
+    /// returning `StateSamplingError::GoalSamplingTimeout` rather than looping forever.
     fn sample_goal(&self, rng: &mut impl Rng) -> Result<S, StateSamplingError>;
 }
+
+/// Runs a rejection-sampling loop for `GoalSampleableRegion::sample_goal`, giving up after
+/// `max_attempts` attempts.
+///
+/// Each attempt calls `propose`, which returns `Ok(Some(state))` to accept a state, `Ok(None)` to
+/// reject it and try again, or an error to stop straight away. Sampling a goal by drawing states
+/// until one lies in the region never terminates if the region is too small to be hit, so
+/// implementations should loop through this rather than with an unbounded `loop`.
+///
+/// # Errors
+///
+/// Returns `StateSamplingError::GoalSamplingTimeout` if every attempt was rejected, or the first
+/// error returned by `propose`.
+///
+/// # Examples
+///
+/// ```
+/// use oxmpl::base::error::StateSamplingError;
+/// use oxmpl::base::goal::sample_goal_with_budget;
+/// use rand::{Rng, SeedableRng};
+///
+/// let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+/// // Draw from [0, 1] until a value lands in the far smaller [0.5, 0.5 + 1e-12].
+/// let result = sample_goal_with_budget(&mut rng, 100, |rng| {
+///     let x: f64 = rng.random();
+///     Ok((0.5..=0.5 + 1e-12).contains(&x).then_some(x))
+/// });
+/// assert_eq!(
+///     result,
+///     Err(StateSamplingError::GoalSamplingTimeout { attempts: 100 })
+/// );
+/// ```
+pub fn sample_goal_with_budget<S, R>(
+    rng: &mut R,
+    max_attempts: u32,
+    mut propose: impl FnMut(&mut R) -> Result<Option<S>, StateSamplingError>,
+) -> Result<S, StateSamplingError>
+where
+    R: Rng + ?Sized,
+{
+    for _ in 0..max_attempts {
+        if let Some(state) = propose(rng)? {
+            return Ok(state);
+        }
+    }
+    Err(StateSamplingError::GoalSamplingTimeout {
+        attempts: max_attempts,
+    })
+}
+
+/// A goal wrapper that only accepts goal states which are also valid.
+///
+/// A user's goal region and validity checker are defined independently, so a region can overlap
+/// an obstacle. A planner would then happily "reach" a goal state that is in collision.
+/// `ValidGoalRegion` guards against this by requiring `is_satisfied` states to pass the validity
+/// checker, and by rejecting invalid samples from `sample_goal`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use oxmpl::base::goal::{Goal, ValidGoalRegion};
+/// use oxmpl::base::state::RealVectorState;
+/// use oxmpl::base::validity::StateValidityChecker;
+///
+/// struct AnyStateGoal;
+///
+/// impl Goal<RealVectorState> for AnyStateGoal {
+///     fn is_satisfied(&self, _state: &RealVectorState) -> bool {
+///         true
+///     }
+/// }
+///
+/// struct PositiveXIsInvalidChecker;
+///
+/// impl StateValidityChecker<RealVectorState> for PositiveXIsInvalidChecker {
+///     fn is_valid(&self, state: &RealVectorState) -> bool {
+///         state.values[0] <= 0.0
+///     }
+/// }
+///
+/// let goal = ValidGoalRegion::new(AnyStateGoal, Arc::new(PositiveXIsInvalidChecker));
+/// assert!(goal.is_satisfied(&RealVectorState::new(vec![-1.0])));
+/// assert!(!goal.is_satisfied(&RealVectorState::new(vec![1.0])));
+/// ```
+pub struct ValidGoalRegion<S: State, G> {
+    goal: G,
+    validity_checker: Arc<dyn StateValidityChecker<S> + Send + Sync>,
+}
+
+impl<S: State, G> ValidGoalRegion<S, G> {
+    /// Wraps `goal` so that only states accepted by `validity_checker` satisfy it.
+    pub fn new(goal: G, validity_checker: Arc<dyn StateValidityChecker<S> + Send + Sync>) -> Self {
+        Self {
+            goal,
+            validity_checker,
+        }
+    }
+
+    /// Returns the wrapped goal.
+    pub fn inner(&self) -> &G {
+        &self.goal
+    }
+}
+
+impl<S: State, G: Goal<S>> Goal<S> for ValidGoalRegion<S, G> {
+    fn is_satisfied(&self, state: &S) -> bool {
+        self.goal.is_satisfied(state) && self.validity_checker.is_valid(state)
+    }
+}
+
+impl<S: State, G: GoalRegion<S>> GoalRegion<S> for ValidGoalRegion<S, G> {
+    /// Returns the wrapped goal's distance. Invalid states inside the region still report `0.0`,
+    /// as the distance to the nearest valid goal state is not known.
+    fn distance_goal(&self, state: &S) -> f64 {
+        self.goal.distance_goal(state)
+    }
+}
+
+impl<S: State, G: GoalSampleableRegion<S>> GoalSampleableRegion<S> for ValidGoalRegion<S, G> {
+    /// Samples the wrapped goal until a valid state is found.
+    ///
+    /// # Errors
+    /// Returns `StateSamplingError::GoalSamplingTimeout` if no valid sample is found within
+    /// a fixed number of attempts, or the wrapped goal's error if its sampling fails.
+    fn sample_goal(&self, rng: &mut impl Rng) -> Result<S, StateSamplingError> {
+        sample_goal_with_budget(rng, MAX_SAMPLING_ATTEMPTS as u32, |rng| {
+            let state = self.goal.sample_goal(rng)?;
+            Ok(self.validity_checker.is_valid(&state).then_some(state))
+        })
+    }
+}
+
+/// An axis-aligned box goal region for `RealVectorState`s, with a separate tolerance per
+/// dimension.
+///
+/// A state satisfies the goal if, in every dimension, it is within that dimension's half-width of
+/// the centre, e.g. tight in x and y but loose in z.
+///
+/// # Examples
+///
+/// ```
+/// use oxmpl::base::goal::{BoxGoalRegion, Goal, GoalRegion};
+/// use oxmpl::base::state::RealVectorState;
+///
+/// let goal = BoxGoalRegion::new(RealVectorState::new(vec![5.0, 5.0, 1.0]), vec![0.1, 0.1, 1.0])
+///     .unwrap();
+/// assert!(goal.is_satisfied(&RealVectorState::new(vec![5.05, 4.95, 1.8])));
+/// assert!(!goal.is_satisfied(&RealVectorState::new(vec![5.2, 5.0, 1.0])));
+/// assert!((goal.distance_goal(&RealVectorState::new(vec![5.6, 5.0, 1.0])) - 0.5).abs() < 1e-9);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BoxGoalRegion {
+    center: RealVectorState,
+    half_widths: Vec<f64>,
+}
+
+impl BoxGoalRegion {
+    /// Creates a box goal region around `center`, extending `half_widths[i]` either side of it in
+    /// dimension `i`.
+    ///
+    /// # Errors
+    ///
+    /// * `StateSpaceError::DimensionMismatch` if there is not exactly one half-width per dimension
+    ///   of `center`.
+    /// * `StateSpaceError::InvalidBound` if a half-width is negative or not finite.
+    pub fn new(center: RealVectorState, half_widths: Vec<f64>) -> Result<Self, StateSpaceError> {
+        if half_widths.len() != center.values.len() {
+            return Err(StateSpaceError::DimensionMismatch {
+                expected: center.values.len(),
+                found: half_widths.len(),
+            });
+        }
+        for (c, &h) in center.values.iter().zip(&half_widths) {
+            if !(h.is_finite() && h >= 0.0) {
+                return Err(StateSpaceError::InvalidBound {
+                    lower: c - h,
+                    upper: c + h,
+                });
+            }
+        }
+        Ok(Self {
+            center,
+            half_widths,
+        })
+    }
+
+    /// Returns the centre of the box.
+    pub fn center(&self) -> &RealVectorState {
+        &self.center
+    }
+
+    /// Returns the half-width of the box in each dimension.
+    pub fn half_widths(&self) -> &[f64] {
+        &self.half_widths
+    }
+}
+
+impl Goal<RealVectorState> for BoxGoalRegion {
+    fn is_satisfied(&self, state: &RealVectorState) -> bool {
+        state
+            .values
+            .iter()
+            .zip(&self.center.values)
+            .zip(&self.half_widths)
+            .all(|((x, c), h)| (x - c).abs() <= *h)
+    }
+}
+
+impl GoalRegion<RealVectorState> for BoxGoalRegion {
+    /// Returns the Euclidean distance from the state to the nearest point of the box.
+    fn distance_goal(&self, state: &RealVectorState) -> f64 {
+        state
+            .values
+            .iter()
+            .zip(&self.center.values)
+            .zip(&self.half_widths)
+            .map(|((x, c), h)| ((x - c).abs() - h).max(0.0).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+impl GoalSampleableRegion<RealVectorState> for BoxGoalRegion {
+    /// Samples the box uniformly.
+    fn sample_goal(&self, rng: &mut impl Rng) -> Result<RealVectorState, StateSamplingError> {
+        let values = self
+            .center
+            .values
+            .iter()
+            .zip(&self.half_widths)
+            .map(|(&c, &h)| {
+                if h > 0.0 {
+                    rng.random_range(c - h..=c + h)
+                } else {
+                    c
+                }
+            })
+            .collect();
+        Ok(RealVectorState::new(values))
+    }
+}
+
+/// A goal of the states within `threshold` of a single target state, in any state space.
+///
+/// Distances are measured with the space's own `distance`, so `GoalState` suits any space without
+/// writing a goal region by hand. In an `SO2StateSpace`, for example, they are measured along the
+/// shorter arc, so a goal may straddle the `+PI`/`-PI` seam.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use oxmpl::base::goal::{Goal, GoalRegion, GoalState};
+/// use oxmpl::base::space::RealVectorStateSpace;
+/// use oxmpl::base::state::RealVectorState;
+///
+/// let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0); 2])).unwrap());
+/// let goal = GoalState::new(space, RealVectorState::new(vec![5.0, 5.0]), 0.5).unwrap();
+/// assert!(goal.is_satisfied(&RealVectorState::new(vec![5.3, 5.3])));
+/// assert!(!goal.is_satisfied(&RealVectorState::new(vec![5.5, 5.5])));
+/// assert!((goal.distance_goal(&RealVectorState::new(vec![8.0, 9.0])) - 4.5).abs() < 1e-9);
+/// ```
+pub struct GoalState<S: State, SP: StateSpace<StateType = S>> {
+    space: Arc<SP>,
+    target: S,
+    threshold: f64,
+}
+
+impl<S: State, SP: StateSpace<StateType = S>> GoalState<S, SP> {
+    /// Creates a goal of the states of `space` within `threshold` of `target`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StateSpaceError::InvalidThreshold` if `threshold` is negative or not finite.
+    pub fn new(space: Arc<SP>, target: S, threshold: f64) -> Result<Self, StateSpaceError> {
+        if !(threshold.is_finite() && threshold >= 0.0) {
+            return Err(StateSpaceError::InvalidThreshold { threshold });
+        }
+        Ok(Self {
+            space,
+            target,
+            threshold,
+        })
+    }
+
+    /// Returns the target state.
+    pub fn target(&self) -> &S {
+        &self.target
+    }
+
+    /// Returns the largest distance from the target that satisfies the goal.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+impl<S: State, SP: StateSpace<StateType = S>> Goal<S> for GoalState<S, SP> {
+    fn is_satisfied(&self, state: &S) -> bool {
+        self.space.distance(state, &self.target) <= self.threshold
+    }
+}
+
+impl<S: State, SP: StateSpace<StateType = S>> GoalRegion<S> for GoalState<S, SP> {
+    /// Returns the distance from the state to the target, less the threshold.
+    fn distance_goal(&self, state: &S) -> f64 {
+        (self.space.distance(state, &self.target) - self.threshold).max(0.0)
+    }
+}
+
+impl<S: State, SP: StateSpace<StateType = S>> GoalSampleableRegion<S> for GoalState<S, SP> {
+    /// Samples a state within `threshold` of the target.
+    ///
+    /// In spaces that implement `StateSpace::sample_uniform_near`, such as `RealVectorStateSpace`
+    /// and `SO2StateSpace`, samples are uniform over the states within the threshold, clamped to
+    /// the bounds of the space.
+    ///
+    /// Other spaces fall back to moving a uniformly random fraction of the threshold from the
+    /// target towards a uniformly sampled state, along the space's `interpolate`. These samples
+    /// are not uniform: they are denser close to the target, and next to a bound of the space
+    /// most of them lie away from it, as do most of the states sampled.
+    ///
+    /// # Errors
+    ///
+    /// In the fallback, returns the space's error if it cannot be sampled uniformly, and
+    /// `StateSamplingError::GoalSamplingTimeout` if no move ends within the threshold in a
+    /// hundred tries.
+    fn sample_goal(&self, rng: &mut impl Rng) -> Result<S, StateSamplingError> {
+        if self.threshold == 0.0 {
+            return Ok(self.target.clone());
+        }
+        if let Some(state) = self
+            .space
+            .sample_uniform_near(&self.target, self.threshold, rng)
+        {
+            return Ok(state);
+        }
+
+        for _ in 0..MAX_SAMPLING_ATTEMPTS {
+            let towards = self.space.sample_uniform(rng)?;
+            let distance = self.space.distance(&self.target, &towards);
+            if distance == 0.0 {
+                continue;
+            }
+
+            let t = (self.threshold * rng.random::<f64>() / distance).min(1.0);
+            let mut state = self.target.clone();
+            self.space
+                .interpolate(&self.target, &towards, t, &mut state);
+            if self.is_satisfied(&state) {
+                return Ok(state);
+            }
+        }
+        Err(StateSamplingError::GoalSamplingTimeout {
+            attempts: MAX_SAMPLING_ATTEMPTS as u32,
+        })
+    }
+}
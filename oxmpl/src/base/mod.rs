@@ -7,8 +7,14 @@ mod states;
 
 pub mod error;
 pub mod goal;
+pub mod motion_validator;
+pub mod nearest_neighbors;
+pub mod objective;
 pub mod planner;
 pub mod problem_definition;
+pub mod projection;
+pub mod sampler;
 pub mod space;
 pub mod state;
+pub mod termination;
 pub mod validity;
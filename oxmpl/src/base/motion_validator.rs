@@ -0,0 +1,229 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{collections::VecDeque, sync::Arc};
+
+use crate::base::{
+    planner,
+    space::{StateSpace, TimeStateSpace},
+    state::{State, TimedState},
+    validity::StateValidityChecker,
+};
+
+/// Decides whether the motion between two states is valid.
+///
+/// Planners check every edge they add to a tree or roadmap with a `MotionValidator`. By default
+/// they use a `DiscreteMotionValidator` built in `setup` from the problem's space and the
+/// `StateValidityChecker`; implement this trait to check motions differently, e.g. with a
+/// continuous collision checker that sweeps the robot's geometry along the motion.
+///
+/// Only the motion itself is checked. Planners check the states they start from separately.
+pub trait MotionValidator<S: State> {
+    /// Returns `true` if the motion from `from` to `to` is valid.
+    fn check_motion(&self, from: &S, to: &S) -> bool;
+}
+
+/// Checks motions by interpolating states along them and checking each with a
+/// `StateValidityChecker`.
+///
+/// The motion is split into `space.segments_for_distance(distance)` segments, so that none is
+/// longer than `get_longest_valid_segment_length`, and the state at the end of each segment is
+/// checked in order, stopping at the first invalid one. A motion short enough to be a single
+/// segment only has its end state checked.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use oxmpl::base::motion_validator::{DiscreteMotionValidator, MotionValidator};
+/// use oxmpl::base::space::RealVectorStateSpace;
+/// use oxmpl::base::state::RealVectorState;
+/// use oxmpl::base::validity::StateValidityChecker;
+///
+/// struct Wall;
+///
+/// impl StateValidityChecker<RealVectorState> for Wall {
+///     fn is_valid(&self, state: &RealVectorState) -> bool {
+///         !(4.9..=5.1).contains(&state.values[0])
+///     }
+/// }
+///
+/// let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0); 2])).unwrap());
+/// let validator = DiscreteMotionValidator::new(space, Arc::new(Wall));
+///
+/// let a = RealVectorState::new(vec![1.0, 1.0]);
+/// let b = RealVectorState::new(vec![4.0, 1.0]);
+/// let c = RealVectorState::new(vec![9.0, 1.0]);
+/// assert!(validator.check_motion(&a, &b));
+/// assert!(!validator.check_motion(&a, &c));
+/// ```
+pub struct DiscreteMotionValidator<SP: StateSpace> {
+    space: Arc<SP>,
+    checker: Arc<dyn StateValidityChecker<SP::StateType>>,
+}
+
+impl<SP: StateSpace> DiscreteMotionValidator<SP> {
+    /// Creates a validator that checks motions in `space` with `checker`.
+    pub fn new(space: Arc<SP>, checker: Arc<dyn StateValidityChecker<SP::StateType>>) -> Self {
+        Self { space, checker }
+    }
+}
+
+impl<SP: StateSpace> MotionValidator<SP::StateType> for DiscreteMotionValidator<SP> {
+    fn check_motion(&self, from: &SP::StateType, to: &SP::StateType) -> bool {
+        planner::check_motion(&*self.space, &*self.checker, from, to)
+    }
+}
+
+/// Checks motions by recursively bisecting them, checking the midpoint of each segment before
+/// its halves.
+///
+/// The end state is checked first, then the midpoint of the motion, then the midpoints of its two
+/// halves, and so on, level by level, until every segment is at most `resolution` long. No two
+/// checked states along a valid motion are further apart than `resolution`, so an obstacle
+/// thicker than that cannot be stepped over. Checking coarse levels first finds obstacles near
+/// the middle of a motion after a few checks, where a `DiscreteMotionValidator` walks along the
+/// motion from its start. That makes a finer resolution affordable, as most rejected motions stop
+/// early and only valid motions are checked in full.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use oxmpl::base::motion_validator::{
+///     BisectionMotionValidator, DiscreteMotionValidator, MotionValidator,
+/// };
+/// use oxmpl::base::space::{RealVectorStateSpace, StateSpace};
+/// use oxmpl::base::state::RealVectorState;
+/// use oxmpl::base::validity::StateValidityChecker;
+///
+/// // A wall 0.1 thick across the space.
+/// struct ThinWall;
+///
+/// impl StateValidityChecker<RealVectorState> for ThinWall {
+///     fn is_valid(&self, state: &RealVectorState) -> bool {
+///         !(2.21..=2.31).contains(&state.values[0])
+///     }
+/// }
+///
+/// let mut space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0); 2])).unwrap();
+/// space.set_longest_valid_segment_fraction(0.05 / space.get_maximum_extent());
+/// let resolution = space.get_longest_valid_segment_length();
+/// let space = Arc::new(space);
+/// let discrete = DiscreteMotionValidator::new(space.clone(), Arc::new(ThinWall));
+/// let bisection = BisectionMotionValidator::new(space, Arc::new(ThinWall), resolution);
+///
+/// // At the same resolution both reject the motion, the bisection validator after 13 checks and
+/// // the discrete one after 45.
+/// let a = RealVectorState::new(vec![0.0, 5.0]);
+/// let b = RealVectorState::new(vec![4.0, 5.0]);
+/// assert!(!discrete.check_motion(&a, &b));
+/// assert!(!bisection.check_motion(&a, &b));
+/// ```
+pub struct BisectionMotionValidator<SP: StateSpace> {
+    /// The length below which segments are not bisected any further. If it is not positive, the
+    /// space's `get_longest_valid_segment_length` is used instead.
+    pub resolution: f64,
+    space: Arc<SP>,
+    checker: Arc<dyn StateValidityChecker<SP::StateType>>,
+}
+
+impl<SP: StateSpace> BisectionMotionValidator<SP> {
+    /// Creates a validator that checks motions in `space` with `checker`, bisecting them until
+    /// every segment is at most `resolution` long.
+    pub fn new(
+        space: Arc<SP>,
+        checker: Arc<dyn StateValidityChecker<SP::StateType>>,
+        resolution: f64,
+    ) -> Self {
+        Self {
+            resolution,
+            space,
+            checker,
+        }
+    }
+}
+
+impl<SP: StateSpace> MotionValidator<SP::StateType> for BisectionMotionValidator<SP> {
+    fn check_motion(&self, from: &SP::StateType, to: &SP::StateType) -> bool {
+        if !self.checker.is_valid(to) {
+            return false;
+        }
+
+        let resolution = if self.resolution > 0.0 {
+            self.resolution
+        } else {
+            self.space.get_longest_valid_segment_length()
+        };
+        let distance = self.space.distance(from, to);
+        let mut state = from.clone();
+        // The segments still to be bisected, as intervals of the interpolation parameter, coarsest
+        // first.
+        let mut segments = VecDeque::from([(0.0, 1.0)]);
+        while let Some((t0, t1)) = segments.pop_front() {
+            if distance * (t1 - t0) <= resolution {
+                continue;
+            }
+            let midpoint = 0.5 * (t0 + t1);
+            self.space.interpolate(from, to, midpoint, &mut state);
+            if !self.checker.is_valid(&state) {
+                return false;
+            }
+            segments.push_back((t0, midpoint));
+            segments.push_back((midpoint, t1));
+        }
+        true
+    }
+}
+
+/// Checks motions in a `TimeStateSpace`, rejecting those that go backwards in time or exceed the
+/// space's `max_speed` before checking their states as a `DiscreteMotionValidator` would.
+///
+/// Planners in a `TimeStateSpace` should use this, or another validator that checks
+/// `TimeStateSpace::is_motion_admissible`, as nothing else stops them from travelling back in
+/// time, e.g. to pass an obstacle before it arrives. Planners check every motion in the
+/// direction their path follows it, including the motions of trees grown from the goal, so this
+/// holds for bidirectional planners such as `RRTConnect` too.
+pub struct TimeMotionValidator<SP: StateSpace> {
+    space: Arc<TimeStateSpace<SP>>,
+    checker: Arc<dyn StateValidityChecker<TimedState<SP::StateType>>>,
+}
+
+impl<SP: StateSpace> TimeMotionValidator<SP> {
+    /// Creates a validator that checks motions in `space` with `checker`.
+    pub fn new(
+        space: Arc<TimeStateSpace<SP>>,
+        checker: Arc<dyn StateValidityChecker<TimedState<SP::StateType>>>,
+    ) -> Self {
+        Self { space, checker }
+    }
+}
+
+impl<SP: StateSpace> MotionValidator<TimedState<SP::StateType>> for TimeMotionValidator<SP> {
+    fn check_motion(
+        &self,
+        from: &TimedState<SP::StateType>,
+        to: &TimedState<SP::StateType>,
+    ) -> bool {
+        self.space.is_motion_admissible(from, to)
+            && planner::check_motion(&*self.space, &*self.checker, from, to)
+    }
+}
+
+/// Returns `custom` if it is set, and otherwise a `DiscreteMotionValidator` for `space` and
+/// `checker`. Planners call this in `setup`.
+pub(crate) fn motion_validator_or_default<S, SP>(
+    custom: &Option<Arc<dyn MotionValidator<S>>>,
+    space: Arc<SP>,
+    checker: Arc<dyn StateValidityChecker<S>>,
+) -> Arc<dyn MotionValidator<S>>
+where
+    S: State,
+    SP: StateSpace<StateType = S> + 'static,
+{
+    match custom {
+        Some(validator) => validator.clone(),
+        None => Arc::new(DiscreteMotionValidator::new(space, checker)),
+    }
+}
@@ -0,0 +1,810 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::sync::Arc;
+
+use crate::base::{
+    space::StateSpace,
+    state::{RealVectorState, State},
+};
+
+/// A data structure for answering nearest-neighbour queries over the states in a planner's tree
+/// or roadmap.
+///
+/// Every state is stored together with the index of the node it belongs to, so query results can
+/// be used to index directly into the planner's own node storage.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use oxmpl::base::nearest_neighbors::{KdTreeNN, NearestNeighbors};
+/// use oxmpl::base::space::RealVectorStateSpace;
+/// use oxmpl::base::state::RealVectorState;
+///
+/// let space = Arc::new(RealVectorStateSpace::new(2, None).unwrap());
+/// let mut nn = KdTreeNN::new(space);
+/// nn.add(0, RealVectorState::new(vec![0.0, 0.0]));
+/// nn.add(1, RealVectorState::new(vec![5.0, 5.0]));
+///
+/// assert_eq!(nn.nearest(&RealVectorState::new(vec![4.0, 4.0])), Some(1));
+/// assert_eq!(nn.nearest_within_radius(&RealVectorState::new(vec![1.0, 0.0]), 2.0), vec![0]);
+/// ```
+pub trait NearestNeighbors<S: State> {
+    /// Adds `state`, identified by the node `index`.
+    fn add(&mut self, index: usize, state: S);
+
+    /// Returns the index of the stored state closest to `query`, or `None` if the structure is
+    /// empty. Ties are broken arbitrarily.
+    fn nearest(&self, query: &S) -> Option<usize>;
+
+    /// Returns the indices of all stored states strictly closer than `radius` to `query`, in
+    /// ascending order.
+    fn nearest_within_radius(&self, query: &S, radius: f64) -> Vec<usize>;
+
+    /// Removes all stored states.
+    fn clear(&mut self);
+
+    /// Reserves capacity for at least `additional` more states, avoiding reallocations while they
+    /// are added. The default implementation does nothing.
+    fn reserve(&mut self, _additional: usize) {}
+
+    /// Returns the number of stored states.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if no states are stored.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Creates a fresh, empty `NearestNeighbors` structure for a given space.
+///
+/// Planners call this in `setup`, once per tree they grow.
+pub type NearestNeighborsFactory<S, SP> = Arc<dyn Fn(Arc<SP>) -> Box<dyn NearestNeighbors<S>>>;
+
+/// A `NearestNeighbors` implementation that compares the query against every stored state.
+///
+/// It works with any `StateSpace` and is what the planners use by default.
+pub struct LinearNN<SP: StateSpace> {
+    space: Arc<SP>,
+    entries: Vec<(usize, SP::StateType)>,
+}
+
+impl<SP: StateSpace> LinearNN<SP> {
+    /// Creates a new, empty `LinearNN` using the distance function of `space`.
+    pub fn new(space: Arc<SP>) -> Self {
+        Self {
+            space,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<SP: StateSpace> NearestNeighbors<SP::StateType> for LinearNN<SP> {
+    fn add(&mut self, index: usize, state: SP::StateType) {
+        self.entries.push((index, state));
+    }
+
+    fn nearest(&self, query: &SP::StateType) -> Option<usize> {
+        let mut best: Option<(usize, f64)> = None;
+        for (index, state) in &self.entries {
+            let dist = self.space.distance(state, query);
+            if best.is_none_or(|(_, best_dist)| dist < best_dist) {
+                best = Some((*index, dist));
+            }
+        }
+        best.map(|(index, _)| index)
+    }
+
+    fn nearest_within_radius(&self, query: &SP::StateType, radius: f64) -> Vec<usize> {
+        let mut neighbours: Vec<usize> = self
+            .entries
+            .iter()
+            .filter(|(_, state)| self.space.distance(state, query) < radius)
+            .map(|(index, _)| *index)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// The minimum number of states added since the last rebuild before a tree-based structure is
+/// rebuilt.
+const MIN_PENDING_BEFORE_REBUILD: usize = 32;
+
+#[derive(Clone, Debug)]
+struct KdNode {
+    /// Position of this node's state in `KdTreeNN::entries`.
+    entry: usize,
+    /// The coordinate this node splits on.
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// A kd-tree `NearestNeighbors` implementation for `RealVectorState`.
+///
+/// Distances are computed with the space's `distance` function, and pruning assumes that the
+/// difference along any single coordinate, scaled by the distance of a unit step along it, is a
+/// lower bound on that distance. This holds for every weighted metric of `RealVectorStateSpace`,
+/// where the scale of a coordinate with weight `w` is `w^(1/p)`.
+///
+/// Split axes are chosen by the spread of the stored states rather than the bounds of the space,
+/// so unbounded dimensions need no special treatment. The tree is static between rebuilds:
+/// newly added states are scanned linearly until there are more than roughly `sqrt(n)` of them,
+/// at which point the whole tree is rebuilt around medians, keeping it balanced.
+pub struct KdTreeNN<SP: StateSpace<StateType = RealVectorState>> {
+    space: Arc<SP>,
+    entries: Vec<(usize, RealVectorState)>,
+    nodes: Vec<KdNode>,
+    root: Option<usize>,
+    /// Entries from this position onwards are not yet in the tree.
+    num_built: usize,
+    /// The distance of a unit step along each coordinate, measured when the tree is first built.
+    axis_scales: Vec<f64>,
+}
+
+impl<SP: StateSpace<StateType = RealVectorState>> KdTreeNN<SP> {
+    /// Creates a new, empty `KdTreeNN` using the distance function of `space`.
+    pub fn new(space: Arc<SP>) -> Self {
+        Self {
+            space,
+            entries: Vec::new(),
+            nodes: Vec::new(),
+            root: None,
+            num_built: 0,
+            axis_scales: Vec::new(),
+        }
+    }
+
+    /// Rebuilds the tree over all entries.
+    fn rebuild(&mut self) {
+        let dimension = self.entries[0].1.values.len();
+        if self.axis_scales.len() != dimension {
+            let origin = RealVectorState::new(vec![0.0; dimension]);
+            self.axis_scales = (0..dimension)
+                .map(|axis| {
+                    let mut step = origin.clone();
+                    step.values[axis] = 1.0;
+                    self.space.distance(&origin, &step)
+                })
+                .collect();
+        }
+        self.nodes.clear();
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        self.root = self.build(&mut order);
+        self.num_built = self.entries.len();
+    }
+
+    /// Recursively builds a balanced subtree over the entries in `order`.
+    fn build(&mut self, order: &mut [usize]) -> Option<usize> {
+        if order.is_empty() {
+            return None;
+        }
+
+        let axis = self.widest_axis(order);
+        let mid = order.len() / 2;
+        let entries = &self.entries;
+        order.select_nth_unstable_by(mid, |&a, &b| {
+            entries[a].1.values[axis].total_cmp(&entries[b].1.values[axis])
+        });
+
+        let node_index = self.nodes.len();
+        self.nodes.push(KdNode {
+            entry: order[mid],
+            axis,
+            left: None,
+            right: None,
+        });
+
+        let (left, rest) = order.split_at_mut(mid);
+        let left = self.build(left);
+        let right = self.build(&mut rest[1..]);
+        self.nodes[node_index].left = left;
+        self.nodes[node_index].right = right;
+
+        Some(node_index)
+    }
+
+    /// Returns the coordinate along which the entries in `order` are most spread out.
+    fn widest_axis(&self, order: &[usize]) -> usize {
+        let dimension = self.entries[order[0]].1.values.len();
+        (0..dimension)
+            .map(|axis| {
+                let (min, max) =
+                    order
+                        .iter()
+                        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &i| {
+                            let v = self.entries[i].1.values[axis];
+                            (min.min(v), max.max(v))
+                        });
+                (axis, max - min)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(0, |(axis, _)| axis)
+    }
+
+    fn search_nearest(
+        &self,
+        node: Option<usize>,
+        query: &RealVectorState,
+        best: &mut (usize, f64),
+    ) {
+        let Some(node) = node.map(|n| &self.nodes[n]) else {
+            return;
+        };
+        let (index, state) = &self.entries[node.entry];
+
+        let dist = self.space.distance(state, query);
+        if dist < best.1 {
+            *best = (*index, dist);
+        }
+
+        let diff =
+            (query.values[node.axis] - state.values[node.axis]) * self.axis_scales[node.axis];
+        let (near, far) = if diff < 0.0 {
+            (node.left, node.right)
+        } else {
+            (node.right, node.left)
+        };
+        self.search_nearest(near, query, best);
+        if diff.abs() < best.1 {
+            self.search_nearest(far, query, best);
+        }
+    }
+
+    fn search_radius(
+        &self,
+        node: Option<usize>,
+        query: &RealVectorState,
+        radius: f64,
+        out: &mut Vec<usize>,
+    ) {
+        let Some(node) = node.map(|n| &self.nodes[n]) else {
+            return;
+        };
+        let (index, state) = &self.entries[node.entry];
+
+        if self.space.distance(state, query) < radius {
+            out.push(*index);
+        }
+
+        // States on the left have a coordinate of at most the split value, those on the right at
+        // least it, so a whole side can be skipped once the split is a radius away.
+        let diff =
+            (query.values[node.axis] - state.values[node.axis]) * self.axis_scales[node.axis];
+        if diff < radius {
+            self.search_radius(node.left, query, radius, out);
+        }
+        if -diff < radius {
+            self.search_radius(node.right, query, radius, out);
+        }
+    }
+}
+
+impl<SP: StateSpace<StateType = RealVectorState>> NearestNeighbors<RealVectorState>
+    for KdTreeNN<SP>
+{
+    fn add(&mut self, index: usize, state: RealVectorState) {
+        self.entries.push((index, state));
+
+        let pending = self.entries.len() - self.num_built;
+        let threshold = MIN_PENDING_BEFORE_REBUILD.max((self.entries.len() as f64).sqrt() as usize);
+        if pending > threshold {
+            self.rebuild();
+        }
+    }
+
+    fn nearest(&self, query: &RealVectorState) -> Option<usize> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let mut best = (usize::MAX, f64::INFINITY);
+        for (index, state) in &self.entries[self.num_built..] {
+            let dist = self.space.distance(state, query);
+            if dist < best.1 {
+                best = (*index, dist);
+            }
+        }
+        self.search_nearest(self.root, query, &mut best);
+
+        // Only reachable with NaN distances, where no state compares as closer.
+        if best.0 == usize::MAX {
+            return Some(self.entries[0].0);
+        }
+        Some(best.0)
+    }
+
+    fn nearest_within_radius(&self, query: &RealVectorState, radius: f64) -> Vec<usize> {
+        let mut neighbours: Vec<usize> = self.entries[self.num_built..]
+            .iter()
+            .filter(|(_, state)| self.space.distance(state, query) < radius)
+            .map(|(index, _)| *index)
+            .collect();
+        self.search_radius(self.root, query, radius, &mut neighbours);
+        neighbours.sort_unstable();
+        neighbours
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.nodes.clear();
+        self.root = None;
+        self.num_built = 0;
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        // Every entry becomes a kd-tree node once the tree is rebuilt.
+        self.entries.reserve(additional);
+        self.nodes.reserve(additional);
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// The number of pivots an interior GNAT node splits its states between.
+const GNAT_DEGREE: usize = 8;
+
+/// The largest number of states a GNAT leaf holds before it is split.
+const GNAT_MAX_LEAF_SIZE: usize = 16;
+
+/// The default slack added to the triangle-inequality bounds used for pruning in `GnatNN`.
+///
+/// `SO3StateSpace` reports a distance of 0 for quaternions whose dot product is within `1e-9` of
+/// ±1, which breaks the triangle inequality by up to about `4.5e-5` near identical and antipodal
+/// rotations.
+pub const GNAT_DEFAULT_SLACK: f64 = 1e-4;
+
+#[derive(Clone, Debug)]
+struct GnatNode {
+    /// Positions in `GnatNN::entries` of the states held by this node: the bucket of a leaf, or
+    /// the pivots of an interior node.
+    pivots: Vec<usize>,
+    /// For interior nodes, `children[j]` holds the states closer to `pivots[j]` than to any other
+    /// pivot. Empty for leaves.
+    children: Vec<Option<usize>>,
+    /// `ranges[i][j]` is the smallest and largest distance from `pivots[i]` to `pivots[j]` or to
+    /// any state below `children[j]`.
+    ranges: Vec<Vec<(f64, f64)>>,
+}
+
+/// A Geometric Near-neighbour Access Tree (GNAT) `NearestNeighbors` implementation.
+///
+/// GNAT is a metric tree: it only ever calls the space's `distance` function and prunes the
+/// search with the triangle inequality, so it works for any `StateSpace` with a proper metric,
+/// including the geodesic metrics of `SO2StateSpace` and `SO3StateSpace`.
+///
+/// Every pruning bound is widened by a slack (`GNAT_DEFAULT_SLACK` unless set with
+/// `with_slack`). Where the triangle inequality only holds approximately, a larger slack keeps the
+/// results exact at the cost of pruning less; an infinite slack degrades to a linear scan.
+///
+/// Like `KdTreeNN`, the tree is static between rebuilds: newly added states are scanned linearly
+/// until there are more than roughly `sqrt(n)` of them, at which point the whole tree is rebuilt.
+pub struct GnatNN<SP: StateSpace> {
+    space: Arc<SP>,
+    slack: f64,
+    entries: Vec<(usize, SP::StateType)>,
+    nodes: Vec<GnatNode>,
+    root: Option<usize>,
+    /// Entries from this position onwards are not yet in the tree.
+    num_built: usize,
+}
+
+impl<SP: StateSpace> GnatNN<SP> {
+    /// Creates a new, empty `GnatNN` using the distance function of `space`.
+    pub fn new(space: Arc<SP>) -> Self {
+        Self::with_slack(space, GNAT_DEFAULT_SLACK)
+    }
+
+    /// Creates a new, empty `GnatNN` that widens its pruning bounds by `slack`.
+    pub fn with_slack(space: Arc<SP>, slack: f64) -> Self {
+        Self {
+            space,
+            slack,
+            entries: Vec::new(),
+            nodes: Vec::new(),
+            root: None,
+            num_built: 0,
+        }
+    }
+
+    /// Rebuilds the tree over all entries.
+    fn rebuild(&mut self) {
+        self.nodes.clear();
+        let order: Vec<usize> = (0..self.entries.len()).collect();
+        self.root = self.build(order);
+        self.num_built = self.entries.len();
+    }
+
+    /// Recursively builds a subtree over the entries in `order`.
+    fn build(&mut self, order: Vec<usize>) -> Option<usize> {
+        if order.is_empty() {
+            return None;
+        }
+
+        let leaf = |order: Vec<usize>| GnatNode {
+            pivots: order,
+            children: Vec::new(),
+            ranges: Vec::new(),
+        };
+        if order.len() <= GNAT_MAX_LEAF_SIZE {
+            self.nodes.push(leaf(order));
+            return Some(self.nodes.len() - 1);
+        }
+
+        // Choose pivots by farthest-point sampling, recording the distance from every pivot to
+        // every state in `order`.
+        let mut pivots: Vec<usize> = vec![0];
+        let mut distances: Vec<Vec<f64>> = Vec::new();
+        let mut min_distance = vec![f64::INFINITY; order.len()];
+        loop {
+            let pivot_state = &self.entries[order[*pivots.last().unwrap()]].1;
+            let row: Vec<f64> = order
+                .iter()
+                .map(|&i| self.space.distance(pivot_state, &self.entries[i].1))
+                .collect();
+            for (min, &dist) in min_distance.iter_mut().zip(&row) {
+                *min = min.min(dist);
+            }
+            distances.push(row);
+
+            if pivots.len() == GNAT_DEGREE {
+                break;
+            }
+            let (farthest, farthest_distance) = min_distance
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap();
+            // Only duplicates of the existing pivots are left.
+            if farthest_distance.is_nan() || *farthest_distance <= 0.0 {
+                break;
+            }
+            pivots.push(farthest);
+        }
+
+        // With all states at the same place, splitting would never make progress.
+        if pivots.len() < 2 {
+            self.nodes.push(leaf(order));
+            return Some(self.nodes.len() - 1);
+        }
+
+        // Assign every other state to the branch of its closest pivot.
+        let mut branches: Vec<Vec<usize>> = vec![Vec::new(); pivots.len()];
+        let mut ranges = vec![vec![(f64::INFINITY, f64::NEG_INFINITY); pivots.len()]; pivots.len()];
+        for position in 0..order.len() {
+            let branch = match pivots.iter().position(|&p| p == position) {
+                Some(branch) => branch,
+                None => {
+                    let branch = (0..pivots.len())
+                        .min_by(|&a, &b| distances[a][position].total_cmp(&distances[b][position]))
+                        .unwrap();
+                    branches[branch].push(order[position]);
+                    branch
+                }
+            };
+            for (i, row) in distances.iter().enumerate() {
+                let (min, max) = &mut ranges[i][branch];
+                *min = min.min(row[position]);
+                *max = max.max(row[position]);
+            }
+        }
+
+        let node_index = self.nodes.len();
+        self.nodes.push(GnatNode {
+            pivots: pivots.iter().map(|&p| order[p]).collect(),
+            children: Vec::new(),
+            ranges,
+        });
+        let children = branches
+            .into_iter()
+            .map(|branch| self.build(branch))
+            .collect();
+        self.nodes[node_index].children = children;
+
+        Some(node_index)
+    }
+
+    /// Whether the triangle inequality rules out every state in branch `j` of `node` lying
+    /// within `radius` of a query at distances `pivot_distances` from the node's pivots.
+    fn can_prune(&self, node: &GnatNode, pivot_distances: &[f64], j: usize, radius: f64) -> bool {
+        pivot_distances.iter().enumerate().any(|(i, &dist)| {
+            let (min, max) = node.ranges[i][j];
+            dist - radius - self.slack > max || dist + radius + self.slack < min
+        })
+    }
+
+    /// Computes the distance from `query` to each state held by `node`.
+    fn pivot_distances(&self, node: &GnatNode, query: &SP::StateType) -> Vec<f64> {
+        node.pivots
+            .iter()
+            .map(|&p| self.space.distance(&self.entries[p].1, query))
+            .collect()
+    }
+
+    fn search_nearest(&self, node: Option<usize>, query: &SP::StateType, best: &mut (usize, f64)) {
+        let Some(node) = node.map(|n| &self.nodes[n]) else {
+            return;
+        };
+
+        let pivot_distances = self.pivot_distances(node, query);
+        for (&p, &dist) in node.pivots.iter().zip(&pivot_distances) {
+            if dist < best.1 {
+                *best = (self.entries[p].0, dist);
+            }
+        }
+
+        // Visit the branches of the closest pivots first, so the radius shrinks quickly.
+        let mut visit_order: Vec<usize> = (0..node.children.len()).collect();
+        visit_order.sort_by(|&a, &b| pivot_distances[a].total_cmp(&pivot_distances[b]));
+        for j in visit_order {
+            if node.children[j].is_some() && !self.can_prune(node, &pivot_distances, j, best.1) {
+                self.search_nearest(node.children[j], query, best);
+            }
+        }
+    }
+
+    fn search_radius(
+        &self,
+        node: Option<usize>,
+        query: &SP::StateType,
+        radius: f64,
+        out: &mut Vec<usize>,
+    ) {
+        let Some(node) = node.map(|n| &self.nodes[n]) else {
+            return;
+        };
+
+        let pivot_distances = self.pivot_distances(node, query);
+        for (&p, &dist) in node.pivots.iter().zip(&pivot_distances) {
+            if dist < radius {
+                out.push(self.entries[p].0);
+            }
+        }
+
+        for j in 0..node.children.len() {
+            if node.children[j].is_some() && !self.can_prune(node, &pivot_distances, j, radius) {
+                self.search_radius(node.children[j], query, radius, out);
+            }
+        }
+    }
+}
+
+impl<SP: StateSpace> NearestNeighbors<SP::StateType> for GnatNN<SP> {
+    fn add(&mut self, index: usize, state: SP::StateType) {
+        self.entries.push((index, state));
+
+        let pending = self.entries.len() - self.num_built;
+        let threshold = MIN_PENDING_BEFORE_REBUILD.max((self.entries.len() as f64).sqrt() as usize);
+        if pending > threshold {
+            self.rebuild();
+        }
+    }
+
+    fn nearest(&self, query: &SP::StateType) -> Option<usize> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let mut best = (usize::MAX, f64::INFINITY);
+        for (index, state) in &self.entries[self.num_built..] {
+            let dist = self.space.distance(state, query);
+            if dist < best.1 {
+                best = (*index, dist);
+            }
+        }
+        self.search_nearest(self.root, query, &mut best);
+
+        // Only reachable with NaN distances, where no state compares as closer.
+        if best.0 == usize::MAX {
+            return Some(self.entries[0].0);
+        }
+        Some(best.0)
+    }
+
+    fn nearest_within_radius(&self, query: &SP::StateType, radius: f64) -> Vec<usize> {
+        let mut neighbours: Vec<usize> = self.entries[self.num_built..]
+            .iter()
+            .filter(|(_, state)| self.space.distance(state, query) < radius)
+            .map(|(index, _)| *index)
+            .collect();
+        self.search_radius(self.root, query, radius, &mut neighbours);
+        neighbours.sort_unstable();
+        neighbours
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.nodes.clear();
+        self.root = None;
+        self.num_built = 0;
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{
+        space::{RealVectorStateSpace, SO2StateSpace, SO3StateSpace},
+        state::{SO2State, SO3State},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn random_states(rng: &mut StdRng, n: usize, dimension: usize) -> Vec<RealVectorState> {
+        (0..n)
+            .map(|_| {
+                RealVectorState::new(
+                    (0..dimension)
+                        .map(|_| rng.random_range(-50.0..50.0))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_kd_tree_matches_linear_scan() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let space = Arc::new(RealVectorStateSpace::new(3, None).unwrap());
+        let mut kd = KdTreeNN::new(space.clone());
+        let mut linear = LinearNN::new(space.clone());
+
+        for (i, state) in random_states(&mut rng, 500, 3).into_iter().enumerate() {
+            kd.add(i, state.clone());
+            linear.add(i, state);
+        }
+        assert_eq!(kd.len(), 500);
+
+        for query in random_states(&mut rng, 100, 3) {
+            assert_eq!(kd.nearest(&query), linear.nearest(&query));
+            assert_eq!(
+                kd.nearest_within_radius(&query, 20.0),
+                linear.nearest_within_radius(&query, 20.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_kd_tree_matches_linear_scan_with_small_weights() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for norm_order in [1.0, 2.0, 3.0, f64::INFINITY] {
+            let mut space = RealVectorStateSpace::new(3, None).unwrap();
+            space.set_weights(vec![1e-6, 0.05, 2.0]).unwrap();
+            space.set_norm_order(norm_order).unwrap();
+            let space = Arc::new(space);
+            let mut kd = KdTreeNN::new(space.clone());
+            let mut linear = LinearNN::new(space.clone());
+
+            for (i, state) in random_states(&mut rng, 500, 3).into_iter().enumerate() {
+                kd.add(i, state.clone());
+                linear.add(i, state);
+            }
+
+            for query in random_states(&mut rng, 100, 3) {
+                assert_eq!(kd.nearest(&query), linear.nearest(&query));
+                assert_eq!(
+                    kd.nearest_within_radius(&query, 10.0),
+                    linear.nearest_within_radius(&query, 10.0)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_kd_tree_handles_unbounded_and_degenerate_data() {
+        let space = Arc::new(RealVectorStateSpace::new(2, None).unwrap());
+        let mut kd = KdTreeNN::new(space);
+        assert_eq!(kd.nearest(&RealVectorState::new(vec![0.0, 0.0])), None);
+
+        // Far-apart coordinates and many duplicates along one axis.
+        for i in 0..200 {
+            kd.add(i, RealVectorState::new(vec![1e12 * (i % 3) as f64, 1.0]));
+        }
+        kd.add(200, RealVectorState::new(vec![-1e15, 1.0]));
+
+        assert_eq!(
+            kd.nearest(&RealVectorState::new(vec![-9e14, 0.0])),
+            Some(200)
+        );
+        let near_origin = kd.nearest_within_radius(&RealVectorState::new(vec![0.0, 1.0]), 1.0);
+        assert_eq!(near_origin, (0..200).step_by(3).collect::<Vec<_>>());
+
+        kd.clear();
+        assert!(kd.is_empty());
+    }
+
+    #[test]
+    fn test_gnat_matches_brute_force_on_so3() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let space = Arc::new(SO3StateSpace::new(None).unwrap());
+        let mut gnat = GnatNN::new(space.clone());
+        let mut linear = LinearNN::new(space.clone());
+
+        let mut states: Vec<SO3State> = (0..400)
+            .map(|_| space.sample_uniform(&mut rng).unwrap())
+            .collect();
+        // Antipodal copies and near-duplicates, where the distance is clamped to 0 and the
+        // triangle inequality only holds approximately.
+        for i in 0..50 {
+            let q = states[i].clone();
+            states.push(SO3State::new(-q.x, -q.y, -q.z, -q.w));
+            states.push(
+                SO3State::new(q.x + 1e-6, q.y, q.z, q.w)
+                    .normalise()
+                    .unwrap(),
+            );
+        }
+        for (i, state) in states.into_iter().enumerate() {
+            gnat.add(i, state.clone());
+            linear.add(i, state);
+        }
+        assert_eq!(gnat.len(), 500);
+
+        for _ in 0..100 {
+            let query = space.sample_uniform(&mut rng).unwrap();
+
+            // Antipodal copies are equally close, so compare distances rather than indices.
+            let distance_to = |index: usize| space.distance(&linear.entries[index].1, &query);
+            assert_eq!(
+                distance_to(gnat.nearest(&query).unwrap()),
+                distance_to(linear.nearest(&query).unwrap())
+            );
+            assert_eq!(
+                gnat.nearest_within_radius(&query, 0.5),
+                linear.nearest_within_radius(&query, 0.5)
+            );
+        }
+    }
+
+    #[test]
+    fn test_gnat_matches_brute_force_on_so2() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let space = Arc::new(SO2StateSpace::new(None).unwrap());
+        let mut gnat = GnatNN::new(space.clone());
+        let mut linear = LinearNN::new(space.clone());
+        assert_eq!(gnat.nearest(&SO2State::new(0.0)), None);
+
+        for i in 0..300 {
+            let state = space.sample_uniform(&mut rng).unwrap();
+            gnat.add(i, state.clone());
+            linear.add(i, state);
+        }
+
+        for _ in 0..100 {
+            let query = space.sample_uniform(&mut rng).unwrap();
+            assert_eq!(gnat.nearest(&query), linear.nearest(&query));
+            assert_eq!(
+                gnat.nearest_within_radius(&query, 0.3),
+                linear.nearest_within_radius(&query, 0.3)
+            );
+        }
+
+        gnat.clear();
+        assert!(gnat.is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::sync::Arc;
+
+use crate::base::{space::StateSpace, state::State};
+
+/// A user-supplied function returning the clearance (distance to the nearest obstacle) of a state.
+pub type ClearanceFn<S> = Arc<dyn Fn(&S) -> f64>;
+
+/// Defines what makes one path better than another for optimising planners such as `RRTStar`.
+///
+/// The cost of a path is the sum of the costs of its motions, so costs must be non-negative.
+pub trait OptimizationObjective<S: State> {
+    /// Returns the cost of the motion from `from` to `to`.
+    fn motion_cost(&self, from: &S, to: &S) -> f64;
+}
+
+/// Minimises path length: the cost of a motion is its length in the space.
+///
+/// This is what optimising planners use unless given another objective.
+pub struct PathLengthObjective<SP: StateSpace> {
+    space: Arc<SP>,
+}
+
+impl<SP: StateSpace> PathLengthObjective<SP> {
+    /// Creates an objective measuring lengths with the distance of `space`.
+    pub fn new(space: Arc<SP>) -> Self {
+        Self { space }
+    }
+}
+
+impl<SP: StateSpace> OptimizationObjective<SP::StateType> for PathLengthObjective<SP> {
+    fn motion_cost(&self, from: &SP::StateType, to: &SP::StateType) -> f64 {
+        self.space.distance(from, to)
+    }
+}
+
+/// Trades path length for distance from obstacles, using a user-supplied clearance function.
+///
+/// The cost of a motion is `length * (1 + weight / average_clearance)`, where the clearance is
+/// averaged over states sampled at the motion-checking resolution. A larger `weight` pushes paths
+/// further from obstacles at the price of longer detours. Since the cost of a motion is never
+/// below its length, informed sampling remains valid under this objective.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use oxmpl::base::objective::{OptimizationObjective, StateCostObjective};
+/// use oxmpl::base::space::RealVectorStateSpace;
+/// use oxmpl::base::state::RealVectorState;
+///
+/// // A wall along y = 0.
+/// let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0); 2])).unwrap());
+/// let objective = StateCostObjective::new(space, Arc::new(|s: &RealVectorState| s.values[1]), 1.0);
+///
+/// let near_wall = objective.motion_cost(
+///     &RealVectorState::new(vec![0.0, 0.5]),
+///     &RealVectorState::new(vec![1.0, 0.5]),
+/// );
+/// let far_from_wall = objective.motion_cost(
+///     &RealVectorState::new(vec![0.0, 5.0]),
+///     &RealVectorState::new(vec![1.0, 5.0]),
+/// );
+/// assert!((near_wall - 3.0).abs() < 1e-9);
+/// assert!((far_from_wall - 1.2).abs() < 1e-9);
+/// ```
+pub struct StateCostObjective<SP: StateSpace> {
+    space: Arc<SP>,
+    clearance: ClearanceFn<SP::StateType>,
+    weight: f64,
+}
+
+impl<SP: StateSpace> StateCostObjective<SP> {
+    /// Creates an objective weighting lengths in `space` by `clearance`, with `weight` setting
+    /// how much clearance is worth.
+    pub fn new(space: Arc<SP>, clearance: ClearanceFn<SP::StateType>, weight: f64) -> Self {
+        Self {
+            space,
+            clearance,
+            weight,
+        }
+    }
+}
+
+impl<SP> OptimizationObjective<SP::StateType> for StateCostObjective<SP>
+where
+    SP: StateSpace,
+    SP::StateType: Clone,
+{
+    fn motion_cost(&self, from: &SP::StateType, to: &SP::StateType) -> f64 {
+        clearance_weighted_cost(&*self.space, &*self.clearance, self.weight, from, to)
+    }
+}
+
+/// Returns the length of the motion from `from` to `to`, scaled by
+/// `1 + weight / average_clearance`.
+pub(crate) fn clearance_weighted_cost<SP>(
+    space: &SP,
+    clearance: &dyn Fn(&SP::StateType) -> f64,
+    weight: f64,
+    from: &SP::StateType,
+    to: &SP::StateType,
+) -> f64
+where
+    SP: StateSpace,
+    SP::StateType: Clone,
+{
+    let length = space.distance(from, to);
+    let num_steps = space.segments_for_distance(length).max(1);
+    let mut interpolated_state = from.clone();
+    let mut total_clearance = 0.0;
+    for i in 0..=num_steps {
+        let t = i as f64 / num_steps as f64;
+        space.interpolate(from, to, t, &mut interpolated_state);
+        total_clearance += clearance(&interpolated_state);
+    }
+    let average_clearance = (total_clearance / (num_steps + 1) as f64).max(1e-9);
+
+    length * (1.0 + weight / average_clearance)
+}
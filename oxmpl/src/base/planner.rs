@@ -3,19 +3,749 @@
 // SPDX-License-Identifier: BSD-3-Clause
 
 use crate::base::{
-    error::PlanningError, goal::Goal, problem_definition::ProblemDefinition, space::StateSpace,
-    state::State, validity::StateValidityChecker,
+    error::PlanningError,
+    goal::{Goal, GoalRegion, GoalSampleableRegion},
+    objective::OptimizationObjective,
+    problem_definition::ProblemDefinition,
+    sampler::StateSampler,
+    space::StateSpace,
+    state::{RealVectorState, State},
+    termination::TerminationCondition,
+    validity::{CountingValidityChecker, StateValidityChecker},
 };
-use std::{sync::Arc, time::Duration};
+use crate::time::Instant;
+use rand::Rng;
+use std::{borrow::Cow, sync::Arc, time::Duration};
 
 /// Represents a solution path found by a planner.
 ///
 /// "newtype" wrapper around a `Vec<S>` to provide stronger type safety. It makes it clear that
 /// this vector of states represents a complete, ordered path from a start state to a goal
 /// state.
+///
+/// Every planner returns its path in start-to-goal order, including bidirectional planners that
+/// grow part of the path from the goal side. Use `reversed` or `reverse_in_place` to obtain the
+/// goal-to-start order.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Path<S: State>(pub Vec<S>);
 
+impl<S: State> Path<S> {
+    /// Returns a copy of this path with the order of its states reversed.
+    ///
+    /// This only reorders the states. In a space whose metric is not symmetric (see
+    /// `StateSpace::is_metric_symmetric`), a valid motion from `a` to `b` does not imply a valid
+    /// motion from `b` to `a`, so the reversed path may need to be validated again.
+    pub fn reversed(&self) -> Path<S> {
+        Path(self.0.iter().rev().cloned().collect())
+    }
+
+    /// Reverses the order of this path's states in place.
+    ///
+    /// As with `reversed`, the reversed motions are not re-validated.
+    pub fn reverse_in_place(&mut self) {
+        self.0.reverse();
+    }
+
+    /// Returns the length of this path, as the sum of the `space` distances between consecutive
+    /// states.
+    ///
+    /// Empty and single-state paths have length `0.0`.
+    pub fn length<SP: StateSpace<StateType = S>>(&self, space: &SP) -> f64 {
+        self.0
+            .windows(2)
+            .map(|pair| space.distance(&pair[0], &pair[1]))
+            .sum()
+    }
+
+    /// Returns the cost of this path under `objective`, as the sum of the costs of the motions
+    /// between consecutive states.
+    ///
+    /// Empty and single-state paths cost `0.0`.
+    pub fn cost(&self, objective: &dyn OptimizationObjective<S>) -> f64 {
+        self.0
+            .windows(2)
+            .map(|pair| objective.motion_cost(&pair[0], &pair[1]))
+            .sum()
+    }
+
+    /// Resamples this path into `n` waypoints equally spaced along its length.
+    ///
+    /// The first and last waypoints are this path's first and last states, and the ones in
+    /// between are found with `space.interpolate` along the segment they fall on. Zero-length
+    /// segments are skipped over.
+    ///
+    /// An empty path or `n == 0` gives an empty path, and `n == 1` gives just the first state. A
+    /// path with a single state, or of zero length, gives `n` copies of its first state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use oxmpl::base::{planner::Path, space::RealVectorStateSpace, state::RealVectorState};
+    ///
+    /// let space = RealVectorStateSpace::new(2, None).unwrap();
+    /// let path = Path(vec![
+    ///     RealVectorState::new(vec![0.0, 0.0]),
+    ///     RealVectorState::new(vec![4.0, 0.0]),
+    /// ]);
+    ///
+    /// let resampled = path.interpolate_uniform(&space, 5);
+    /// assert_eq!(resampled.0[1], RealVectorState::new(vec![1.0, 0.0]));
+    /// assert_eq!(resampled.length(&space), path.length(&space));
+    /// ```
+    pub fn interpolate_uniform<SP: StateSpace<StateType = S>>(
+        &self,
+        space: &SP,
+        n: usize,
+    ) -> Path<S> {
+        let (Some(first), Some(last)) = (self.0.first(), self.0.last()) else {
+            return Path(Vec::new());
+        };
+        if n == 0 {
+            return Path(Vec::new());
+        }
+
+        // cumulative[i] is the length of the path up to state i.
+        let mut cumulative = Vec::with_capacity(self.0.len());
+        let mut total = 0.0;
+        cumulative.push(total);
+        for pair in self.0.windows(2) {
+            total += space.distance(&pair[0], &pair[1]);
+            cumulative.push(total);
+        }
+        if n == 1 || total <= 0.0 {
+            return Path(vec![first.clone(); n]);
+        }
+
+        let mut states = Vec::with_capacity(n);
+        states.push(first.clone());
+        let mut segment = 0;
+        for k in 1..n - 1 {
+            let target = total * k as f64 / (n - 1) as f64;
+            // Advance to the segment containing `target`, which skips zero-length segments.
+            while cumulative[segment + 1] < target {
+                segment += 1;
+            }
+            let segment_length = cumulative[segment + 1] - cumulative[segment];
+            let t = (target - cumulative[segment]) / segment_length;
+
+            let mut state = self.0[segment].clone();
+            space.interpolate(&self.0[segment], &self.0[segment + 1], t, &mut state);
+            states.push(state);
+        }
+        states.push(last.clone());
+
+        Path(states)
+    }
+
+    /// Resamples this path so that no segment is longer than `max_segment`, while keeping its
+    /// corners.
+    ///
+    /// An interior state is a corner if the path turns there by more than
+    /// `corner_angle_threshold` radians. The turn is found from the `space` distances between the
+    /// state and its neighbours, so it is the exact Euclidean angle in a `RealVectorStateSpace`.
+    /// Corners and the end states are kept exactly. Each stretch between them is resampled
+    /// uniformly along its length with `interpolate_uniform`, into the fewest segments that are no
+    /// longer than `max_segment`, so waypoints on straight runs are not kept.
+    ///
+    /// A non-positive `max_segment` leaves each stretch as a single segment. Paths with fewer
+    /// than two states are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use oxmpl::base::{planner::Path, space::RealVectorStateSpace, state::RealVectorState};
+    /// use std::f64::consts::FRAC_PI_4;
+    ///
+    /// let space = RealVectorStateSpace::new(2, None).unwrap();
+    /// let path = Path(vec![
+    ///     RealVectorState::new(vec![0.0, 0.0]),
+    ///     RealVectorState::new(vec![2.0, 0.0]),
+    ///     RealVectorState::new(vec![2.0, 2.0]),
+    /// ]);
+    ///
+    /// let resampled = path.resample_preserving_corners(&space, 1.0, FRAC_PI_4);
+    /// assert_eq!(resampled.0.len(), 5);
+    /// assert_eq!(resampled.0[2], RealVectorState::new(vec![2.0, 0.0]));
+    /// ```
+    pub fn resample_preserving_corners<SP: StateSpace<StateType = S>>(
+        &self,
+        space: &SP,
+        max_segment: f64,
+        corner_angle_threshold: f64,
+    ) -> Path<S> {
+        if self.0.len() < 2 {
+            return self.clone();
+        }
+
+        let last = self.0.len() - 1;
+        let mut breaks = vec![0];
+        for i in 1..last {
+            let angle = turn_angle(space, &self.0[i - 1], &self.0[i], &self.0[i + 1]);
+            if angle > corner_angle_threshold {
+                breaks.push(i);
+            }
+        }
+        breaks.push(last);
+
+        let mut states = vec![self.0[0].clone()];
+        for pair in breaks.windows(2) {
+            let stretch = Path(self.0[pair[0]..=pair[1]].to_vec());
+            let length = stretch.length(space);
+            let segments = if max_segment > 0.0 {
+                ((length / max_segment).ceil() as usize).max(1)
+            } else {
+                1
+            };
+            // The stretch's first state is the previous stretch's last.
+            states.extend(
+                stretch
+                    .interpolate_uniform(space, segments + 1)
+                    .0
+                    .into_iter()
+                    .skip(1),
+            );
+        }
+
+        Path(states)
+    }
+
+    /// Returns the indices of `paths` sorted by ascending length.
+    ///
+    /// Each path's length is computed once. Paths of equal length keep their relative order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use oxmpl::base::{planner::Path, space::RealVectorStateSpace, state::RealVectorState};
+    ///
+    /// let space = RealVectorStateSpace::new(1, None).unwrap();
+    /// let line = |end: f64| {
+    ///     Path(vec![
+    ///         RealVectorState::new(vec![0.0]),
+    ///         RealVectorState::new(vec![end]),
+    ///     ])
+    /// };
+    /// let paths = vec![line(3.0), line(1.0), line(2.0)];
+    ///
+    /// assert_eq!(Path::rank_by_length(&paths, &space), vec![1, 2, 0]);
+    /// assert_eq!(Path::shortest(&paths, &space).map(|p| &p.0), Some(&paths[1].0));
+    /// ```
+    pub fn rank_by_length<SP: StateSpace<StateType = S>>(
+        paths: &[Path<S>],
+        space: &SP,
+    ) -> Vec<usize> {
+        let lengths: Vec<f64> = paths.iter().map(|path| path.length(space)).collect();
+        let mut indices: Vec<usize> = (0..paths.len()).collect();
+        indices.sort_by(|&a, &b| lengths[a].total_cmp(&lengths[b]));
+        indices
+    }
+
+    /// Returns the shortest of `paths`, or `None` if there are none.
+    ///
+    /// If several paths share the shortest length, the first of them is returned.
+    pub fn shortest<'a, SP: StateSpace<StateType = S>>(
+        paths: &'a [Path<S>],
+        space: &SP,
+    ) -> Option<&'a Path<S>> {
+        paths
+            .iter()
+            .map(|path| (path, path.length(space)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(path, _)| path)
+    }
+
+    /// Appends `other` to the end of this path, validating the junction between them.
+    ///
+    /// The motion from this path's last state to `other`'s first state is checked with the same
+    /// discretisation the planners use. If the two junction states coincide, the duplicate is
+    /// dropped so the resulting path does not contain a zero-length segment.
+    ///
+    /// The states of `other` themselves are assumed to be valid; only the junction is checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PlanningError::InvalidMotion` if the junction motion is not valid. In that case
+    /// this path is left unchanged.
+    pub fn append<SP: StateSpace<StateType = S>>(
+        &mut self,
+        other: Path<S>,
+        space: &SP,
+        checker: &dyn StateValidityChecker<S>,
+    ) -> Result<(), PlanningError> {
+        let (Some(last), Some(first)) = (self.0.last(), other.0.first()) else {
+            self.0.extend(other.0);
+            return Ok(());
+        };
+
+        let is_duplicate = space.distance(last, first) < 1e-9;
+        if !is_duplicate && !check_motion(space, checker, last, first) {
+            return Err(PlanningError::InvalidMotion);
+        }
+
+        let skip = if is_duplicate { 1 } else { 0 };
+        self.0.extend(other.0.into_iter().skip(skip));
+        Ok(())
+    }
+}
+
+impl Path<RealVectorState> {
+    /// Removes interior states that lie on the straight line between their neighbours.
+    ///
+    /// Each segment of the result replaces a run of states that all lie within `epsilon` of it,
+    /// so every removed state stays within `epsilon` of the simplified path, even along slow
+    /// curves where each state is nearly collinear with its neighbours. This is purely geometric
+    /// and does not consult a validity checker, so it is only safe on paths already known to be
+    /// valid.
+    pub fn remove_collinear(&mut self, epsilon: f64) {
+        if self.0.len() < 3 {
+            return;
+        }
+
+        let states = std::mem::take(&mut self.0);
+        let last = states.len() - 1;
+        let mut kept = vec![states[0].clone()];
+        let mut anchor = 0;
+        for end in 2..=last {
+            // Extend the segment from the last kept state to `end` only while it still passes
+            // within `epsilon` of every state it would replace.
+            let covers_run = (anchor + 1..end)
+                .all(|i| distance_to_segment(&states[i], &states[anchor], &states[end]) <= epsilon);
+            if !covers_run {
+                anchor = end - 1;
+                kept.push(states[anchor].clone());
+            }
+        }
+        kept.push(states[last].clone());
+
+        self.0 = kept;
+    }
+}
+
+/// Angle in radians by which a path through `a`, `b` and `c` turns at `b`, from the law of cosines
+/// on the `space` distances. A zero-length segment on either side counts as no turn.
+fn turn_angle<SP: StateSpace>(
+    space: &SP,
+    a: &SP::StateType,
+    b: &SP::StateType,
+    c: &SP::StateType,
+) -> f64 {
+    let ab = space.distance(a, b);
+    let bc = space.distance(b, c);
+    if ab <= 0.0 || bc <= 0.0 {
+        return 0.0;
+    }
+    let ac = space.distance(a, c);
+    let cos_interior = ((ab * ab + bc * bc - ac * ac) / (2.0 * ab * bc)).clamp(-1.0, 1.0);
+    std::f64::consts::PI - cos_interior.acos()
+}
+
+/// Euclidean distance from `point` to the segment between `a` and `b`.
+fn distance_to_segment(point: &RealVectorState, a: &RealVectorState, b: &RealVectorState) -> f64 {
+    let ab: Vec<f64> = a.values.iter().zip(&b.values).map(|(a, b)| b - a).collect();
+    let ap: Vec<f64> = a
+        .values
+        .iter()
+        .zip(&point.values)
+        .map(|(a, p)| p - a)
+        .collect();
+
+    let ab_sq: f64 = ab.iter().map(|v| v * v).sum();
+    let t = if ab_sq > 0.0 {
+        (ap.iter().zip(&ab).map(|(p, b)| p * b).sum::<f64>() / ab_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    ap.iter()
+        .zip(&ab)
+        .map(|(p, b)| (p - t * b).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Checks if the motion between two states is valid.
+///
+/// It works by discretizing the straight-line path between `from` and `to` into small steps and
+/// calling the `StateValidityChecker` on each intermediate state. If any intermediate state is
+/// invalid, the entire motion is considered invalid.
+///
+/// The states are checked in order and checking stops at the first invalid one. For expensive
+/// checkers, `check_motion_parallel` (with the `parallel` feature) checks them concurrently.
+pub fn check_motion<S: State, SP: StateSpace<StateType = S>>(
+    space: &SP,
+    checker: &dyn StateValidityChecker<S>,
+    from: &S,
+    to: &S,
+) -> bool {
+    let dist = space.distance(from, to);
+    let num_steps = space.segments_for_distance(dist);
+
+    if num_steps <= 1 {
+        return checker.is_valid(to);
+    }
+
+    let mut interpolated_state = from.clone();
+    for i in 1..=num_steps {
+        let t = i as f64 / num_steps as f64;
+        space.interpolate(from, to, t, &mut interpolated_state);
+        if !checker.is_valid(&interpolated_state) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Checks if the motion between two states is valid, checking its intermediate states in parallel.
+///
+/// The motion is discretized exactly as in `check_motion`, and the result is the same, but the
+/// intermediate states are interpolated and checked on `rayon`'s thread pool. This gives up most
+/// of `check_motion`'s early exit, so it only pays off for long motions with an expensive checker;
+/// for cheap checkers the sequential version is faster.
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+pub fn check_motion_parallel<S, SP>(
+    space: &SP,
+    checker: &(dyn StateValidityChecker<S> + Sync),
+    from: &S,
+    to: &S,
+) -> bool
+where
+    S: State + Send + Sync,
+    SP: StateSpace<StateType = S> + Sync,
+{
+    use rayon::prelude::*;
+
+    let dist = space.distance(from, to);
+    let num_steps = space.segments_for_distance(dist);
+
+    if num_steps <= 1 {
+        return checker.is_valid(to);
+    }
+
+    (1..=num_steps).into_par_iter().all(|i| {
+        let t = i as f64 / num_steps as f64;
+        let mut interpolated_state = from.clone();
+        space.interpolate(from, to, t, &mut interpolated_state);
+        checker.is_valid(&interpolated_state)
+    })
+}
+
+/// Runs `num_planners` planners on separate threads and returns the first path any of them finds,
+/// cancelling the rest.
+///
+/// Planners hold their random number generator, checker and motion validator behind types that
+/// are not thread-safe, so they cannot be sent to another thread once created. Instead,
+/// `make_planner(i)` is called on the `i`-th thread to create and `setup` the `i`-th planner, e.g.
+/// with seed `i`. It is shared between the threads, so everything it captures must be `Sync`: an
+/// `Arc<ProblemDefinition>` is `Send + Sync` exactly when its state, space and goal types are, and
+/// the validity checker should be captured as an `Arc` of a concrete `Send + Sync` checker, which
+/// coerces to `Arc<dyn StateValidityChecker>` when passed to `setup`.
+///
+/// Each planner solves until `condition` is met or another planner succeeds, whichever is first.
+/// Every thread has finished by the time this returns.
+///
+/// # Returns
+///
+/// The index of the planner that found a path first, with its path.
+///
+/// # Errors
+///
+/// If no planner finds a path, the error returned by planner 0, or
+/// `PlanningError::NoSolutionFound` if `num_planners` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use oxmpl::base::{
+///     goal::BoxGoalRegion,
+///     planner::{solve_parallel, Planner},
+///     problem_definition::ProblemDefinition,
+///     space::RealVectorStateSpace,
+///     state::RealVectorState,
+///     termination::TerminationCondition,
+///     validity::StateValidityChecker,
+/// };
+/// use oxmpl::geometric::RRT;
+///
+/// struct Open;
+/// impl StateValidityChecker<RealVectorState> for Open {
+///     fn is_valid(&self, _state: &RealVectorState) -> bool {
+///         true
+///     }
+/// }
+///
+/// let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0); 2])).unwrap());
+/// let goal = BoxGoalRegion::new(RealVectorState::new(vec![9.0, 9.0]), vec![0.5, 0.5]).unwrap();
+/// let problem = Arc::new(ProblemDefinition::new(
+///     space,
+///     vec![RealVectorState::new(vec![1.0, 1.0])],
+///     Arc::new(goal),
+/// ));
+/// let checker = Arc::new(Open);
+///
+/// let (winner, path) = solve_parallel(
+///     4,
+///     |i| {
+///         let mut planner = RRT::new(0.5, 0.05);
+///         planner.set_seed(i as u64);
+///         planner.setup(problem.clone(), checker.clone());
+///         planner
+///     },
+///     TerminationCondition::Timeout(Duration::from_secs(5)),
+/// )
+/// .unwrap();
+/// assert!(winner < 4);
+/// assert!(path.0.len() >= 2);
+/// ```
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+pub fn solve_parallel<S, SP, G, P, F>(
+    num_planners: usize,
+    make_planner: F,
+    condition: TerminationCondition,
+) -> Result<(usize, Path<S>), PlanningError>
+where
+    S: State + Send,
+    SP: StateSpace<StateType = S>,
+    G: Goal<S>,
+    P: Planner<S, SP, G>,
+    F: Fn(usize) -> P + Sync,
+{
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let condition = condition.or(TerminationCondition::Cancelled(cancel.clone()));
+
+    let results: Vec<Result<Path<S>, PlanningError>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..num_planners)
+            .map(|i| {
+                let (make_planner, condition, cancel) = (&make_planner, condition.clone(), &cancel);
+                scope.spawn(move || {
+                    let path = make_planner(i).solve_with(condition)?;
+                    // Only the first planner to succeed sets the flag; the others' paths are
+                    // discarded, as if they had been cancelled in time.
+                    if cancel.swap(true, Ordering::Relaxed) {
+                        Err(PlanningError::NoSolutionFound)
+                    } else {
+                        Ok(path)
+                    }
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+
+    let mut first_error = None;
+    for (i, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(path) => return Ok((i, path)),
+            Err(err) => {
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+    Err(first_error.unwrap_or(PlanningError::NoSolutionFound))
+}
+
+/// The number of consecutive uniform sampling failures after which `sample_target` gives up.
+pub(crate) const MAX_SAMPLING_ATTEMPTS: usize = 100;
+
+/// Wraps the validity checker given to `setup` so that its checks are counted, up to
+/// `max_checks`.
+pub(crate) fn count_validity_checks<S: State>(
+    checker: Arc<dyn StateValidityChecker<S>>,
+    max_checks: Option<usize>,
+) -> Arc<CountingValidityChecker<S>> {
+    Arc::new(CountingValidityChecker::new(checker, max_checks))
+}
+
+/// Returns `PlanningError::CheckBudgetExhausted` if `vc` refused a check because its budget was
+/// spent, or `PlanningError::ValidityCheckFailed` if it recorded an error since it was last polled.
+pub(crate) fn check_validity_error<S: State, C: StateValidityChecker<S> + ?Sized>(
+    vc: &CountingValidityChecker<S, C>,
+) -> Result<(), PlanningError> {
+    if let Some(max_checks) = vc.max_checks().filter(|_| vc.budget_exhausted()) {
+        return Err(PlanningError::CheckBudgetExhausted { max_checks });
+    }
+    match vc.take_error() {
+        Some(message) => Err(PlanningError::ValidityCheckFailed(message)),
+        None => Ok(()),
+    }
+}
+
+/// Returns `state` brought within the bounds of `space` with `StateSpace::enforce_bounds`.
+///
+/// Planners apply this to start states and goal samples, so that e.g. a raw SO(2) angle of `3*PI`
+/// is wrapped into range before it is added to a tree and compared with other states.
+pub(crate) fn enforce_bounds<S, SP>(space: &SP, mut state: S) -> S
+where
+    S: State,
+    SP: StateSpace<StateType = S>,
+{
+    space.enforce_bounds(&mut state);
+    state
+}
+
+/// Checks that every start state lies within the bounds of `space` and is valid according to
+/// `checker`.
+///
+/// Tree planners call this before planning, as they would otherwise grow their tree from a start
+/// in collision and return a path beginning there.
+pub(crate) fn check_start_states<S, SP>(
+    space: &SP,
+    checker: &dyn StateValidityChecker<S>,
+    start_states: &[S],
+) -> Result<(), PlanningError>
+where
+    S: State,
+    SP: StateSpace<StateType = S>,
+{
+    let is_valid_start = |start: &S| {
+        space.is_compatible(start) && space.satisfies_bounds(start) && checker.is_valid(start)
+    };
+    if start_states.iter().all(is_valid_start) {
+        Ok(())
+    } else {
+        Err(PlanningError::InvalidStartState)
+    }
+}
+
+/// Returns the index of the state in `states` closest to `goal` by `GoalRegion::distance_goal`,
+/// or `None` if there are no states.
+///
+/// Tree planners use this to find where a partial path should end when the goal was not reached.
+pub(crate) fn closest_to_goal<'a, S, G>(
+    goal: &G,
+    states: impl IntoIterator<Item = &'a S>,
+) -> Option<usize>
+where
+    S: State + 'a,
+    G: GoalRegion<S> + ?Sized,
+{
+    states
+        .into_iter()
+        .map(|state| goal.distance_goal(state))
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+}
+
+/// Returns `condition`, also met once the tree holds `max_nodes` nodes if there is such a cap.
+///
+/// Tree planners apply their `max_nodes` cap with this, so that it holds whichever way they
+/// were asked to solve.
+pub(crate) fn with_max_nodes(
+    condition: &TerminationCondition,
+    max_nodes: Option<usize>,
+) -> Cow<'_, TerminationCondition> {
+    match max_nodes {
+        Some(max_nodes) => Cow::Owned(
+            condition
+                .clone()
+                .or(TerminationCondition::MaxTreeSize(max_nodes)),
+        ),
+        None => Cow::Borrowed(condition),
+    }
+}
+
+/// Samples the target state for one iteration of a tree-growing planner.
+///
+/// With probability `goal_bias` the goal region is sampled. Otherwise, or if that fails, the state
+/// is drawn with `sampler`, or uniformly from `space` without one, which is retried up to
+/// `MAX_SAMPLING_ATTEMPTS` times. Either way the sample is brought within the bounds of `space`,
+/// since it may be added to the tree as it is.
+///
+/// # Errors
+///
+/// Returns `PlanningError::SamplingFailed` with the last sampling error if every attempt failed,
+/// e.g. because a dimension of `space` is unbounded.
+pub(crate) fn sample_target<S, SP, G>(
+    space: &SP,
+    goal: &G,
+    goal_bias: f64,
+    sampler: Option<&dyn StateSampler<S>>,
+    rng: &mut impl Rng,
+) -> Result<S, PlanningError>
+where
+    S: State,
+    SP: StateSpace<StateType = S>,
+    G: GoalSampleableRegion<S>,
+{
+    if rng.random_bool(goal_bias) {
+        if let Ok(state) = goal.sample_goal(rng) {
+            return Ok(enforce_bounds(space, state));
+        }
+    }
+
+    let mut last_error = None;
+    for _ in 0..MAX_SAMPLING_ATTEMPTS {
+        let sample = match sampler {
+            Some(sampler) => sampler.sample(rng),
+            None => space.sample_uniform(rng),
+        };
+        match sample {
+            Ok(state) => return Ok(enforce_bounds(space, state)),
+            Err(err) => last_error = Some(err),
+        }
+    }
+    Err(PlanningError::SamplingFailed(last_error.unwrap()))
+}
+
+/// Statistics about the work a planner did to find a `Solution`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlannerStats {
+    /// The time spent in the call that found the solution.
+    pub planning_time: Duration,
+    /// The number of validity checks made since `setup`.
+    pub validity_checks: usize,
+}
+
+/// A path found by a planner, together with how it was found.
+///
+/// Returned by `Planner::solve_detailed`.
+#[derive(Clone)]
+pub struct Solution<S: State> {
+    /// The path from a start state to the goal.
+    pub path: Path<S>,
+    /// The cost of `path`: its length in the problem's space, or for an optimising planner such
+    /// as `RRTStar` its cost under the planner's objective.
+    pub cost: f64,
+    /// Whether `path` stops short of the goal. The planners in this crate report failure with an
+    /// error instead of an approximate path, so this is `false` for all of them. The tree
+    /// planners' `solve_partial` returns approximate paths instead.
+    pub approximate: bool,
+    /// The name of the planner that found the path, e.g. `"RRT"`.
+    pub planner_name: &'static str,
+    /// Statistics about the search.
+    pub stats: PlannerStats,
+}
+
+impl<S: State> Solution<S> {
+    /// Creates an exact solution from `path`, costing it by its length in `space`.
+    pub(crate) fn exact<SP: StateSpace<StateType = S>>(
+        planner_name: &'static str,
+        path: Path<S>,
+        space: &SP,
+        stats: PlannerStats,
+    ) -> Self {
+        Self {
+            cost: path.length(space),
+            path,
+            approximate: false,
+            planner_name,
+            stats,
+        }
+    }
+}
+
 /// The central trait for all motion planning algorithms.
 ///
 /// A `Planner` is responsible for finding a valid `Path` that connects a start state to a goal,
@@ -45,8 +775,61 @@ pub trait Planner<S: State, SP: StateSpace<StateType = S>, G: Goal<S>> {
         validity_checker: Arc<dyn StateValidityChecker<S>>,
     );
 
+    /// Attempt to find a solution to the planning problem, giving up once `condition` is met.
+    ///
+    /// # Parameters
+    ///
+    /// * `condition` - When to stop searching, e.g. a timeout, an iteration budget or an external
+    ///   cancellation flag. See `TerminationCondition`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Path<S>)` if a solution is found. The `Path` contains the sequence of states.
+    /// * `Err(PlanningError::Timeout)` if a `TerminationCondition::Timeout` stopped the search.
+    /// * `Err(PlanningError::NoSolutionFound)` if any other condition stopped the search.
+    /// * Another `Err(PlanningError)` if some other error occurs.
+    fn solve_with(&mut self, condition: TerminationCondition) -> Result<Path<S>, PlanningError>;
+
+    /// Attempt to find a solution to the planning problem, giving up once `condition` is met, and
+    /// describe how it was found.
+    ///
+    /// This is `solve_with`, returning a `Solution` with the path's cost, the planner's name and
+    /// statistics about the search alongside the path.
+    ///
+    /// The default implementation wraps `solve_with`, which knows nothing of the problem's space
+    /// or validity checks. It names the planner by its type, reports a `cost` of `f64::NAN` and no
+    /// validity checks, and only measures the planning time. The planners in this crate override
+    /// it to fill in every field.
+    fn solve_detailed_with(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<Solution<S>, PlanningError> {
+        let start_time = Instant::now();
+        let path = self.solve_with(condition)?;
+        Ok(Solution {
+            path,
+            cost: f64::NAN,
+            approximate: false,
+            planner_name: std::any::type_name::<Self>(),
+            stats: PlannerStats {
+                planning_time: start_time.elapsed(),
+                validity_checks: 0,
+            },
+        })
+    }
+
+    /// Attempt to find a solution to the planning problem within `timeout`, and describe how it
+    /// was found.
+    ///
+    /// This is `solve_detailed_with` under a `TerminationCondition::Timeout`.
+    fn solve_detailed(&mut self, timeout: Duration) -> Result<Solution<S>, PlanningError> {
+        self.solve_detailed_with(TerminationCondition::Timeout(timeout))
+    }
+
     /// Attempt to find a solution to the planning problem.
     ///
+    /// This is `solve_with` under a `TerminationCondition::Timeout`.
+    ///
     /// # Parameters
     ///
     /// * `timeout` - The maximum `Duration` the planner is allowed to run before giving up.
@@ -56,5 +839,305 @@ pub trait Planner<S: State, SP: StateSpace<StateType = S>, G: Goal<S>> {
     /// * `Ok(Path<S>)` if a solution is found. The `Path` contains the sequence of states.
     /// * `Err(PlanningError)` if no solution is found within the timeout, or if another error
     ///   occurs.
-    fn solve(&mut self, timeout: Duration) -> Result<Path<S>, PlanningError>;
+    fn solve(&mut self, timeout: Duration) -> Result<Path<S>, PlanningError> {
+        self.solve_with(TerminationCondition::Timeout(timeout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{space::RealVectorStateSpace, state::RealVectorState};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Rejects states inside a thin vertical wall at `x = 5` spanning `2 <= y <= 8`.
+    struct WallChecker;
+
+    impl StateValidityChecker<RealVectorState> for WallChecker {
+        fn is_valid(&self, state: &RealVectorState) -> bool {
+            let (x, y) = (state.values[0], state.values[1]);
+            !((4.9..=5.1).contains(&x) && (2.0..=8.0).contains(&y))
+        }
+    }
+
+    fn rv_path(points: &[[f64; 2]]) -> Path<RealVectorState> {
+        Path(
+            points
+                .iter()
+                .map(|p| RealVectorState::new(p.to_vec()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_path_reversed() {
+        let path = rv_path(&[[1.0, 1.0], [2.0, 3.0], [4.0, 1.0]]);
+
+        let reversed = path.reversed();
+        assert_eq!(reversed.0, rv_path(&[[4.0, 1.0], [2.0, 3.0], [1.0, 1.0]]).0);
+        assert_eq!(reversed.reversed().0, path.0);
+
+        let mut in_place = path.clone();
+        in_place.reverse_in_place();
+        assert_eq!(in_place.0, reversed.0);
+        in_place.reverse_in_place();
+        assert_eq!(in_place.0, path.0);
+    }
+
+    /// The 3-4-5 triangle (0, 0) -> (3, 0) -> (3, 4) -> (0, 0), of perimeter 12.
+    fn triangle() -> Path<RealVectorState> {
+        rv_path(&[[0.0, 0.0], [3.0, 0.0], [3.0, 4.0], [0.0, 0.0]])
+    }
+
+    fn assert_close(a: &RealVectorState, b: [f64; 2]) {
+        assert!(
+            a.values.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-9),
+            "{a:?} is not close to {b:?}"
+        );
+    }
+
+    #[test]
+    fn test_path_length() {
+        let space = RealVectorStateSpace::new(2, None).unwrap();
+        assert!((triangle().length(&space) - 12.0).abs() < 1e-9);
+        assert_eq!(rv_path(&[]).length(&space), 0.0);
+        assert_eq!(rv_path(&[[1.0, 1.0]]).length(&space), 0.0);
+    }
+
+    #[test]
+    fn test_path_interpolate_uniform() {
+        let space = RealVectorStateSpace::new(2, None).unwrap();
+        let path = triangle();
+
+        // Waypoints every 3 units of the 12-unit perimeter.
+        let resampled = path.interpolate_uniform(&space, 5);
+        assert_eq!(resampled.0.len(), 5);
+        assert_eq!(resampled.0.first(), path.0.first());
+        assert_eq!(resampled.0.last(), path.0.last());
+        assert_close(&resampled.0[1], [3.0, 0.0]);
+        assert_close(&resampled.0[2], [3.0, 3.0]);
+        assert_close(&resampled.0[3], [1.8, 2.4]);
+    }
+
+    #[test]
+    fn test_path_interpolate_uniform_degenerate_paths() {
+        let space = RealVectorStateSpace::new(2, None).unwrap();
+
+        assert!(rv_path(&[]).interpolate_uniform(&space, 3).0.is_empty());
+        assert!(triangle().interpolate_uniform(&space, 0).0.is_empty());
+        assert_eq!(
+            triangle().interpolate_uniform(&space, 1).0,
+            rv_path(&[[0.0, 0.0]]).0
+        );
+        assert_eq!(
+            rv_path(&[[1.0, 1.0]]).interpolate_uniform(&space, 3).0,
+            rv_path(&[[1.0, 1.0], [1.0, 1.0], [1.0, 1.0]]).0
+        );
+
+        // A repeated state makes a zero-length segment, which must not produce NaNs.
+        let with_duplicate = rv_path(&[[0.0, 0.0], [2.0, 0.0], [2.0, 0.0], [4.0, 0.0]]);
+        let resampled = with_duplicate.interpolate_uniform(&space, 5);
+        for (state, x) in resampled.0.iter().zip([0.0, 1.0, 2.0, 3.0, 4.0]) {
+            assert_close(state, [x, 0.0]);
+        }
+    }
+
+    #[test]
+    fn test_path_resample_preserving_corners() {
+        let space = RealVectorStateSpace::new(2, None).unwrap();
+        // An L with a straight-run waypoint at (2, 0) and its corner at (4, 0).
+        let path = rv_path(&[[0.0, 0.0], [2.0, 0.0], [4.0, 0.0], [4.0, 3.0]]);
+
+        let resampled = path.resample_preserving_corners(&space, 1.5, std::f64::consts::FRAC_PI_4);
+
+        // The 4-unit leg needs 3 segments and the 3-unit leg 2.
+        assert_eq!(resampled.0.len(), 6);
+        assert!(resampled.0.contains(&RealVectorState::new(vec![4.0, 0.0])));
+        assert!(!resampled.0.contains(&RealVectorState::new(vec![2.0, 0.0])));
+        assert_close(&resampled.0[1], [4.0 / 3.0, 0.0]);
+        assert_close(&resampled.0[4], [4.0, 1.5]);
+        assert!(resampled
+            .0
+            .windows(2)
+            .all(|pair| space.distance(&pair[0], &pair[1]) <= 1.5 + 1e-9));
+        assert!((resampled.length(&space) - 7.0).abs() < 1e-9);
+
+        // Uniform resampling of the same path cuts the corner off.
+        assert!(!path
+            .interpolate_uniform(&space, 6)
+            .0
+            .contains(&RealVectorState::new(vec![4.0, 0.0])));
+    }
+
+    #[test]
+    fn test_path_resample_preserving_corners_ignores_shallow_turns() {
+        let space = RealVectorStateSpace::new(2, None).unwrap();
+        let path = rv_path(&[[0.0, 0.0], [2.0, 0.0], [4.0, 2.0]]);
+
+        // The path turns by 45 degrees at (2, 0).
+        let kept = path.resample_preserving_corners(&space, 10.0, 0.7);
+        assert_eq!(kept.0, path.0);
+        let smoothed = path.resample_preserving_corners(&space, 10.0, 0.8);
+        assert_eq!(smoothed.0.len(), 2);
+
+        assert_eq!(
+            rv_path(&[[1.0, 1.0]])
+                .resample_preserving_corners(&space, 1.0, 0.5)
+                .0,
+            rv_path(&[[1.0, 1.0]]).0
+        );
+    }
+
+    #[test]
+    fn test_path_rank_by_length() {
+        let space = RealVectorStateSpace::new(2, None).unwrap();
+        let paths = vec![
+            // Length 12.
+            triangle(),
+            // Length 5.
+            rv_path(&[[0.0, 0.0], [3.0, 4.0]]),
+            // Length 7.
+            rv_path(&[[0.0, 0.0], [3.0, 0.0], [3.0, 4.0]]),
+        ];
+
+        assert_eq!(Path::rank_by_length(&paths, &space), vec![1, 2, 0]);
+        assert!(std::ptr::eq(
+            Path::shortest(&paths, &space).unwrap(),
+            &paths[1]
+        ));
+
+        assert!(Path::rank_by_length(&[] as &[Path<RealVectorState>], &space).is_empty());
+        assert!(Path::shortest(&[] as &[Path<RealVectorState>], &space).is_none());
+    }
+
+    #[test]
+    fn test_path_append_valid_junction() {
+        let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+        let mut approach = rv_path(&[[1.0, 1.0], [4.0, 1.0]]);
+        let grasp = rv_path(&[[6.0, 1.0], [9.0, 1.0]]);
+
+        assert!(approach.append(grasp, &space, &WallChecker).is_ok());
+        assert_eq!(
+            approach.0,
+            rv_path(&[[1.0, 1.0], [4.0, 1.0], [6.0, 1.0], [9.0, 1.0]]).0
+        );
+    }
+
+    /// Accepts every state, counting how often it was asked.
+    struct CountingChecker(AtomicUsize);
+
+    impl StateValidityChecker<RealVectorState> for CountingChecker {
+        fn is_valid(&self, _state: &RealVectorState) -> bool {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            true
+        }
+    }
+
+    #[test]
+    fn test_segments_for_distance_matches_motion_checking() {
+        // A 10 x 10 space has a maximum extent of 10 * sqrt(2), so with a fraction of 0.005 the
+        // motion-checking resolution is 0.005 * 10 * sqrt(2) ~= 0.0707.
+        let mut space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+        space.set_longest_valid_segment_fraction(0.005);
+        assert_eq!(space.segments_for_distance(1.0), 15);
+
+        let checker = CountingChecker(AtomicUsize::new(0));
+        let from = RealVectorState::new(vec![1.0, 1.0]);
+        let to = RealVectorState::new(vec![2.0, 1.0]);
+        assert!(check_motion(&space, &checker, &from, &to));
+        assert_eq!(
+            checker.0.load(Ordering::Relaxed),
+            space.segments_for_distance(1.0)
+        );
+    }
+
+    #[test]
+    fn test_larger_segment_fraction_reduces_validity_checks_proportionally() {
+        let from = RealVectorState::new(vec![0.0, 5.0]);
+        let to = RealVectorState::new(vec![10.0, 5.0]);
+        let checks_with_fraction = |fraction: f64| {
+            let mut space =
+                RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+            space.set_longest_valid_segment_fraction(fraction);
+            let checker = CountingChecker(AtomicUsize::new(0));
+            assert!(check_motion(&space, &checker, &from, &to));
+            checker.0.load(Ordering::Relaxed)
+        };
+
+        // The step is the fraction of the maximum extent, 10 * sqrt(2), with nothing hidden on top.
+        assert_eq!(checks_with_fraction(0.005), 142);
+        assert_eq!(checks_with_fraction(0.05), 15);
+        assert_eq!(checks_with_fraction(0.5), 2);
+    }
+
+    #[test]
+    fn test_path_append_deduplicates_coincident_junction() {
+        let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+        let mut approach = rv_path(&[[1.0, 1.0], [4.0, 1.0]]);
+        let grasp = rv_path(&[[4.0, 1.0], [4.0, 9.0]]);
+
+        assert!(approach.append(grasp, &space, &WallChecker).is_ok());
+        assert_eq!(approach.0, rv_path(&[[1.0, 1.0], [4.0, 1.0], [4.0, 9.0]]).0);
+    }
+
+    #[test]
+    fn test_path_append_colliding_junction() {
+        let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+        let mut approach = rv_path(&[[1.0, 5.0], [4.0, 5.0]]);
+        let grasp = rv_path(&[[6.0, 5.0], [9.0, 5.0]]);
+
+        assert_eq!(
+            approach.append(grasp, &space, &WallChecker).err(),
+            Some(PlanningError::InvalidMotion)
+        );
+        assert_eq!(approach.0, rv_path(&[[1.0, 5.0], [4.0, 5.0]]).0);
+    }
+
+    #[test]
+    fn test_path_remove_collinear() {
+        let mut path = rv_path(&[[0.0, 0.0], [1.0, 1.0], [2.0, 2.0], [3.0, 0.0]]);
+        path.remove_collinear(1e-9);
+
+        // (1, 1) lies on the line from (0, 0) to (2, 2), while (2, 2) is a genuine corner.
+        assert_eq!(path.0, rv_path(&[[0.0, 0.0], [2.0, 2.0], [3.0, 0.0]]).0);
+    }
+
+    #[test]
+    fn test_path_remove_collinear_respects_epsilon() {
+        let mut path = rv_path(&[[0.0, 0.0], [1.0, 0.05], [2.0, 0.0]]);
+        path.remove_collinear(0.01);
+        assert_eq!(path.0.len(), 3);
+
+        path.remove_collinear(0.1);
+        assert_eq!(path.0, rv_path(&[[0.0, 0.0], [2.0, 0.0]]).0);
+    }
+
+    #[test]
+    fn test_path_remove_collinear_bounds_drift_along_arc() {
+        // A slow arc of radius 10, along which every state is within 1e-3 of the chord between
+        // its neighbours, but far from the chord between the ends.
+        let arc: Vec<[f64; 2]> = (0..=90)
+            .map(|i| {
+                let angle = (i as f64).to_radians();
+                [10.0 * angle.cos(), 10.0 * angle.sin()]
+            })
+            .collect();
+        let original = rv_path(&arc);
+        let epsilon = 0.05;
+
+        let mut path = original.clone();
+        path.remove_collinear(epsilon);
+
+        assert!(path.0.len() > 2 && path.0.len() < original.0.len());
+        assert_eq!(path.0.first(), original.0.first());
+        assert_eq!(path.0.last(), original.0.last());
+        for state in &original.0 {
+            let drift = path
+                .0
+                .windows(2)
+                .map(|pair| distance_to_segment(state, &pair[0], &pair[1]))
+                .fold(f64::INFINITY, f64::min);
+            assert!(drift <= epsilon, "{state:?} is {drift} from the path");
+        }
+    }
 }
@@ -2,9 +2,33 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
-use crate::base::{goal::Goal, space::StateSpace, state::State};
+use crate::base::{
+    error::{PlanningError, ProblemError},
+    goal::{Goal, GoalSampleableRegion},
+    planner::MAX_SAMPLING_ATTEMPTS,
+    space::StateSpace,
+    state::State,
+    validity::StateValidityChecker,
+};
+use rand::{Rng, RngCore};
 use std::sync::Arc;
 
+/// A closure drawing a single start state.
+pub type StartSampleFn<S> = Arc<dyn Fn(&mut dyn RngCore) -> S + Send + Sync>;
+
+/// Draws start states from a continuous distribution, e.g. to model uncertainty in the initial
+/// configuration of the system.
+///
+/// The tree-based planners (`RRT`, `RRTConnect`, `RRTStar` and `KPIECE`) add every valid sampled
+/// state as an additional root of their (start) tree. `PRM` and `FMTStar` ignore the sampler.
+#[derive(Clone)]
+pub struct StartSampler<S: State> {
+    /// Draws a single start state.
+    pub sample: StartSampleFn<S>,
+    /// The number of start states planners draw when seeding their trees.
+    pub num_samples: usize,
+}
+
 /// Encapsulates the definition of a complete motion planning problem.
 ///
 /// This struct brings together all the necessary components that define a planning problem: the
@@ -13,8 +37,121 @@ use std::sync::Arc;
 ///
 /// The fields use `Arc` to allow for safe, shared ownership of the space and goal definitions,
 /// which might be referenced by multiple parts of the planning process.
+///
+/// Use `ProblemDefinition::new` to construct one, and `with_start_sampler` to add a start sampler.
 pub struct ProblemDefinition<S: State, SP: StateSpace<StateType = S>, G: Goal<S>> {
     pub space: Arc<SP>,
     pub start_states: Vec<S>,
     pub goal: Arc<G>,
+    /// An optional distribution of start states, used in addition to `start_states`.
+    pub start_sampler: Option<StartSampler<S>>,
+}
+
+impl<S, SP, G> ProblemDefinition<S, SP, G>
+where
+    S: State,
+    SP: StateSpace<StateType = S>,
+    G: Goal<S>,
+{
+    /// Creates a problem definition with the given space, start states and goal, and no start
+    /// sampler.
+    pub fn new(space: Arc<SP>, start_states: Vec<S>, goal: Arc<G>) -> Self {
+        Self {
+            space,
+            start_states,
+            goal,
+            start_sampler: None,
+        }
+    }
+
+    /// Sets the distribution that planners draw additional start states from.
+    pub fn with_start_sampler(mut self, start_sampler: StartSampler<S>) -> Self {
+        self.start_sampler = Some(start_sampler);
+        self
+    }
+
+    /// Draws `num_samples` start states from the `start_sampler`, or none if there is no sampler.
+    ///
+    /// Sampled states are not checked for validity; that is left to the planner.
+    pub fn sample_start_states(&self, rng: &mut impl Rng) -> Vec<S> {
+        match &self.start_sampler {
+            Some(sampler) => (0..sampler.num_samples)
+                .map(|_| (sampler.sample)(rng))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Checks that the problem is well-formed before planning, to fail fast with a clear error.
+    ///
+    /// Every start state must be compatible with the space (e.g. have the right dimension), lie
+    /// within its bounds and be valid according to `checker`. The tree planners make the same
+    /// checks when they start planning, but this reports which start is at fault and why.
+    ///
+    /// # Errors
+    ///
+    /// * `ProblemError::NoStartStates` if there are no start states.
+    /// * `ProblemError::IncompatibleStart`, `ProblemError::OutOfBounds` or
+    ///   `ProblemError::InvalidStart` with the index of the first start state that is incompatible
+    ///   with the space, out of its bounds or invalid.
+    pub fn validate(&self, checker: &dyn StateValidityChecker<S>) -> Result<(), ProblemError> {
+        if self.start_states.is_empty() {
+            return Err(ProblemError::NoStartStates);
+        }
+        for (index, start) in self.start_states.iter().enumerate() {
+            if !self.space.is_compatible(start) {
+                return Err(ProblemError::IncompatibleStart { index });
+            }
+            if !self.space.satisfies_bounds(start) {
+                return Err(ProblemError::OutOfBounds { index });
+            }
+            if !checker.is_valid(start) {
+                return Err(ProblemError::InvalidStart { index });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S, SP, G> ProblemDefinition<S, SP, G>
+where
+    S: State,
+    SP: StateSpace<StateType = S>,
+    G: Goal<S> + GoalSampleableRegion<S>,
+{
+    /// Probes the goal region for a valid state, as a cheap check that it is reachable at all.
+    ///
+    /// Draws goal samples, brought within the bounds of the space, until one is valid according to
+    /// `checker`, giving up after a fixed number of attempts. A failed probe does not prove the
+    /// goal unreachable, but usually means it is inside an obstacle or outside the space.
+    ///
+    /// # Errors
+    ///
+    /// * `PlanningError::SamplingFailed` if the goal region cannot be sampled.
+    /// * `PlanningError::InvalidProblemDefinition` if a goal sample is incompatible with the space,
+    ///   or no valid goal sample was found.
+    pub fn probe_goal(
+        &self,
+        checker: &dyn StateValidityChecker<S>,
+        rng: &mut impl Rng,
+    ) -> Result<(), PlanningError> {
+        for _ in 0..MAX_SAMPLING_ATTEMPTS {
+            let mut sample = self
+                .goal
+                .sample_goal(rng)
+                .map_err(PlanningError::SamplingFailed)?;
+            if !self.space.is_compatible(&sample) {
+                return Err(PlanningError::InvalidProblemDefinition(
+                    "goal samples do not match the dimension of the space".to_string(),
+                ));
+            }
+            self.space.enforce_bounds(&mut sample);
+            if checker.is_valid(&sample) {
+                return Ok(());
+            }
+        }
+        Err(PlanningError::InvalidProblemDefinition(format!(
+            "no valid goal state was found in {MAX_SAMPLING_ATTEMPTS} samples"
+        )))
+    }
 }
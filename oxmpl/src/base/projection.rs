@@ -0,0 +1,47 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use crate::base::state::{RealVectorState, State};
+
+/// Maps states to a low-dimensional Euclidean space, in which planners such as `KPIECE` track how
+/// well the state space has been covered.
+///
+/// A good projection captures the coordinates that matter for getting to the goal, e.g. the
+/// position of a robot's base while ignoring the angles of its arm. Every state must project to a
+/// vector of the same length.
+pub trait ProjectionEvaluator<S: State> {
+    /// Returns the projection of `state`.
+    fn project(&self, state: &S) -> Vec<f64>;
+}
+
+/// Projects a `RealVectorState` onto its first `k` coordinates.
+///
+/// # Examples
+///
+/// ```
+/// use oxmpl::base::projection::{ProjectionEvaluator, RealVectorProjection};
+/// use oxmpl::base::state::RealVectorState;
+///
+/// let projection = RealVectorProjection::new(2);
+/// let state = RealVectorState::new(vec![1.0, 2.0, 3.0]);
+/// assert_eq!(projection.project(&state), vec![1.0, 2.0]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RealVectorProjection {
+    dimension: usize,
+}
+
+impl RealVectorProjection {
+    /// Creates a projection onto the first `k` coordinates.
+    pub fn new(k: usize) -> Self {
+        Self { dimension: k }
+    }
+}
+
+impl ProjectionEvaluator<RealVectorState> for RealVectorProjection {
+    /// Returns the first `k` coordinates, or all of them if the state has fewer.
+    fn project(&self, state: &RealVectorState) -> Vec<f64> {
+        state.values.iter().take(self.dimension).copied().collect()
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use rand::{Rng, RngCore};
+
+use crate::base::{
+    error::StateSamplingError,
+    space::{RealVectorStateSpace, StateSpace},
+    state::{RealVectorState, State},
+    validity::StateValidityChecker,
+};
+
+/// Draws states from a distribution over a state space, e.g. one that concentrates samples where
+/// planning is hard instead of sampling the space uniformly.
+pub trait StateSampler<S: State> {
+    /// Draws a single state.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `StateSamplingError` if no state could be drawn.
+    fn sample(&self, rng: &mut dyn RngCore) -> Result<S, StateSamplingError>;
+}
+
+/// The number of bridges a `BridgeTestSampler` tries before giving up, unless set otherwise.
+pub const DEFAULT_BRIDGE_ATTEMPTS: u32 = 1000;
+
+/// Samples narrow passages with the bridge test.
+///
+/// Each attempt draws a uniform state and a second state `bridge_length` away from it, towards
+/// another uniform state. If both ends of this "bridge" are invalid but its midpoint is valid, the
+/// midpoint is returned. Such midpoints mostly lie in gaps between obstacles that are narrower
+/// than the bridge, which uniform sampling rarely hits.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use oxmpl::base::sampler::{BridgeTestSampler, StateSampler};
+/// use oxmpl::base::space::RealVectorStateSpace;
+/// use oxmpl::base::state::RealVectorState;
+/// use oxmpl::base::validity::StateValidityChecker;
+/// use rand::SeedableRng;
+///
+/// // Two walls, leaving a corridor between y = 4.8 and y = 5.2.
+/// struct Corridor;
+///
+/// impl StateValidityChecker<RealVectorState> for Corridor {
+///     fn is_valid(&self, state: &RealVectorState) -> bool {
+///         (4.8..=5.2).contains(&state.values[1])
+///     }
+/// }
+///
+/// let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0); 2])).unwrap());
+/// let sampler = BridgeTestSampler::new(space, Arc::new(Corridor), 1.0);
+///
+/// let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+/// let state = sampler.sample(&mut rng).unwrap();
+/// assert!((4.8..=5.2).contains(&state.values[1]));
+/// ```
+pub struct BridgeTestSampler<SP: StateSpace> {
+    /// The distance between the ends of each bridge. Passages narrower than this are sampled.
+    pub bridge_length: f64,
+    /// The number of bridges tried before `sample` gives up.
+    pub max_attempts: u32,
+    space: Arc<SP>,
+    checker: Arc<dyn StateValidityChecker<SP::StateType>>,
+}
+
+impl<SP: StateSpace> BridgeTestSampler<SP> {
+    /// Creates a sampler for narrow passages of `space` under `checker`, using bridges of length
+    /// `bridge_length` and trying up to `DEFAULT_BRIDGE_ATTEMPTS` of them per sample.
+    pub fn new(
+        space: Arc<SP>,
+        checker: Arc<dyn StateValidityChecker<SP::StateType>>,
+        bridge_length: f64,
+    ) -> Self {
+        Self {
+            bridge_length,
+            max_attempts: DEFAULT_BRIDGE_ATTEMPTS,
+            space,
+            checker,
+        }
+    }
+
+    /// Returns the midpoint of a bridge with invalid ends and a valid midpoint, or `None` if this
+    /// bridge is not one.
+    fn try_bridge(&self, rng: &mut impl Rng) -> Result<Option<SP::StateType>, StateSamplingError>
+    where
+        SP::StateType: Clone,
+    {
+        let start = self.space.sample_uniform(rng)?;
+        if self.checker.is_valid(&start) {
+            return Ok(None);
+        }
+
+        let towards = self.space.sample_uniform(rng)?;
+        let distance = self.space.distance(&start, &towards);
+        if distance <= 0.0 {
+            return Ok(None);
+        }
+        let mut end = start.clone();
+        let t = (self.bridge_length / distance).min(1.0);
+        self.space.interpolate(&start, &towards, t, &mut end);
+        if self.checker.is_valid(&end) {
+            return Ok(None);
+        }
+
+        let mut midpoint = start.clone();
+        self.space.interpolate(&start, &end, 0.5, &mut midpoint);
+        Ok(self.checker.is_valid(&midpoint).then_some(midpoint))
+    }
+}
+
+impl<SP> StateSampler<SP::StateType> for BridgeTestSampler<SP>
+where
+    SP: StateSpace,
+    SP::StateType: Clone,
+{
+    /// Draws the midpoint of a bridge with invalid ends and a valid midpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StateSamplingError::GoalSamplingTimeout` if none of `max_attempts` bridges
+    /// qualified, and passes on errors from sampling the space uniformly.
+    fn sample(&self, mut rng: &mut dyn RngCore) -> Result<SP::StateType, StateSamplingError> {
+        for _ in 0..self.max_attempts {
+            if let Some(midpoint) = self.try_bridge(&mut rng)? {
+                return Ok(midpoint);
+            }
+        }
+        Err(StateSamplingError::GoalSamplingTimeout {
+            attempts: self.max_attempts,
+        })
+    }
+}
+
+/// Draws the points of a Halton sequence, a low-discrepancy sequence that covers a bounded
+/// `RealVectorStateSpace` more evenly than uniform random samples.
+///
+/// Each dimension takes the radical inverse of the point's index in its own prime base (2, 3, 5,
+/// ...), scaled to the dimension's bounds. The sampler keeps the index of the next point, so
+/// successive calls fill in the gaps left by earlier ones. The sequence ignores the `rng`, so
+/// planners sampling with it build the same roadmap from every fresh sampler, whatever their
+/// seed.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use oxmpl::base::sampler::{HaltonSampler, StateSampler};
+/// use oxmpl::base::space::RealVectorStateSpace;
+/// use rand::SeedableRng;
+///
+/// let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 8.0), (0.0, 9.0)])).unwrap());
+/// let sampler = HaltonSampler::new(space);
+/// let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+///
+/// // The first points are (1/2, 1/3), (1/4, 2/3) and (3/4, 1/9) of the way along each axis.
+/// assert_eq!(sampler.sample(&mut rng).unwrap().values, vec![4.0, 3.0]);
+/// assert_eq!(sampler.sample(&mut rng).unwrap().values, vec![2.0, 6.0]);
+/// assert_eq!(sampler.sample(&mut rng).unwrap().values, vec![6.0, 1.0]);
+/// ```
+pub struct HaltonSampler {
+    space: Arc<RealVectorStateSpace>,
+    bases: Vec<u64>,
+    next_index: AtomicU64,
+}
+
+impl HaltonSampler {
+    /// Creates a sampler for the Halton sequence over `space`, starting from its first point.
+    pub fn new(space: Arc<RealVectorStateSpace>) -> Self {
+        let bases = first_primes(space.dimension);
+        Self {
+            space,
+            bases,
+            // Index 0 maps to the lower corner of the space, so the sequence starts at 1.
+            next_index: AtomicU64::new(1),
+        }
+    }
+}
+
+impl StateSampler<RealVectorState> for HaltonSampler {
+    /// Returns the next point of the sequence.
+    ///
+    /// # Errors
+    ///
+    /// Like `RealVectorStateSpace::sample_uniform`:
+    /// * `StateSamplingError::UnboundedDimension` if any dimension of the space is infinite.
+    /// * `StateSamplingError::ZeroVolume` if any dimension has a lower bound greater than or equal
+    ///   to its upper bound.
+    fn sample(&self, _rng: &mut dyn RngCore) -> Result<RealVectorState, StateSamplingError> {
+        for (i, &(lower, upper)) in self.space.bounds.iter().enumerate() {
+            if !lower.is_finite() || !upper.is_finite() {
+                return Err(StateSamplingError::UnboundedDimension { dimension_index: i });
+            }
+            if lower >= upper {
+                return Err(StateSamplingError::ZeroVolume);
+            }
+        }
+
+        let index = self.next_index.fetch_add(1, Ordering::Relaxed);
+        let values = self
+            .space
+            .bounds
+            .iter()
+            .zip(&self.bases)
+            .map(|(&(lower, upper), &base)| lower + radical_inverse(index, base) * (upper - lower))
+            .collect();
+        Ok(RealVectorState { values })
+    }
+}
+
+/// Returns the digits of `index` in `base`, mirrored about the radix point.
+fn radical_inverse(mut index: u64, base: u64) -> f64 {
+    let mut result = 0.0;
+    let mut scale = 1.0 / base as f64;
+    while index > 0 {
+        result += (index % base) as f64 * scale;
+        index /= base;
+        scale /= base as f64;
+    }
+    result
+}
+
+/// Returns the first `n` prime numbers.
+fn first_primes(n: usize) -> Vec<u64> {
+    let mut primes: Vec<u64> = Vec::with_capacity(n);
+    let mut candidate = 2;
+    while primes.len() < n {
+        if primes.iter().all(|p| candidate % p != 0) {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
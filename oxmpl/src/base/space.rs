@@ -5,10 +5,30 @@
 use rand::Rng;
 
 pub use crate::base::spaces::{
-    real_vector_state_space::RealVectorStateSpace, so2_state_space::SO2StateSpace,
+    compound_state_space::{CompoundStateSpace, DynStateSpace},
+    dubins_state_space::{DubinsPath, DubinsStateSpace, DubinsWord},
+    grid_state_space::{Connectivity, GridStateSpace},
+    real_vector_state_space::RealVectorStateSpace,
+    se3_state_space::SE3StateSpace,
+    so2_state_space::SO2StateSpace,
     so3_state_space::SO3StateSpace,
+    time_state_space::TimeStateSpace,
 };
-use crate::base::{error::StateSamplingError, state::State};
+use crate::base::{
+    error::{StateSamplingError, StateSpaceError},
+    state::State,
+};
+
+/// Starting values for the tuning parameters of the tree-based planners in a given space.
+///
+/// See `StateSpace::suggested_planner_params`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SuggestedParams {
+    /// The maximum length of a single branch in the tree (the "step size").
+    pub max_distance: f64,
+    /// The probability of sampling the goal region instead of the whole space.
+    pub goal_bias: f64,
+}
 
 /// Defines a space in which planning can be performed.
 ///
@@ -66,6 +86,10 @@ use crate::base::{error::StateSamplingError, state::State};
 ///     fn get_longest_valid_segment_length(&self) -> f64 {
 ///         (self.bounds.1 - self.bounds.0) * 0.05
 ///     }
+///
+///     fn get_maximum_extent(&self) -> f64 {
+///         self.bounds.1 - self.bounds.0
+///     }
 /// }
 ///
 /// let space = LineSegmentSpace { bounds: (0.0, 10.0) };
@@ -125,6 +149,16 @@ pub trait StateSpace {
     /// Returns `true` if the state is within bounds, `false` otherwise.
     fn satisfies_bounds(&self, state: &Self::StateType) -> bool;
 
+    /// Returns whether `state` has the shape this space expects, e.g. the right number of
+    /// dimensions.
+    ///
+    /// The other methods may panic when given an incompatible state, so this is the one to use to
+    /// check states from outside the planner. Defaults to `true`, for spaces whose state type
+    /// always fits.
+    fn is_compatible(&self, _state: &Self::StateType) -> bool {
+        true
+    }
+
     /// Generates a state uniformly at random from the entire state space.
     ///
     /// This method relies on the space having well-defined, finite bounds.
@@ -137,9 +171,210 @@ pub trait StateSpace {
     /// in any dimension, as uniform sampling from an infinite domain is not possible.
     fn sample_uniform(&self, rng: &mut impl Rng) -> Result<Self::StateType, StateSamplingError>;
 
+    /// Samples a state uniformly from the informed set of a path from `start` to `goal`.
+    ///
+    /// The informed set holds the states `x` within the bounds of the space for which
+    /// `distance(start, x) + distance(x, goal) <= max_cost`. Only these states can lie on a path
+    /// from `start` to `goal` shorter than `max_cost`, so sampling them focuses an optimising
+    /// planner on the states that can still improve its solution.
+    ///
+    /// Returns `None` if the space does not support informed sampling, which is the default, or if
+    /// no sample within bounds could be drawn. Planners then fall back to `sample_uniform`.
+    fn sample_informed(
+        &self,
+        _start: &Self::StateType,
+        _goal: &Self::StateType,
+        _max_cost: f64,
+        _rng: &mut impl Rng,
+    ) -> Option<Self::StateType> {
+        None
+    }
+
+    /// Samples a state uniformly from the states within `distance` of `near`, brought within the
+    /// bounds of the space with `enforce_bounds`.
+    ///
+    /// Goals such as `GoalState` use this to sample the states close to a target. Returns `None`
+    /// if the space does not support it, which is the default, or if no sample could be drawn.
+    fn sample_uniform_near(
+        &self,
+        _near: &Self::StateType,
+        _distance: f64,
+        _rng: &mut impl Rng,
+    ) -> Option<Self::StateType> {
+        None
+    }
+
     /// Gets the length of the longest segment that can be assumed valid.
     ///
-    /// This is a heuristic used to determine the resolution for motion validation. A smaller value
-    /// means motions are checked more frequently.
+    /// This is a heuristic used to determine the resolution for motion validation: planners check
+    /// motions at steps of this length. A smaller value means motions are checked more frequently.
     fn get_longest_valid_segment_length(&self) -> f64;
+
+    /// Returns the largest distance between any two states in the space.
+    ///
+    /// This gives a scale for parameters that would otherwise depend on the units of the space,
+    /// such as motion-checking resolution or neighbourhood radii.
+    ///
+    /// The default returns `1.0`, the same fallback an unbounded `RealVectorStateSpace` uses, so
+    /// that spaces with no known extent still get usable parameters. Bounded spaces should
+    /// override it with their actual extent.
+    fn get_maximum_extent(&self) -> f64 {
+        1.0
+    }
+
+    /// Returns the number of segments a motion of length `dist` is split into when it is checked.
+    ///
+    /// Planners check motions at steps of `get_longest_valid_segment_length`, calling the
+    /// validity checker once per segment (and once in total for motions of at most one segment).
+    /// This lets the cost of motion checking be estimated before planning, e.g. when choosing a
+    /// `longest_valid_segment_fraction`.
+    fn segments_for_distance(&self, dist: f64) -> usize {
+        (dist / self.get_longest_valid_segment_length()).ceil() as usize
+    }
+
+    /// Suggests reasonable planner parameters for this space.
+    ///
+    /// By default, `max_distance` is a tenth of `get_maximum_extent` and `goal_bias` is 0.05.
+    /// These are a starting point for tuning; spaces with unusual scales may override this.
+    fn suggested_planner_params(&self) -> SuggestedParams {
+        SuggestedParams {
+            max_distance: 0.1 * self.get_maximum_extent(),
+            goal_bias: 0.05,
+        }
+    }
+
+    /// Returns `true` if `distance(a, b) == distance(b, a)` for all states in this space.
+    ///
+    /// Spaces with a direction-dependent metric (e.g., for non-holonomic systems) should override
+    /// this to return `false` so that `check_axioms` skips the symmetry check.
+    fn is_metric_symmetric(&self) -> bool {
+        true
+    }
+
+    /// Checks that `distance` and `interpolate` behave sensibly on the given states.
+    ///
+    /// This is a debugging aid for custom `StateSpace` implementations. For every pair of the
+    /// three states it verifies:
+    /// - non-negativity: `distance(a, b) >= 0`,
+    /// - identity: `distance(a, a) == 0`,
+    /// - symmetry: `distance(a, b) == distance(b, a)` (only if `is_metric_symmetric`),
+    /// - interpolation endpoints: `interpolate(a, b, 0) == a` and `interpolate(a, b, 1) == b`,
+    ///   where equality is measured with `distance`.
+    ///
+    /// Comparisons use a small tolerance relative to the distances involved. Planners run this
+    /// check on a few sampled states in `setup` when enabled with their `set_check_axioms` and
+    /// built with `debug_assertions`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StateSpaceError::AxiomViolation` naming the first axiom that does not hold.
+    fn check_axioms(
+        &self,
+        a: &Self::StateType,
+        b: &Self::StateType,
+        c: &Self::StateType,
+    ) -> Result<(), StateSpaceError> {
+        for (from, to) in [(a, b), (b, c), (a, c)] {
+            let dist = self.distance(from, to);
+            let tolerance = 1e-6 * (1.0 + dist.abs());
+
+            if dist.is_nan() || dist < 0.0 {
+                return Err(StateSpaceError::AxiomViolation {
+                    axiom: "non-negativity",
+                });
+            }
+            if self.distance(from, from).abs() > tolerance {
+                return Err(StateSpaceError::AxiomViolation { axiom: "identity" });
+            }
+            if self.is_metric_symmetric() && (dist - self.distance(to, from)).abs() > tolerance {
+                return Err(StateSpaceError::AxiomViolation { axiom: "symmetry" });
+            }
+
+            let mut interpolated = from.clone();
+            self.interpolate(from, to, 0.0, &mut interpolated);
+            if self.distance(&interpolated, from) > tolerance {
+                return Err(StateSpaceError::AxiomViolation {
+                    axiom: "interpolation start",
+                });
+            }
+            self.interpolate(from, to, 1.0, &mut interpolated);
+            if self.distance(&interpolated, to) > tolerance {
+                return Err(StateSpaceError::AxiomViolation {
+                    axiom: "interpolation end",
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs `StateSpace::check_axioms` on three states sampled uniformly with `rng`, panicking on a
+/// violation.
+///
+/// Called by planners in `setup` when enabled with their `set_check_axioms` and built with
+/// `debug_assertions`. Spaces that cannot be sampled uniformly (e.g., unbounded spaces) are
+/// skipped.
+pub(crate) fn debug_check_axioms<SP: StateSpace>(space: &SP, rng: &mut impl Rng) {
+    let (Ok(a), Ok(b), Ok(c)) = (
+        space.sample_uniform(rng),
+        space.sample_uniform(rng),
+        space.sample_uniform(rng),
+    ) else {
+        return;
+    };
+    if let Err(e) = space.check_axioms(&a, &b, &c) {
+        panic!("StateSpace failed its axiom check: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::state::RealVectorState;
+    use crate::test_fixtures::BrokenSpace;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn rv(x: f64) -> RealVectorState {
+        RealVectorState::new(vec![x])
+    }
+
+    #[test]
+    fn test_check_axioms_detects_broken_space() {
+        let result = BrokenSpace.check_axioms(&rv(0.0), &rv(0.5), &rv(1.0));
+        assert!(matches!(
+            result,
+            Err(StateSpaceError::AxiomViolation { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_axioms_passes_for_builtin_spaces() {
+        let rv_space = RealVectorStateSpace::new(1, Some(vec![(0.0, 1.0)])).unwrap();
+        assert!(rv_space.check_axioms(&rv(0.0), &rv(0.5), &rv(1.0)).is_ok());
+
+        let so2_space = SO2StateSpace::new(None).unwrap();
+        let (a, b, c) = (
+            crate::base::state::SO2State::new(3.0),
+            crate::base::state::SO2State::new(-3.0),
+            crate::base::state::SO2State::new(0.5),
+        );
+        assert!(so2_space.check_axioms(&a, &b, &c).is_ok());
+    }
+
+    #[test]
+    fn test_suggested_planner_params_for_unit_square() {
+        let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 1.0), (0.0, 1.0)])).unwrap();
+        let params = space.suggested_planner_params();
+
+        let diagonal = 2.0f64.sqrt();
+        assert!((params.max_distance - 0.1 * diagonal).abs() < 1e-9);
+        assert!(params.max_distance > 0.0 && params.max_distance < diagonal / 2.0);
+        assert!(params.goal_bias > 0.0 && params.goal_bias < 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "axiom check")]
+    fn test_debug_check_axioms_panics_for_broken_space() {
+        debug_check_axioms(&BrokenSpace, &mut StdRng::seed_from_u64(0));
+    }
 }
@@ -0,0 +1,449 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt;
+
+use rand::{Rng, RngCore};
+
+use crate::base::{
+    error::{StateSamplingError, StateSpaceError},
+    space::StateSpace,
+    state::{CompoundState, DynState},
+};
+
+/// An object-safe, type-erased counterpart of `StateSpace`, operating on `DynState`s.
+///
+/// `StateSpace` cannot be used as a trait object, since it has an associated `StateType` and a
+/// generic `sample_uniform`. `DynStateSpace` is implemented for every `Send + Sync` `StateSpace`
+/// whose states are `DynState`s, by downcasting its arguments to the space's `StateType`, so that
+/// spaces of different state types can be stored side by side as `Box<dyn DynStateSpace>`.
+///
+/// The methods are prefixed with `dyn_` so that they do not clash with those of `StateSpace`,
+/// which every `DynStateSpace` also implements.
+///
+/// # Panics
+///
+/// The methods panic if given a state that is not of the space's `StateType`.
+pub trait DynStateSpace: Send + Sync {
+    /// See `StateSpace::distance`.
+    fn dyn_distance(&self, state1: &dyn DynState, state2: &dyn DynState) -> f64;
+
+    /// See `StateSpace::interpolate`.
+    fn dyn_interpolate(
+        &self,
+        from: &dyn DynState,
+        to: &dyn DynState,
+        t: f64,
+        state: &mut dyn DynState,
+    );
+
+    /// See `StateSpace::enforce_bounds`.
+    fn dyn_enforce_bounds(&self, state: &mut dyn DynState);
+
+    /// See `StateSpace::satisfies_bounds`.
+    fn dyn_satisfies_bounds(&self, state: &dyn DynState) -> bool;
+
+    /// See `StateSpace::is_compatible`. Also returns `false` if `state` is not of the space's
+    /// `StateType`, rather than panicking.
+    fn dyn_is_compatible(&self, state: &dyn DynState) -> bool;
+
+    /// See `StateSpace::sample_uniform`.
+    fn dyn_sample_uniform(
+        &self,
+        rng: &mut dyn RngCore,
+    ) -> Result<Box<dyn DynState>, StateSamplingError>;
+
+    /// See `StateSpace::get_maximum_extent`.
+    fn dyn_get_maximum_extent(&self) -> f64;
+
+    /// See `StateSpace::is_metric_symmetric`.
+    fn dyn_is_metric_symmetric(&self) -> bool;
+}
+
+fn downcast<S: 'static>(state: &dyn DynState) -> &S {
+    state
+        .as_any()
+        .downcast_ref()
+        .expect("State does not match the type of its subspace.")
+}
+
+fn downcast_mut<S: 'static>(state: &mut dyn DynState) -> &mut S {
+    state
+        .as_any_mut()
+        .downcast_mut()
+        .expect("State does not match the type of its subspace.")
+}
+
+impl<SP> DynStateSpace for SP
+where
+    SP: StateSpace + Send + Sync,
+    SP::StateType: fmt::Debug + Send + Sync + 'static,
+{
+    fn dyn_distance(&self, state1: &dyn DynState, state2: &dyn DynState) -> f64 {
+        StateSpace::distance(self, downcast(state1), downcast(state2))
+    }
+
+    fn dyn_interpolate(
+        &self,
+        from: &dyn DynState,
+        to: &dyn DynState,
+        t: f64,
+        state: &mut dyn DynState,
+    ) {
+        StateSpace::interpolate(self, downcast(from), downcast(to), t, downcast_mut(state));
+    }
+
+    fn dyn_enforce_bounds(&self, state: &mut dyn DynState) {
+        StateSpace::enforce_bounds(self, downcast_mut(state));
+    }
+
+    fn dyn_satisfies_bounds(&self, state: &dyn DynState) -> bool {
+        StateSpace::satisfies_bounds(self, downcast(state))
+    }
+
+    fn dyn_is_compatible(&self, state: &dyn DynState) -> bool {
+        state
+            .as_any()
+            .downcast_ref()
+            .is_some_and(|state| StateSpace::is_compatible(self, state))
+    }
+
+    fn dyn_sample_uniform(
+        &self,
+        mut rng: &mut dyn RngCore,
+    ) -> Result<Box<dyn DynState>, StateSamplingError> {
+        let state = StateSpace::sample_uniform(self, &mut rng)?;
+        Ok(Box::new(state))
+    }
+
+    fn dyn_get_maximum_extent(&self) -> f64 {
+        StateSpace::get_maximum_extent(self)
+    }
+
+    fn dyn_is_metric_symmetric(&self) -> bool {
+        StateSpace::is_metric_symmetric(self)
+    }
+}
+
+/// A state space formed as the product of several subspaces, e.g. an arm's joint angles together
+/// with the pose of its mobile base.
+///
+/// The subspaces are stored as `Box<dyn DynStateSpace>`, so they may have different state types,
+/// and their states are the type-erased components of a `CompoundState`, in the same order.
+///
+/// Each subspace has a weight: `distance` is the weighted sum of the subspace distances, so the
+/// weights set how a unit of motion in one subspace trades off against another.
+///
+/// # Examples
+///
+/// ```
+/// use oxmpl::base::space::{CompoundStateSpace, RealVectorStateSpace, SO2StateSpace, StateSpace};
+/// use oxmpl::base::state::{CompoundState, RealVectorState, SO2State};
+///
+/// let space = CompoundStateSpace::new(
+///     vec![
+///         Box::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap()),
+///         Box::new(SO2StateSpace::new(None).unwrap()),
+///     ],
+///     vec![1.0, 0.5],
+/// )
+/// .unwrap();
+///
+/// let a = CompoundState::new(vec![
+///     Box::new(RealVectorState::new(vec![0.0, 0.0])),
+///     Box::new(SO2State::new(0.0)),
+/// ]);
+/// let b = CompoundState::new(vec![
+///     Box::new(RealVectorState::new(vec![3.0, 4.0])),
+///     Box::new(SO2State::new(1.0)),
+/// ]);
+/// assert!((space.distance(&a, &b) - 5.5).abs() < 1e-9);
+/// ```
+pub struct CompoundStateSpace {
+    subspaces: Vec<Box<dyn DynStateSpace>>,
+    weights: Vec<f64>,
+
+    longest_valid_segment_fraction: f64,
+}
+
+impl CompoundStateSpace {
+    /// Creates a new `CompoundStateSpace` from its subspaces and their weights.
+    ///
+    /// # Errors
+    ///
+    /// * `StateSpaceError::DimensionMismatch` if there is not exactly one weight per subspace.
+    /// * `StateSpaceError::InvalidWeight` if a weight is not positive and finite.
+    pub fn new(
+        subspaces: Vec<Box<dyn DynStateSpace>>,
+        weights: Vec<f64>,
+    ) -> Result<Self, StateSpaceError> {
+        if weights.len() != subspaces.len() {
+            return Err(StateSpaceError::DimensionMismatch {
+                expected: subspaces.len(),
+                found: weights.len(),
+            });
+        }
+        if let Some(&weight) = weights.iter().find(|w| !(w.is_finite() && **w > 0.0)) {
+            return Err(StateSpaceError::InvalidWeight { weight });
+        }
+
+        Ok(Self {
+            subspaces,
+            weights,
+            longest_valid_segment_fraction: 0.005,
+        })
+    }
+
+    /// Returns the subspaces, in the order of the components of their states.
+    pub fn subspaces(&self) -> &[Box<dyn DynStateSpace>] {
+        &self.subspaces
+    }
+
+    /// Returns the weight of each subspace.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Sets the fraction of the maximum extent used as the motion checking resolution.
+    ///
+    /// Motions are checked at steps of `fraction * get_maximum_extent()`, so a larger fraction
+    /// means fewer validity checks per motion. The fraction is clamped to `[0, 1]` and defaults to
+    /// 0.005.
+    ///
+    /// Earlier releases checked motions at a tenth of this step and defaulted the fraction to 0.05.
+    /// Divide a fraction written for those releases by 10 to keep its old resolution.
+    pub fn set_longest_valid_segment_fraction(&mut self, fraction: f64) {
+        if fraction > 0.0 && fraction <= 1.0 {
+            self.longest_valid_segment_fraction = fraction;
+        } else if fraction <= 0.0 {
+            self.longest_valid_segment_fraction = 0.;
+        } else {
+            self.longest_valid_segment_fraction = 1.;
+        }
+    }
+
+    fn check_components(&self, state: &CompoundState) {
+        assert_eq!(
+            state.components.len(),
+            self.subspaces.len(),
+            "CompoundState has a different number of components than the space has subspaces."
+        );
+    }
+}
+
+impl StateSpace for CompoundStateSpace {
+    type StateType = CompoundState;
+
+    /// Computes the weighted sum of the distances in each subspace.
+    fn distance(&self, state1: &Self::StateType, state2: &Self::StateType) -> f64 {
+        self.check_components(state1);
+        self.check_components(state2);
+        self.subspaces
+            .iter()
+            .zip(&self.weights)
+            .zip(state1.components.iter().zip(&state2.components))
+            .map(|((space, weight), (a, b))| weight * space.dyn_distance(&**a, &**b))
+            .sum()
+    }
+
+    /// Interpolates each component independently in its own subspace.
+    fn interpolate(
+        &self,
+        from: &Self::StateType,
+        to: &Self::StateType,
+        t: f64,
+        state: &mut Self::StateType,
+    ) {
+        self.check_components(from);
+        self.check_components(to);
+        self.check_components(state);
+        for (i, space) in self.subspaces.iter().enumerate() {
+            space.dyn_interpolate(
+                &*from.components[i],
+                &*to.components[i],
+                t,
+                &mut *state.components[i],
+            );
+        }
+    }
+
+    /// Enforces the bounds of each subspace on its component.
+    fn enforce_bounds(&self, state: &mut Self::StateType) {
+        self.check_components(state);
+        for (space, component) in self.subspaces.iter().zip(&mut state.components) {
+            space.dyn_enforce_bounds(&mut **component);
+        }
+    }
+
+    /// Checks that every component is within the bounds of its subspace.
+    fn satisfies_bounds(&self, state: &Self::StateType) -> bool {
+        self.check_components(state);
+        self.subspaces
+            .iter()
+            .zip(&state.components)
+            .all(|(space, component)| space.dyn_satisfies_bounds(&**component))
+    }
+
+    /// Checks that there is one component per subspace, and that each is compatible with its
+    /// subspace.
+    fn is_compatible(&self, state: &Self::StateType) -> bool {
+        state.components.len() == self.subspaces.len()
+            && self
+                .subspaces
+                .iter()
+                .zip(&state.components)
+                .all(|(space, component)| space.dyn_is_compatible(&**component))
+    }
+
+    /// Samples each subspace independently.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first subspace that fails to sample.
+    fn sample_uniform(&self, rng: &mut impl Rng) -> Result<Self::StateType, StateSamplingError> {
+        let components = self
+            .subspaces
+            .iter()
+            .map(|space| space.dyn_sample_uniform(rng))
+            .collect::<Result<_, _>>()?;
+        Ok(CompoundState::new(components))
+    }
+
+    fn get_longest_valid_segment_length(&self) -> f64 {
+        self.get_maximum_extent() * self.longest_valid_segment_fraction
+    }
+
+    /// Returns the weighted sum of the maximum extents of the subspaces.
+    fn get_maximum_extent(&self) -> f64 {
+        self.subspaces
+            .iter()
+            .zip(&self.weights)
+            .map(|(space, weight)| weight * space.dyn_get_maximum_extent())
+            .sum()
+    }
+
+    fn is_metric_symmetric(&self) -> bool {
+        self.subspaces
+            .iter()
+            .all(|space| space.dyn_is_metric_symmetric())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{
+        space::{RealVectorStateSpace, SO2StateSpace},
+        state::{RealVectorState, SO2State},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn plane_and_heading() -> CompoundStateSpace {
+        CompoundStateSpace::new(
+            vec![
+                Box::new(
+                    RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap(),
+                ),
+                Box::new(SO2StateSpace::new(None).unwrap()),
+            ],
+            vec![1.0, 2.0],
+        )
+        .unwrap()
+    }
+
+    fn pose(x: f64, y: f64, theta: f64) -> CompoundState {
+        CompoundState::new(vec![
+            Box::new(RealVectorState::new(vec![x, y])),
+            Box::new(SO2State::new(theta)),
+        ])
+    }
+
+    #[test]
+    fn test_new_validates_weights() {
+        let space =
+            || -> Vec<Box<dyn DynStateSpace>> { vec![Box::new(SO2StateSpace::new(None).unwrap())] };
+
+        assert_eq!(
+            CompoundStateSpace::new(space(), vec![]).err(),
+            Some(StateSpaceError::DimensionMismatch {
+                expected: 1,
+                found: 0
+            })
+        );
+        assert_eq!(
+            CompoundStateSpace::new(space(), vec![0.0]).err(),
+            Some(StateSpaceError::InvalidWeight { weight: 0.0 })
+        );
+    }
+
+    #[test]
+    fn test_distance_is_weighted_sum() {
+        let space = plane_and_heading();
+        let d = space.distance(&pose(0.0, 0.0, 0.0), &pose(3.0, 4.0, 0.5));
+        assert!((d - (5.0 + 2.0 * 0.5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_interpolate_each_component_independently() {
+        let space = plane_and_heading();
+        let from = pose(0.0, 0.0, 3.0);
+        let to = pose(2.0, 4.0, -3.0);
+
+        let mut out = from.clone();
+        space.interpolate(&from, &to, 0.5, &mut out);
+
+        assert_eq!(
+            out.component::<RealVectorState>(0).unwrap().values,
+            vec![1.0, 2.0]
+        );
+        // SO2 interpolates the short way round, across the +-PI seam.
+        let heading = out.component::<SO2State>(1).unwrap().value;
+        assert!((heading.abs() - std::f64::consts::PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sample_uniform_samples_every_subspace() {
+        let space = plane_and_heading();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..50 {
+            let state = space.sample_uniform(&mut rng).unwrap();
+            assert_eq!(state.components.len(), 2);
+            assert!(state.component::<RealVectorState>(0).is_some());
+            assert!(state.component::<SO2State>(1).is_some());
+            assert!(space.satisfies_bounds(&state));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the type of its subspace")]
+    fn test_mismatched_component_type_panics() {
+        let space = plane_and_heading();
+        let swapped = CompoundState::new(vec![
+            Box::new(SO2State::new(0.0)),
+            Box::new(RealVectorState::new(vec![0.0, 0.0])),
+        ]);
+        space.distance(&swapped, &pose(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_is_compatible_checks_component_types_and_dimensions() {
+        let space = plane_and_heading();
+        assert!(space.is_compatible(&pose(1.0, 2.0, 0.0)));
+
+        let swapped = CompoundState::new(vec![
+            Box::new(SO2State::new(0.0)),
+            Box::new(RealVectorState::new(vec![0.0, 0.0])),
+        ]);
+        let wrong_dimension = CompoundState::new(vec![
+            Box::new(RealVectorState::new(vec![0.0, 0.0, 0.0])),
+            Box::new(SO2State::new(0.0)),
+        ]);
+        let missing_component =
+            CompoundState::new(vec![Box::new(RealVectorState::new(vec![0.0, 0.0]))]);
+        assert!(!space.is_compatible(&swapped));
+        assert!(!space.is_compatible(&wrong_dimension));
+        assert!(!space.is_compatible(&missing_component));
+    }
+}
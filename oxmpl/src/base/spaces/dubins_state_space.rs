@@ -0,0 +1,550 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use rand::Rng;
+use std::f64::consts::{PI, TAU};
+
+use crate::base::{
+    error::{StateSamplingError, StateSpaceError},
+    space::{RealVectorStateSpace, StateSpace},
+    state::{RealVectorState, SE2State, SO2State},
+};
+
+/// Paths shorter than this, in units of the turning radius, between states with the same heading
+/// are treated as straight lines, so that the distance from a state to itself is 0.
+const DUBINS_EPS: f64 = 1e-6;
+
+/// The tolerance on the squared length of a straight segment, in units of the turning radius, so
+/// that paths whose turns are tangent are not lost to rounding errors.
+const P_SQUARED_EPS: f64 = DUBINS_EPS * DUBINS_EPS;
+
+/// The sequence of turns and straight lines making up a Dubins path: `L` is a left turn, `R` a
+/// right turn and `S` a straight line.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DubinsWord {
+    LSL,
+    RSR,
+    LSR,
+    RSL,
+    RLR,
+    LRL,
+}
+
+impl DubinsWord {
+    /// All six words, in the order they are tried when looking for the shortest path.
+    pub const ALL: [DubinsWord; 6] = [
+        DubinsWord::LSL,
+        DubinsWord::RSR,
+        DubinsWord::LSR,
+        DubinsWord::RSL,
+        DubinsWord::RLR,
+        DubinsWord::LRL,
+    ];
+
+    fn segments(self) -> [Segment; 3] {
+        use Segment::{Left, Right, Straight};
+        match self {
+            DubinsWord::LSL => [Left, Straight, Left],
+            DubinsWord::RSR => [Right, Straight, Right],
+            DubinsWord::LSR => [Left, Straight, Right],
+            DubinsWord::RSL => [Right, Straight, Left],
+            DubinsWord::RLR => [Right, Left, Right],
+            DubinsWord::LRL => [Left, Right, Left],
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Segment {
+    Left,
+    Straight,
+    Right,
+}
+
+/// A path made of three segments, each a turn at the turning radius or a straight line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DubinsPath {
+    /// The kind of each segment.
+    pub word: DubinsWord,
+    /// The arc length of each segment.
+    pub lengths: [f64; 3],
+}
+
+impl DubinsPath {
+    /// Returns the total arc length of the path.
+    pub fn length(&self) -> f64 {
+        self.lengths.iter().sum()
+    }
+}
+
+/// A state space for car-like robots that can only drive forwards, with a minimum turning radius.
+///
+/// States are `SE2State`s. The distance between two states is the length of the shortest Dubins
+/// path between them: the shortest path that starts and ends with the states' headings and never
+/// turns tighter than `turning_radius`. Such a path is always made of three segments, turns or
+/// straight lines, in one of the six orders of `DubinsWord`. `interpolate` follows the path, so
+/// planners only connect states with motions the robot can drive.
+///
+/// The distance is not symmetric, as driving from `a` to `b` is generally not the reverse of
+/// driving from `b` to `a`.
+///
+/// # Examples
+///
+/// ```
+/// use oxmpl::base::space::{DubinsStateSpace, StateSpace};
+/// use oxmpl::base::state::SE2State;
+/// use std::f64::consts::PI;
+///
+/// let space = DubinsStateSpace::new(Some(vec![(0.0, 10.0), (0.0, 10.0)]), 1.0).unwrap();
+///
+/// // A U-turn at the turning radius is half a circle.
+/// let from = SE2State::new(5.0, 5.0, 0.0);
+/// let to = SE2State::new(5.0, 7.0, PI);
+/// assert!((space.distance(&from, &to) - PI).abs() < 1e-9);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DubinsStateSpace {
+    /// The space of positions, bounded by a box.
+    pub translation: RealVectorStateSpace,
+
+    turning_radius: f64,
+    longest_valid_segment_fraction: f64,
+}
+
+impl DubinsStateSpace {
+    /// Creates a new `DubinsStateSpace`.
+    ///
+    /// # Arguments
+    ///
+    /// * `translation_bounds` - Optional `(min, max)` bounds for the x and y axes, interpreted as
+    ///   in `RealVectorStateSpace::new`. If `None`, positions are unbounded.
+    /// * `turning_radius` - The radius of the tightest turn the robot can make.
+    ///
+    /// # Errors
+    ///
+    /// * `StateSpaceError::DimensionMismatch` if `translation_bounds` does not have 2 entries.
+    /// * `StateSpaceError::InvalidBound` if a lower bound is not below its upper bound.
+    /// * `StateSpaceError::InvalidTurningRadius` if `turning_radius` is not positive and finite.
+    pub fn new(
+        translation_bounds: Option<Vec<(f64, f64)>>,
+        turning_radius: f64,
+    ) -> Result<Self, StateSpaceError> {
+        if !(turning_radius > 0.0 && turning_radius.is_finite()) {
+            return Err(StateSpaceError::InvalidTurningRadius {
+                radius: turning_radius,
+            });
+        }
+        Ok(Self {
+            translation: RealVectorStateSpace::new(2, translation_bounds)?,
+            turning_radius,
+            longest_valid_segment_fraction: 0.005,
+        })
+    }
+
+    /// Returns the radius of the tightest turn the robot can make.
+    pub fn turning_radius(&self) -> f64 {
+        self.turning_radius
+    }
+
+    /// Sets the fraction of the maximum extent used as the motion checking resolution.
+    ///
+    /// Motions are checked at steps of `fraction * get_maximum_extent()` along the Dubins path,
+    /// so a larger fraction means fewer validity checks per motion. The fraction is clamped to
+    /// `[0, 1]` and defaults to 0.005.
+    pub fn set_longest_valid_segment_fraction(&mut self, fraction: f64) {
+        if fraction > 0.0 && fraction <= 1.0 {
+            self.longest_valid_segment_fraction = fraction;
+        } else if fraction <= 0.0 {
+            self.longest_valid_segment_fraction = 0.;
+        } else {
+            self.longest_valid_segment_fraction = 1.;
+        }
+    }
+
+    /// Returns the shortest Dubins path from `from` to `to`.
+    pub fn dubins_path(&self, from: &SE2State, to: &SE2State) -> DubinsPath {
+        let dx = to.translation[0] - from.translation[0];
+        let dy = to.translation[1] - from.translation[1];
+        let d = dx.hypot(dy) / self.turning_radius;
+        let th = dy.atan2(dx);
+        let alpha = mod_two_pi(from.rotation.value - th);
+        let beta = mod_two_pi(to.rotation.value - th);
+
+        let normalised = if d < DUBINS_EPS && SO2State::new(alpha - beta).value.abs() < DUBINS_EPS {
+            (DubinsWord::LSL, [0.0, d, 0.0])
+        } else {
+            DubinsWord::ALL
+                .into_iter()
+                .filter_map(|word| normalised_lengths(word, d, alpha, beta).map(|l| (word, l)))
+                .min_by(|(_, a), (_, b)| a.iter().sum::<f64>().total_cmp(&b.iter().sum()))
+                // LSL or RSR is always feasible.
+                .expect("No Dubins path found")
+        };
+
+        DubinsPath {
+            word: normalised.0,
+            lengths: normalised.1.map(|length| length * self.turning_radius),
+        }
+    }
+
+    /// Returns the state reached after driving `arc_length` along `path` from `from`.
+    fn follow(&self, from: &SE2State, path: &DubinsPath, arc_length: f64) -> SE2State {
+        let r = self.turning_radius;
+        let [mut x, mut y] = from.translation;
+        let mut phi = from.rotation.value;
+        let mut remaining = arc_length;
+
+        for (segment, length) in path.word.segments().into_iter().zip(path.lengths) {
+            let v = remaining.min(length);
+            let angle = v / r;
+            match segment {
+                Segment::Left => {
+                    x += r * ((phi + angle).sin() - phi.sin());
+                    y += r * (phi.cos() - (phi + angle).cos());
+                    phi += angle;
+                }
+                Segment::Right => {
+                    x += r * (phi.sin() - (phi - angle).sin());
+                    y += r * ((phi - angle).cos() - phi.cos());
+                    phi -= angle;
+                }
+                Segment::Straight => {
+                    x += v * phi.cos();
+                    y += v * phi.sin();
+                }
+            }
+            remaining -= v;
+            if remaining <= 0.0 {
+                break;
+            }
+        }
+
+        SE2State::new(x, y, phi)
+    }
+}
+
+/// Wraps `angle` to `[0, 2 * PI)`, rounding values just below `2 * PI` down to 0 so that rounding
+/// errors do not add a full circle to a path.
+fn mod_two_pi(angle: f64) -> f64 {
+    let wrapped = angle.rem_euclid(TAU);
+    if TAU - wrapped < 0.5 * DUBINS_EPS {
+        0.0
+    } else {
+        wrapped
+    }
+}
+
+/// Returns the segment lengths, in units of the turning radius, of the path of kind `word`
+/// between states `d` turning radii apart, with headings `alpha` and `beta` relative to the line
+/// between them, or `None` if there is no such path.
+///
+/// The formulas are those of Shkel and Lumelsky, "Classification of the Dubins set" (2001).
+fn normalised_lengths(word: DubinsWord, d: f64, alpha: f64, beta: f64) -> Option<[f64; 3]> {
+    let (sa, ca) = alpha.sin_cos();
+    let (sb, cb) = beta.sin_cos();
+    let cos_ab = ca * cb + sa * sb;
+
+    match word {
+        // When both turns are about the same circle, the straight line has no length and no
+        // direction, so the path is a single turn to the final heading.
+        DubinsWord::LSL => {
+            let p_squared = 2.0 + d * d - 2.0 * (cos_ab - d * (sa - sb));
+            (p_squared >= -P_SQUARED_EPS).then(|| {
+                let theta = if p_squared < P_SQUARED_EPS {
+                    beta
+                } else {
+                    (cb - ca).atan2(d + sa - sb)
+                };
+                [
+                    mod_two_pi(theta - alpha),
+                    p_squared.max(0.0).sqrt(),
+                    mod_two_pi(beta - theta),
+                ]
+            })
+        }
+        DubinsWord::RSR => {
+            let p_squared = 2.0 + d * d - 2.0 * (cos_ab - d * (sb - sa));
+            (p_squared >= -P_SQUARED_EPS).then(|| {
+                let theta = if p_squared < P_SQUARED_EPS {
+                    beta
+                } else {
+                    (ca - cb).atan2(d - sa + sb)
+                };
+                [
+                    mod_two_pi(alpha - theta),
+                    p_squared.max(0.0).sqrt(),
+                    mod_two_pi(theta - beta),
+                ]
+            })
+        }
+        DubinsWord::LSR => {
+            let p_squared = d * d - 2.0 + 2.0 * (cos_ab + d * (sa + sb));
+            (p_squared >= -P_SQUARED_EPS).then(|| {
+                let p = p_squared.max(0.0).sqrt();
+                let theta = (-ca - cb).atan2(d + sa + sb) - (-2.0f64).atan2(p);
+                [mod_two_pi(theta - alpha), p, mod_two_pi(theta - beta)]
+            })
+        }
+        DubinsWord::RSL => {
+            let p_squared = d * d - 2.0 + 2.0 * (cos_ab - d * (sa + sb));
+            (p_squared >= -P_SQUARED_EPS).then(|| {
+                let p = p_squared.max(0.0).sqrt();
+                let theta = (ca + cb).atan2(d - sa - sb) - 2.0f64.atan2(p);
+                [mod_two_pi(alpha - theta), p, mod_two_pi(beta - theta)]
+            })
+        }
+        DubinsWord::RLR => {
+            let cos_p = 0.125 * (6.0 - d * d + 2.0 * (cos_ab + d * (sa - sb)));
+            (cos_p.abs() < 1.0).then(|| {
+                let p = TAU - cos_p.acos();
+                let theta = (ca - cb).atan2(d - sa + sb);
+                let t = mod_two_pi(alpha - theta + 0.5 * p);
+                [t, p, mod_two_pi(alpha - beta - t + p)]
+            })
+        }
+        DubinsWord::LRL => {
+            let cos_p = 0.125 * (6.0 - d * d + 2.0 * (cos_ab - d * (sa - sb)));
+            (cos_p.abs() < 1.0).then(|| {
+                let p = TAU - cos_p.acos();
+                let theta = (cb - ca).atan2(d + sa - sb);
+                let t = mod_two_pi(theta - alpha + 0.5 * p);
+                [t, p, mod_two_pi(beta - alpha - t + p)]
+            })
+        }
+    }
+}
+
+impl StateSpace for DubinsStateSpace {
+    type StateType = SE2State;
+
+    /// Computes the length of the shortest Dubins path from `state1` to `state2`.
+    fn distance(&self, state1: &Self::StateType, state2: &Self::StateType) -> f64 {
+        self.dubins_path(state1, state2).length()
+    }
+
+    /// Finds the state a fraction `t` of the way along the shortest Dubins path from `from` to
+    /// `to`.
+    ///
+    /// The path may leave the bounds of the space even when both ends are within them, so the
+    /// interpolated state is not clamped.
+    fn interpolate(
+        &self,
+        from: &Self::StateType,
+        to: &Self::StateType,
+        t: f64,
+        out_state: &mut Self::StateType,
+    ) {
+        if t <= 0.0 {
+            out_state.clone_from(from);
+        } else if t >= 1.0 {
+            out_state.clone_from(to);
+        } else {
+            let path = self.dubins_path(from, to);
+            *out_state = self.follow(from, &path, t * path.length());
+        }
+    }
+
+    /// Clamps the position to its box and normalises the heading.
+    fn enforce_bounds(&self, state: &mut Self::StateType) {
+        let mut translation = RealVectorState::new(state.translation.to_vec());
+        self.translation.enforce_bounds(&mut translation);
+        state.translation.copy_from_slice(&translation.values);
+
+        state.rotation.normalise();
+    }
+
+    /// Checks that the position is within its box. Every heading is allowed.
+    fn satisfies_bounds(&self, state: &Self::StateType) -> bool {
+        self.translation
+            .satisfies_bounds(&RealVectorState::new(state.translation.to_vec()))
+    }
+
+    /// Generates a uniformly random position within the bounds, with a uniformly random heading.
+    ///
+    /// # Errors
+    ///
+    /// * `StateSamplingError::UnboundedDimension` if either axis is unbounded.
+    fn sample_uniform(&self, rng: &mut impl Rng) -> Result<SE2State, StateSamplingError> {
+        let translation = self.translation.sample_uniform(rng)?;
+        Ok(SE2State::new(
+            translation.values[0],
+            translation.values[1],
+            rng.random_range(-PI..PI),
+        ))
+    }
+
+    /// Returns the arc length between the states checked along a motion.
+    fn get_longest_valid_segment_length(&self) -> f64 {
+        self.get_maximum_extent() * self.longest_valid_segment_fraction
+    }
+
+    /// Returns the diagonal of the position box plus two full circles at the turning radius, which
+    /// bounds the length of the shortest Dubins path between any two states.
+    fn get_maximum_extent(&self) -> f64 {
+        self.translation.get_maximum_extent() + 2.0 * TAU * self.turning_radius
+    }
+
+    fn is_metric_symmetric(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::f64::consts::FRAC_PI_2;
+
+    fn space(turning_radius: f64) -> DubinsStateSpace {
+        DubinsStateSpace::new(Some(vec![(-10.0, 10.0), (-10.0, 10.0)]), turning_radius).unwrap()
+    }
+
+    fn assert_same_pose(a: &SE2State, b: &SE2State) {
+        assert!(
+            (a.translation[0] - b.translation[0]).abs() < 1e-6
+                && (a.translation[1] - b.translation[1]).abs() < 1e-6
+                && SO2State::new(a.rotation.value - b.rotation.value)
+                    .value
+                    .abs()
+                    < 1e-6,
+            "{a:?} is not {b:?}"
+        );
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_turning_radius() {
+        for radius in [0.0, -1.0, f64::INFINITY, f64::NAN] {
+            assert!(matches!(
+                DubinsStateSpace::new(None, radius),
+                Err(StateSpaceError::InvalidTurningRadius { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_distance_matches_analytic_lengths() {
+        let origin = SE2State::new(0.0, 0.0, 0.0);
+        let cases = [
+            // Straight ahead.
+            (1.0, SE2State::new(5.0, 0.0, 0.0), 5.0),
+            // A quarter circle to the left.
+            (1.0, SE2State::new(1.0, 1.0, FRAC_PI_2), FRAC_PI_2),
+            // A quarter circle to the right.
+            (1.0, SE2State::new(1.0, -1.0, -FRAC_PI_2), FRAC_PI_2),
+            // A U-turn at twice the radius.
+            (2.0, SE2State::new(0.0, 4.0, PI), 2.0 * PI),
+            // Half a circle, then straight on.
+            (1.0, SE2State::new(-3.0, 2.0, PI), PI + 3.0),
+            // A quarter circle each way, with tangent circles so no straight line in between.
+            (1.0, SE2State::new(2.0, 2.0, 0.0), PI),
+            (1.0, SE2State::new(2.0, -2.0, 0.0), PI),
+        ];
+
+        for (radius, to, expected) in cases {
+            let distance = space(radius).distance(&origin, &to);
+            assert!(
+                (distance - expected).abs() < 1e-6,
+                "{to:?}: expected {expected}, found {distance}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_turning_around_in_place_takes_three_turns() {
+        let space = space(1.0);
+        let path = space.dubins_path(&SE2State::new(0.0, 0.0, 0.0), &SE2State::new(0.0, 0.0, PI));
+
+        // A sixth of a circle one way, five sixths the other and a sixth back.
+        assert!(matches!(path.word, DubinsWord::RLR | DubinsWord::LRL));
+        assert!((path.length() - 7.0 * PI / 3.0).abs() < 1e-6);
+        assert!((path.lengths[1] - 5.0 * PI / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_every_word_ends_at_the_target() {
+        let space = space(1.5);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut words_seen = Vec::new();
+
+        for _ in 0..500 {
+            let from = space.sample_uniform(&mut rng).unwrap();
+            let to = SE2State::new(
+                from.translation[0] + rng.random_range(-4.0..4.0),
+                from.translation[1] + rng.random_range(-4.0..4.0),
+                rng.random_range(-PI..PI),
+            );
+            let dx = to.translation[0] - from.translation[0];
+            let dy = to.translation[1] - from.translation[1];
+            let d = dx.hypot(dy) / space.turning_radius();
+            let th = dy.atan2(dx);
+            let alpha = mod_two_pi(from.rotation.value - th);
+            let beta = mod_two_pi(to.rotation.value - th);
+
+            for word in DubinsWord::ALL {
+                let Some(lengths) = normalised_lengths(word, d, alpha, beta) else {
+                    continue;
+                };
+                let path = DubinsPath {
+                    word,
+                    lengths: lengths.map(|length| length * space.turning_radius()),
+                };
+                assert_same_pose(&space.follow(&from, &path, path.length()), &to);
+                if !words_seen.contains(&word) {
+                    words_seen.push(word);
+                }
+            }
+
+            let shortest = space.distance(&from, &to);
+            assert!(shortest >= dx.hypot(dy) - 1e-9);
+        }
+        assert_eq!(words_seen.len(), 6);
+    }
+
+    #[test]
+    fn test_interpolate_follows_the_curve() {
+        let space = space(1.0);
+        let from = SE2State::new(0.0, 0.0, 0.0);
+        let to = SE2State::new(0.0, 2.0, PI);
+
+        let mut out = from.clone();
+        space.interpolate(&from, &to, 0.5, &mut out);
+        // Halfway round the U-turn, on the circle of radius 1 about (0, 1).
+        assert_same_pose(&out, &SE2State::new(1.0, 1.0, FRAC_PI_2));
+        assert!((space.distance(&from, &out) - FRAC_PI_2).abs() < 1e-6);
+        assert!((space.distance(&out, &to) - FRAC_PI_2).abs() < 1e-6);
+
+        space.interpolate(&from, &to, 1.0, &mut out);
+        assert_eq!(out, to);
+    }
+
+    #[test]
+    fn test_longest_valid_segment_length_scales_with_arc_length() {
+        let mut space = space(1.0);
+        let extent = 20.0 * 2.0_f64.sqrt() + 4.0 * PI;
+        assert!((space.get_maximum_extent() - extent).abs() < 1e-9);
+
+        space.set_longest_valid_segment_fraction(0.01);
+        assert!((space.get_longest_valid_segment_length() - 0.01 * extent).abs() < 1e-9);
+        // A U-turn is checked along the half circle, not the chord between its ends.
+        let u_turn = space.distance(&SE2State::new(0.0, 0.0, 0.0), &SE2State::new(0.0, 2.0, PI));
+        assert_eq!(
+            space.segments_for_distance(u_turn),
+            (PI / (0.01 * extent)).ceil() as usize
+        );
+    }
+
+    #[test]
+    fn test_axioms_hold_on_samples() {
+        let space = space(1.0);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..50 {
+            let a = space.sample_uniform(&mut rng).unwrap();
+            let b = space.sample_uniform(&mut rng).unwrap();
+            let c = space.sample_uniform(&mut rng).unwrap();
+            assert_eq!(space.check_axioms(&a, &b, &c), Ok(()));
+        }
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use rand::Rng;
+use std::f64::consts::SQRT_2;
+
+use crate::base::{error::StateSamplingError, space::StateSpace, state::GridState};
+
+/// The moves allowed between neighbouring cells of a `GridStateSpace`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Connectivity {
+    /// Moves to the 4 cells sharing an edge. Distances are Manhattan distances.
+    Four,
+    /// Moves to the 8 cells sharing an edge or a corner. Distances are octile distances, in which
+    /// a diagonal move costs `sqrt(2)`.
+    Eight,
+}
+
+/// A state space of the cells of a 2D grid, for classic grid and maze planning.
+///
+/// Motions between cells follow the grid: `interpolate` steps from cell to neighbouring cell, so
+/// that checking a motion visits every cell along it. This pairs with the `GridOccupancyChecker`,
+/// which also implements `StateValidityChecker<GridState>`.
+///
+/// # Examples
+///
+/// ```
+/// use oxmpl::base::space::{Connectivity, GridStateSpace, StateSpace};
+/// use oxmpl::base::state::GridState;
+///
+/// let space = GridStateSpace::new(10, 10, Connectivity::Eight);
+/// let from = GridState::new(0, 0);
+/// let to = GridState::new(3, 1);
+///
+/// // One diagonal move and two straight ones.
+/// assert!((space.distance(&from, &to) - (2.0 + 2.0_f64.sqrt())).abs() < 1e-9);
+///
+/// let mut halfway = from;
+/// space.interpolate(&from, &to, 0.5, &mut halfway);
+/// assert_eq!(halfway, GridState::new(2, 1));
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridStateSpace {
+    /// Number of rows in the grid.
+    pub rows: usize,
+    /// Number of columns in the grid.
+    pub cols: usize,
+    /// The moves allowed between neighbouring cells.
+    pub connectivity: Connectivity,
+}
+
+impl GridStateSpace {
+    /// Creates a new `GridStateSpace` of `rows` by `cols` cells.
+    ///
+    /// A grid with no rows or no columns has no cells, so sampling it fails with
+    /// `StateSamplingError::ZeroVolume`.
+    pub fn new(rows: usize, cols: usize, connectivity: Connectivity) -> Self {
+        Self {
+            rows,
+            cols,
+            connectivity,
+        }
+    }
+
+    /// Returns the number of single-cell moves on the way from `from` to `to`.
+    fn steps(&self, from: &GridState, to: &GridState) -> usize {
+        let d_row = from.row.abs_diff(to.row);
+        let d_col = from.col.abs_diff(to.col);
+        match self.connectivity {
+            Connectivity::Four => d_row + d_col,
+            Connectivity::Eight => d_row.max(d_col),
+        }
+    }
+}
+
+impl StateSpace for GridStateSpace {
+    type StateType = GridState;
+
+    /// Computes the length of the shortest grid path between two cells, ignoring obstacles: the
+    /// Manhattan distance with 4-connectivity and the octile distance with 8-connectivity.
+    fn distance(&self, state1: &Self::StateType, state2: &Self::StateType) -> f64 {
+        let d_row = state1.row.abs_diff(state2.row) as f64;
+        let d_col = state1.col.abs_diff(state2.col) as f64;
+        match self.connectivity {
+            Connectivity::Four => d_row + d_col,
+            Connectivity::Eight => d_row.max(d_col) + (SQRT_2 - 1.0) * d_row.min(d_col),
+        }
+    }
+
+    /// Finds the cell a fraction `t` of the single-cell moves along a grid path from `from` to
+    /// `to`.
+    ///
+    /// The path keeps as close as it can to the straight line between the cells, so consecutive
+    /// steps of `t` give neighbouring cells.
+    fn interpolate(
+        &self,
+        from: &Self::StateType,
+        to: &Self::StateType,
+        t: f64,
+        state: &mut Self::StateType,
+    ) {
+        let steps = self.steps(from, to);
+        if steps == 0 {
+            *state = *to;
+            return;
+        }
+        let step = (t.clamp(0.0, 1.0) * steps as f64).round();
+        let fraction = step / steps as f64;
+
+        let d_row = from.row.abs_diff(to.row) as f64;
+        let d_col = from.col.abs_diff(to.col) as f64;
+        let (row_moves, col_moves) = match self.connectivity {
+            Connectivity::Four => {
+                let row_moves = (fraction * d_row).round();
+                (row_moves, step - row_moves)
+            }
+            Connectivity::Eight => ((fraction * d_row).round(), (fraction * d_col).round()),
+        };
+
+        let advance = |from: usize, to: usize, moves: f64| {
+            if to >= from {
+                from + moves as usize
+            } else {
+                from - moves as usize
+            }
+        };
+        state.row = advance(from.row, to.row, row_moves);
+        state.col = advance(from.col, to.col, col_moves);
+    }
+
+    /// Modifies the state by clamping its row and column to the grid.
+    fn enforce_bounds(&self, state: &mut Self::StateType) {
+        state.row = state.row.min(self.rows.saturating_sub(1));
+        state.col = state.col.min(self.cols.saturating_sub(1));
+    }
+
+    /// Checks if a state is a cell of the grid.
+    fn satisfies_bounds(&self, state: &Self::StateType) -> bool {
+        state.row < self.rows && state.col < self.cols
+    }
+
+    /// Picks a cell of the grid uniformly at random.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StateSamplingError::ZeroVolume` if the grid has no cells.
+    fn sample_uniform(&self, rng: &mut impl Rng) -> Result<Self::StateType, StateSamplingError> {
+        if self.rows == 0 || self.cols == 0 {
+            return Err(StateSamplingError::ZeroVolume);
+        }
+        Ok(GridState {
+            row: rng.random_range(0..self.rows),
+            col: rng.random_range(0..self.cols),
+        })
+    }
+
+    /// Returns the length of a single straight move, since motions are checked cell by cell.
+    fn get_longest_valid_segment_length(&self) -> f64 {
+        1.0
+    }
+
+    /// Returns the distance between opposite corners of the grid.
+    fn get_maximum_extent(&self) -> f64 {
+        self.distance(
+            &GridState::new(0, 0),
+            &GridState::new(self.rows.saturating_sub(1), self.cols.saturating_sub(1)),
+        )
+    }
+
+    /// Returns one segment per unit of distance, which is at least one per single-cell move, so
+    /// that every cell along a motion is checked.
+    fn segments_for_distance(&self, dist: f64) -> usize {
+        dist.ceil() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_grid_distance_by_connectivity() {
+        let from = GridState::new(1, 2);
+        let to = GridState::new(4, 7);
+
+        let four = GridStateSpace::new(10, 10, Connectivity::Four);
+        assert_eq!(four.distance(&from, &to), 8.0);
+
+        // Three diagonal moves and two straight ones.
+        let eight = GridStateSpace::new(10, 10, Connectivity::Eight);
+        assert!((eight.distance(&from, &to) - (3.0 * SQRT_2 + 2.0)).abs() < 1e-12);
+        assert!((eight.distance(&to, &from) - eight.distance(&from, &to)).abs() < 1e-12);
+        assert_eq!(eight.distance(&from, &from), 0.0);
+    }
+
+    #[test]
+    fn test_grid_sample_uniform_is_within_bounds() {
+        let space = GridStateSpace::new(3, 7, Connectivity::Four);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        for _ in 0..200 {
+            let state = space.sample_uniform(&mut rng).unwrap();
+            assert!(space.satisfies_bounds(&state));
+        }
+
+        let empty = GridStateSpace::new(0, 7, Connectivity::Four);
+        assert_eq!(
+            empty.sample_uniform(&mut rng),
+            Err(StateSamplingError::ZeroVolume)
+        );
+    }
+
+    #[test]
+    fn test_grid_interpolate_steps_between_neighbouring_cells() {
+        let from = GridState::new(5, 1);
+        let to = GridState::new(2, 6);
+
+        for (connectivity, max_step) in [(Connectivity::Four, 1.0), (Connectivity::Eight, SQRT_2)] {
+            let space = GridStateSpace::new(10, 10, connectivity);
+            let segments = space.segments_for_distance(space.distance(&from, &to));
+
+            let mut previous = from;
+            let mut state = from;
+            for i in 0..=segments {
+                space.interpolate(&from, &to, i as f64 / segments as f64, &mut state);
+                assert!(space.satisfies_bounds(&state));
+                assert!(space.distance(&previous, &state) <= max_step + 1e-12);
+                previous = state;
+            }
+            assert_eq!(state, to);
+        }
+    }
+
+    #[test]
+    fn test_grid_enforce_bounds_clamps_to_last_cell() {
+        let space = GridStateSpace::new(4, 6, Connectivity::Eight);
+        let mut state = GridState::new(9, 2);
+        space.enforce_bounds(&mut state);
+        assert_eq!(state, GridState::new(3, 2));
+        assert!(space.satisfies_bounds(&state));
+    }
+}
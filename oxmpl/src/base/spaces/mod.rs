@@ -2,6 +2,11 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
+pub mod compound_state_space;
+pub mod dubins_state_space;
+pub mod grid_state_space;
 pub mod real_vector_state_space;
+pub mod se3_state_space;
 pub mod so2_state_space;
 pub mod so3_state_space;
+pub mod time_state_space;
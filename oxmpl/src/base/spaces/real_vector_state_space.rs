@@ -6,6 +6,7 @@ use rand::Rng;
 
 use crate::base::{
     error::{StateSamplingError, StateSpaceError},
+    planner::MAX_SAMPLING_ATTEMPTS,
     space::StateSpace,
     state::RealVectorState,
 };
@@ -14,7 +15,11 @@ use crate::base::{
 ///
 /// Most common state space for systems whose configuration can be described by a vector of real
 /// numbers. Supports both bounded (hyper-rectangle) and unbounded configurations.
+///
+/// Distances use the Euclidean (L2) norm by default. For dimensions with very different scales,
+/// `set_weights` and `set_norm_order` switch to a weighted L-p norm.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RealVectorStateSpace {
     /// n-Dimensionality of VectorStateSpace i.e. R^n.
     pub dimension: usize,
@@ -23,6 +28,8 @@ pub struct RealVectorStateSpace {
     pub bounds: Vec<(f64, f64)>,
 
     longest_valid_segment_fraction: f64,
+    weights: Vec<f64>,
+    norm_order: f64,
 }
 
 impl RealVectorStateSpace {
@@ -95,29 +102,90 @@ impl RealVectorStateSpace {
         Ok(Self {
             dimension,
             bounds,
-            longest_valid_segment_fraction: 0.05,
+            longest_valid_segment_fraction: 0.005,
+            weights: vec![1.0; dimension],
+            norm_order: 2.0,
         })
     }
 
-    /// A helper to calculate the diagonal of the space's bounding box.
-    pub fn get_maximum_extent(&self) -> f64 {
-        if self
-            .bounds
-            .iter()
-            .any(|(low, high)| !low.is_finite() || !high.is_finite())
-        {
-            1.0
+    /// Sets the weight of each dimension in `distance`, e.g. to make a metre and a millimetre
+    /// count alike. All weights are 1 by default.
+    ///
+    /// With norm order `p`, a weight `w` scales differences along its dimension by `w^(1/p)`. The
+    /// weights therefore have no effect on the Chebyshev distance, the limit as `p` grows.
+    ///
+    /// # Errors
+    ///
+    /// * `StateSpaceError::DimensionMismatch` if there is not one weight per dimension.
+    /// * `StateSpaceError::InvalidWeight` if a weight is not positive and finite.
+    pub fn set_weights(&mut self, weights: Vec<f64>) -> Result<(), StateSpaceError> {
+        if weights.len() != self.dimension {
+            return Err(StateSpaceError::DimensionMismatch {
+                expected: self.dimension,
+                found: weights.len(),
+            });
+        }
+        if let Some(&weight) = weights.iter().find(|w| !(w.is_finite() && **w > 0.0)) {
+            return Err(StateSpaceError::InvalidWeight { weight });
+        }
+        self.weights = weights;
+        Ok(())
+    }
+
+    /// Returns the weight of each dimension.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Sets the order `p` of the norm used by `distance`: 1 for the Manhattan distance, 2 (the
+    /// default) for the Euclidean distance and `f64::INFINITY` for the Chebyshev distance.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StateSpaceError::InvalidNormOrder` if `p` is below 1 or NaN.
+    pub fn set_norm_order(&mut self, p: f64) -> Result<(), StateSpaceError> {
+        if p.is_nan() || p < 1.0 {
+            return Err(StateSpaceError::InvalidNormOrder { p });
+        }
+        self.norm_order = p;
+        Ok(())
+    }
+
+    /// Returns the order `p` of the norm used by `distance`.
+    pub fn norm_order(&self) -> f64 {
+        self.norm_order
+    }
+
+    /// Returns `true` if `distance` is the unweighted Euclidean distance.
+    fn is_euclidean(&self) -> bool {
+        self.norm_order == 2.0 && self.weights.iter().all(|&w| w == 1.0)
+    }
+
+    /// Combines per-dimension differences with the weighted L-p norm.
+    fn weighted_norm(&self, diffs: impl Iterator<Item = f64>) -> f64 {
+        let p = self.norm_order;
+        if p == f64::INFINITY {
+            return diffs.map(f64::abs).fold(0.0, f64::max);
+        }
+        let weighted = diffs.zip(&self.weights);
+        if p == 2.0 {
+            weighted.map(|(d, w)| w * d * d).sum::<f64>().sqrt()
         } else {
-            let sum_sq_diff: f64 = self
-                .bounds
-                .iter()
-                .map(|(low, high)| (high - low).powi(2))
-                .sum();
-            sum_sq_diff.sqrt()
+            weighted
+                .map(|(d, w)| w * d.abs().powf(p))
+                .sum::<f64>()
+                .powf(1.0 / p)
         }
     }
 
-    /// Allows a user to configure the motion checking resolution.
+    /// Sets the fraction of the maximum extent used as the motion checking resolution.
+    ///
+    /// Motions are checked at steps of `fraction * get_maximum_extent()`, so a larger fraction
+    /// means fewer validity checks per motion. The fraction is clamped to `[0, 1]` and defaults to
+    /// 0.005.
+    ///
+    /// Earlier releases checked motions at a tenth of this step and defaulted the fraction to 0.05.
+    /// Divide a fraction written for those releases by 10 to keep its old resolution.
     pub fn set_longest_valid_segment_fraction(&mut self, fraction: f64) {
         if fraction > 0.0 && fraction <= 1.0 {
             self.longest_valid_segment_fraction = fraction;
@@ -127,13 +195,61 @@ impl RealVectorStateSpace {
             self.longest_valid_segment_fraction = 1.;
         }
     }
+
+    /// Snaps a state to the nearest point of a lattice with spacing `resolution`.
+    ///
+    /// In each dimension the lattice points are the lower bound plus multiples of `resolution`,
+    /// or multiples of `resolution` if the dimension is unbounded below. A value that would round
+    /// past the upper bound is snapped to the last lattice point within the bounds instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resolution` is not positive and finite, or if `state` has the wrong dimension.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use oxmpl::base::{space::RealVectorStateSpace, state::RealVectorState};
+    ///
+    /// let space = RealVectorStateSpace::new(2, Some(vec![(0.5, 10.0), (0.0, 10.0)])).unwrap();
+    /// let snapped = space.snap_to_lattice(&RealVectorState::new(vec![1.2, 3.8]), 1.0);
+    /// assert_eq!(snapped.values, vec![1.5, 4.0]);
+    /// ```
+    pub fn snap_to_lattice(&self, state: &RealVectorState, resolution: f64) -> RealVectorState {
+        assert!(
+            resolution.is_finite() && resolution > 0.0,
+            "Lattice resolution must be positive and finite."
+        );
+        assert_eq!(
+            state.values.len(),
+            self.dimension,
+            "State has incorrect dimension for this space."
+        );
+
+        let values = state
+            .values
+            .iter()
+            .zip(&self.bounds)
+            .map(|(&value, &(lower, upper))| {
+                let origin = if lower.is_finite() { lower } else { 0.0 };
+                let steps = ((value - origin) / resolution).round();
+                let max_steps = ((upper - origin) / resolution).floor();
+                let min_steps = ((lower - origin) / resolution).ceil();
+                origin + steps.min(max_steps).max(min_steps) * resolution
+            })
+            .collect();
+
+        RealVectorState { values }
+    }
 }
 
 impl StateSpace for RealVectorStateSpace {
     type StateType = RealVectorState;
 
     /// Find distance between current state1 and target state2. Depends on StateSpace.
-    /// In RealVectorStateSpace, this refers to the L2-norm.
+    /// In RealVectorStateSpace, this refers to the weighted L-p norm
+    /// `(sum(w_i * |a_i - b_i|^p))^(1/p)`, which is the L2-norm by default. With `p` infinite it
+    /// is its limit `max(|a_i - b_i|)`, whatever the weights.
     fn distance(&self, state1: &Self::StateType, state2: &Self::StateType) -> f64 {
         assert_eq!(
             state1.values.len(),
@@ -145,13 +261,13 @@ impl StateSpace for RealVectorStateSpace {
             self.dimension,
             "State2 has incorrect dimension for this space."
         );
-        state1
-            .values
-            .iter()
-            .zip(state2.values.iter())
-            .map(|(v1, v2)| (v1 - v2).powi(2))
-            .sum::<f64>()
-            .sqrt()
+        self.weighted_norm(
+            state1
+                .values
+                .iter()
+                .zip(state2.values.iter())
+                .map(|(v1, v2)| v1 - v2),
+        )
     }
 
     /// Performs linear interpolation between two states.
@@ -202,6 +318,11 @@ impl StateSpace for RealVectorStateSpace {
         }
     }
 
+    /// Checks that the state has one value per dimension of the space.
+    fn is_compatible(&self, state: &Self::StateType) -> bool {
+        state.values.len() == self.dimension
+    }
+
     /// Checks if a state is within the space's bounds, allowing for a small tolerance.
     ///
     /// This check uses a machine epsilon tolerance to prevent floating-point inaccuracies from
@@ -248,7 +369,402 @@ impl StateSpace for RealVectorStateSpace {
         Ok(RealVectorState { values })
     }
 
+    /// Samples the prolate hyperspheroid with foci `start` and `goal` and transverse diameter
+    /// `max_cost`.
+    ///
+    /// A uniform sample from the unit ball is scaled to the hyperspheroid's radii, rotated so that
+    /// its first axis runs from `start` to `goal`, and centred between them. Samples outside the
+    /// bounds are rejected and redrawn, up to a fixed number of attempts.
+    ///
+    /// If `start` and `goal` (nearly) coincide, the hyperspheroid is a ball of diameter
+    /// `max_cost`. If `max_cost` is below `distance(start, goal)`, it collapses to the segment
+    /// between them.
+    ///
+    /// The hyperspheroid is only the informed set of the unweighted Euclidean distance, so with
+    /// other weights or another norm order this returns `None`.
+    fn sample_informed(
+        &self,
+        start: &Self::StateType,
+        goal: &Self::StateType,
+        max_cost: f64,
+        rng: &mut impl Rng,
+    ) -> Option<Self::StateType> {
+        if !max_cost.is_finite() || !self.is_euclidean() {
+            return None;
+        }
+
+        let min_cost = self.distance(start, goal);
+        let max_cost = max_cost.max(min_cost);
+        let transverse_radius = max_cost / 2.0;
+        let conjugate_radius = (max_cost.powi(2) - min_cost.powi(2)).max(0.0).sqrt() / 2.0;
+
+        let centre: Vec<f64> = start
+            .values
+            .iter()
+            .zip(&goal.values)
+            .map(|(s, g)| (s + g) / 2.0)
+            .collect();
+        let axis: Vec<f64> = if min_cost > 1e-12 {
+            start
+                .values
+                .iter()
+                .zip(&goal.values)
+                .map(|(s, g)| (g - s) / min_cost)
+                .collect()
+        } else {
+            // With coincident foci every direction is equivalent, so any axis will do.
+            let mut axis = vec![0.0; self.dimension];
+            axis[0] = 1.0;
+            axis
+        };
+        let rotation = rotation_to_world(&axis);
+
+        for _ in 0..MAX_SAMPLING_ATTEMPTS {
+            let ball = sample_unit_ball(self.dimension, rng);
+            let values = (0..self.dimension)
+                .map(|row| {
+                    let offset: f64 = (0..self.dimension)
+                        .map(|col| {
+                            let radius = if col == 0 {
+                                transverse_radius
+                            } else {
+                                conjugate_radius
+                            };
+                            rotation[row][col] * radius * ball[col]
+                        })
+                        .sum();
+                    centre[row] + offset
+                })
+                .collect();
+            let state = RealVectorState { values };
+            if self.satisfies_bounds(&state) {
+                return Some(state);
+            }
+        }
+        None
+    }
+
+    /// Samples uniformly from the ball of radius `distance` around `near` under the weighted metric
+    /// of the space, then clamps the sample to the bounds.
+    ///
+    /// With the Euclidean norm the ball is sampled directly. With other norms, points are drawn
+    /// from the ball's bounding box until one falls inside it, giving up after a hundred tries.
+    fn sample_uniform_near(
+        &self,
+        near: &Self::StateType,
+        distance: f64,
+        rng: &mut impl Rng,
+    ) -> Option<Self::StateType> {
+        if !(distance.is_finite() && distance >= 0.0) || near.values.len() != self.dimension {
+            return None;
+        }
+
+        let p = self.norm_order;
+        let mut state = if p == 2.0 {
+            let ball = sample_unit_ball(self.dimension, rng);
+            let values = near
+                .values
+                .iter()
+                .zip(ball)
+                .zip(&self.weights)
+                .map(|((x, b), w)| x + b * distance / w.sqrt())
+                .collect();
+            RealVectorState { values }
+        } else {
+            // The ball reaches `distance / w^(1/p)` along an axis of weight `w`.
+            let half_widths: Vec<f64> = self
+                .weights
+                .iter()
+                .map(|w| distance / w.powf(1.0 / p))
+                .collect();
+            (0..MAX_SAMPLING_ATTEMPTS).find_map(|_| {
+                let values = near
+                    .values
+                    .iter()
+                    .zip(&half_widths)
+                    .map(|(&x, &h)| {
+                        if h > 0.0 {
+                            rng.random_range(x - h..=x + h)
+                        } else {
+                            x
+                        }
+                    })
+                    .collect();
+                let state = RealVectorState { values };
+                (self.distance(near, &state) <= distance).then_some(state)
+            })?
+        };
+        self.enforce_bounds(&mut state);
+        Some(state)
+    }
+
     fn get_longest_valid_segment_length(&self) -> f64 {
         self.get_maximum_extent() * self.longest_valid_segment_fraction
     }
+
+    /// A helper to calculate the diagonal of the space's bounding box, measured with `distance`.
+    fn get_maximum_extent(&self) -> f64 {
+        if self
+            .bounds
+            .iter()
+            .any(|(low, high)| !low.is_finite() || !high.is_finite())
+        {
+            1.0
+        } else {
+            self.weighted_norm(self.bounds.iter().map(|(low, high)| high - low))
+        }
+    }
+}
+
+/// Returns a rotation matrix, as rows, whose first column is the unit vector `axis`.
+///
+/// This is the Householder reflection mapping the first basis vector onto `axis`, with the sign
+/// of its second column flipped to turn it into a proper rotation.
+fn rotation_to_world(axis: &[f64]) -> Vec<Vec<f64>> {
+    let n = axis.len();
+    let mut matrix: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    // v = e1 - axis, and the reflection is I - 2 v v^T / (v^T v).
+    let v: Vec<f64> = axis
+        .iter()
+        .enumerate()
+        .map(|(i, a)| if i == 0 { 1.0 - a } else { -a })
+        .collect();
+    let v_norm_sq: f64 = v.iter().map(|x| x * x).sum();
+    if v_norm_sq < 1e-24 {
+        // `axis` is already the first basis vector.
+        return matrix;
+    }
+    for (i, row) in matrix.iter_mut().enumerate() {
+        for (j, entry) in row.iter_mut().enumerate() {
+            *entry -= 2.0 * v[i] * v[j] / v_norm_sq;
+        }
+    }
+    if n > 1 {
+        for row in matrix.iter_mut() {
+            row[1] = -row[1];
+        }
+    }
+    matrix
+}
+
+/// Draws a point uniformly from the `n`-dimensional unit ball.
+///
+/// The direction is a normalised vector of standard normal samples (drawn with the Box-Muller
+/// transform), and the radius is scaled by `u^(1/n)` so that volume is covered uniformly.
+fn sample_unit_ball(n: usize, rng: &mut impl Rng) -> Vec<f64> {
+    loop {
+        let direction: Vec<f64> = (0..n)
+            .map(|_| {
+                let u1: f64 = 1.0 - rng.random::<f64>();
+                let u2: f64 = rng.random();
+                (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+            })
+            .collect();
+        let norm = direction.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm > 1e-12 {
+            let radius = rng.random::<f64>().powf(1.0 / n as f64);
+            return direction.iter().map(|x| x * radius / norm).collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn rv(values: &[f64]) -> RealVectorState {
+        RealVectorState::new(values.to_vec())
+    }
+
+    #[test]
+    fn test_distance_for_norm_orders() {
+        let mut space = RealVectorStateSpace::new(2, None).unwrap();
+        let (a, b) = (rv(&[1.0, 1.0]), rv(&[4.0, -3.0]));
+
+        assert_eq!(space.distance(&a, &b), 5.0);
+        space.set_norm_order(1.0).unwrap();
+        assert_eq!(space.distance(&a, &b), 7.0);
+        space.set_norm_order(f64::INFINITY).unwrap();
+        assert_eq!(space.distance(&a, &b), 4.0);
+        space.set_norm_order(3.0).unwrap();
+        assert!((space.distance(&a, &b) - 91.0_f64.cbrt()).abs() < 1e-12);
+
+        assert_eq!(
+            space.set_norm_order(0.5),
+            Err(StateSpaceError::InvalidNormOrder { p: 0.5 })
+        );
+    }
+
+    #[test]
+    fn test_weights_change_which_state_is_nearer() {
+        // Metres along x, millimetres along y.
+        let mut space = RealVectorStateSpace::new(2, None).unwrap();
+        let origin = rv(&[0.0, 0.0]);
+        let along_x = rv(&[1.0, 0.0]);
+        let along_y = rv(&[0.0, 500.0]);
+        assert!(space.distance(&origin, &along_x) < space.distance(&origin, &along_y));
+
+        space.set_weights(vec![1.0, 1e-6]).unwrap();
+        assert!(space.distance(&origin, &along_y) < space.distance(&origin, &along_x));
+        assert!((space.distance(&origin, &along_y) - 0.5).abs() < 1e-12);
+
+        space.set_norm_order(1.0).unwrap();
+        assert!((space.distance(&origin, &along_y) - 5e-4).abs() < 1e-12);
+
+        assert_eq!(
+            space.set_weights(vec![1.0]),
+            Err(StateSpaceError::DimensionMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            space.set_weights(vec![1.0, 0.0]),
+            Err(StateSpaceError::InvalidWeight { weight: 0.0 })
+        );
+    }
+
+    #[test]
+    fn test_chebyshev_distance_is_limit_of_weighted_norms() {
+        let mut space = RealVectorStateSpace::new(2, None).unwrap();
+        space.set_weights(vec![4.0, 0.25]).unwrap();
+        let (a, b) = (rv(&[1.0, 1.0]), rv(&[4.0, -3.0]));
+
+        space.set_norm_order(f64::INFINITY).unwrap();
+        let chebyshev = space.distance(&a, &b);
+        assert_eq!(chebyshev, 4.0);
+        space.set_norm_order(200.0).unwrap();
+        assert!((space.distance(&a, &b) - chebyshev).abs() / chebyshev < 1e-2);
+    }
+
+    #[test]
+    fn test_maximum_extent_is_weighted_diagonal() {
+        let mut space = RealVectorStateSpace::new(2, Some(vec![(0.0, 3.0), (0.0, 4.0)])).unwrap();
+        assert_eq!(space.get_maximum_extent(), 5.0);
+
+        space.set_weights(vec![4.0, 1.0]).unwrap();
+        assert_eq!(space.get_maximum_extent(), 52.0_f64.sqrt());
+        space.set_norm_order(f64::INFINITY).unwrap();
+        assert_eq!(space.get_maximum_extent(), 4.0);
+        assert_eq!(
+            space.get_maximum_extent(),
+            space.distance(&rv(&[0.0, 0.0]), &rv(&[3.0, 4.0]))
+        );
+    }
+
+    #[test]
+    fn test_snap_to_lattice_picks_nearer_point_within_bounds() {
+        let space = RealVectorStateSpace::new(2, Some(vec![(0.5, 3.2), (-1.0, 1.0)])).unwrap();
+
+        // 1.2 lies between the lattice points 1.0 and 1.5, nearer 1.0; -0.3 lies between -0.5
+        // and 0.0, nearer -0.5.
+        let snapped = space.snap_to_lattice(&RealVectorState::new(vec![1.2, -0.3]), 0.5);
+        assert_eq!(snapped.values, vec![1.0, -0.5]);
+
+        // 3.1 would round up to 3.5, past the upper bound of 3.2, so it snaps down to 2.5.
+        let near_upper = space.snap_to_lattice(&RealVectorState::new(vec![3.1, 1.0]), 1.0);
+        assert_eq!(near_upper.values, vec![2.5, 1.0]);
+        assert!(space.satisfies_bounds(&near_upper));
+    }
+
+    #[test]
+    fn test_snap_to_lattice_in_unbounded_space_uses_origin() {
+        let space = RealVectorStateSpace::new(1, None).unwrap();
+        let snapped = space.snap_to_lattice(&RealVectorState::new(vec![-0.74]), 0.25);
+        assert_eq!(snapped.values, vec![-0.75]);
+    }
+
+    #[test]
+    fn test_rotation_to_world_is_a_rotation_onto_the_axis() {
+        let norm = (1.0f64 + 4.0 + 4.0).sqrt();
+        let axis = [1.0 / norm, -2.0 / norm, 2.0 / norm];
+        let rotation = rotation_to_world(&axis);
+
+        for (i, a) in axis.iter().enumerate() {
+            assert!((rotation[i][0] - a).abs() < 1e-12);
+        }
+        // Columns are orthonormal.
+        for j in 0..3 {
+            for k in 0..3 {
+                let dot: f64 = (0..3).map(|i| rotation[i][j] * rotation[i][k]).sum();
+                let expected = if j == k { 1.0 } else { 0.0 };
+                assert!((dot - expected).abs() < 1e-12);
+            }
+        }
+        let det = rotation[0][0]
+            * (rotation[1][1] * rotation[2][2] - rotation[1][2] * rotation[2][1])
+            - rotation[0][1] * (rotation[1][0] * rotation[2][2] - rotation[1][2] * rotation[2][0])
+            + rotation[0][2] * (rotation[1][0] * rotation[2][1] - rotation[1][1] * rotation[2][0]);
+        assert!((det - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sample_informed_stays_within_the_informed_set() {
+        let space = RealVectorStateSpace::new(3, Some(vec![(-10.0, 10.0); 3])).unwrap();
+        let start = RealVectorState::new(vec![-2.0, 1.0, 0.5]);
+        let goal = RealVectorState::new(vec![3.0, -1.0, 2.0]);
+        let max_cost = 1.2 * space.distance(&start, &goal);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+
+        for _ in 0..1000 {
+            let state = space
+                .sample_informed(&start, &goal, max_cost, &mut rng)
+                .unwrap();
+            let cost = space.distance(&start, &state) + space.distance(&state, &goal);
+            assert!(
+                cost <= max_cost + 1e-9,
+                "{state:?} costs {cost} > {max_cost}"
+            );
+            assert!(space.satisfies_bounds(&state));
+        }
+    }
+
+    #[test]
+    fn test_sample_uniform_near_stays_within_distance_and_bounds() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let near = RealVectorState::new(vec![0.5, 5.0, 5.0]);
+        for p in [1.0, 2.0, 3.0, f64::INFINITY] {
+            let mut space = RealVectorStateSpace::new(3, Some(vec![(0.0, 10.0); 3])).unwrap();
+            space.set_weights(vec![0.25, 1.0, 4.0]).unwrap();
+            space.set_norm_order(p).unwrap();
+
+            for _ in 0..200 {
+                let state = space.sample_uniform_near(&near, 1.5, &mut rng).unwrap();
+                assert!(space.distance(&near, &state) <= 1.5 + 1e-9, "{state:?}");
+                assert!(space.satisfies_bounds(&state));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_informed_degenerate_foci() {
+        let space = RealVectorStateSpace::new(2, Some(vec![(-10.0, 10.0); 2])).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+
+        // Coincident foci give a ball of diameter `max_cost` around them.
+        let point = RealVectorState::new(vec![1.0, 1.0]);
+        for _ in 0..100 {
+            let state = space
+                .sample_informed(&point, &point, 2.0, &mut rng)
+                .unwrap();
+            assert!(space.distance(&point, &state) <= 1.0 + 1e-9);
+        }
+
+        // A cost at the straight-line distance collapses the set onto the segment.
+        let start = RealVectorState::new(vec![0.0, 0.0]);
+        let goal = RealVectorState::new(vec![4.0, 0.0]);
+        for _ in 0..100 {
+            let state = space.sample_informed(&start, &goal, 3.0, &mut rng).unwrap();
+            assert!(state.values[1].abs() < 1e-9);
+            assert!((0.0..=4.0).contains(&state.values[0]));
+        }
+
+        assert!(space
+            .sample_informed(&start, &goal, f64::INFINITY, &mut rng)
+            .is_none());
+    }
 }
@@ -0,0 +1,288 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use rand::Rng;
+
+use crate::base::{
+    error::{StateSamplingError, StateSpaceError},
+    space::{RealVectorStateSpace, SO3StateSpace, StateSpace},
+    state::{RealVectorState, SE3State, SO3State},
+};
+
+/// A state space representing rigid-body poses in 3D (the Special Euclidean group SE(3)).
+///
+/// The space is composed of a 3D `RealVectorStateSpace` for the translation and an
+/// `SO3StateSpace` for the rotation, and delegates to them for bounds and sampling.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SE3StateSpace {
+    /// The space of translations, bounded by a box.
+    pub translation: RealVectorStateSpace,
+    /// The space of rotations, bounded by a cone around a center rotation.
+    pub rotation: SO3StateSpace,
+    /// The factor the rotational distance (in radians) is multiplied by before being added to the
+    /// translational distance. Defaults to 1.0.
+    pub rotation_weight: f64,
+
+    longest_valid_segment_fraction: f64,
+}
+
+impl SE3StateSpace {
+    /// Creates a new `SE3StateSpace`.
+    ///
+    /// # Arguments
+    ///
+    /// * `translation_bounds` - Optional `(min, max)` bounds for each of the x, y and z axes,
+    ///   interpreted as in `RealVectorStateSpace::new`. If `None`, translations are unbounded.
+    /// * `rotation_cone` - An optional `(center_rotation, max_angle)` tuple, interpreted as in
+    ///   `SO3StateSpace::new`. If `None`, all rotations are allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of whichever sub-space rejects its bounds:
+    /// * `StateSpaceError::DimensionMismatch` if `translation_bounds` does not have 3 entries.
+    /// * `StateSpaceError::InvalidBound` if a translation lower bound is not below its upper bound.
+    /// * `StateSpaceError::InvalidAngularDistance` if `max_angle` is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use oxmpl::base::space::SE3StateSpace;
+    /// use oxmpl::base::state::SO3State;
+    ///
+    /// // A 10m cube, with rotations up to 30 degrees away from the identity.
+    /// let space = SE3StateSpace::new(
+    ///     Some(vec![(0.0, 10.0), (0.0, 10.0), (0.0, 10.0)]),
+    ///     Some((SO3State::identity(), 30.0f64.to_radians())),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(space.translation.bounds[2], (0.0, 10.0));
+    /// ```
+    pub fn new(
+        translation_bounds: Option<Vec<(f64, f64)>>,
+        rotation_cone: Option<(SO3State, f64)>,
+    ) -> Result<Self, StateSpaceError> {
+        Ok(Self {
+            translation: RealVectorStateSpace::new(3, translation_bounds)?,
+            rotation: SO3StateSpace::new(rotation_cone)?,
+            rotation_weight: 1.0,
+            longest_valid_segment_fraction: 0.005,
+        })
+    }
+
+    /// Sets the fraction of the maximum extent used as the motion checking resolution.
+    ///
+    /// Motions are checked at steps of `fraction * get_maximum_extent()`, so a larger fraction
+    /// means fewer validity checks per motion. The fraction is clamped to `[0, 1]` and defaults to
+    /// 0.005.
+    ///
+    /// Earlier releases checked motions at a tenth of this step and defaulted the fraction to 0.05.
+    /// Divide a fraction written for those releases by 10 to keep its old resolution.
+    pub fn set_longest_valid_segment_fraction(&mut self, fraction: f64) {
+        if fraction > 0.0 && fraction <= 1.0 {
+            self.longest_valid_segment_fraction = fraction;
+        } else if fraction <= 0.0 {
+            self.longest_valid_segment_fraction = 0.;
+        } else {
+            self.longest_valid_segment_fraction = 1.;
+        }
+    }
+}
+
+impl StateSpace for SE3StateSpace {
+    type StateType = SE3State;
+
+    /// Computes the L2 distance between the translations plus `rotation_weight` times the
+    /// geodesic distance between the rotations.
+    fn distance(&self, state1: &Self::StateType, state2: &Self::StateType) -> f64 {
+        let translation_distance = state1
+            .translation
+            .iter()
+            .zip(&state2.translation)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        let rotation_distance = self.rotation.distance(&state1.rotation, &state2.rotation);
+
+        translation_distance + self.rotation_weight * rotation_distance
+    }
+
+    /// Linearly interpolates the translation and SLERPs the rotation, as in `SO3StateSpace`.
+    fn interpolate(
+        &self,
+        from: &Self::StateType,
+        to: &Self::StateType,
+        t: f64,
+        out_state: &mut Self::StateType,
+    ) {
+        for i in 0..3 {
+            out_state.translation[i] =
+                from.translation[i] + (to.translation[i] - from.translation[i]) * t;
+        }
+        self.rotation
+            .interpolate(&from.rotation, &to.rotation, t, &mut out_state.rotation);
+    }
+
+    /// Clamps the translation to its box and projects the rotation into its cone.
+    fn enforce_bounds(&self, state: &mut Self::StateType) {
+        let mut translation = RealVectorState::new(state.translation.to_vec());
+        self.translation.enforce_bounds(&mut translation);
+        state.translation.copy_from_slice(&translation.values);
+
+        self.rotation.enforce_bounds(&mut state.rotation);
+    }
+
+    /// Checks that both the translation and the rotation are within their bounds.
+    fn satisfies_bounds(&self, state: &Self::StateType) -> bool {
+        self.translation
+            .satisfies_bounds(&RealVectorState::new(state.translation.to_vec()))
+            && self.rotation.satisfies_bounds(&state.rotation)
+    }
+
+    /// Generates a uniformly random translation and rotation within the defined bounds.
+    ///
+    /// # Errors
+    ///
+    /// * `StateSamplingError::UnboundedDimension` if any translation axis is unbounded.
+    fn sample_uniform(&self, rng: &mut impl Rng) -> Result<SE3State, StateSamplingError> {
+        let translation = self.translation.sample_uniform(rng)?;
+        let rotation = self.rotation.sample_uniform(rng)?;
+
+        Ok(SE3State {
+            translation: [
+                translation.values[0],
+                translation.values[1],
+                translation.values[2],
+            ],
+            rotation,
+        })
+    }
+
+    fn get_longest_valid_segment_length(&self) -> f64 {
+        self.get_maximum_extent() * self.longest_valid_segment_fraction
+    }
+
+    /// Returns the diagonal of the translation box plus the weighted maximum rotational distance.
+    fn get_maximum_extent(&self) -> f64 {
+        self.translation.get_maximum_extent()
+            + self.rotation_weight * self.rotation.get_maximum_extent()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::f64::consts::FRAC_PI_2;
+
+    fn rotation_about_z(angle: f64) -> SO3State {
+        SO3State::new(0.0, 0.0, (angle / 2.0).sin(), (angle / 2.0).cos())
+    }
+
+    fn unit_box() -> Option<Vec<(f64, f64)>> {
+        Some(vec![(0.0, 1.0), (0.0, 1.0), (0.0, 1.0)])
+    }
+
+    #[test]
+    fn test_new_surfaces_sub_space_errors() {
+        assert_eq!(
+            SE3StateSpace::new(Some(vec![(0.0, 1.0), (0.0, 1.0)]), None).err(),
+            Some(StateSpaceError::DimensionMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            SE3StateSpace::new(Some(vec![(0.0, 1.0), (2.0, 1.0), (0.0, 1.0)]), None).err(),
+            Some(StateSpaceError::InvalidBound {
+                lower: 2.0,
+                upper: 1.0
+            })
+        );
+        assert_eq!(
+            SE3StateSpace::new(unit_box(), Some((SO3State::identity(), -0.1))).err(),
+            Some(StateSpaceError::InvalidAngularDistance { lower: -0.1 })
+        );
+    }
+
+    #[test]
+    fn test_sampling_respects_translation_box_and_rotation_cone() {
+        let center = rotation_about_z(1.0);
+        let max_angle = 30.0f64.to_radians();
+        let space = SE3StateSpace::new(
+            Some(vec![(-1.0, 1.0), (2.0, 3.0), (10.0, 20.0)]),
+            Some((center.clone(), max_angle)),
+        )
+        .unwrap();
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..200 {
+            let state = space.sample_uniform(&mut rng).unwrap();
+            assert!((-1.0..1.0).contains(&state.translation[0]));
+            assert!((2.0..3.0).contains(&state.translation[1]));
+            assert!((10.0..20.0).contains(&state.translation[2]));
+            assert!(space.rotation.distance(&center, &state.rotation) <= max_angle);
+            assert!(space.satisfies_bounds(&state));
+        }
+    }
+
+    #[test]
+    fn test_sampling_fails_with_unbounded_translation() {
+        let space = SE3StateSpace::new(None, None).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(
+            space.sample_uniform(&mut rng),
+            Err(StateSamplingError::UnboundedDimension { dimension_index: 0 })
+        );
+    }
+
+    #[test]
+    fn test_distance_weights_rotation() {
+        let mut space = SE3StateSpace::new(unit_box(), None).unwrap();
+        let a = SE3State::new([0.0, 0.0, 0.0], rotation_about_z(0.0));
+        let b = SE3State::new([0.0, 3.0, 4.0], rotation_about_z(1.0));
+
+        // The geodesic distance between the quaternions is half the rotation angle.
+        assert!((space.distance(&a, &b) - 5.5).abs() < 1e-9);
+
+        space.rotation_weight = 2.0;
+        assert!((space.distance(&a, &b) - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_interpolate_lerps_translation_and_slerps_rotation() {
+        let space = SE3StateSpace::new(unit_box(), None).unwrap();
+        let from = SE3State::new([0.0, 0.0, 0.0], rotation_about_z(0.0));
+        let to = SE3State::new([1.0, 2.0, -4.0], rotation_about_z(2.0));
+
+        let mut out = from.clone();
+        space.interpolate(&from, &to, 0.25, &mut out);
+
+        assert_eq!(out.translation, [0.25, 0.5, -1.0]);
+        assert!(
+            space
+                .rotation
+                .distance(&out.rotation, &rotation_about_z(0.5))
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn test_enforce_bounds_clamps_translation_and_projects_rotation() {
+        let max_angle = 30.0f64.to_radians();
+        let space =
+            SE3StateSpace::new(unit_box(), Some((SO3State::identity(), max_angle))).unwrap();
+        let mut state = SE3State::new([-1.0, 0.5, 3.0], rotation_about_z(FRAC_PI_2));
+
+        space.enforce_bounds(&mut state);
+
+        assert_eq!(state.translation, [0.0, 0.5, 1.0]);
+        let deviation = space
+            .rotation
+            .distance(&SO3State::identity(), &state.rotation);
+        assert!((deviation - max_angle).abs() < 1e-6);
+        assert!(space.satisfies_bounds(&state));
+    }
+}
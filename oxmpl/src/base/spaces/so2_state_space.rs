@@ -15,11 +15,18 @@ use crate::base::{
 ///
 /// This space handles the circular nature of angles, ensuring that distance and interpolation
 /// calculations correctly use the shortest path.
+///
+/// The bounds may be wrapped, i.e. describe an arc that crosses the `+PI`/`-PI` seam.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SO2StateSpace {
     /// The bounds of the space, as a `(min, max)` tuple.
+    ///
+    /// If `min > max`, the bounds are wrapped: the allowed arc runs from `min` up through `PI`
+    /// and on from `-PI` to `max`.
     pub bounds: (f64, f64),
 
+    wrapped: bool,
     longest_valid_segment_fraction: f64,
 }
 
@@ -27,7 +34,8 @@ impl SO2StateSpace {
     /// Creates a new `SO2StateSpace`.
     ///
     /// If `bounds_option` is `None`, the space defaults to the full circle `[-PI, PI)`.
-    /// Provided bounds are normalized to the `[-PI, PI)` range.
+    /// Provided bounds are clamped to the `[-PI, PI]` range. If `min > max`, the bounds are
+    /// wrapped instead and both are normalised to the `[-PI, PI)` range.
     ///
     /// # Arguments
     ///
@@ -36,9 +44,8 @@ impl SO2StateSpace {
     ///
     /// # Errors
     ///
-    /// Returns `StateSpaceError::InvalidBound` if the provided `min` bound is greater
-    /// than or equal to the `max` bound. Note that wrapped ranges (e.g., from `1.5` to `-1.5`)
-    /// are not supported by this constructor and should be handled by a `StateValidityChecker`.
+    /// Returns `StateSpaceError::InvalidBound` if the provided `min` and `max` bounds are equal
+    /// (once normalised, for wrapped bounds) or are not numbers.
     ///
     /// # Examples
     ///
@@ -53,32 +60,56 @@ impl SO2StateSpace {
     /// // Create a space bounded to the upper semi-circle
     /// let upper_half_space = SO2StateSpace::new(Some((0.0, PI))).unwrap();
     /// assert_eq!(upper_half_space.bounds, (0.0, PI));
+    ///
+    /// // Create a space bounded to the arc from 2.5 through PI to -2.5
+    /// let wrapped_space = SO2StateSpace::new(Some((2.5, -2.5))).unwrap();
+    /// assert!(wrapped_space.is_wrapped());
     /// ```
     pub fn new(bounds_option: Option<(f64, f64)>) -> Result<Self, StateSpaceError> {
         let bounds = bounds_option.unwrap_or((-PI, PI));
+        let invalid_bound = StateSpaceError::InvalidBound {
+            lower: bounds.0,
+            upper: bounds.1,
+        };
 
-        if bounds.0 >= bounds.1 {
-            return Err(StateSpaceError::InvalidBound {
-                lower: bounds.0,
-                upper: bounds.1,
-            });
+        if bounds.0.is_nan() || bounds.1.is_nan() || bounds.0 == bounds.1 {
+            return Err(invalid_bound);
         }
 
-        // TODO: Do we want to enforce a boundary here if it is above or below +/- PI?
-        let clamped_bounds = (bounds.0.max(-PI), bounds.1.min(PI));
+        let wrapped = bounds.0 > bounds.1;
+        let bounds = if wrapped {
+            let normalise = |angle: f64| SO2State::new(angle).normalised().value;
+            let normalised_bounds = (normalise(bounds.0), normalise(bounds.1));
+            if normalised_bounds.0 == normalised_bounds.1 {
+                return Err(invalid_bound);
+            }
+            normalised_bounds
+        } else {
+            // TODO: Do we want to enforce a boundary here if it is above or below +/- PI?
+            (bounds.0.max(-PI), bounds.1.min(PI))
+        };
 
         Ok(Self {
-            bounds: clamped_bounds,
-            longest_valid_segment_fraction: 0.05,
+            bounds,
+            // Normalising unwraps bounds that do not cross the seam, e.g. (5.0, 1.0).
+            wrapped: bounds.0 > bounds.1,
+            longest_valid_segment_fraction: 0.005,
         })
     }
 
-    /// Returns the maximum possible distance in this space, which is always PI.
-    pub fn get_maximum_extent(&self) -> f64 {
-        PI
+    /// Returns whether the bounds are wrapped, i.e. the allowed arc crosses the `+PI`/`-PI` seam.
+    pub fn is_wrapped(&self) -> bool {
+        self.wrapped
     }
 
-    /// Sets the fraction used to determine motion checking resolution.
+    /// Sets the fraction of the maximum extent used as the motion checking resolution.
+    ///
+    /// Motions are checked at steps of `fraction * get_maximum_extent()`, so a larger fraction
+    /// means fewer validity checks per motion. The fraction is clamped to `[0, 1]` and defaults to
+    /// 0.005.
+    ///
+    /// Earlier releases checked motions at a tenth of this step and defaulted the fraction to 0.05.
+    /// Divide a fraction written for those releases by 10 to keep its old resolution.
     pub fn set_longest_valid_segment_fraction(&mut self, fraction: f64) {
         if fraction > 0.0 && fraction <= 1.0 {
             self.longest_valid_segment_fraction = fraction;
@@ -111,14 +142,14 @@ impl StateSpace for SO2StateSpace {
         t: f64,
         out_state: &mut Self::StateType,
     ) {
-        let mut diff_to_from = to.clone().normalise().value - from.clone().normalise().value;
+        let mut diff_to_from = to.normalised().value - from.normalised().value;
         if diff_to_from > PI {
             diff_to_from -= 2.0 * PI;
         } else if diff_to_from < -PI {
             diff_to_from += 2.0 * PI;
         }
         out_state.value = from.value + diff_to_from * t;
-        out_state.value = out_state.normalise().value;
+        out_state.normalise();
     }
 
     /// Modifies the state by clamping each of its values to the space's bounds.
@@ -142,12 +173,17 @@ impl StateSpace for SO2StateSpace {
 
     /// Checks if a state is within the defined angular bounds.
     fn satisfies_bounds(&self, state: &Self::StateType) -> bool {
-        let val = state.clone().normalise().value;
+        let val = state.normalised().value;
         let (lower, upper) = self.bounds;
-        val >= lower && val <= upper
+        if self.wrapped {
+            val >= lower || val <= upper
+        } else {
+            val >= lower && val <= upper
+        }
     }
 
-    /// Generates a random angle from within the defined bounds.
+    /// Generates a random angle from within the defined bounds, uniformly over the length of the
+    /// allowed arc.
     ///
     /// # Arguments
     ///
@@ -160,12 +196,180 @@ impl StateSpace for SO2StateSpace {
     /// satisfy the `StateSpace` trait.
     fn sample_uniform(&self, rng: &mut impl Rng) -> Result<SO2State, StateSamplingError> {
         let (lower, upper) = self.bounds;
-        Ok(SO2State {
-            value: rng.random_range(lower..upper),
-        })
+        if !self.wrapped {
+            return Ok(SO2State {
+                value: rng.random_range(lower..upper),
+            });
+        }
+
+        // Sample along the arc as if it did not cross the seam, then wrap the result back.
+        let mut state = SO2State {
+            value: lower + rng.random_range(0.0..upper - lower + 2.0 * PI),
+        };
+        state.normalise();
+        Ok(state)
+    }
+
+    /// Samples an angle uniformly from the arc within `distance` of `near`, then brings it within
+    /// the bounds.
+    fn sample_uniform_near(
+        &self,
+        near: &SO2State,
+        distance: f64,
+        rng: &mut impl Rng,
+    ) -> Option<SO2State> {
+        if !(distance.is_finite() && distance >= 0.0) {
+            return None;
+        }
+        let half_width = distance.min(PI);
+        let offset = if half_width > 0.0 {
+            rng.random_range(-half_width..=half_width)
+        } else {
+            0.0
+        };
+        let mut state = SO2State {
+            value: near.value + offset,
+        };
+        self.enforce_bounds(&mut state);
+        Some(state)
     }
 
     fn get_longest_valid_segment_length(&self) -> f64 {
         self.get_maximum_extent() * self.longest_valid_segment_fraction
     }
+
+    /// Returns the maximum possible distance in this space, which is always PI.
+    fn get_maximum_extent(&self) -> f64 {
+        PI
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_satisfies_bounds_wraps_angles_onto_the_full_circle() {
+        let space = SO2StateSpace::new(None).unwrap();
+
+        assert!(space.satisfies_bounds(&SO2State::new(0.3)));
+        assert!(space.satisfies_bounds(&SO2State::new(-PI)));
+        assert!(space.satisfies_bounds(&SO2State::new(3.0 * PI / 2.0)));
+        assert!(space.satisfies_bounds(&SO2State::new(-5.0 * PI)));
+    }
+
+    #[test]
+    fn test_satisfies_bounds_rejects_angles_outside_bounded_arc() {
+        let space = SO2StateSpace::new(Some((0.0, PI / 2.0))).unwrap();
+
+        assert!(space.satisfies_bounds(&SO2State::new(0.0)));
+        assert!(space.satisfies_bounds(&SO2State::new(1.0)));
+        assert!(space.satisfies_bounds(&SO2State::new(1.0 + 2.0 * PI)));
+        assert!(!space.satisfies_bounds(&SO2State::new(-0.1)));
+        assert!(!space.satisfies_bounds(&SO2State::new(2.0)));
+    }
+
+    #[test]
+    fn test_enforce_bounds_normalises_angles_within_bounds() {
+        let space = SO2StateSpace::new(None).unwrap();
+        let mut state = SO2State::new(0.3 + 2.0 * PI);
+
+        space.enforce_bounds(&mut state);
+
+        assert!((state.value - 0.3).abs() < 1e-9);
+        assert!(space.satisfies_bounds(&state));
+    }
+
+    #[test]
+    fn test_enforce_bounds_clamps_to_nearest_bound_around_the_circle() {
+        let space = SO2StateSpace::new(Some((0.0, PI / 2.0))).unwrap();
+
+        let mut just_below = SO2State::new(-0.2);
+        space.enforce_bounds(&mut just_below);
+        assert_eq!(just_below.value, 0.0);
+
+        let mut just_above = SO2State::new(PI / 2.0 + 0.2);
+        space.enforce_bounds(&mut just_above);
+        assert_eq!(just_above.value, PI / 2.0);
+
+        // -3.0 is 3.0 from the lower bound but only about 1.71 from the upper one, going the
+        // other way around the circle.
+        let mut far_side = SO2State::new(-3.0);
+        space.enforce_bounds(&mut far_side);
+        assert_eq!(far_side.value, PI / 2.0);
+    }
+
+    #[test]
+    fn test_new_accepts_wrapped_bounds() {
+        let space = SO2StateSpace::new(Some((2.5, -2.5))).unwrap();
+        assert!(space.is_wrapped());
+        assert_eq!(space.bounds, (2.5, -2.5));
+
+        // Bounds past PI are normalised, which may leave an arc that does not cross the seam.
+        let space = SO2StateSpace::new(Some((5.0, 1.0))).unwrap();
+        assert!(!space.is_wrapped());
+        assert!((space.bounds.0 - (5.0 - 2.0 * PI)).abs() < 1e-12);
+
+        assert!(SO2StateSpace::new(Some((1.0, 1.0))).is_err());
+        assert!(SO2StateSpace::new(Some((PI, -PI))).is_err());
+    }
+
+    #[test]
+    fn test_satisfies_bounds_accepts_arc_across_the_seam() {
+        let space = SO2StateSpace::new(Some((2.5, -2.5))).unwrap();
+
+        assert!(space.satisfies_bounds(&SO2State::new(2.5)));
+        assert!(space.satisfies_bounds(&SO2State::new(3.0)));
+        assert!(space.satisfies_bounds(&SO2State::new(-3.0)));
+        assert!(space.satisfies_bounds(&SO2State::new(-2.5)));
+        assert!(!space.satisfies_bounds(&SO2State::new(0.0)));
+        assert!(!space.satisfies_bounds(&SO2State::new(2.4)));
+        assert!(!space.satisfies_bounds(&SO2State::new(-2.4)));
+    }
+
+    #[test]
+    fn test_sample_uniform_never_lands_in_the_forbidden_arc() {
+        let space = SO2StateSpace::new(Some((2.5, -2.5))).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        let (mut above_seam, mut below_seam) = (0, 0);
+
+        for _ in 0..10_000 {
+            let state = space.sample_uniform(&mut rng).unwrap();
+            assert!(
+                space.satisfies_bounds(&state),
+                "{} is out of bounds",
+                state.value
+            );
+            assert!((-PI..PI).contains(&state.value));
+            if state.value > 0.0 {
+                above_seam += 1;
+            } else {
+                below_seam += 1;
+            }
+        }
+
+        // The arc is symmetric about the seam, so uniform sampling should split evenly.
+        assert!(
+            (4500..5500).contains(&above_seam),
+            "{above_seam} vs {below_seam}"
+        );
+    }
+
+    #[test]
+    fn test_enforce_bounds_projects_to_nearer_end_of_wrapped_arc() {
+        let space = SO2StateSpace::new(Some((2.5, -2.5))).unwrap();
+
+        let mut near_lower = SO2State::new(2.0);
+        space.enforce_bounds(&mut near_lower);
+        assert_eq!(near_lower.value, 2.5);
+
+        let mut near_upper = SO2State::new(-1.0);
+        space.enforce_bounds(&mut near_upper);
+        assert_eq!(near_upper.value, -2.5);
+
+        let mut inside = SO2State::new(3.0 + 2.0 * PI);
+        space.enforce_bounds(&mut inside);
+        assert!((inside.value - 3.0).abs() < 1e-9);
+    }
 }
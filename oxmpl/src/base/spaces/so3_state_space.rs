@@ -15,6 +15,7 @@ use crate::base::{
 ///
 /// States are represented by unit quaternions.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SO3StateSpace {
     /// The bounds of the space, as a `(center_rotation, max_angle)` tuple.
     pub bounds: (SO3State, f64),
@@ -74,16 +75,27 @@ impl SO3StateSpace {
 
         Ok(Self {
             bounds,
-            longest_valid_segment_fraction: 0.05,
+            longest_valid_segment_fraction: 0.005,
         })
     }
 
-    /// Returns the maximum possible distance in this space, which is always 0.5*PI.
-    pub fn get_maximum_extent(&self) -> f64 {
-        0.5 * PI
+    /// Returns the rotation halfway along the shortest geodesic between `a` and `b`.
+    ///
+    /// This is SLERP at `t = 0.5`, so the result is equidistant from both endpoints.
+    pub fn geodesic_midpoint(&self, a: &SO3State, b: &SO3State) -> SO3State {
+        let mut midpoint = a.clone();
+        self.interpolate(a, b, 0.5, &mut midpoint);
+        midpoint
     }
 
-    /// Sets the fraction used to determine motion checking resolution.
+    /// Sets the fraction of the maximum extent used as the motion checking resolution.
+    ///
+    /// Motions are checked at steps of `fraction * get_maximum_extent()`, so a larger fraction
+    /// means fewer validity checks per motion. The fraction is clamped to `[0, 1]` and defaults to
+    /// 0.005.
+    ///
+    /// Earlier releases checked motions at a tenth of this step and defaulted the fraction to 0.05.
+    /// Divide a fraction written for those releases by 10 to keep its old resolution.
     pub fn set_longest_valid_segment_fraction(&mut self, fraction: f64) {
         if fraction > 0.0 && fraction <= 1.0 {
             self.longest_valid_segment_fraction = fraction;
@@ -234,4 +246,92 @@ impl StateSpace for SO3StateSpace {
     fn get_longest_valid_segment_length(&self) -> f64 {
         self.get_maximum_extent() * self.longest_valid_segment_fraction
     }
+
+    /// Returns the maximum possible distance in this space, which is always 0.5*PI.
+    fn get_maximum_extent(&self) -> f64 {
+        0.5 * PI
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rotation_about_z(angle: f64) -> SO3State {
+        SO3State::new(0.0, 0.0, (angle / 2.0).sin(), (angle / 2.0).cos())
+    }
+
+    fn rotation_about_axis(axis: (f64, f64, f64), angle: f64) -> SO3State {
+        let norm = (axis.0 * axis.0 + axis.1 * axis.1 + axis.2 * axis.2).sqrt();
+        let s = (angle / 2.0).sin() / norm;
+        SO3State::new(axis.0 * s, axis.1 * s, axis.2 * s, (angle / 2.0).cos())
+    }
+
+    #[test]
+    fn test_geodesic_midpoint_is_equidistant() {
+        let space = SO3StateSpace::new(None).unwrap();
+        let a = rotation_about_axis((1.0, 2.0, 3.0), 0.3);
+        let b = rotation_about_axis((-2.0, 0.5, 1.0), 2.0);
+
+        let midpoint = space.geodesic_midpoint(&a, &b);
+        let d_a = space.distance(&a, &midpoint);
+        let d_b = space.distance(&midpoint, &b);
+
+        assert!((d_a - d_b).abs() < 1e-9);
+        assert!((d_a + d_b - space.distance(&a, &b)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_geodesic_midpoint_of_rotations_about_same_axis() {
+        let space = SO3StateSpace::new(None).unwrap();
+        let midpoint = space.geodesic_midpoint(&rotation_about_z(0.0), &rotation_about_z(1.0));
+
+        assert!(space.distance(&midpoint, &rotation_about_z(0.5)) < 1e-9);
+    }
+
+    #[test]
+    fn test_geodesic_midpoint_takes_shortest_path_across_double_cover() {
+        let space = SO3StateSpace::new(None).unwrap();
+        let a = rotation_about_z(0.4);
+        let b = rotation_about_z(1.2);
+        let negated_b = SO3State::new(-b.x, -b.y, -b.z, -b.w);
+
+        let midpoint = space.geodesic_midpoint(&a, &negated_b);
+
+        assert!(space.distance(&midpoint, &rotation_about_z(0.8)) < 1e-9);
+    }
+
+    #[test]
+    fn test_interpolate_endpoints_slerp_branch() {
+        let space = SO3StateSpace::new(None).unwrap();
+        let from = rotation_about_axis((1.0, 0.0, 1.0), 0.2);
+        let to = rotation_about_axis((0.0, 1.0, 0.0), 2.5);
+        let mut out = SO3State::identity();
+
+        space.interpolate(&from, &to, 0.0, &mut out);
+        assert!(space.distance(&out, &from) < 1e-9);
+        space.interpolate(&from, &to, 1.0, &mut out);
+        assert!(space.distance(&out, &to) < 1e-9);
+    }
+
+    #[test]
+    fn test_interpolate_endpoints_lerp_branch() {
+        // Rotations this close have a quaternion dot product above `DOT_THRESHOLD`.
+        let space = SO3StateSpace::new(None).unwrap();
+        let from = rotation_about_z(0.10);
+        let to = rotation_about_z(0.12);
+        let mut out = SO3State::identity();
+
+        space.interpolate(&from, &to, 0.0, &mut out);
+        assert!(space.distance(&out, &from) < 1e-9);
+        space.interpolate(&from, &to, 1.0, &mut out);
+        assert!(space.distance(&out, &to) < 1e-9);
+
+        let midpoint = space.geodesic_midpoint(&from, &to);
+        let norm =
+            (midpoint.x.powi(2) + midpoint.y.powi(2) + midpoint.z.powi(2) + midpoint.w.powi(2))
+                .sqrt();
+        assert!((norm - 1.0).abs() < 1e-12);
+        assert!((space.distance(&from, &midpoint) - space.distance(&midpoint, &to)).abs() < 1e-6);
+    }
 }
@@ -0,0 +1,249 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use rand::Rng;
+
+use crate::base::{
+    error::{StateSamplingError, StateSpaceError},
+    space::StateSpace,
+    state::TimedState,
+};
+
+/// A state space that augments an inner space with a time dimension, for planning among moving
+/// obstacles.
+///
+/// States are `TimedState`s: a state of the inner space together with the time it is reached,
+/// within `time_bounds`. A `StateValidityChecker` for this space sees the time of every state it
+/// checks, so obstacles may depend on it.
+///
+/// The distance is symmetric, as nearest-neighbour searches expect, so it does not stop a planner
+/// from moving backwards in time. Use `is_motion_admissible`, or a `TimeMotionValidator` which
+/// checks it before every motion, to forbid such motions and, with `max_speed`, motions that
+/// cover the inner space faster than the robot can move.
+///
+/// # Examples
+///
+/// ```
+/// use oxmpl::base::space::{RealVectorStateSpace, StateSpace, TimeStateSpace};
+/// use oxmpl::base::state::{RealVectorState, TimedState};
+///
+/// let inner = RealVectorStateSpace::new(1, Some(vec![(0.0, 10.0)])).unwrap();
+/// let mut space = TimeStateSpace::new(inner, (0.0, 20.0)).unwrap();
+/// space.max_speed = Some(1.0);
+///
+/// let a = TimedState::new(RealVectorState::new(vec![0.0]), 0.0);
+/// let b = TimedState::new(RealVectorState::new(vec![3.0]), 4.0);
+/// assert_eq!(space.distance(&a, &b), 7.0);
+/// assert!(space.is_motion_admissible(&a, &b));
+/// // Backwards in time.
+/// assert!(!space.is_motion_admissible(&b, &a));
+/// // Forwards in time, but faster than `max_speed`.
+/// let c = TimedState::new(RealVectorState::new(vec![9.0]), 5.0);
+/// assert!(!space.is_motion_admissible(&b, &c));
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeStateSpace<SP> {
+    /// The space the time dimension is added to.
+    pub inner: SP,
+    /// The `(earliest, latest)` times of states in the space.
+    pub time_bounds: (f64, f64),
+    /// The factor the difference in time is multiplied by before being added to the inner
+    /// distance. Defaults to 1.0.
+    pub time_weight: f64,
+    /// The largest inner distance that may be covered per unit of time, or `None` for no limit
+    /// (the default). Only motions are limited by it, through `is_motion_admissible`.
+    pub max_speed: Option<f64>,
+
+    longest_valid_segment_fraction: f64,
+}
+
+impl<SP: StateSpace> TimeStateSpace<SP> {
+    /// Creates a new `TimeStateSpace` over `inner`, with times between the bounds of
+    /// `time_bounds`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StateSpaceError::InvalidBound` if the time bounds are not finite or the lower
+    /// bound is not below the upper one.
+    pub fn new(inner: SP, time_bounds: (f64, f64)) -> Result<Self, StateSpaceError> {
+        let (lower, upper) = time_bounds;
+        if !(lower.is_finite() && upper.is_finite() && lower < upper) {
+            return Err(StateSpaceError::InvalidBound { lower, upper });
+        }
+        Ok(Self {
+            inner,
+            time_bounds,
+            time_weight: 1.0,
+            max_speed: None,
+            longest_valid_segment_fraction: 0.005,
+        })
+    }
+
+    /// Sets the fraction of the maximum extent used as the motion checking resolution.
+    ///
+    /// Motions are checked at steps of `fraction * get_maximum_extent()`, so a larger fraction
+    /// means fewer validity checks per motion. The fraction is clamped to `[0, 1]` and defaults to
+    /// 0.005.
+    pub fn set_longest_valid_segment_fraction(&mut self, fraction: f64) {
+        if fraction > 0.0 && fraction <= 1.0 {
+            self.longest_valid_segment_fraction = fraction;
+        } else if fraction <= 0.0 {
+            self.longest_valid_segment_fraction = 0.;
+        } else {
+            self.longest_valid_segment_fraction = 1.;
+        }
+    }
+
+    /// Returns whether the motion from `from` to `to` can be executed: it must not go backwards in
+    /// time, and if `max_speed` is set, it must not cover the inner space faster than that.
+    pub fn is_motion_admissible(
+        &self,
+        from: &TimedState<SP::StateType>,
+        to: &TimedState<SP::StateType>,
+    ) -> bool {
+        let duration = to.time - from.time;
+        if duration < 0.0 {
+            return false;
+        }
+        self.max_speed.is_none_or(|max_speed| {
+            self.inner.distance(&from.state, &to.state) <= max_speed * duration
+        })
+    }
+}
+
+impl<SP: StateSpace> StateSpace for TimeStateSpace<SP> {
+    type StateType = TimedState<SP::StateType>;
+
+    /// Computes the inner distance plus `time_weight` times the difference in time.
+    fn distance(&self, state1: &Self::StateType, state2: &Self::StateType) -> f64 {
+        self.inner.distance(&state1.state, &state2.state)
+            + self.time_weight * (state2.time - state1.time).abs()
+    }
+
+    /// Interpolates the inner states as the inner space does, and the times linearly.
+    fn interpolate(
+        &self,
+        from: &Self::StateType,
+        to: &Self::StateType,
+        t: f64,
+        out_state: &mut Self::StateType,
+    ) {
+        self.inner
+            .interpolate(&from.state, &to.state, t, &mut out_state.state);
+        out_state.time = from.time + (to.time - from.time) * t;
+    }
+
+    /// Enforces the inner space's bounds and clamps the time to `time_bounds`.
+    fn enforce_bounds(&self, state: &mut Self::StateType) {
+        self.inner.enforce_bounds(&mut state.state);
+        state.time = state.time.clamp(self.time_bounds.0, self.time_bounds.1);
+    }
+
+    /// Checks that the inner state is within the inner space's bounds and the time is within
+    /// `time_bounds`.
+    fn satisfies_bounds(&self, state: &Self::StateType) -> bool {
+        self.inner.satisfies_bounds(&state.state)
+            && (self.time_bounds.0..=self.time_bounds.1).contains(&state.time)
+    }
+
+    fn is_compatible(&self, state: &Self::StateType) -> bool {
+        self.inner.is_compatible(&state.state)
+    }
+
+    /// Samples the inner space uniformly, together with a uniformly random time.
+    ///
+    /// # Errors
+    ///
+    /// Returns the inner space's sampling error.
+    fn sample_uniform(&self, rng: &mut impl Rng) -> Result<Self::StateType, StateSamplingError> {
+        let state = self.inner.sample_uniform(rng)?;
+        let time = rng.random_range(self.time_bounds.0..self.time_bounds.1);
+        Ok(TimedState { state, time })
+    }
+
+    fn get_longest_valid_segment_length(&self) -> f64 {
+        self.get_maximum_extent() * self.longest_valid_segment_fraction
+    }
+
+    /// Returns the inner space's maximum extent plus the weighted length of `time_bounds`.
+    fn get_maximum_extent(&self) -> f64 {
+        self.inner.get_maximum_extent()
+            + self.time_weight * (self.time_bounds.1 - self.time_bounds.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{space::RealVectorStateSpace, state::RealVectorState};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn space() -> TimeStateSpace<RealVectorStateSpace> {
+        let inner = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+        TimeStateSpace::new(inner, (0.0, 5.0)).unwrap()
+    }
+
+    fn timed(x: f64, y: f64, time: f64) -> TimedState<RealVectorState> {
+        TimedState::new(RealVectorState::new(vec![x, y]), time)
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_time_bounds() {
+        let inner = RealVectorStateSpace::new(1, Some(vec![(0.0, 1.0)])).unwrap();
+        for (lower, upper) in [(1.0, 1.0), (2.0, 1.0), (0.0, f64::INFINITY)] {
+            assert!(matches!(
+                TimeStateSpace::new(inner.clone(), (lower, upper)),
+                Err(StateSpaceError::InvalidBound { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_distance_and_interpolation_include_time() {
+        let mut space = space();
+        let a = timed(0.0, 0.0, 1.0);
+        let b = timed(3.0, 4.0, 3.0);
+        assert_eq!(space.distance(&a, &b), 7.0);
+        assert_eq!(space.distance(&b, &a), 7.0);
+        space.time_weight = 0.5;
+        assert_eq!(space.distance(&a, &b), 6.0);
+
+        let mut out = a.clone();
+        space.interpolate(&a, &b, 0.5, &mut out);
+        assert_eq!(out, timed(1.5, 2.0, 2.0));
+    }
+
+    #[test]
+    fn test_bounds_and_sampling_cover_time() {
+        let space = space();
+        let mut state = timed(12.0, 5.0, 7.0);
+        assert!(!space.satisfies_bounds(&state));
+        space.enforce_bounds(&mut state);
+        assert_eq!(state, timed(10.0, 5.0, 5.0));
+        assert!(space.satisfies_bounds(&state));
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let state = space.sample_uniform(&mut rng).unwrap();
+            assert!((0.0..5.0).contains(&state.time));
+            assert!(space.satisfies_bounds(&state));
+        }
+    }
+
+    #[test]
+    fn test_motions_must_go_forwards_in_time_within_max_speed() {
+        let mut space = space();
+        let a = timed(0.0, 0.0, 1.0);
+        let b = timed(3.0, 4.0, 2.0);
+        assert!(space.is_motion_admissible(&a, &b));
+        assert!(!space.is_motion_admissible(&b, &a));
+        // Waiting in place takes no speed.
+        assert!(space.is_motion_admissible(&a, &timed(0.0, 0.0, 1.0)));
+
+        space.max_speed = Some(2.0);
+        assert!(!space.is_motion_admissible(&a, &b));
+        assert!(space.is_motion_admissible(&a, &timed(3.0, 4.0, 3.5)));
+    }
+}
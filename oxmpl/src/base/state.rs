@@ -2,9 +2,14 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
+pub use crate::base::states::compound_state::{CompoundState, DynState};
+pub use crate::base::states::grid_state::GridState;
 pub use crate::base::states::real_vector_state::RealVectorState;
+pub use crate::base::states::se2_state::SE2State;
+pub use crate::base::states::se3_state::SE3State;
 pub use crate::base::states::so2_state::SO2State;
 pub use crate::base::states::so3_state::SO3State;
+pub use crate::base::states::timed_state::TimedState;
 
 /// A marker trait for all state types in the planning library.
 ///
@@ -0,0 +1,111 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{any::Any, fmt};
+
+use crate::base::state::State;
+
+/// An object-safe, type-erased `State`, used for the components of a `CompoundState`.
+///
+/// `State` itself requires `Clone`, so it cannot be used as a trait object. `DynState` is
+/// implemented for every `State` that is also `Debug`, `Send`, `Sync` and `'static`, and recovers
+/// the concrete type through `as_any` and `as_any_mut`.
+pub trait DynState: fmt::Debug + Send + Sync {
+    /// Clones the state into a new box.
+    fn clone_box(&self) -> Box<dyn DynState>;
+
+    /// Returns the state as `&dyn Any`, for downcasting to its concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns the state as `&mut dyn Any`, for downcasting to its concrete type.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<S: State + fmt::Debug + Send + Sync + 'static> DynState for S {
+    fn clone_box(&self) -> Box<dyn DynState> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Clone for Box<dyn DynState> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// A state made up of one component state per subspace of a `CompoundStateSpace`.
+///
+/// Components are stored type-erased and in the same order as the subspaces. Use `component`
+/// and `component_mut` to access them as their concrete types.
+#[derive(Clone, Debug)]
+pub struct CompoundState {
+    /// The component states, one per subspace.
+    pub components: Vec<Box<dyn DynState>>,
+}
+impl CompoundState {
+    /// Creates a new `CompoundState` from its component states.
+    pub fn new(components: Vec<Box<dyn DynState>>) -> Self {
+        CompoundState { components }
+    }
+
+    /// Returns the component at `index` as an `S`, or `None` if the index is out of range or the
+    /// component is not an `S`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use oxmpl::base::state::{CompoundState, RealVectorState, SO2State};
+    ///
+    /// let state = CompoundState::new(vec![
+    ///     Box::new(RealVectorState::new(vec![1.0, 2.0])),
+    ///     Box::new(SO2State::new(0.5)),
+    /// ]);
+    ///
+    /// assert_eq!(state.component::<SO2State>(1), Some(&SO2State::new(0.5)));
+    /// assert_eq!(state.component::<SO2State>(0), None);
+    /// ```
+    pub fn component<S: State + 'static>(&self, index: usize) -> Option<&S> {
+        self.components.get(index)?.as_any().downcast_ref()
+    }
+
+    /// Returns the component at `index` as a mutable `S`, or `None` if the index is out of range
+    /// or the component is not an `S`.
+    pub fn component_mut<S: State + 'static>(&mut self, index: usize) -> Option<&mut S> {
+        self.components.get_mut(index)?.as_any_mut().downcast_mut()
+    }
+}
+impl State for CompoundState {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::state::{RealVectorState, SO2State};
+
+    #[test]
+    fn test_compound_state_clone_is_deep() {
+        let state1 = CompoundState::new(vec![
+            Box::new(RealVectorState::new(vec![1.0, 2.0])),
+            Box::new(SO2State::new(0.5)),
+        ]);
+        let mut state2 = state1.clone();
+        state2.component_mut::<RealVectorState>(0).unwrap().values[0] = 5.0;
+
+        assert_eq!(
+            state1.component::<RealVectorState>(0).unwrap().values,
+            vec![1.0, 2.0]
+        );
+        assert_eq!(
+            state2.component::<RealVectorState>(0).unwrap().values,
+            vec![5.0, 2.0]
+        );
+    }
+}
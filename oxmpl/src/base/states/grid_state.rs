@@ -0,0 +1,23 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use crate::base::state::State;
+
+/// A state representing a single cell of a 2D grid, addressed by its row and column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridState {
+    /// Row index of the cell, counted from 0.
+    pub row: usize,
+    /// Column index of the cell, counted from 0.
+    pub col: usize,
+}
+
+impl GridState {
+    /// Creates a new `GridState` for the cell at `row` and `col`.
+    pub fn new(row: usize, col: usize) -> Self {
+        GridState { row, col }
+    }
+}
+impl State for GridState {}
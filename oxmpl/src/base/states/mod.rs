@@ -2,6 +2,11 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
+pub mod compound_state;
+pub mod grid_state;
 pub mod real_vector_state;
+pub mod se2_state;
+pub mod se3_state;
 pub mod so2_state;
 pub mod so3_state;
+pub mod timed_state;
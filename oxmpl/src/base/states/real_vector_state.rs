@@ -1,7 +1,10 @@
+use std::ops::{Index, IndexMut};
+
 use crate::base::state::State;
 
 /// A state representing a point in an N-dimensional Euclidean space (R^n).
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RealVectorState {
     /// Values of each dimension of the state.
     pub values: Vec<f64>,
@@ -11,10 +14,114 @@ impl RealVectorState {
     pub fn new(vals: Vec<f64>) -> Self {
         RealVectorState { values: vals }
     }
+
+    /// Creates a `RealVectorState` at the origin of a `dim`-dimensional space.
+    pub fn zeros(dim: usize) -> Self {
+        RealVectorState::new(vec![0.0; dim])
+    }
+
+    /// Returns the number of dimensions of this state.
+    pub fn dim(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the element-wise sum of this state and `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the states have different dimensions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use oxmpl::base::state::RealVectorState;
+    ///
+    /// let a = RealVectorState::new(vec![1.0, 2.0]);
+    /// let b = RealVectorState::new(vec![0.5, -1.0]);
+    /// assert_eq!(a.add(&b).values, vec![1.5, 1.0]);
+    /// assert_eq!(a.sub(&b).values, vec![0.5, 3.0]);
+    /// assert_eq!(a.scale(2.0).values, vec![2.0, 4.0]);
+    /// ```
+    pub fn add(&self, other: &RealVectorState) -> RealVectorState {
+        self.zip_with(other, |a, b| a + b)
+    }
+
+    /// Returns the element-wise difference of this state and `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the states have different dimensions.
+    pub fn sub(&self, other: &RealVectorState) -> RealVectorState {
+        self.zip_with(other, |a, b| a - b)
+    }
+
+    /// Returns this state with every value multiplied by `factor`.
+    pub fn scale(&self, factor: f64) -> RealVectorState {
+        RealVectorState::new(self.values.iter().map(|value| value * factor).collect())
+    }
+
+    fn zip_with(&self, other: &RealVectorState, f: impl Fn(f64, f64) -> f64) -> RealVectorState {
+        assert_eq!(
+            self.dim(),
+            other.dim(),
+            "RealVectorStates must have the same dimension"
+        );
+        RealVectorState::new(
+            self.values
+                .iter()
+                .zip(&other.values)
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+        )
+    }
 }
+
+/// Indexes the values of the state, panicking if `index` is not less than `dim()`.
+impl Index<usize> for RealVectorState {
+    type Output = f64;
+
+    fn index(&self, index: usize) -> &f64 {
+        &self.values[index]
+    }
+}
+
+impl IndexMut<usize> for RealVectorState {
+    fn index_mut(&mut self, index: usize) -> &mut f64 {
+        &mut self.values[index]
+    }
+}
+
 /// Implements the `State` marker trait for `RealVectorState`.
 impl State for RealVectorState {}
 
+#[cfg(feature = "ndarray")]
+impl From<ndarray::Array1<f64>> for RealVectorState {
+    fn from(array: ndarray::Array1<f64>) -> Self {
+        RealVectorState::new(array.to_vec())
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl From<RealVectorState> for ndarray::Array1<f64> {
+    fn from(state: RealVectorState) -> Self {
+        ndarray::Array1::from(state.values)
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::DVector<f64>> for RealVectorState {
+    fn from(vector: nalgebra::DVector<f64>) -> Self {
+        RealVectorState::new(vector.data.into())
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<RealVectorState> for nalgebra::DVector<f64> {
+    fn from(state: RealVectorState) -> Self {
+        nalgebra::DVector::from_vec(state.values)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -35,4 +142,47 @@ mod tests {
         let state2 = state1.clone();
         assert_eq!(state1, state2);
     }
+
+    #[test]
+    fn test_real_vector_state_zeros_and_dim() {
+        let state = RealVectorState::zeros(3);
+        assert_eq!(state.dim(), 3);
+        assert_eq!(state.values, vec![0.0; 3]);
+        assert_eq!(RealVectorState::zeros(0).dim(), 0);
+    }
+
+    #[test]
+    fn test_real_vector_state_indexing() {
+        let mut state = RealVectorState::new(vec![1.0, 2.0]);
+        assert_eq!(state[0], 1.0);
+        assert_eq!(state[1], 2.0);
+
+        state[1] = 5.0;
+        assert_eq!(state.values, vec![1.0, 5.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_real_vector_state_index_out_of_bounds_panics() {
+        let state = RealVectorState::new(vec![1.0, 2.0]);
+        let _ = state[2];
+    }
+
+    #[test]
+    fn test_real_vector_state_arithmetic() {
+        let a = RealVectorState::new(vec![1.0, 2.0, 3.0]);
+        let b = RealVectorState::new(vec![0.5, -1.0, 0.0]);
+
+        assert_eq!(a.add(&b).values, vec![1.5, 1.0, 3.0]);
+        assert_eq!(a.sub(&b).values, vec![0.5, 3.0, 3.0]);
+        assert_eq!(a.scale(-2.0).values, vec![-2.0, -4.0, -6.0]);
+        // The operands are left unchanged.
+        assert_eq!(a.values, vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "same dimension")]
+    fn test_real_vector_state_add_with_mismatched_dimensions_panics() {
+        let _ = RealVectorState::new(vec![1.0, 2.0]).add(&RealVectorState::zeros(3));
+    }
 }
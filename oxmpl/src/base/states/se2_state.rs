@@ -0,0 +1,42 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use crate::base::state::{SO2State, State};
+
+/// A state representing a rigid-body pose in the plane, an element of the Special Euclidean group
+/// SE(2).
+///
+/// The pose is stored as a translation together with a heading.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SE2State {
+    /// The position `[x, y]` of the body.
+    pub translation: [f64; 2],
+    /// The heading of the body.
+    pub rotation: SO2State,
+}
+impl SE2State {
+    /// Creates a new `SE2State` from a position and a heading in radians, normalised to range
+    /// `[-PI, PI)`.
+    pub fn new(x: f64, y: f64, theta: f64) -> Self {
+        SE2State {
+            translation: [x, y],
+            rotation: SO2State::new(theta),
+        }
+    }
+}
+impl State for SE2State {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_se2_state_creation_normalises_heading() {
+        let state = SE2State::new(1.0, 2.0, 3.0 * PI / 2.0);
+        assert_eq!(state.translation, [1.0, 2.0]);
+        assert!((state.rotation.value + PI / 2.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use crate::base::state::{SO3State, State};
+
+/// A state representing a rigid-body pose in 3D, an element of the Special Euclidean group SE(3).
+///
+/// The pose is stored as a translation together with a rotation given as a unit quaternion.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SE3State {
+    /// The position `[x, y, z]` of the body.
+    pub translation: [f64; 3],
+    /// The orientation of the body.
+    pub rotation: SO3State,
+}
+impl SE3State {
+    /// Creates a new `SE3State` from a translation and a rotation.
+    ///
+    /// > [!NOTE]
+    /// > As with `SO3State::new`, the rotation is not normalised.
+    pub fn new(translation: [f64; 3], rotation: SO3State) -> Self {
+        SE3State {
+            translation,
+            rotation,
+        }
+    }
+}
+impl State for SE3State {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_se3_state_creation() {
+        let state = SE3State::new([1.0, 2.0, 3.0], SO3State::identity());
+        assert_eq!(state.translation, [1.0, 2.0, 3.0]);
+        assert_eq!(state.rotation, SO3State::identity());
+    }
+}
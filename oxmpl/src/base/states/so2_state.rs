@@ -9,6 +9,7 @@ use std::f64::consts::PI;
 ///
 /// The angle is stored in radians and is canonically represented in the range `[-PI, PI)`.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SO2State {
     /// Angle of rotation in radians.
     pub value: f64,
@@ -39,7 +40,7 @@ impl SO2State {
 
     /// Normalises the state's angle in-place to range `[-PI, PI)`.
     ///
-    /// Method modifies the current state.
+    /// Use `normalised` to get a normalised copy instead.
     ///
     /// # Examples
     ///
@@ -48,14 +49,53 @@ impl SO2State {
     /// use oxmpl::base::state::SO2State;
     ///
     /// let mut state = SO2State { value: 3.0 * PI / 2.0 }; // Equivalent to -PI/2
-    /// state = state.normalise();
+    /// state.normalise();
     ///
     /// assert!((state.value + PI / 2.0).abs() < 1e-9);
     /// ```
-    pub fn normalise(&mut self) -> Self {
-        SO2State {
-            value: (self.value + PI).rem_euclid(2.0 * PI) - PI,
-        }
+    pub fn normalise(&mut self) {
+        *self = self.normalised();
+    }
+
+    /// Returns a copy of the state with its angle normalised to range `[-PI, PI)`, leaving the
+    /// state itself unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use oxmpl::base::state::SO2State;
+    ///
+    /// let state = SO2State { value: 3.0 * PI / 2.0 };
+    ///
+    /// assert!((state.normalised().value + PI / 2.0).abs() < 1e-9);
+    /// assert_eq!(state.value, 3.0 * PI / 2.0);
+    /// ```
+    pub fn normalised(&self) -> Self {
+        SO2State::new(self.value)
+    }
+
+    /// Returns the heading of this rotation as the unit vector `[cos, sin]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use oxmpl::base::state::SO2State;
+    ///
+    /// let [x, y] = SO2State::new(PI / 2.0).to_unit_vector();
+    /// assert!(x.abs() < 1e-9 && (y - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn to_unit_vector(&self) -> [f64; 2] {
+        [self.value.cos(), self.value.sin()]
+    }
+
+    /// Creates a new `SO2State` from a heading vector `[x, y]`, normalised to range `[-PI, PI)`.
+    ///
+    /// Only the direction of `vector` matters, so it does not need to have unit length. The zero
+    /// vector maps to an angle of 0.
+    pub fn from_unit_vector(vector: [f64; 2]) -> Self {
+        SO2State::new(vector[1].atan2(vector[0]))
     }
 }
 impl State for SO2State {}
@@ -79,10 +119,36 @@ mod tests {
 
     #[test]
     fn test_so2_state_normalise() {
-        let mut state1 = SO2State {
+        let mut state = SO2State {
             value: 3.0 * PI / 2.0,
         };
-        let state2 = state1.normalise();
-        assert_eq!(state2.value, -PI / 2.0);
+        state.normalise();
+        assert_eq!(state.value, -PI / 2.0);
+    }
+
+    #[test]
+    fn test_so2_state_normalised_leaves_state_unchanged() {
+        let state = SO2State { value: -5.0 * PI };
+        assert_eq!(state.normalised().value, -PI);
+        assert_eq!(state.value, -5.0 * PI);
+    }
+
+    #[test]
+    fn test_so2_state_unit_vector_round_trip() {
+        let cardinals = [
+            (0.0, [1.0, 0.0]),
+            (PI / 2.0, [0.0, 1.0]),
+            (-PI, [-1.0, 0.0]),
+            (-PI / 2.0, [0.0, -1.0]),
+        ];
+
+        for (angle, expected) in cardinals {
+            let vector = SO2State::new(angle).to_unit_vector();
+            assert!((vector[0] - expected[0]).abs() < 1e-9);
+            assert!((vector[1] - expected[1]).abs() < 1e-9);
+
+            let round_trip = SO2State::from_unit_vector(vector);
+            assert!((round_trip.value - angle).abs() < 1e-9);
+        }
     }
 }
@@ -7,7 +7,11 @@ use crate::base::{error::StateError, state::State};
 /// A state representing a 3D rotation, an element of the Special Orthogonal group SO(3).
 ///
 /// The rotation is stored as a unit quaternion `(x, y, z, w)`.
+///
+/// With the `serde` feature, the raw components are serialized and deserialized as they are,
+/// without normalising.
 #[derive(Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SO3State {
     /// The x-component of the quaternion's vector.
     pub x: f64,
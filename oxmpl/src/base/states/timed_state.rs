@@ -0,0 +1,36 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use crate::base::state::State;
+
+/// A state of an inner state space together with the time at which it is reached, an element of
+/// a `TimeStateSpace`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedState<S> {
+    /// The state in the inner space.
+    pub state: S,
+    /// The time at which `state` is reached.
+    pub time: f64,
+}
+impl<S> TimedState<S> {
+    /// Creates a new `TimedState` from an inner state and a time.
+    pub fn new(state: S, time: f64) -> Self {
+        TimedState { state, time }
+    }
+}
+impl<S: State> State for TimedState<S> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::state::RealVectorState;
+
+    #[test]
+    fn test_timed_state_creation() {
+        let state = TimedState::new(RealVectorState::new(vec![1.0, 2.0]), 3.0);
+        assert_eq!(state.state.values, vec![1.0, 2.0]);
+        assert_eq!(state.time, 3.0);
+    }
+}
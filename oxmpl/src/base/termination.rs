@@ -0,0 +1,135 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::time::Duration;
+
+/// A condition under which a planner stops searching for a solution.
+///
+/// Planners check the condition at the top of every iteration of their main loop. A planner
+/// stopped by `Timeout` returns `PlanningError::Timeout`; one stopped by any other condition
+/// returns `PlanningError::NoSolutionFound`, unless it has a solution to return.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::{atomic::AtomicBool, Arc};
+/// use std::time::Duration;
+///
+/// use oxmpl::base::termination::{TerminationCondition, TerminationReason};
+///
+/// let cancel = Arc::new(AtomicBool::new(false));
+/// let condition = TerminationCondition::Timeout(Duration::from_secs(5))
+///     .or(TerminationCondition::MaxIterations(1000))
+///     .or(TerminationCondition::Cancelled(cancel.clone()));
+///
+/// assert_eq!(condition.check(Duration::from_secs(1), 10, 10), None);
+/// assert_eq!(
+///     condition.check(Duration::from_secs(1), 1000, 10),
+///     Some(TerminationReason::MaxIterations)
+/// );
+///
+/// cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+/// assert_eq!(
+///     condition.check(Duration::from_secs(1), 10, 10),
+///     Some(TerminationReason::Cancelled)
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub enum TerminationCondition {
+    /// Stop once more than the given time has elapsed since planning started.
+    Timeout(Duration),
+    /// Stop after the given number of iterations of the planner's main loop.
+    MaxIterations(usize),
+    /// Stop once the planner's tree holds at least the given number of nodes.
+    MaxTreeSize(usize),
+    /// Stop as soon as the flag is set, e.g. from another thread.
+    Cancelled(Arc<AtomicBool>),
+    /// Stop as soon as any of the conditions is met. An empty `Any` is never met.
+    Any(Vec<TerminationCondition>),
+}
+
+/// The reason a `TerminationCondition` was met.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerminationReason {
+    Timeout,
+    MaxIterations,
+    MaxTreeSize,
+    Cancelled,
+}
+
+impl TerminationCondition {
+    /// Combines this condition with `other`, so that planning stops as soon as either is met.
+    pub fn or(self, other: TerminationCondition) -> Self {
+        match self {
+            Self::Any(mut conditions) => {
+                conditions.push(other);
+                Self::Any(conditions)
+            }
+            condition => Self::Any(vec![condition, other]),
+        }
+    }
+
+    /// Checks the condition against a planner's progress.
+    ///
+    /// # Parameters
+    /// * `elapsed` - The time since planning started.
+    /// * `iterations` - The number of main loop iterations completed so far.
+    /// * `tree_size` - The number of nodes in the planner's tree.
+    ///
+    /// # Returns
+    ///
+    /// The reason planning should stop, or `None` to carry on. For `Any`, the reason of the first
+    /// condition met is returned.
+    pub fn check(
+        &self,
+        elapsed: Duration,
+        iterations: usize,
+        tree_size: usize,
+    ) -> Option<TerminationReason> {
+        match self {
+            Self::Timeout(timeout) => (elapsed > *timeout).then_some(TerminationReason::Timeout),
+            Self::MaxIterations(max) => {
+                (iterations >= *max).then_some(TerminationReason::MaxIterations)
+            }
+            Self::MaxTreeSize(max) => (tree_size >= *max).then_some(TerminationReason::MaxTreeSize),
+            Self::Cancelled(flag) => flag
+                .load(Ordering::Relaxed)
+                .then_some(TerminationReason::Cancelled),
+            Self::Any(conditions) => conditions
+                .iter()
+                .find_map(|condition| condition.check(elapsed, iterations, tree_size)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_or_flattens_into_a_single_any() {
+        let condition = TerminationCondition::MaxIterations(10)
+            .or(TerminationCondition::MaxTreeSize(5))
+            .or(TerminationCondition::Timeout(Duration::from_secs(1)));
+
+        match &condition {
+            TerminationCondition::Any(conditions) => assert_eq!(conditions.len(), 3),
+            other => panic!("Expected Any, got {other:?}"),
+        }
+        assert_eq!(
+            condition.check(Duration::ZERO, 0, 5),
+            Some(TerminationReason::MaxTreeSize)
+        );
+        assert_eq!(
+            condition.check(Duration::from_secs(2), 0, 0),
+            Some(TerminationReason::Timeout)
+        );
+        assert_eq!(condition.check(Duration::ZERO, 9, 4), None);
+    }
+}
@@ -2,7 +2,21 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
-use crate::base::state;
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::base::{
+    error::ValidityCheckerError,
+    space::StateSpace,
+    state,
+    state::{GridState, RealVectorState},
+};
 
 /// A trait for checking if states are valid.
 ///
@@ -45,4 +59,617 @@ pub trait StateValidityChecker<S: state::State> {
     /// # Returns
     /// Returns `true` if the state is valid, and `false` otherwise.
     fn is_valid(&self, state: &S) -> bool;
+
+    /// Returns, and clears, the first error raised while checking states since the last call.
+    ///
+    /// A checker backed by fallible code, such as a callback into another language, can record a
+    /// failure here, reporting the state as invalid in the meantime. Planners poll this once per
+    /// iteration and abort with `PlanningError::ValidityCheckFailed` if an error was recorded.
+    ///
+    /// The default implementation never reports an error.
+    fn take_error(&self) -> Option<String> {
+        None
+    }
+}
+
+/// A `StateValidityChecker` for environments that change over time, e.g. with moving obstacles.
+///
+/// The generation is a counter that must be bumped whenever the environment changes, so that
+/// anything derived from earlier validity checks (cached results, a PRM roadmap) knows to
+/// invalidate itself. It only needs to be compared for equality, so it may also wrap around.
+pub trait IncrementalValidityChecker<S: state::State>: StateValidityChecker<S> {
+    /// Returns the current generation of the environment.
+    fn generation(&self) -> u64;
+}
+
+/// A `StateValidityChecker` that memoises the results of an expensive inner checker.
+///
+/// States are keyed by the exact bit patterns of their values, so this only pays off when the
+/// same states are checked repeatedly, e.g. lattice or roadmap states. The memo is flushed
+/// whenever the inner checker's generation changes.
+pub struct CachingValidityChecker<C> {
+    inner: C,
+    cache: Mutex<ValidityCache>,
+}
+
+struct ValidityCache {
+    generation: u64,
+    memo: HashMap<Vec<u64>, bool>,
+}
+
+impl<C: IncrementalValidityChecker<RealVectorState>> CachingValidityChecker<C> {
+    /// Creates a new `CachingValidityChecker` wrapping `inner`.
+    pub fn new(inner: C) -> Self {
+        let generation = inner.generation();
+        Self {
+            inner,
+            cache: Mutex::new(ValidityCache {
+                generation,
+                memo: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Returns the wrapped checker.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns the number of states currently memoised.
+    pub fn cached_len(&self) -> usize {
+        self.cache.lock().unwrap().memo.len()
+    }
+}
+
+impl<C: IncrementalValidityChecker<RealVectorState>> StateValidityChecker<RealVectorState>
+    for CachingValidityChecker<C>
+{
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let mut cache = self.cache.lock().unwrap();
+
+        let generation = self.inner.generation();
+        if cache.generation != generation {
+            cache.memo.clear();
+            cache.generation = generation;
+        }
+
+        let key = state.values.iter().map(|v| v.to_bits()).collect();
+        *cache
+            .memo
+            .entry(key)
+            .or_insert_with(|| self.inner.is_valid(state))
+    }
+}
+
+impl<C: IncrementalValidityChecker<RealVectorState>> IncrementalValidityChecker<RealVectorState>
+    for CachingValidityChecker<C>
+{
+    fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+}
+
+/// A `StateValidityChecker` that counts the checks passed to an inner checker, optionally up to a
+/// budget.
+///
+/// Planners wrap the checker given to `setup` in one of these, so that `validity_check_count`
+/// can report how many checks a run made and `set_max_validity_checks` can bound them. Once the
+/// budget is spent, further checks are refused without calling the inner checker: the state is
+/// reported as invalid and the refusal is recorded, and planners poll for it once per iteration,
+/// aborting with `PlanningError::CheckBudgetExhausted`.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use oxmpl::base::state::RealVectorState;
+/// use oxmpl::base::validity::{CountingValidityChecker, StateValidityChecker};
+///
+/// struct AlwaysValid;
+///
+/// impl StateValidityChecker<RealVectorState> for AlwaysValid {
+///     fn is_valid(&self, _state: &RealVectorState) -> bool {
+///         true
+///     }
+/// }
+///
+/// let checker = CountingValidityChecker::new(Arc::new(AlwaysValid), Some(2));
+/// let state = RealVectorState::new(vec![0.0]);
+///
+/// assert!(checker.is_valid(&state));
+/// assert!(checker.is_valid(&state));
+/// assert!(!checker.budget_exhausted());
+///
+/// // The third check is over budget.
+/// assert!(!checker.is_valid(&state));
+/// assert!(checker.budget_exhausted());
+/// assert_eq!(checker.count(), 2);
+/// ```
+pub struct CountingValidityChecker<
+    S: state::State,
+    C: StateValidityChecker<S> + ?Sized = dyn StateValidityChecker<S>,
+> {
+    inner: Arc<C>,
+    budget: Arc<CheckBudget>,
+    _state: PhantomData<fn(&S)>,
+}
+
+/// The check count and budget of a `CountingValidityChecker`, shared with any thread-safe view of
+/// it that a planner makes.
+struct CheckBudget {
+    max_checks: Option<usize>,
+    count: AtomicUsize,
+    refused: AtomicBool,
+}
+
+impl<S: state::State> CountingValidityChecker<S> {
+    /// Creates a new `CountingValidityChecker` wrapping `inner`, which allows at most
+    /// `max_checks` checks, or any number if it is `None`.
+    pub fn new(inner: Arc<dyn StateValidityChecker<S>>, max_checks: Option<usize>) -> Self {
+        Self {
+            inner,
+            budget: Arc::new(CheckBudget {
+                max_checks,
+                count: AtomicUsize::new(0),
+                refused: AtomicBool::new(false),
+            }),
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<S: state::State, C: StateValidityChecker<S> + ?Sized> CountingValidityChecker<S, C> {
+    /// Returns a checker wrapping `inner` that shares this checker's count and budget.
+    ///
+    /// `inner` should check the same states as this checker's own inner checker. This lets a
+    /// planner keep a thread-safe view of the counting checker made in `setup`, whose checks
+    /// count towards the same budget.
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    pub(crate) fn with_inner<D: StateValidityChecker<S> + ?Sized>(
+        &self,
+        inner: Arc<D>,
+    ) -> CountingValidityChecker<S, D> {
+        CountingValidityChecker {
+            inner,
+            budget: self.budget.clone(),
+            _state: PhantomData,
+        }
+    }
+
+    /// Returns the budget of checks, if there is one.
+    pub fn max_checks(&self) -> Option<usize> {
+        self.budget.max_checks
+    }
+
+    /// Returns the number of checks passed to the inner checker.
+    pub fn count(&self) -> usize {
+        self.budget.count.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if a check was refused because the budget was spent.
+    pub fn budget_exhausted(&self) -> bool {
+        self.budget.refused.load(Ordering::Relaxed)
+    }
+}
+
+impl<S: state::State, C: StateValidityChecker<S> + ?Sized> StateValidityChecker<S>
+    for CountingValidityChecker<S, C>
+{
+    fn is_valid(&self, state: &S) -> bool {
+        if self.max_checks().is_some_and(|max| self.count() >= max) {
+            self.budget.refused.store(true, Ordering::Relaxed);
+            return false;
+        }
+        self.budget.count.fetch_add(1, Ordering::Relaxed);
+        self.inner.is_valid(state)
+    }
+
+    fn take_error(&self) -> Option<String> {
+        self.inner.take_error()
+    }
+}
+
+/// A `StateValidityChecker` that keeps a safety margin around the obstacles of an inner checker.
+///
+/// A state is valid only if it and `num_samples` points at distance `radius` around it are all
+/// valid according to the inner checker. This approximates inflating the obstacles by `radius`
+/// without a distance field, so thin obstacles can still be missed between the checked points.
+///
+/// The points lie in a fixed set of directions, the same for every state: first along each
+/// coordinate axis in both directions, then along the diagonals between two axes, then three, and
+/// so on, until `num_samples` points are found. In 2D, 8 points cover the axes and diagonals.
+/// Each point is placed at distance `radius` from the state under the metric of the space, and
+/// brought within its bounds if it lies outside them, so the same state always gets the same
+/// answer and states near the bounds are probed in the same directions as any other.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use oxmpl::base::space::RealVectorStateSpace;
+/// use oxmpl::base::state::RealVectorState;
+/// use oxmpl::base::validity::{InflatedChecker, StateValidityChecker};
+///
+/// // Everything right of x = 5 is an obstacle.
+/// struct HalfPlane;
+///
+/// impl StateValidityChecker<RealVectorState> for HalfPlane {
+///     fn is_valid(&self, state: &RealVectorState) -> bool {
+///         state.values[0] < 5.0
+///     }
+/// }
+///
+/// let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0); 2])).unwrap());
+/// let checker = InflatedChecker::new(space, Arc::new(HalfPlane), 1.0, 32);
+///
+/// assert!(checker.is_valid(&RealVectorState::new(vec![2.0, 5.0])));
+/// assert!(!checker.is_valid(&RealVectorState::new(vec![4.5, 5.0])));
+/// ```
+pub struct InflatedChecker<SP: StateSpace> {
+    /// The distance around each state that must be free of obstacles.
+    pub radius: f64,
+    /// The number of points around each state that are checked.
+    pub num_samples: usize,
+    space: Arc<SP>,
+    inner: Arc<dyn StateValidityChecker<SP::StateType>>,
+}
+
+impl<SP: StateSpace> InflatedChecker<SP> {
+    /// Creates a new `InflatedChecker` that inflates the obstacles of `inner` in `space` by
+    /// `radius`, checking `num_samples` points around each state.
+    pub fn new(
+        space: Arc<SP>,
+        inner: Arc<dyn StateValidityChecker<SP::StateType>>,
+        radius: f64,
+        num_samples: usize,
+    ) -> Self {
+        Self {
+            radius,
+            num_samples,
+            space,
+            inner,
+        }
+    }
+}
+
+impl<SP> StateValidityChecker<RealVectorState> for InflatedChecker<SP>
+where
+    SP: StateSpace<StateType = RealVectorState>,
+{
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        if !self.inner.is_valid(state) {
+            return false;
+        }
+
+        let mut point = state.clone();
+        for direction in probe_directions(state.values.len(), self.num_samples) {
+            let step = RealVectorState::new(
+                state
+                    .values
+                    .iter()
+                    .zip(&direction)
+                    .map(|(x, d)| x + d)
+                    .collect(),
+            );
+            let distance = self.space.distance(state, &step);
+            if distance.is_nan() || distance <= 0.0 {
+                continue;
+            }
+            let scale = self.radius / distance;
+            for ((p, x), d) in point.values.iter_mut().zip(&state.values).zip(&direction) {
+                *p = x + d * scale;
+            }
+            self.space.enforce_bounds(&mut point);
+            if !self.inner.is_valid(&point) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn take_error(&self) -> Option<String> {
+        self.inner.take_error()
+    }
+}
+
+/// Returns up to `limit` directions in `dimension` dimensions, in the order `InflatedChecker`
+/// probes them: each coordinate axis in both directions, then the diagonals through two axes, then
+/// three, and so on.
+///
+/// The directions are not normalised, as each is scaled to the inflation radius under the metric
+/// of the space.
+fn probe_directions(dimension: usize, limit: usize) -> Vec<Vec<f64>> {
+    let mut directions = Vec::new();
+    for num_axes in 1..=dimension {
+        // The axes of the current diagonal, advanced through every combination in turn.
+        let mut axes: Vec<usize> = (0..num_axes).collect();
+        loop {
+            let num_sign_patterns = 1usize.checked_shl(num_axes as u32).unwrap_or(usize::MAX);
+            for signs in 0..num_sign_patterns {
+                if directions.len() == limit {
+                    return directions;
+                }
+                let mut direction = vec![0.0; dimension];
+                for (bit, &axis) in axes.iter().enumerate() {
+                    let negative = signs.checked_shr(bit as u32).unwrap_or(0) & 1 == 1;
+                    direction[axis] = if negative { -1.0 } else { 1.0 };
+                }
+                directions.push(direction);
+            }
+
+            // Advance to the next combination of axes, if there is one.
+            let Some(i) = (0..num_axes)
+                .rev()
+                .find(|&i| axes[i] < dimension - num_axes + i)
+            else {
+                break;
+            };
+            axes[i] += 1;
+            for j in i + 1..num_axes {
+                axes[j] = axes[j - 1] + 1;
+            }
+        }
+    }
+    directions
+}
+
+/// A `StateValidityChecker` backed by a 2D occupancy grid.
+///
+/// The grid covers an axis-aligned rectangle starting at `origin` (the lower-left corner) and
+/// extending `width * cell_size` along x and `height * cell_size` along y. Occupancy is stored in
+/// row-major order, so the cell at column `col` and row `row` is `occupancy[row * width + col]`,
+/// with row 0 at the bottom of the map.
+///
+/// A state is valid if its first two coordinates fall inside a free cell. States outside the
+/// grid are treated as invalid.
+///
+/// # Example
+///
+/// ```
+/// use oxmpl::base::state::RealVectorState;
+/// use oxmpl::base::validity::{GridOccupancyChecker, StateValidityChecker};
+///
+/// // A 2x2 grid of unit cells where only the top-right cell is occupied.
+/// let checker =
+///     GridOccupancyChecker::new(2, 2, 1.0, (0.0, 0.0), vec![false, false, false, true]).unwrap();
+///
+/// assert!(checker.is_valid(&RealVectorState::new(vec![0.5, 0.5])));
+/// assert!(!checker.is_valid(&RealVectorState::new(vec![1.5, 1.5])));
+/// assert!(!checker.is_valid(&RealVectorState::new(vec![2.5, 0.5])));
+/// ```
+#[derive(Clone, Debug)]
+pub struct GridOccupancyChecker {
+    /// Number of cells along the x-axis.
+    pub width: usize,
+    /// Number of cells along the y-axis.
+    pub height: usize,
+    /// Side length of a single (square) cell.
+    pub cell_size: f64,
+    /// World coordinates of the lower-left corner of the grid.
+    pub origin: (f64, f64),
+    /// Row-major occupancy data. `true` marks an occupied cell.
+    pub occupancy: Vec<bool>,
+}
+
+impl GridOccupancyChecker {
+    /// Creates a new `GridOccupancyChecker`.
+    ///
+    /// # Errors
+    ///
+    /// * `ValidityCheckerError::OccupancyMismatch` if `occupancy.len() != width * height`.
+    /// * `ValidityCheckerError::InvalidCellSize` if `cell_size` is not strictly positive and
+    ///   finite.
+    pub fn new(
+        width: usize,
+        height: usize,
+        cell_size: f64,
+        origin: (f64, f64),
+        occupancy: Vec<bool>,
+    ) -> Result<Self, ValidityCheckerError> {
+        if !(cell_size > 0.0 && cell_size.is_finite()) {
+            return Err(ValidityCheckerError::InvalidCellSize { cell_size });
+        }
+        if occupancy.len() != width * height {
+            return Err(ValidityCheckerError::OccupancyMismatch {
+                expected: width * height,
+                found: occupancy.len(),
+            });
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cell_size,
+            origin,
+            occupancy,
+        })
+    }
+
+    /// Maps a world-space `(x, y)` point to its `(col, row)` cell, or `None` if the point lies
+    /// outside the grid.
+    pub fn cell_of(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+        let col = ((x - self.origin.0) / self.cell_size).floor();
+        let row = ((y - self.origin.1) / self.cell_size).floor();
+
+        if col < 0.0 || row < 0.0 || col >= self.width as f64 || row >= self.height as f64 {
+            return None;
+        }
+        Some((col as usize, row as usize))
+    }
+
+    /// Returns `true` if the cell at `(col, row)` is occupied.
+    ///
+    /// Cells outside the grid are reported as occupied.
+    pub fn is_occupied(&self, col: usize, row: usize) -> bool {
+        if col >= self.width || row >= self.height {
+            return true;
+        }
+        self.occupancy[row * self.width + col]
+    }
+}
+
+impl StateValidityChecker<RealVectorState> for GridOccupancyChecker {
+    /// Checks the cell containing the state's first two coordinates.
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        if state.values.len() < 2 {
+            return false;
+        }
+        match self.cell_of(state.values[0], state.values[1]) {
+            Some((col, row)) => !self.is_occupied(col, row),
+            None => false,
+        }
+    }
+}
+
+impl StateValidityChecker<GridState> for GridOccupancyChecker {
+    /// Checks the cell with the state's row and column, ignoring `cell_size` and `origin`.
+    fn is_valid(&self, state: &GridState) -> bool {
+        !self.is_occupied(state.col, state.row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering as AtomicOrdering};
+
+    #[test]
+    fn test_probe_directions_cover_axes_before_diagonals() {
+        let directions = probe_directions(3, usize::MAX);
+        // 6 axis directions, 12 diagonals through two axes and 8 through all three.
+        assert_eq!(directions.len(), 26);
+        let nonzeros: Vec<usize> = directions
+            .iter()
+            .map(|d| d.iter().filter(|x| **x != 0.0).count())
+            .collect();
+        assert!(nonzeros.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(nonzeros.iter().filter(|&&n| n == 1).count(), 6);
+
+        let first = probe_directions(2, 4);
+        assert_eq!(
+            first,
+            vec![
+                vec![1.0, 0.0],
+                vec![-1.0, 0.0],
+                vec![0.0, 1.0],
+                vec![0.0, -1.0]
+            ]
+        );
+    }
+
+    // A 3x2 grid with 0.5m cells, offset from the world origin.
+    //
+    //   row 1: [free, occ,  free]
+    //   row 0: [free, free, occ ]
+    fn test_grid() -> GridOccupancyChecker {
+        GridOccupancyChecker::new(
+            3,
+            2,
+            0.5,
+            (1.0, -1.0),
+            vec![false, false, true, false, true, false],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_grid_occupancy_free_cells_are_valid() {
+        let checker = test_grid();
+        assert!(checker.is_valid(&RealVectorState::new(vec![1.25, -0.75])));
+        assert!(checker.is_valid(&RealVectorState::new(vec![1.75, -0.75])));
+        assert!(checker.is_valid(&RealVectorState::new(vec![2.25, -0.25])));
+    }
+
+    #[test]
+    fn test_grid_occupancy_occupied_cells_are_invalid() {
+        let checker = test_grid();
+        assert!(!checker.is_valid(&RealVectorState::new(vec![2.25, -0.75])));
+        assert!(!checker.is_valid(&RealVectorState::new(vec![1.75, -0.25])));
+    }
+
+    #[test]
+    fn test_grid_occupancy_out_of_grid_is_invalid() {
+        let checker = test_grid();
+        assert!(!checker.is_valid(&RealVectorState::new(vec![0.9, -0.75])));
+        assert!(!checker.is_valid(&RealVectorState::new(vec![1.25, -1.1])));
+        assert!(!checker.is_valid(&RealVectorState::new(vec![2.5, -0.75])));
+        assert!(!checker.is_valid(&RealVectorState::new(vec![1.25, 0.0])));
+    }
+
+    #[test]
+    fn test_grid_occupancy_checks_grid_states_by_cell() {
+        let checker = test_grid();
+        assert!(checker.is_valid(&GridState::new(0, 0)));
+        assert!(!checker.is_valid(&GridState::new(0, 2)));
+        assert!(!checker.is_valid(&GridState::new(1, 1)));
+        assert!(!checker.is_valid(&GridState::new(2, 0)));
+    }
+
+    #[test]
+    fn test_grid_occupancy_rejects_mismatched_data() {
+        let result = GridOccupancyChecker::new(3, 2, 0.5, (0.0, 0.0), vec![false; 5]);
+        assert_eq!(
+            result.err(),
+            Some(ValidityCheckerError::OccupancyMismatch {
+                expected: 6,
+                found: 5
+            })
+        );
+
+        let result = GridOccupancyChecker::new(3, 2, 0.0, (0.0, 0.0), vec![false; 6]);
+        assert_eq!(
+            result.err(),
+            Some(ValidityCheckerError::InvalidCellSize { cell_size: 0.0 })
+        );
+    }
+
+    /// A circular obstacle that can be moved, counting how often it is queried.
+    struct MovingObstacle {
+        centre: Mutex<(f64, f64)>,
+        generation: AtomicU64,
+        checks: AtomicUsize,
+    }
+
+    impl MovingObstacle {
+        fn move_to(&self, centre: (f64, f64)) {
+            *self.centre.lock().unwrap() = centre;
+            self.generation.fetch_add(1, AtomicOrdering::SeqCst);
+        }
+    }
+
+    impl StateValidityChecker<RealVectorState> for MovingObstacle {
+        fn is_valid(&self, state: &RealVectorState) -> bool {
+            self.checks.fetch_add(1, AtomicOrdering::SeqCst);
+            let (cx, cy) = *self.centre.lock().unwrap();
+            (state.values[0] - cx).hypot(state.values[1] - cy) > 1.0
+        }
+    }
+
+    impl IncrementalValidityChecker<RealVectorState> for MovingObstacle {
+        fn generation(&self) -> u64 {
+            self.generation.load(AtomicOrdering::SeqCst)
+        }
+    }
+
+    #[test]
+    fn test_caching_checker_flushes_memo_on_generation_change() {
+        let checker = CachingValidityChecker::new(MovingObstacle {
+            centre: Mutex::new((0.0, 0.0)),
+            generation: AtomicU64::new(0),
+            checks: AtomicUsize::new(0),
+        });
+        let state = RealVectorState::new(vec![5.0, 5.0]);
+
+        assert!(checker.is_valid(&state));
+        assert!(checker.is_valid(&state));
+        assert_eq!(checker.inner().checks.load(AtomicOrdering::SeqCst), 1);
+        assert_eq!(checker.cached_len(), 1);
+
+        // Moving the obstacle onto the state must not return the stale cached result.
+        checker.inner().move_to((5.0, 5.0));
+        assert!(!checker.is_valid(&state));
+        assert_eq!(checker.inner().checks.load(AtomicOrdering::SeqCst), 2);
+        assert_eq!(checker.cached_len(), 1);
+        assert_eq!(checker.generation(), 1);
+    }
 }
@@ -2,9 +2,12 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
+pub mod path_utils;
 mod planners;
 
-pub use self::planners::prm::PRM;
+pub use self::planners::fmt_star::FMTStar;
+pub use self::planners::kpiece::KPIECE;
+pub use self::planners::prm::{ClearanceFn, PrefilterFn, RoadmapData, PRM};
 pub use self::planners::rrt::RRT;
 pub use self::planners::rrt_connect::RRTConnect;
-pub use self::planners::rrt_star::RRTStar;
+pub use self::planners::rrt_star::{ParentCandidate, ParentTieBreak, ProgressCallback, RRTStar};
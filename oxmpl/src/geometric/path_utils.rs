@@ -0,0 +1,382 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Post-processing for paths returned by the planners.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::base::{
+    error::PathValidationError,
+    planner::{self, Path},
+    space::StateSpace,
+    state::State,
+    validity::StateValidityChecker,
+};
+
+/// Simplifies `path` by replacing detours with straight-line shortcuts.
+///
+/// Each of the `iterations` attempts picks two non-adjacent states at random and, if the motion
+/// between them is valid, removes the states in between. Motions are checked with the same
+/// discretisation the planners use, so the result is valid whenever `path` is, and it is never
+/// longer. The first and last states are always kept unchanged.
+///
+/// The random choices are drawn from a fixed seed, so the same inputs always give the same
+/// result. Use `shortcut_path_with_rng` to supply a different source of randomness.
+///
+/// # Examples
+///
+/// ```
+/// use oxmpl::base::{
+///     planner::Path, space::RealVectorStateSpace, state::RealVectorState,
+///     validity::StateValidityChecker,
+/// };
+/// use oxmpl::geometric::path_utils::shortcut_path;
+///
+/// struct Open;
+/// impl StateValidityChecker<RealVectorState> for Open {
+///     fn is_valid(&self, _state: &RealVectorState) -> bool {
+///         true
+///     }
+/// }
+///
+/// let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+/// let zigzag = Path(vec![
+///     RealVectorState::new(vec![0.0, 0.0]),
+///     RealVectorState::new(vec![1.0, 2.0]),
+///     RealVectorState::new(vec![2.0, 0.0]),
+///     RealVectorState::new(vec![3.0, 2.0]),
+/// ]);
+///
+/// let shortcut = shortcut_path(&zigzag, &space, &Open, 50);
+/// assert_eq!(shortcut.0.len(), 2);
+/// ```
+pub fn shortcut_path<S, SP>(
+    path: &Path<S>,
+    space: &SP,
+    vc: &dyn StateValidityChecker<S>,
+    iterations: usize,
+) -> Path<S>
+where
+    S: State,
+    SP: StateSpace<StateType = S>,
+{
+    shortcut_path_with_rng(path, space, vc, iterations, &mut StdRng::seed_from_u64(0))
+}
+
+/// As `shortcut_path`, drawing the random choices from `rng`.
+pub fn shortcut_path_with_rng<S, SP>(
+    path: &Path<S>,
+    space: &SP,
+    vc: &dyn StateValidityChecker<S>,
+    iterations: usize,
+    rng: &mut impl Rng,
+) -> Path<S>
+where
+    S: State,
+    SP: StateSpace<StateType = S>,
+{
+    let mut states = path.0.clone();
+    for _ in 0..iterations {
+        // Only paths of three or more states have non-adjacent pairs.
+        if states.len() < 3 {
+            break;
+        }
+
+        let i = rng.random_range(0..states.len() - 2);
+        let j = rng.random_range(i + 2..states.len());
+        if planner::check_motion(space, vc, &states[i], &states[j]) {
+            states.drain(i + 1..j);
+        }
+    }
+    Path(states)
+}
+
+/// Smooths the corners of `path` by repeatedly pulling each interior state towards its
+/// neighbours.
+///
+/// In each of up to `max_steps` passes, every interior state is moved a fraction `weight` of
+/// the way towards the midpoint of its two neighbours, both computed with `space.interpolate`.
+/// A move is rejected if either motion to the moved state is invalid, so the result is valid
+/// whenever `path` is. Smoothing stops early once a pass moves no state.
+///
+/// `weight` is clamped to `[0, 1]`: `0` leaves the path unchanged and `1` moves each state all the
+/// way to the midpoint. The first and last states are always kept unchanged, as is the number of
+/// states, so it is usually worth calling `shortcut_path` first.
+pub fn smooth_path<S, SP>(
+    path: &Path<S>,
+    space: &SP,
+    vc: &dyn StateValidityChecker<S>,
+    weight: f64,
+    max_steps: usize,
+) -> Path<S>
+where
+    S: State,
+    SP: StateSpace<StateType = S>,
+{
+    let weight = weight.clamp(0.0, 1.0);
+    let mut states = path.0.clone();
+    if states.len() < 3 || weight == 0.0 {
+        return Path(states);
+    }
+
+    let mut midpoint = states[0].clone();
+    let mut candidate = states[0].clone();
+    for _ in 0..max_steps {
+        let mut moved = false;
+        for i in 1..states.len() - 1 {
+            space.interpolate(&states[i - 1], &states[i + 1], 0.5, &mut midpoint);
+            space.interpolate(&states[i], &midpoint, weight, &mut candidate);
+            if space.distance(&states[i], &candidate) < 1e-9 {
+                continue;
+            }
+
+            if planner::check_motion(space, vc, &states[i - 1], &candidate)
+                && planner::check_motion(space, vc, &candidate, &states[i + 1])
+            {
+                std::mem::swap(&mut states[i], &mut candidate);
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+    Path(states)
+}
+
+/// Checks that every state of `path`, and every motion between consecutive states, is valid.
+///
+/// Each motion is checked at the same resolution the planners use, at steps of the space's
+/// `get_longest_valid_segment_length`. This verifies paths returned by a planner, or built by
+/// hand, against a checker of the caller's choosing. An empty path is valid.
+///
+/// # Errors
+///
+/// Returns the first failure along the path: `PathValidationError::InvalidState` for an invalid
+/// state of the path itself, or `PathValidationError::InvalidMotion` with the segment and
+/// interpolation parameter of the first invalid state between two of them.
+///
+/// # Examples
+///
+/// ```
+/// use oxmpl::base::{
+///     error::PathValidationError, planner::Path, space::RealVectorStateSpace,
+///     state::RealVectorState, validity::StateValidityChecker,
+/// };
+/// use oxmpl::geometric::path_utils::validate_path;
+///
+/// // A thin wall at x = 5.
+/// struct Wall;
+/// impl StateValidityChecker<RealVectorState> for Wall {
+///     fn is_valid(&self, state: &RealVectorState) -> bool {
+///         !(4.9..=5.1).contains(&state.values[0])
+///     }
+/// }
+///
+/// let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+/// let path = Path(vec![
+///     RealVectorState::new(vec![1.0, 1.0]),
+///     RealVectorState::new(vec![9.0, 1.0]),
+/// ]);
+///
+/// match validate_path(&path, &space, &Wall) {
+///     Err(PathValidationError::InvalidMotion { segment, t }) => {
+///         assert_eq!(segment, 0);
+///         assert!((0.45..=0.5).contains(&t));
+///     }
+///     other => panic!("unexpected result {other:?}"),
+/// }
+/// ```
+pub fn validate_path<S, SP>(
+    path: &Path<S>,
+    space: &SP,
+    vc: &dyn StateValidityChecker<S>,
+) -> Result<(), PathValidationError>
+where
+    S: State,
+    SP: StateSpace<StateType = S>,
+{
+    let Some(first) = path.0.first() else {
+        return Ok(());
+    };
+    if !vc.is_valid(first) {
+        return Err(PathValidationError::InvalidState { index: 0 });
+    }
+
+    let mut interpolated_state = first.clone();
+    for (segment, pair) in path.0.windows(2).enumerate() {
+        let (from, to) = (&pair[0], &pair[1]);
+        let num_steps = space.segments_for_distance(space.distance(from, to));
+        for i in 1..num_steps {
+            let t = i as f64 / num_steps as f64;
+            space.interpolate(from, to, t, &mut interpolated_state);
+            if !vc.is_valid(&interpolated_state) {
+                return Err(PathValidationError::InvalidMotion { segment, t });
+            }
+        }
+        if !vc.is_valid(to) {
+            return Err(PathValidationError::InvalidState { index: segment + 1 });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{
+        space::{RealVectorStateSpace, SO2StateSpace},
+        state::{RealVectorState, SO2State},
+    };
+
+    /// Rejects states inside a thin vertical wall at `x = 5` spanning `2 <= y <= 8`.
+    struct WallChecker;
+
+    impl StateValidityChecker<RealVectorState> for WallChecker {
+        fn is_valid(&self, state: &RealVectorState) -> bool {
+            let (x, y) = (state.values[0], state.values[1]);
+            !((4.9..=5.1).contains(&x) && (2.0..=8.0).contains(&y))
+        }
+    }
+
+    /// Rejects angles within 0.2 rad of PI, so SO2 motions cannot cross the seam.
+    struct SeamChecker;
+
+    impl StateValidityChecker<SO2State> for SeamChecker {
+        fn is_valid(&self, state: &SO2State) -> bool {
+            state.value.abs() < std::f64::consts::PI - 0.2
+        }
+    }
+
+    #[test]
+    fn test_shortcut_path_around_wall_stays_valid_and_shorter() {
+        let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+        let path = Path(
+            [
+                [1.0, 5.0],
+                [2.0, 7.0],
+                [3.0, 8.5],
+                [4.0, 9.0],
+                [5.0, 9.5],
+                [6.0, 9.0],
+                [7.0, 8.5],
+                [8.0, 7.0],
+                [9.0, 5.0],
+            ]
+            .iter()
+            .map(|p| RealVectorState::new(p.to_vec()))
+            .collect(),
+        );
+        assert_eq!(validate_path(&path, &space, &WallChecker), Ok(()));
+
+        let shortcut = shortcut_path(&path, &space, &WallChecker, 100);
+
+        assert!(shortcut.0.len() < path.0.len());
+        assert_eq!(shortcut.0.first(), path.0.first());
+        assert_eq!(shortcut.0.last(), path.0.last());
+        assert!(shortcut.length(&space) <= path.length(&space));
+        assert_eq!(validate_path(&shortcut, &space, &WallChecker), Ok(()));
+    }
+
+    #[test]
+    fn test_shortcut_path_in_so2_keeps_detour_around_seam() {
+        let space = SO2StateSpace::new(None).unwrap();
+        // From 2.5 rad to -2.5 rad the long way round, since the short way crosses the seam.
+        let path = Path(
+            [2.5, 1.5, 0.5, -0.5, -1.5, -2.5]
+                .iter()
+                .map(|&value| SO2State::new(value))
+                .collect(),
+        );
+        assert_eq!(validate_path(&path, &space, &SeamChecker), Ok(()));
+
+        let shortcut = shortcut_path(&path, &space, &SeamChecker, 100);
+
+        assert_eq!(shortcut.0.first(), path.0.first());
+        assert_eq!(shortcut.0.last(), path.0.last());
+        assert!(shortcut.0.len() > 2, "The direct motion crosses the seam");
+        assert!(shortcut.length(&space) <= path.length(&space) + 1e-9);
+        assert_eq!(validate_path(&shortcut, &space, &SeamChecker), Ok(()));
+    }
+
+    #[test]
+    fn test_smooth_path_shortens_corners_and_stays_valid() {
+        let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+        // A staircase over the top of the wall.
+        let path = Path(
+            [
+                [1.0, 5.0],
+                [1.0, 9.0],
+                [3.0, 9.0],
+                [5.0, 9.0],
+                [7.0, 9.0],
+                [9.0, 9.0],
+                [9.0, 5.0],
+            ]
+            .iter()
+            .map(|p| RealVectorState::new(p.to_vec()))
+            .collect(),
+        );
+        assert_eq!(validate_path(&path, &space, &WallChecker), Ok(()));
+
+        let smoothed = smooth_path(&path, &space, &WallChecker, 0.5, 100);
+
+        assert_eq!(smoothed.0.len(), path.0.len());
+        assert_eq!(smoothed.0.first(), path.0.first());
+        assert_eq!(smoothed.0.last(), path.0.last());
+        assert!(smoothed.length(&space) < path.length(&space));
+        assert_eq!(validate_path(&smoothed, &space, &WallChecker), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_path_accepts_path_around_wall() {
+        let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+        let path = Path(vec![
+            RealVectorState::new(vec![1.0, 5.0]),
+            RealVectorState::new(vec![5.0, 9.0]),
+            RealVectorState::new(vec![9.0, 5.0]),
+        ]);
+
+        assert_eq!(validate_path(&path, &space, &WallChecker), Ok(()));
+        assert_eq!(validate_path(&Path(vec![]), &space, &WallChecker), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_path_reports_invalid_state() {
+        let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+        let path = Path(vec![
+            RealVectorState::new(vec![1.0, 5.0]),
+            RealVectorState::new(vec![3.0, 9.0]),
+            RealVectorState::new(vec![5.0, 8.05]),
+            // Shorter than one segment, so only the state the motion ends at is checked.
+            RealVectorState::new(vec![5.0, 8.0]),
+        ]);
+
+        assert_eq!(
+            validate_path(&path, &space, &WallChecker),
+            Err(PathValidationError::InvalidState { index: 3 })
+        );
+    }
+
+    #[test]
+    fn test_validate_path_reports_where_motion_clips_obstacle() {
+        let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+        // Both motions end in free space, but the second one crosses the wall halfway along.
+        let path = Path(vec![
+            RealVectorState::new(vec![1.0, 9.0]),
+            RealVectorState::new(vec![3.0, 5.0]),
+            RealVectorState::new(vec![7.0, 5.0]),
+        ]);
+
+        let Err(PathValidationError::InvalidMotion { segment, t }) =
+            validate_path(&path, &space, &WallChecker)
+        else {
+            panic!("The second motion should be invalid");
+        };
+        assert_eq!(segment, 1);
+        // The wall starts at x = 4.9, so the first invalid state is just past t = 0.475.
+        let step = space.get_longest_valid_segment_length() / 4.0;
+        assert!((0.475..0.475 + step).contains(&t), "t = {t}");
+    }
+}
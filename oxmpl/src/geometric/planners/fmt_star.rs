@@ -0,0 +1,510 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{cmp::Ordering, collections::BinaryHeap, f64::consts::PI, sync::Arc};
+
+use crate::{logging, time::Instant};
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::base::{
+    error::{PlanningError, ProblemError},
+    goal::{Goal, GoalSampleableRegion},
+    motion_validator::{self, MotionValidator},
+    nearest_neighbors::{KdTreeNN, NearestNeighbors},
+    planner::{self, Path, Planner, PlannerStats, Solution},
+    problem_definition::ProblemDefinition,
+    space::{RealVectorStateSpace, StateSpace},
+    state::RealVectorState,
+    termination::{TerminationCondition, TerminationReason},
+    validity::{CountingValidityChecker, StateValidityChecker},
+};
+
+/// Where a sample is in the wavefront expansion.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// Not yet connected to the tree.
+    Unvisited,
+    /// Connected to the tree and on the wavefront.
+    Open,
+    /// Connected to the tree and already expanded.
+    Closed,
+}
+
+// A sample, with its place in the tree once the wavefront has reached it.
+struct Node {
+    state: RealVectorState,
+    parent_index: Option<usize>,
+    cost: f64,
+    status: Status,
+    // The samples within the connection radius, computed the first time they are needed.
+    neighbours: Option<Vec<usize>>,
+}
+
+// An entry in the wavefront, ordered so that `BinaryHeap` pops the lowest cost first.
+struct OpenEntry {
+    cost: f64,
+    index: usize,
+}
+
+impl PartialEq for OpenEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for OpenEntry {}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// An implementation of the Fast Marching Tree (FMT*) algorithm for `RealVectorStateSpace`.
+///
+/// FMT* is a batch, asymptotically optimal planner. Rather than growing a tree one sample at a
+/// time, it draws all of its samples up front and then sweeps a wavefront through them in order
+/// of cost from the start, much like Dijkstra's algorithm on an implicit graph.
+///
+/// # Algorithm Overview
+///
+/// 1.  Sample `num_samples` valid states, plus one from the goal region, and connect samples
+///     closer than the connection radius `r_n`.
+/// 2.  Starting from the start state, repeatedly take the wavefront node `z` of lowest cost:
+///     a. For each unvisited neighbour `x` of `z`, find the wavefront node `y` that gives `x` the
+///     lowest cost, and connect `x` to `y` if that one motion is valid.
+///     b. Add the newly connected nodes to the wavefront and remove `z` from it.
+/// 3.  Stop once `z` is in the goal region, or fail once the wavefront is empty.
+///
+/// Motions are checked lazily: only the best connection for each node is checked, so FMT* does
+/// far fewer motion checks than PRM* on the same samples. The path it returns is optimal over
+/// the sample set whenever no obstacle lies between a node and its locally best parent.
+///
+/// The connection radius is `r_n = radius_factor * gamma * (ln(n) / n)^(1/d)`, where `n` is the
+/// number of samples, `d` is the space's dimension and `gamma = 2 * (mu / (d * zeta_d))^(1/d)`,
+/// with `mu` the volume of the space's bounds and `zeta_d` the volume of the unit `d`-ball. A
+/// `radius_factor` above 1 keeps FMT* asymptotically optimal; 1.1 is a common choice.
+///
+/// The wavefront grows from a single state, the problem's first start state. Any other start
+/// states and the problem's `start_sampler` are ignored.
+pub struct FMTStar<G: Goal<RealVectorState>> {
+    /// The number of valid states to sample, not counting the start and goal samples.
+    pub num_samples: usize,
+    /// The factor by which the theoretical connection radius is scaled.
+    pub radius_factor: f64,
+
+    problem_def: Option<Arc<ProblemDefinition<RealVectorState, RealVectorStateSpace, G>>>,
+    validity_checker: Option<Arc<CountingValidityChecker<RealVectorState>>>,
+    max_validity_checks: Option<usize>,
+    check_axioms: bool,
+    motion_validator: Option<Arc<dyn MotionValidator<RealVectorState>>>,
+    custom_motion_validator: Option<Arc<dyn MotionValidator<RealVectorState>>>,
+    nodes: Vec<Node>,
+    nn: Option<KdTreeNN<RealVectorStateSpace>>,
+    connection_radius: f64,
+    rng: Box<dyn RngCore>,
+}
+
+impl<G: Goal<RealVectorState>> FMTStar<G> {
+    /// Creates a new `FMTStar` planner with the specified parameters.
+    ///
+    /// # Parameters
+    /// * `num_samples` - The number of valid states to sample.
+    /// * `radius_factor` - The factor by which the theoretical connection radius is scaled.
+    pub fn new(num_samples: usize, radius_factor: f64) -> Self {
+        FMTStar {
+            num_samples,
+            radius_factor,
+            problem_def: None,
+            validity_checker: None,
+            max_validity_checks: None,
+            check_axioms: false,
+            motion_validator: None,
+            custom_motion_validator: None,
+            nodes: Vec::new(),
+            nn: None,
+            connection_radius: 0.0,
+            rng: Box::new(StdRng::from_os_rng()),
+        }
+    }
+
+    /// Sets the motion validator used to check the motions between states.
+    ///
+    /// It takes effect in `setup`. Without it, `setup` creates a `DiscreteMotionValidator` from
+    /// the problem's space and validity checker.
+    pub fn set_motion_validator(&mut self, validator: Arc<dyn MotionValidator<RealVectorState>>) {
+        self.custom_motion_validator = Some(validator);
+    }
+
+    /// Sets the largest number of validity checks the planner may make, or removes the limit.
+    ///
+    /// It takes effect in `setup`, and counts every check made from then on, including those of
+    /// the start states and of motions checked by the default motion validator. A planner that
+    /// needs more checks stops with `PlanningError::CheckBudgetExhausted`.
+    pub fn set_max_validity_checks(&mut self, max_checks: Option<usize>) {
+        self.max_validity_checks = max_checks;
+    }
+
+    /// Sets whether `setup` checks the problem's space with `StateSpace::check_axioms`, panicking
+    /// if the space breaks one of them.
+    ///
+    /// The check only runs in builds with `debug_assertions`, and is off by default. It draws its
+    /// three states from the planner's random number generator, so a failure reproduces under
+    /// `set_seed`.
+    pub fn set_check_axioms(&mut self, check: bool) {
+        self.check_axioms = check;
+    }
+
+    /// Returns the number of validity checks made since `setup`.
+    pub fn validity_check_count(&self) -> usize {
+        self.validity_checker
+            .as_ref()
+            .map_or(0, |checker| checker.count())
+    }
+
+    /// Seeds the planner's random number generator, used for both uniform and goal sampling.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Box::new(StdRng::seed_from_u64(seed));
+    }
+
+    /// Replaces the planner's random number generator, used for both uniform and goal sampling.
+    ///
+    /// Like `set_seed`, this makes runs reproducible with a generator of the caller's choosing,
+    /// e.g. a `RecordingRng` or `ReplayRng` from `oxmpl::testing` to debug a nondeterministic run.
+    pub fn set_rng(&mut self, rng: impl RngCore + 'static) {
+        self.rng = Box::new(rng);
+    }
+
+    /// Returns the number of samples drawn by the last `solve`, including the start state.
+    pub fn sample_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the connection radius used by the last `solve`.
+    pub fn connection_radius(&self) -> f64 {
+        self.connection_radius
+    }
+
+    /// Computes the FMT* connection radius for `n` samples in `space`.
+    fn compute_connection_radius(&self, space: &RealVectorStateSpace, n: usize) -> f64 {
+        let d = space.dimension as f64;
+        let volume: f64 = space
+            .bounds
+            .iter()
+            .map(|(lower, upper)| upper - lower)
+            .product();
+        let gamma = 2.0 * (volume / (d * unit_ball_volume(space.dimension))).powf(1.0 / d);
+        let n = n as f64;
+        self.radius_factor * gamma * (n.ln() / n).powf(1.0 / d)
+    }
+
+    /// Returns the samples within the connection radius of sample `index`, other than itself.
+    fn neighbours(&mut self, index: usize) -> Vec<usize> {
+        if let Some(neighbours) = &self.nodes[index].neighbours {
+            return neighbours.clone();
+        }
+        let neighbours: Vec<usize> = self
+            .nn
+            .as_ref()
+            .map(|nn| nn.nearest_within_radius(&self.nodes[index].state, self.connection_radius))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|&i| i != index)
+            .collect();
+        self.nodes[index].neighbours = Some(neighbours.clone());
+        neighbours
+    }
+
+    /// Checks the motion between two states with the motion validator created in `setup`.
+    fn check_motion(&self, from: &RealVectorState, to: &RealVectorState) -> bool {
+        self.motion_validator
+            .as_ref()
+            .is_some_and(|validator| validator.check_motion(from, to))
+    }
+
+    fn reconstruct_path(&self, node_index: usize) -> Path<RealVectorState> {
+        let mut states = Vec::new();
+        let mut current = Some(node_index);
+        while let Some(index) = current {
+            states.push(self.nodes[index].state.clone());
+            current = self.nodes[index].parent_index;
+        }
+        states.reverse();
+        Path(states)
+    }
+}
+
+/// The volume of the unit ball in `d` dimensions, from `V_d = V_(d-2) * 2 * PI / d`.
+fn unit_ball_volume(d: usize) -> f64 {
+    match d {
+        0 => 1.0,
+        1 => 2.0,
+        _ => unit_ball_volume(d - 2) * 2.0 * PI / d as f64,
+    }
+}
+
+impl<G> Planner<RealVectorState, RealVectorStateSpace, G> for FMTStar<G>
+where
+    G: Goal<RealVectorState> + GoalSampleableRegion<RealVectorState>,
+{
+    fn setup(
+        &mut self,
+        problem_def: Arc<ProblemDefinition<RealVectorState, RealVectorStateSpace, G>>,
+        validity_checker: Arc<dyn StateValidityChecker<RealVectorState>>,
+    ) {
+        let validity_checker =
+            planner::count_validity_checks(validity_checker, self.max_validity_checks);
+        if cfg!(debug_assertions) && self.check_axioms {
+            crate::base::space::debug_check_axioms(&*problem_def.space, &mut self.rng);
+        }
+
+        self.motion_validator = Some(motion_validator::motion_validator_or_default(
+            &self.custom_motion_validator,
+            problem_def.space.clone(),
+            validity_checker.clone(),
+        ));
+        self.problem_def = Some(problem_def);
+        self.validity_checker = Some(validity_checker);
+        self.nodes.clear();
+        self.nn = None;
+        self.connection_radius = 0.0;
+    }
+
+    /// Samples and then expands the wavefront, giving up once `condition` is met.
+    ///
+    /// Each sample drawn and each wavefront node expanded counts as one iteration, and the tree
+    /// size checked against the condition is the number of samples.
+    fn solve_with(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<Path<RealVectorState>, PlanningError> {
+        self.run(&condition)
+    }
+
+    fn solve_detailed_with(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<Solution<RealVectorState>, PlanningError> {
+        let start_time = Instant::now();
+        let path = self.run(&condition)?;
+        let stats = PlannerStats {
+            planning_time: start_time.elapsed(),
+            validity_checks: self.validity_check_count(),
+        };
+        let pd = self
+            .problem_def
+            .as_ref()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        Ok(Solution::exact("FMT*", path, &*pd.space, stats))
+    }
+}
+
+impl<G> FMTStar<G>
+where
+    G: Goal<RealVectorState> + GoalSampleableRegion<RealVectorState>,
+{
+    /// Checks `condition`, returning the error to give up with once it is met.
+    fn check_termination(
+        &self,
+        condition: &TerminationCondition,
+        start_time: Instant,
+        iteration: usize,
+    ) -> Result<(), PlanningError> {
+        if let Some(vc) = &self.validity_checker {
+            planner::check_validity_error(&**vc)?;
+        }
+        match condition.check(start_time.elapsed(), iteration, self.nodes.len()) {
+            Some(TerminationReason::Timeout) => {
+                let best_goal_distance = self
+                    .problem_def
+                    .as_ref()
+                    .map(|pd| {
+                        self.nodes
+                            .iter()
+                            .filter(|node| node.status != Status::Unvisited)
+                            .map(|node| pd.goal.distance_goal(&node.state))
+                            .fold(f64::INFINITY, f64::min)
+                    })
+                    .unwrap_or(f64::INFINITY);
+                let nodes = self.nodes.len();
+                logging::timeout("FMT*", nodes, best_goal_distance);
+                Err(PlanningError::Timeout {
+                    best_goal_distance,
+                    nodes,
+                })
+            }
+            Some(_) => Err(PlanningError::NoSolutionFound),
+            None => Ok(()),
+        }
+    }
+
+    /// Samples the states, then expands the wavefront until it reaches the goal region.
+    fn run(
+        &mut self,
+        condition: &TerminationCondition,
+    ) -> Result<Path<RealVectorState>, PlanningError> {
+        // Ensure setup has been called.
+        let pd = self
+            .problem_def
+            .clone()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        let vc = self
+            .validity_checker
+            .clone()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+
+        planner::check_start_states(&*pd.space, &*vc, &pd.start_states)?;
+        let Some(start_state) = pd.start_states.first() else {
+            return Err(PlanningError::InvalidProblemDefinition(
+                ProblemError::NoStartStates.to_string(),
+            ));
+        };
+        let start_state = planner::enforce_bounds(&*pd.space, start_state.clone());
+
+        let start_time = Instant::now();
+        let mut iteration = 0;
+
+        // 1. Sample the start state, a goal state and `num_samples` valid states.
+        self.nodes.clear();
+        let mut samples = vec![start_state];
+        if let Ok(goal_state) = pd.goal.sample_goal(&mut self.rng) {
+            let goal_state = planner::enforce_bounds(&*pd.space, goal_state);
+            if vc.is_valid(&goal_state) {
+                samples.push(goal_state);
+            }
+        }
+        let target = samples.len() + self.num_samples;
+        while samples.len() < target {
+            self.check_termination(condition, start_time, iteration)?;
+            iteration += 1;
+
+            let state = pd
+                .space
+                .sample_uniform(&mut self.rng)
+                .map_err(PlanningError::SamplingFailed)?;
+            if vc.is_valid(&state) {
+                samples.push(state);
+            }
+        }
+
+        let mut nn = KdTreeNN::new(pd.space.clone());
+        nn.reserve(samples.len());
+        for (index, state) in samples.into_iter().enumerate() {
+            nn.add(index, state.clone());
+            self.nodes.push(Node {
+                state,
+                parent_index: None,
+                cost: f64::INFINITY,
+                status: Status::Unvisited,
+                neighbours: None,
+            });
+        }
+        self.nn = Some(nn);
+        self.connection_radius = self.compute_connection_radius(&pd.space, self.nodes.len());
+
+        // 2. Expand the wavefront from the start state in order of cost.
+        self.nodes[0].cost = 0.0;
+        self.nodes[0].status = Status::Open;
+        let mut open = BinaryHeap::new();
+        open.push(OpenEntry {
+            cost: 0.0,
+            index: 0,
+        });
+
+        while let Some(OpenEntry { index: z, .. }) = open.pop() {
+            self.check_termination(condition, start_time, iteration)?;
+            iteration += 1;
+
+            if pd.goal.is_satisfied(&self.nodes[z].state) {
+                logging::solution_found("FMT*", self.nodes.len());
+                return Ok(self.reconstruct_path(z));
+            }
+
+            let mut newly_open = Vec::new();
+            for x in self.neighbours(z) {
+                if self.nodes[x].status != Status::Unvisited {
+                    continue;
+                }
+
+                // Connect `x` through its locally best parent on the wavefront, checking only that
+                // one motion.
+                let candidates = self.neighbours(x);
+                let x_state = &self.nodes[x].state;
+                let best_parent = candidates
+                    .into_iter()
+                    .filter(|&y| self.nodes[y].status == Status::Open)
+                    .map(|y| {
+                        let cost =
+                            self.nodes[y].cost + pd.space.distance(&self.nodes[y].state, x_state);
+                        (y, cost)
+                    })
+                    .min_by(|(_, a), (_, b)| a.total_cmp(b));
+                let Some((y, cost)) = best_parent else {
+                    continue;
+                };
+                if self.check_motion(&self.nodes[y].state, &self.nodes[x].state) {
+                    self.nodes[x].parent_index = Some(y);
+                    self.nodes[x].cost = cost;
+                    newly_open.push(x);
+                }
+            }
+
+            for x in newly_open {
+                self.nodes[x].status = Status::Open;
+                open.push(OpenEntry {
+                    cost: self.nodes[x].cost,
+                    index: x,
+                });
+            }
+            self.nodes[z].status = Status::Closed;
+        }
+
+        planner::check_validity_error(&*vc)?;
+        Err(PlanningError::NoSolutionFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_ball_volume() {
+        assert!((unit_ball_volume(1) - 2.0).abs() < 1e-12);
+        assert!((unit_ball_volume(2) - PI).abs() < 1e-12);
+        assert!((unit_ball_volume(3) - 4.0 / 3.0 * PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_connection_radius_uses_space_dimension() {
+        struct AnyState;
+        impl Goal<RealVectorState> for AnyState {
+            fn is_satisfied(&self, _state: &RealVectorState) -> bool {
+                true
+            }
+        }
+
+        let planner: FMTStar<AnyState> = FMTStar::new(1000, 1.0);
+        let plane = RealVectorStateSpace::new(2, Some(vec![(0.0, 1.0); 2])).unwrap();
+        let cube = RealVectorStateSpace::new(3, Some(vec![(0.0, 1.0); 3])).unwrap();
+
+        // gamma = 2 * (1 / (2 * PI))^(1/2) in the unit square.
+        let n = 1000.0_f64;
+        let expected = 2.0 * (1.0 / (2.0 * PI)).sqrt() * (n.ln() / n).sqrt();
+        assert!((planner.compute_connection_radius(&plane, 1000) - expected).abs() < 1e-12);
+
+        // More samples are needed to cover a higher-dimensional space, so the radius is larger.
+        assert!(
+            planner.compute_connection_radius(&cube, 1000)
+                > planner.compute_connection_radius(&plane, 1000)
+        );
+    }
+}
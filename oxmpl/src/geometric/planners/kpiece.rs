@@ -0,0 +1,681 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    sync::Arc,
+};
+
+use crate::{logging, time::Instant};
+
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+use crate::base::{
+    error::PlanningError,
+    goal::{Goal, GoalSampleableRegion},
+    motion_validator::{self, MotionValidator},
+    planner::{self, Path, Planner, PlannerStats, Solution},
+    problem_definition::ProblemDefinition,
+    projection::ProjectionEvaluator,
+    space::StateSpace,
+    state::State,
+    termination::{TerminationCondition, TerminationReason},
+    validity::{CountingValidityChecker, StateValidityChecker},
+};
+
+// A helper struct to build the tree. Each node stores its state and the index of its parent in the
+// tree vector.
+#[derive(Clone)]
+struct Node<S: State> {
+    state: S,
+    parent_index: Option<usize>,
+}
+
+// A cell of the projection grid, holding the tree nodes whose states project into it.
+struct Cell {
+    coordinates: Vec<i64>,
+    nodes: Vec<usize>,
+    // The number of the cell's 2k axis-aligned neighbours that contain nodes.
+    neighbours: usize,
+    // Set from the iteration and goal distance at creation, and lowered after every expansion
+    // from the cell, more so if it makes no progress.
+    score: f64,
+    // The number of times the cell was selected for expansion, starting at 1.
+    selections: usize,
+    // Bumped whenever the cell's importance changes, so that older entries for it in the cell
+    // orderings can be told apart and skipped.
+    version: usize,
+}
+
+impl Cell {
+    /// How promising the cell is for expansion: high-scoring cells with low coverage (few
+    /// nodes), few neighbours and few previous selections are preferred.
+    fn importance(&self) -> f64 {
+        self.score
+            / ((self.neighbours + 1) as f64 * self.nodes.len() as f64 * self.selections as f64)
+    }
+
+    /// Whether the cell lies on the frontier of the grid, with fewer than `2k` occupied
+    /// neighbours in `k` dimensions.
+    fn is_exterior(&self) -> bool {
+        self.neighbours < 2 * self.coordinates.len()
+    }
+}
+
+// An entry of a cell ordering, holding a cell's importance at the time it was pushed.
+struct RankedCell {
+    importance: f64,
+    cell_index: usize,
+    version: usize,
+}
+
+impl PartialEq for RankedCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RankedCell {}
+
+impl PartialOrd for RankedCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RankedCell {
+    // Ties go to the older cell, so the ordering does not depend on the heap's layout.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.importance
+            .total_cmp(&other.importance)
+            .then_with(|| other.cell_index.cmp(&self.cell_index))
+    }
+}
+
+/// An implementation of the KPIECE1 (Kinodynamic motion Planning by Interior-Exterior Cell
+/// Exploration) algorithm.
+///
+/// KPIECE grows a tree, like RRT, but decides where to grow it from a grid over a low-dimensional
+/// projection of the states rather than from nearest-neighbour queries. This makes it effective
+/// in high-dimensional spaces where a good projection is known.
+///
+/// # Algorithm Overview
+/// 1. Start with a tree containing only the start state, in the grid cell its projection falls in.
+/// 2. Loop:
+///    a. Select a cell: with probability `border_fraction` an exterior cell (one with fewer than
+///    `2k` occupied neighbours in the `k`-dimensional grid), otherwise an interior cell. Within
+///    that group, the cell of highest importance is chosen. Importance favours cells created
+///    late and close to the goal that contain few nodes and have rarely been selected. As in
+///    OMPL, the exterior and interior cells are kept in two priority queues ordered by
+///    importance, so selecting a cell does not scan the whole grid.
+///    b. Pick a node in the cell, favouring recently added ones, and extend it by at most `range`
+///    towards a uniform sample of the space (or, with probability `goal_bias`, a goal sample).
+///    c. If the new state falls in another cell the tree already covers, discard it without
+///    checking the motion, since it would only thicken the tree where it already is. States in
+///    the goal region, and states in the selected cell itself, are kept.
+///    d. If the motion is valid, add the new state to the tree and to its cell. The selected
+///    cell's score is then multiplied by `successful_expansion_score_factor` if this covers a new
+///    cell, and by `failed_expansion_score_factor` otherwise (including discarded states and
+///    invalid motions), so that cells selected repeatedly give way to others.
+///    e. If the new state is in the goal region, a solution is found.
+///
+/// Since exterior cells lie on the frontier of the explored region, the tree spreads through the
+/// projection rather than thickening where it already is, which helps it through narrow passages.
+pub struct KPIECE<S: State, SP: StateSpace<StateType = S>, G: Goal<S>> {
+    /// The maximum distance between nodes in the tree. This is the "step size".
+    pub range: f64,
+    /// The probability of extending towards the goal region instead of the whole space.
+    pub goal_bias: f64,
+    /// The side length of the grid cells in the projection. Defaults to `range`, so that a full
+    /// step usually leaves the cell it starts in.
+    pub cell_size: f64,
+    /// The probability of expanding an exterior rather than an interior cell. Defaults to 0.9.
+    pub border_fraction: f64,
+    /// The factor a cell's score is multiplied by when an expansion from it covers a new cell.
+    /// Defaults to 0.9.
+    pub successful_expansion_score_factor: f64,
+    /// The factor a cell's score is multiplied by when an expansion from it makes no progress.
+    /// Defaults to 0.9.
+    pub failed_expansion_score_factor: f64,
+
+    projection: Arc<dyn ProjectionEvaluator<S>>,
+    problem_def: Option<Arc<ProblemDefinition<S, SP, G>>>,
+    validity_checker: Option<Arc<CountingValidityChecker<S>>>,
+    max_validity_checks: Option<usize>,
+    check_axioms: bool,
+    motion_validator: Option<Arc<dyn MotionValidator<S>>>,
+    custom_motion_validator: Option<Arc<dyn MotionValidator<S>>>,
+    tree: Vec<Node<S>>,
+    cells: Vec<Cell>,
+    grid: HashMap<Vec<i64>, usize>,
+    exterior_cells: BinaryHeap<RankedCell>,
+    interior_cells: BinaryHeap<RankedCell>,
+    rng: Box<dyn RngCore>,
+}
+
+impl<S, SP, G> KPIECE<S, SP, G>
+where
+    S: State,
+    SP: StateSpace<StateType = S>,
+    G: Goal<S>,
+{
+    /// Creates a new `KPIECE` planner with the specified parameters.
+    ///
+    /// # Parameters
+    /// * `range` - The maximum length of a single branch in the tree.
+    /// * `goal_bias` - The probability (0.0 to 1.0) of extending towards the goal.
+    /// * `projection` - The projection the coverage grid is built over.
+    pub fn new(range: f64, goal_bias: f64, projection: Arc<dyn ProjectionEvaluator<S>>) -> Self {
+        KPIECE {
+            range,
+            goal_bias,
+            cell_size: range,
+            border_fraction: 0.9,
+            successful_expansion_score_factor: 0.9,
+            failed_expansion_score_factor: 0.9,
+            projection,
+            problem_def: None,
+            validity_checker: None,
+            max_validity_checks: None,
+            check_axioms: false,
+            motion_validator: None,
+            custom_motion_validator: None,
+            tree: Vec::new(),
+            cells: Vec::new(),
+            grid: HashMap::new(),
+            exterior_cells: BinaryHeap::new(),
+            interior_cells: BinaryHeap::new(),
+            rng: Box::new(StdRng::from_os_rng()),
+        }
+    }
+
+    /// Sets the motion validator used to check the motions between states.
+    ///
+    /// It takes effect in `setup`. Without it, `setup` creates a `DiscreteMotionValidator` from
+    /// the problem's space and validity checker.
+    pub fn set_motion_validator(&mut self, validator: Arc<dyn MotionValidator<S>>) {
+        self.custom_motion_validator = Some(validator);
+    }
+
+    /// Sets the largest number of validity checks the planner may make, or removes the limit.
+    ///
+    /// It takes effect in `setup`, and counts every check made from then on, including those of
+    /// the start states and of motions checked by the default motion validator. A planner that
+    /// needs more checks stops with `PlanningError::CheckBudgetExhausted`.
+    pub fn set_max_validity_checks(&mut self, max_checks: Option<usize>) {
+        self.max_validity_checks = max_checks;
+    }
+
+    /// Sets whether `setup` checks the problem's space with `StateSpace::check_axioms`, panicking
+    /// if the space breaks one of them.
+    ///
+    /// The check only runs in builds with `debug_assertions`, and is off by default. It draws its
+    /// three states from the planner's random number generator, so a failure reproduces under
+    /// `set_seed`.
+    pub fn set_check_axioms(&mut self, check: bool) {
+        self.check_axioms = check;
+    }
+
+    /// Returns the number of validity checks made since `setup`.
+    pub fn validity_check_count(&self) -> usize {
+        self.validity_checker
+            .as_ref()
+            .map_or(0, |checker| checker.count())
+    }
+
+    /// Seeds the planner's random number generator.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Box::new(StdRng::seed_from_u64(seed));
+    }
+
+    /// Replaces the planner's random number generator, used for both uniform and goal sampling.
+    ///
+    /// Like `set_seed`, this makes runs reproducible with a generator of the caller's choosing,
+    /// e.g. a `RecordingRng` or `ReplayRng` from `oxmpl::testing` to debug a nondeterministic run.
+    pub fn set_rng(&mut self, rng: impl RngCore + 'static) {
+        self.rng = Box::new(rng);
+    }
+
+    /// Returns the number of nodes currently in the tree.
+    pub fn tree_size(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns the number of grid cells the tree covers.
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Checks the motion between two states with the motion validator created in `setup`.
+    fn check_motion(&self, from: &S, to: &S) -> bool {
+        self.motion_validator
+            .as_ref()
+            .is_some_and(|validator| validator.check_motion(from, to))
+    }
+
+    /// Returns the grid coordinates of the cell `state` projects into.
+    fn coordinates(&self, state: &S) -> Vec<i64> {
+        self.projection
+            .project(state)
+            .iter()
+            .map(|x| (x / self.cell_size).floor() as i64)
+            .collect()
+    }
+
+    /// Adds `state` to the tree and to its grid cell, creating the cell if needed.
+    ///
+    /// A new cell's score favours cells created late and close to the goal.
+    fn add_node(
+        &mut self,
+        state: S,
+        parent_index: Option<usize>,
+        iteration: usize,
+        goal_distance: f64,
+    ) -> usize {
+        let node_index = self.tree.len();
+        let coordinates = self.coordinates(&state);
+        self.tree.push(Node {
+            state,
+            parent_index,
+        });
+
+        if let Some(&cell_index) = self.grid.get(&coordinates) {
+            self.cells[cell_index].nodes.push(node_index);
+            self.rank(cell_index);
+            return node_index;
+        }
+
+        let mut neighbours = 0;
+        for axis in 0..coordinates.len() {
+            for step in [-1, 1] {
+                let mut neighbour = coordinates.clone();
+                neighbour[axis] += step;
+                if let Some(&neighbour_index) = self.grid.get(&neighbour) {
+                    self.cells[neighbour_index].neighbours += 1;
+                    self.rank(neighbour_index);
+                    neighbours += 1;
+                }
+            }
+        }
+        self.grid.insert(coordinates.clone(), self.cells.len());
+        self.cells.push(Cell {
+            coordinates,
+            nodes: vec![node_index],
+            neighbours,
+            score: (1.0 + (iteration as f64).max(1.0).ln()) / (1.0 + goal_distance),
+            selections: 1,
+            version: 0,
+        });
+        self.rank(self.cells.len() - 1);
+        node_index
+    }
+
+    /// Pushes the cell's current importance onto the ordering for its exterior status, making
+    /// its earlier entries stale.
+    fn rank(&mut self, cell_index: usize) {
+        let cell = &mut self.cells[cell_index];
+        cell.version += 1;
+        let entry = RankedCell {
+            importance: cell.importance(),
+            cell_index,
+            version: cell.version,
+        };
+        let ordering = if cell.is_exterior() {
+            &mut self.exterior_cells
+        } else {
+            &mut self.interior_cells
+        };
+        ordering.push(entry);
+
+        // Drop the stale entries once they outnumber the cells, to bound the orderings' size.
+        if self.exterior_cells.len() + self.interior_cells.len() > 4 * self.cells.len() + 16 {
+            let cells = &self.cells;
+            let is_current = |entry: &RankedCell| entry.version == cells[entry.cell_index].version;
+            self.exterior_cells.retain(is_current);
+            self.interior_cells.retain(is_current);
+        }
+    }
+
+    /// Multiplies the cell's score by `factor` and counts the selection that led to it.
+    ///
+    /// The score stays positive, so that cells penalised many times are still ordered by their
+    /// coverage rather than tying at zero.
+    fn rescore(&mut self, cell_index: usize, factor: f64) {
+        let cell = &mut self.cells[cell_index];
+        cell.score = (cell.score * factor).max(f64::MIN_POSITIVE);
+        cell.selections += 1;
+        self.rank(cell_index);
+    }
+
+    /// Selects the cell to expand from, preferring exterior cells with probability
+    /// `border_fraction`, or returns `None` if there are no cells.
+    fn select_cell(&mut self) -> Option<usize> {
+        let prefer_exterior = self.rng.random::<f64>() < self.border_fraction;
+        let cells = &self.cells;
+        let top = |ordering: &mut BinaryHeap<RankedCell>| {
+            while let Some(entry) = ordering.peek() {
+                if entry.version == cells[entry.cell_index].version {
+                    return Some(entry.cell_index);
+                }
+                ordering.pop();
+            }
+            None
+        };
+
+        let (preferred, other) = if prefer_exterior {
+            (&mut self.exterior_cells, &mut self.interior_cells)
+        } else {
+            (&mut self.interior_cells, &mut self.exterior_cells)
+        };
+        top(preferred).or_else(|| top(other))
+    }
+
+    /// Selects a node of the cell to expand from, favouring the most recently added nodes, which
+    /// tend to lie on the frontier of the tree.
+    ///
+    /// The offset from the newest node follows a half-normal distribution with a standard
+    /// deviation of a third of the cell's node count.
+    fn select_node(&mut self, cell_index: usize) -> usize {
+        let nodes = &self.cells[cell_index].nodes;
+        let u1: f64 = 1.0 - self.rng.random::<f64>();
+        let u2: f64 = self.rng.random();
+        let normal = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+        let offset = (normal.abs() * nodes.len() as f64 / 3.0) as usize;
+        nodes[nodes.len() - 1 - offset.min(nodes.len() - 1)]
+    }
+
+    fn reconstruct_path(&self, start_node_idx: usize) -> Path<S> {
+        let mut path_states = Vec::new();
+        let mut current_index = Some(start_node_idx);
+        while let Some(index) = current_index {
+            path_states.push(self.tree[index].state.clone());
+            current_index = self.tree[index].parent_index;
+        }
+        path_states.reverse();
+
+        Path(path_states)
+    }
+}
+
+impl<S, SP, G> Planner<S, SP, G> for KPIECE<S, SP, G>
+where
+    S: State + Clone + 'static,
+    SP: StateSpace<StateType = S> + 'static,
+    G: Goal<S> + GoalSampleableRegion<S>,
+{
+    fn setup(
+        &mut self,
+        problem_def: Arc<ProblemDefinition<S, SP, G>>,
+        validity_checker: Arc<dyn StateValidityChecker<S>>,
+    ) {
+        let validity_checker =
+            planner::count_validity_checks(validity_checker, self.max_validity_checks);
+        if cfg!(debug_assertions) && self.check_axioms {
+            crate::base::space::debug_check_axioms(&*problem_def.space, &mut self.rng);
+        }
+
+        self.tree.clear();
+        self.cells.clear();
+        self.grid.clear();
+        self.exterior_cells.clear();
+        self.interior_cells.clear();
+
+        // Initialise the tree with the start states, plus the states drawn from the start
+        // sampler, brought within the bounds of the space. Sampled states are checked for
+        // validity once they are within the bounds, as that is the state the tree holds.
+        let sampled_starts: Vec<S> = problem_def
+            .sample_start_states(&mut self.rng)
+            .into_iter()
+            .map(|state| planner::enforce_bounds(&*problem_def.space, state))
+            .filter(|state| validity_checker.is_valid(state))
+            .collect();
+        let start_states = problem_def
+            .start_states
+            .iter()
+            .map(|state| planner::enforce_bounds(&*problem_def.space, state.clone()));
+        for start_state in start_states.chain(sampled_starts) {
+            let goal_distance = problem_def.goal.distance_goal(&start_state);
+            self.add_node(start_state, None, 0, goal_distance);
+        }
+
+        self.motion_validator = Some(motion_validator::motion_validator_or_default(
+            &self.custom_motion_validator,
+            problem_def.space.clone(),
+            validity_checker.clone(),
+        ));
+        self.problem_def = Some(problem_def);
+        self.validity_checker = Some(validity_checker);
+    }
+
+    fn solve_with(&mut self, condition: TerminationCondition) -> Result<Path<S>, PlanningError> {
+        self.run(&condition)
+    }
+
+    fn solve_detailed_with(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<Solution<S>, PlanningError> {
+        let start_time = Instant::now();
+        let path = self.run(&condition)?;
+        let stats = PlannerStats {
+            planning_time: start_time.elapsed(),
+            validity_checks: self.validity_check_count(),
+        };
+        let pd = self
+            .problem_def
+            .as_ref()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        Ok(Solution::exact("KPIECE", path, &*pd.space, stats))
+    }
+}
+
+impl<S, SP, G> KPIECE<S, SP, G>
+where
+    S: State + Clone,
+    SP: StateSpace<StateType = S>,
+    G: Goal<S> + GoalSampleableRegion<S>,
+{
+    /// Runs at most `iterations` iterations of the main loop, regardless of elapsed time.
+    ///
+    /// Combined with `set_seed`, this makes planning runs reproducible. Returns
+    /// `PlanningError::NoSolutionFound` if no solution was found within the iteration budget.
+    pub fn solve_iterations(&mut self, iterations: usize) -> Result<Path<S>, PlanningError> {
+        self.run(&TerminationCondition::MaxIterations(iterations))
+    }
+
+    /// Like `solve_with`, but if the goal has not been reached when `condition` is met, returns
+    /// the path from the start to the node of the tree closest to the goal instead of an error.
+    ///
+    /// The flag is `true` if the path reaches the goal, and `false` if it is partial. Closeness is
+    /// measured by `GoalRegion::distance_goal`. Errors other than running out of time or
+    /// iterations, e.g. `PlanningError::InvalidStartState`, are still returned.
+    pub fn solve_partial(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<(Path<S>, bool), PlanningError> {
+        match self.run(&condition) {
+            Ok(path) => Ok((path, true)),
+            Err(PlanningError::Timeout { .. } | PlanningError::NoSolutionFound) => {
+                let pd = self
+                    .problem_def
+                    .as_ref()
+                    .ok_or(PlanningError::PlannerUninitialised)?;
+                let closest =
+                    planner::closest_to_goal(&*pd.goal, self.tree.iter().map(|node| &node.state))
+                        .ok_or(PlanningError::InvalidStartState)?;
+                Ok((self.reconstruct_path(closest), false))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The main KPIECE loop, stopping once `condition` is met.
+    fn run(&mut self, condition: &TerminationCondition) -> Result<Path<S>, PlanningError> {
+        // Ensure setup has been called.
+        let pd = self
+            .problem_def
+            .clone()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        let vc = self
+            .validity_checker
+            .clone()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        let goal = &pd.goal;
+
+        planner::check_start_states(&*pd.space, &*vc, &pd.start_states)?;
+        if self.tree.is_empty() {
+            return Err(PlanningError::InvalidStartState);
+        }
+
+        let start_time = Instant::now();
+        let mut iteration = 0;
+
+        // Main Loop
+        loop {
+            // Abort if the validity checker failed during the previous iteration.
+            planner::check_validity_error(&*vc)?;
+            // 1. Check the termination condition
+            match condition.check(start_time.elapsed(), iteration, self.tree.len()) {
+                Some(TerminationReason::Timeout) => {
+                    let best_goal_distance = self
+                        .tree
+                        .iter()
+                        .map(|node| goal.distance_goal(&node.state))
+                        .fold(f64::INFINITY, f64::min);
+                    let nodes = self.tree.len();
+                    logging::timeout("KPIECE", nodes, best_goal_distance);
+                    return Err(PlanningError::Timeout {
+                        best_goal_distance,
+                        nodes,
+                    });
+                }
+                Some(_) => return Err(PlanningError::NoSolutionFound),
+                None => {}
+            }
+            iteration += 1;
+
+            // 2. Select a cell, and a node within it, to expand from
+            let cell_index = self.select_cell().ok_or(PlanningError::NoSolutionFound)?;
+            let node_index = self.select_node(cell_index);
+            let q_near = self.tree[node_index].state.clone();
+
+            // 3. Extend by at most `range` towards a uniform sample, or a goal sample
+            let q_rand =
+                planner::sample_target(&*pd.space, &**goal, self.goal_bias, None, &mut self.rng)?;
+            let dist = pd.space.distance(&q_near, &q_rand);
+            let q_new = if dist > self.range {
+                let mut q_new = q_near.clone();
+                pd.space
+                    .interpolate(&q_near, &q_rand, self.range / dist, &mut q_new);
+                planner::enforce_bounds(&*pd.space, q_new)
+            } else {
+                q_rand
+            };
+
+            // 4. Skip states that would only thicken another covered cell, without checking the
+            //    motion
+            if !goal.is_satisfied(&q_new) {
+                let coordinates = self.coordinates(&q_new);
+                if coordinates != self.cells[cell_index].coordinates
+                    && self.grid.contains_key(&coordinates)
+                {
+                    self.rescore(cell_index, self.failed_expansion_score_factor);
+                    continue;
+                }
+            }
+
+            // 5. Add the new state if the motion is valid, penalising the cell more if this
+            //    covers no new cell
+            if self.check_motion(&q_near, &q_new) {
+                let goal_distance = goal.distance_goal(&q_new);
+                let cells_before = self.cells.len();
+                let new_node_index =
+                    self.add_node(q_new.clone(), Some(node_index), iteration, goal_distance);
+                let factor = if self.cells.len() == cells_before {
+                    self.failed_expansion_score_factor
+                } else {
+                    self.successful_expansion_score_factor
+                };
+                self.rescore(cell_index, factor);
+                logging::node_milestone("KPIECE", self.tree.len());
+
+                // 6. Check if the new node satisfies the goal
+                if goal.is_satisfied(&q_new) {
+                    logging::solution_found("KPIECE", self.tree.len());
+                    return Ok(self.reconstruct_path(new_node_index));
+                }
+            } else {
+                self.rescore(cell_index, self.failed_expansion_score_factor);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{
+        projection::RealVectorProjection, space::RealVectorStateSpace, state::RealVectorState,
+    };
+    use crate::test_fixtures::{AlwaysValid, PointGoal};
+
+    /// A planner with unit cells, set up with its start state in cell `[0, 0]`.
+    fn planner() -> KPIECE<RealVectorState, RealVectorStateSpace, PointGoal> {
+        let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(-5.0, 5.0); 2])).unwrap());
+        let problem_def = Arc::new(ProblemDefinition {
+            space,
+            start_states: vec![RealVectorState::new(vec![0.5, 0.5])],
+            goal: Arc::new(PointGoal(RealVectorState::new(vec![4.0, 4.0]))),
+            start_sampler: None,
+        });
+
+        let mut planner = KPIECE::new(1.0, 0.0, Arc::new(RealVectorProjection::new(2)));
+        planner.set_seed(0);
+        planner.setup(problem_def, Arc::new(AlwaysValid));
+        planner
+    }
+
+    #[test]
+    fn test_add_node_tracks_cells_and_neighbours() {
+        let mut planner = planner();
+        planner.add_node(RealVectorState::new(vec![1.5, 0.5]), Some(0), 1, 0.0);
+        planner.add_node(RealVectorState::new(vec![0.5, -0.5]), Some(0), 2, 0.0);
+        planner.add_node(RealVectorState::new(vec![0.6, 0.6]), Some(0), 3, 0.0);
+        // Diagonal cells are not neighbours.
+        planner.add_node(RealVectorState::new(vec![1.5, -0.5]), Some(1), 4, 0.0);
+
+        assert_eq!(planner.tree_size(), 5);
+        assert_eq!(planner.cell_count(), 4);
+        let origin = &planner.cells[planner.grid[&vec![0, 0]]];
+        assert_eq!(origin.nodes, vec![0, 3]);
+        assert_eq!(origin.neighbours, 2);
+        assert_eq!(planner.cells[planner.grid[&vec![1, -1]]].neighbours, 2);
+    }
+
+    #[test]
+    fn test_select_cell_prefers_exterior_cells_by_border_fraction() {
+        // Fill a 3x3 block of cells, so that only the centre cell is interior.
+        let mut planner = planner();
+        for x in -1..=1 {
+            for y in -1..=1 {
+                if (x, y) != (0, 0) {
+                    let state = RealVectorState::new(vec![x as f64 + 0.5, y as f64 + 0.5]);
+                    planner.add_node(state, Some(0), 1, 0.0);
+                }
+            }
+        }
+        let centre = planner.grid[&vec![0, 0]];
+        assert_eq!(planner.cells[centre].neighbours, 4);
+
+        planner.border_fraction = 1.0;
+        for _ in 0..100 {
+            assert_ne!(planner.select_cell(), Some(centre));
+        }
+        planner.border_fraction = 0.0;
+        for _ in 0..100 {
+            assert_eq!(planner.select_cell(), Some(centre));
+        }
+    }
+}
@@ -2,6 +2,8 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
+pub mod fmt_star;
+pub mod kpiece;
 pub mod prm;
 pub mod rrt;
 pub mod rrt_connect;
@@ -3,22 +3,35 @@
 // SPDX-License-Identifier: BSD-3-Clause
 
 use std::{
-    collections::{HashMap, VecDeque},
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
     sync::Arc,
 };
 
-use crate::time::{Duration, Instant};
+use crate::{
+    logging,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
 
 use crate::base::{
     error::PlanningError,
     goal::{Goal, GoalSampleableRegion},
-    planner::{Path, Planner},
+    motion_validator::{self, MotionValidator},
+    nearest_neighbors::{NearestNeighbors, NearestNeighborsFactory},
+    objective,
+    planner::{self, Path, Planner, PlannerStats, Solution},
     problem_definition::ProblemDefinition,
+    sampler::StateSampler,
     space::StateSpace,
     state::State,
-    validity::StateValidityChecker,
+    termination::{TerminationCondition, TerminationReason},
+    validity::{CountingValidityChecker, IncrementalValidityChecker, StateValidityChecker},
 };
 
+pub use crate::base::objective::ClearanceFn;
+
 /// Represents a node (or "milestone") in the probabilistic roadmap.
 #[derive(Clone)]
 pub struct Node<S: State> {
@@ -26,6 +39,90 @@ pub struct Node<S: State> {
     state: S,
     /// A list of indices pointing to other connected nodes in the roadmap.
     edges: Vec<usize>,
+    /// The cost of traversing each edge, parallel to `edges`.
+    edge_costs: Vec<f64>,
+    /// Whether each edge, parallel to `edges`, is known to be collision-free. In lazy mode edges
+    /// start out unchecked and are only checked when a query tries to use them.
+    edge_checked: Vec<bool>,
+}
+
+impl<S: State> Node<S> {
+    /// The state associated with this node.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// The indices of the nodes this node is connected to.
+    ///
+    /// In lazy mode (`PRM::set_lazy`), this includes edges whose motion has not been checked yet
+    /// and may be in collision. Use `is_edge_validated` to tell them apart.
+    pub fn neighbors(&self) -> &[usize] {
+        &self.edges
+    }
+
+    /// Whether the edge to `neighbors()[index]` is known to be collision-free.
+    ///
+    /// This is always `true` outside lazy mode. In lazy mode, an edge is only checked once a query
+    /// tries to use it, and is removed from the roadmap if it turns out to be in collision.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for `neighbors`.
+    pub fn is_edge_validated(&self, index: usize) -> bool {
+        self.edge_checked[index]
+    }
+}
+
+/// A roadmap exported from a `PRM` with `export_roadmap`, to be imported into another `PRM` with
+/// `import_roadmap`.
+///
+/// With the `serde` feature it can be serialized, so that a roadmap built once can be reused by
+/// later runs of a program.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoadmapData<S: State> {
+    /// The state of each milestone.
+    pub states: Vec<S>,
+    /// For each milestone, the indices into `states` of the milestones it is connected to. Each
+    /// edge is listed at both of its ends.
+    pub edges: Vec<Vec<usize>>,
+}
+
+/// The thread-safe view of the counting checker made by `PRM::setup_parallel`.
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+type ParallelChecker<S> =
+    Arc<CountingValidityChecker<S, dyn StateValidityChecker<S> + Send + Sync>>;
+
+/// A cheap test run before the exact distance when searching for roadmap neighbours. Returning
+/// `false` rejects the pair outright, so it must never reject states within the connection radius.
+pub type PrefilterFn<S> = Arc<dyn Fn(&S, &S) -> bool>;
+
+// An entry in the search's priority queue, ordered so that `BinaryHeap` pops the lowest priority
+// first. The priority is the cost from the start, plus the heuristic estimate to the goal for A*.
+struct QueueEntry {
+    priority: f64,
+    cost: f64,
+    index: usize,
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl Eq for QueueEntry {}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.total_cmp(&self.priority)
+    }
 }
 
 /// An implementation of the Probabilistic Roadmap (PRM) algorithm.
@@ -43,17 +140,53 @@ pub struct Node<S: State> {
 ///     edge connecting them in the roadmap.
 /// 2.  **Query Phase**:
 ///     a. Connect the start and goal states to the roadmap.
-///     b. Use a graph search algorithm (in this case, Breadth-First Search) to find a path on the
-///     roadmap from the start to the goal.
+///     b. Use a graph search algorithm (in this case, Dijkstra's algorithm over the edge costs, or
+///     A* if enabled with `set_astar`) to find a path on the roadmap from the start to the goal.
+///
+/// By default the cost of an edge is its length. Calling `set_clearance_weighting` additionally
+/// penalises edges with a low average clearance, so the query prefers safer corridors.
+///
+/// In lazy mode (`set_lazy`, known as Lazy PRM), edges are added without checking the motion
+/// between their nodes. The query checks only the edges along the shortest path it finds,
+/// removes those in collision and searches again, until it finds a path whose edges are all
+/// valid. This saves most of the motion checks when few edges are ever used.
+///
+/// With the `parallel` feature, `construct_roadmap_parallel` builds the roadmap in batches whose
+/// samples and motions are checked on multiple threads, with a thread-safe checker given to
+/// `setup_parallel`.
+///
+/// In PRM* mode (`set_star`), the connection radius shrinks as the roadmap grows, which makes the
+/// paths found asymptotically optimal while keeping the number of edges per milestone low.
 pub struct PRM<S: State, SP: StateSpace<StateType = S>, G: Goal<S>> {
     /// The time allocated for roadmap construction, in seconds.
     pub timeout: f64,
     /// The radius within which to search for neighbors to connect to a new sample.
+    ///
+    /// In PRM* mode this is the largest radius used, while the roadmap is still small.
     pub connection_radius: f64,
 
     problem_def: Option<Arc<ProblemDefinition<S, SP, G>>>,
-    validity_checker: Option<Arc<dyn StateValidityChecker<S>>>,
+    validity_checker: Option<Arc<CountingValidityChecker<S>>>,
+    max_validity_checks: Option<usize>,
+    check_axioms: bool,
+    motion_validator: Option<Arc<dyn MotionValidator<S>>>,
+    custom_motion_validator: Option<Arc<dyn MotionValidator<S>>>,
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    parallel_checker: Option<ParallelChecker<S>>,
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    parallel_motion_validator: Option<Arc<dyn MotionValidator<S> + Send + Sync>>,
     roadmap: Vec<Node<S>>,
+    clearance: Option<(ClearanceFn<S>, f64)>,
+    prefilter: Option<PrefilterFn<S>>,
+    generation_source: Option<Arc<dyn IncrementalValidityChecker<S>>>,
+    roadmap_generation: Option<u64>,
+    lazy: bool,
+    astar: bool,
+    star: Option<(f64, usize)>,
+    nn_factory: Option<NearestNeighborsFactory<S, SP>>,
+    nn: Option<Box<dyn NearestNeighbors<S>>>,
+    sampler: Option<Arc<dyn StateSampler<S>>>,
+    rng: Box<dyn RngCore>,
 }
 
 impl<S, SP, G> PRM<S, SP, G>
@@ -73,8 +206,237 @@ where
             connection_radius,
             problem_def: None,
             validity_checker: None,
+            max_validity_checks: None,
+            check_axioms: false,
+            motion_validator: None,
+            custom_motion_validator: None,
+            #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+            parallel_checker: None,
+            #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+            parallel_motion_validator: None,
             roadmap: Vec::new(),
+            clearance: None,
+            prefilter: None,
+            generation_source: None,
+            roadmap_generation: None,
+            lazy: false,
+            astar: false,
+            star: None,
+            nn_factory: None,
+            nn: None,
+            sampler: None,
+            rng: Box::new(StdRng::from_os_rng()),
+        }
+    }
+
+    /// Sets the motion validator used to check the motions between states.
+    ///
+    /// It takes effect in `setup`. Without it, `setup` creates a `DiscreteMotionValidator` from
+    /// the problem's space and validity checker.
+    pub fn set_motion_validator(&mut self, validator: Arc<dyn MotionValidator<S>>) {
+        self.custom_motion_validator = Some(validator);
+    }
+
+    /// Sets the largest number of validity checks the planner may make, or removes the limit.
+    ///
+    /// It takes effect in `setup`, and counts every check made from then on, including those of
+    /// the start states and of motions checked by the default motion validator. A planner that
+    /// needs more checks stops with `PlanningError::CheckBudgetExhausted`.
+    pub fn set_max_validity_checks(&mut self, max_checks: Option<usize>) {
+        self.max_validity_checks = max_checks;
+    }
+
+    /// Sets whether `setup` checks the problem's space with `StateSpace::check_axioms`, panicking
+    /// if the space breaks one of them.
+    ///
+    /// The check only runs in builds with `debug_assertions`, and is off by default. It draws its
+    /// three states from the planner's random number generator, so a failure reproduces under
+    /// `set_seed`.
+    pub fn set_check_axioms(&mut self, check: bool) {
+        self.check_axioms = check;
+    }
+
+    /// Returns the number of validity checks made since `setup`.
+    pub fn validity_check_count(&self) -> usize {
+        self.validity_checker
+            .as_ref()
+            .map_or(0, |checker| checker.count())
+    }
+
+    /// Seeds the planner's random number generator used for roadmap construction.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Box::new(StdRng::seed_from_u64(seed));
+    }
+
+    /// Replaces the planner's random number generator, used for both uniform and goal sampling.
+    ///
+    /// Like `set_seed`, this makes runs reproducible with a generator of the caller's choosing,
+    /// e.g. a `RecordingRng` or `ReplayRng` from `oxmpl::testing` to debug a nondeterministic run.
+    pub fn set_rng(&mut self, rng: impl RngCore + 'static) {
+        self.rng = Box::new(rng);
+    }
+
+    /// Sets whether edges are collision-checked lazily, i.e. only when a query tries to use them.
+    ///
+    /// This decides how `construct_roadmap` adds edges, so it must be called before the roadmap is
+    /// built. Edges found to be in collision during a query are removed from the roadmap, so later
+    /// queries do not check them again.
+    pub fn set_lazy(&mut self, lazy: bool) {
+        self.lazy = lazy;
+    }
+
+    /// Sets whether the query uses A* instead of Dijkstra's algorithm.
+    ///
+    /// A* orders the search by the cost so far plus the goal's `distance_goal`, so it expands
+    /// fewer nodes on the way to the goal. It returns a path as short as Dijkstra's as long as
+    /// `distance_goal` never overestimates the remaining cost, which holds for goal regions
+    /// measuring distance in the problem's space.
+    pub fn set_astar(&mut self, astar: bool) {
+        self.astar = astar;
+    }
+
+    /// Weights roadmap edges by their average clearance in addition to their length.
+    ///
+    /// The clearance of an edge is averaged over states sampled at the motion-checking
+    /// resolution. The resulting edge cost is `length * (1 + weight / average_clearance)`, so a
+    /// larger `weight` trades path length for distance from obstacles.
+    ///
+    /// Edge costs are computed when the roadmap is built, so this must be called before
+    /// `construct_roadmap`.
+    pub fn set_clearance_weighting(&mut self, clearance: ClearanceFn<S>, weight: f64) {
+        self.clearance = Some((clearance, weight));
+    }
+
+    /// Sets a cheap pre-filter that rejects obvious non-neighbours before the exact distance is
+    /// computed, e.g. a bounding-box test.
+    ///
+    /// This only saves work; the roadmap is unchanged as long as the pre-filter never rejects a
+    /// pair of states closer than `connection_radius`.
+    pub fn set_connection_prefilter(&mut self, prefilter: PrefilterFn<S>) {
+        self.prefilter = Some(prefilter);
+    }
+
+    /// Switches to PRM*, whose connection radius shrinks as the roadmap grows.
+    ///
+    /// With `n` milestones, the radius is `gamma * (ln(n) / n)^(1/d)` for a `dimension` of `d`.
+    /// It is recomputed from the current size of the roadmap for each new milestone, and for the
+    /// start and goal when querying. For a space whose bounds have volume `mu`, PRM* is
+    /// asymptotically optimal if `gamma > 2 * (1 + 1/d)^(1/d) * (mu / zeta_d)^(1/d)`, where
+    /// `zeta_d` is the volume of the unit `d`-ball.
+    ///
+    /// The radius is capped at `connection_radius`, which keeps a small roadmap from being
+    /// connected almost completely. Set `connection_radius` to `f64::INFINITY` to use the PRM*
+    /// radius unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PlanningError::InvalidParameter` if `gamma` is not positive and finite, or
+    /// `dimension` is 0. PRM* mode is left unchanged.
+    pub fn set_star(&mut self, gamma: f64, dimension: usize) -> Result<(), PlanningError> {
+        if !(gamma.is_finite() && gamma > 0.0) {
+            return Err(PlanningError::InvalidParameter {
+                name: "gamma",
+                value: gamma,
+            });
         }
+        if dimension == 0 {
+            return Err(PlanningError::InvalidParameter {
+                name: "dimension",
+                value: 0.0,
+            });
+        }
+        self.star = Some((gamma, dimension));
+        Ok(())
+    }
+
+    /// Returns the radius within which milestones are connected to a new state, given the current
+    /// size of the roadmap.
+    pub fn current_connection_radius(&self) -> f64 {
+        match self.star {
+            Some((gamma, dimension)) => {
+                // Count the state being connected, so that the radius is defined from the start.
+                let n = (self.roadmap.len() + 1).max(2) as f64;
+                let radius = gamma * (n.ln() / n).powf(1.0 / dimension as f64);
+                radius.min(self.connection_radius)
+            }
+            None => self.connection_radius,
+        }
+    }
+
+    /// Sets the sampler that draws the roadmap's states, e.g. a `HaltonSampler` for an even,
+    /// reproducible roadmap. Without it, the space is sampled uniformly.
+    pub fn set_sampler(&mut self, sampler: Arc<dyn StateSampler<S>>) {
+        self.sampler = Some(sampler);
+    }
+
+    /// Sets the nearest-neighbour structure used to find the roadmap nodes within
+    /// `connection_radius` of a new sample or of the start state.
+    ///
+    /// `factory` is called in `setup` with the problem's space. Without it, the roadmap is
+    /// scanned linearly, applying the connection pre-filter before each distance computation.
+    pub fn set_nearest_neighbors(&mut self, factory: NearestNeighborsFactory<S, SP>) {
+        self.nn_factory = Some(factory);
+    }
+
+    /// Ties the roadmap to the generation of a changing environment.
+    ///
+    /// The roadmap remembers the generation it was built at. Once `source` reports a different
+    /// generation the roadmap is stale: `construct_roadmap` rebuilds it from scratch and `solve`
+    /// refuses to use it, returning `PlanningError::UnsampledStateSpace`. `source` is usually the
+    /// same checker passed to `setup`.
+    pub fn track_validity_generation(&mut self, source: Arc<dyn IncrementalValidityChecker<S>>) {
+        self.generation_source = Some(source);
+    }
+
+    /// Whether the environment has changed since the roadmap was built.
+    pub fn is_roadmap_stale(&self) -> bool {
+        match (&self.generation_source, self.roadmap_generation) {
+            (Some(source), Some(generation)) => source.generation() != generation,
+            _ => false,
+        }
+    }
+
+    /// Whether the pre-filter, if any, allows `a` and `b` to be connected.
+    fn passes_prefilter(&self, a: &S, b: &S) -> bool {
+        self.prefilter
+            .as_ref()
+            .is_none_or(|prefilter| prefilter(a, b))
+    }
+
+    /// Returns the indices, in ascending order, of the roadmap nodes within the current
+    /// connection radius of `state` that pass the pre-filter.
+    fn candidate_neighbours(&self, space: &SP, state: &S) -> Vec<usize> {
+        let radius = self.current_connection_radius();
+        match &self.nn {
+            Some(nn) => nn
+                .nearest_within_radius(state, radius)
+                .into_iter()
+                .filter(|&i| self.passes_prefilter(state, &self.roadmap[i].state))
+                .collect(),
+            None => (0..self.roadmap.len())
+                .filter(|&i| {
+                    self.passes_prefilter(state, &self.roadmap[i].state)
+                        && space.distance(state, &self.roadmap[i].state) < radius
+                })
+                .collect(),
+        }
+    }
+
+    /// Reserves capacity for at least `n` more roadmap nodes, so that building a large roadmap
+    /// does not repeatedly reallocate.
+    ///
+    /// `setup` keeps the roadmap's capacity but creates a fresh nearest-neighbour structure, so
+    /// call this after `setup` for the reservation to cover both.
+    pub fn reserve(&mut self, n: usize) {
+        self.roadmap.reserve(n);
+        if let Some(nn) = self.nn.as_mut() {
+            nn.reserve(n);
+        }
+    }
+
+    /// Returns the number of milestones in the roadmap.
+    pub fn roadmap_size(&self) -> usize {
+        self.roadmap.len()
     }
 
     /// Get private variable `roadmap` as a clone.
@@ -83,6 +445,106 @@ where
         self.roadmap.clone()
     }
 
+    /// Exports the roadmap's milestones and edges, e.g. to save them for a later run.
+    ///
+    /// In lazy mode, edges that no query has checked yet are left out, so that every exported
+    /// edge is known to be collision-free.
+    pub fn export_roadmap(&self) -> RoadmapData<S> {
+        RoadmapData {
+            states: self.roadmap.iter().map(|node| node.state.clone()).collect(),
+            edges: self
+                .roadmap
+                .iter()
+                .map(|node| {
+                    node.edges
+                        .iter()
+                        .zip(&node.edge_checked)
+                        .filter(|(_, &checked)| checked)
+                        .map(|(&i, _)| i)
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Replaces the roadmap with one exported by `export_roadmap`, so that `solve` queries it
+    /// straight away instead of constructing a new one.
+    ///
+    /// Edges are trusted to be collision-free and their costs are recomputed, e.g. with the
+    /// clearance weighting of this planner. `setup` discards the roadmap, so call this after it.
+    ///
+    /// # Errors
+    ///
+    /// * `PlanningError::PlannerUninitialised` if `setup` has not been called.
+    /// * `PlanningError::InvalidProblemDefinition` if `data` does not have one list of edges per
+    ///   state, an edge refers to a missing milestone, or a state does not fit the space. The
+    ///   roadmap is left unchanged.
+    pub fn import_roadmap(&mut self, data: RoadmapData<S>) -> Result<(), PlanningError> {
+        let pd = self
+            .problem_def
+            .clone()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        let n = data.states.len();
+        if data.edges.len() != n {
+            return Err(PlanningError::InvalidProblemDefinition(format!(
+                "the roadmap has {n} states but {} lists of edges",
+                data.edges.len()
+            )));
+        }
+        if let Some(i) = data.states.iter().position(|s| !pd.space.is_compatible(s)) {
+            return Err(PlanningError::InvalidProblemDefinition(format!(
+                "roadmap state {i} does not match the dimension of the space"
+            )));
+        }
+        if let Some(i) = data.edges.iter().flatten().find(|&&i| i >= n) {
+            return Err(PlanningError::InvalidProblemDefinition(format!(
+                "a roadmap edge refers to milestone {i}, but there are only {n}"
+            )));
+        }
+
+        self.clear_roadmap();
+        self.roadmap_generation = self
+            .generation_source
+            .as_ref()
+            .map(|source| source.generation());
+        for (i, state) in data.states.into_iter().enumerate() {
+            if let Some(nn) = self.nn.as_mut() {
+                nn.add(i, state.clone());
+            }
+            self.roadmap.push(Node {
+                state,
+                edges: Vec::new(),
+                edge_costs: Vec::new(),
+                edge_checked: Vec::new(),
+            });
+        }
+        // Collect each edge once, however many of its ends list it.
+        let mut edges: Vec<(usize, usize)> = data
+            .edges
+            .iter()
+            .enumerate()
+            .flat_map(|(i, neighbours)| {
+                neighbours
+                    .iter()
+                    .filter(move |&&j| j != i)
+                    .map(move |&j| (i.min(j), i.max(j)))
+            })
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        for (i, j) in edges {
+            let cost = self.edge_cost(&self.roadmap[i].state, &self.roadmap[j].state);
+            for (from, to) in [(i, j), (j, i)] {
+                let node = &mut self.roadmap[from];
+                node.edges.push(to);
+                node.edge_costs.push(cost);
+                node.edge_checked.push(true);
+            }
+        }
+        logging::roadmap_constructed("PRM", self.roadmap.len(), true);
+        Ok(())
+    }
+
     /// Update ProblemDefinition. This is so that you can use an already sampled roadmap but just
     /// change the start and goal states.
     pub fn set_problem_definition(&mut self, pd: Arc<ProblemDefinition<S, SP, G>>) {
@@ -94,96 +556,244 @@ where
     /// This method populates the roadmap by sampling states and connecting them until the
     /// specified timeout is reached.
     pub fn construct_roadmap(&mut self) -> Result<(), PlanningError> {
+        self.construct_roadmap_with(TerminationCondition::Timeout(Duration::from_secs_f64(
+            self.timeout,
+        )))
+    }
+
+    /// Constructs the probabilistic roadmap from exactly `iterations` samples, regardless of
+    /// elapsed time.
+    ///
+    /// Combined with `set_seed`, this builds the same roadmap on every machine.
+    pub fn construct_roadmap_iterations(&mut self, iterations: usize) -> Result<(), PlanningError> {
+        self.construct_roadmap_with(TerminationCondition::MaxIterations(iterations))
+    }
+
+    /// Constructs the probabilistic roadmap, sampling until `condition` is met.
+    ///
+    /// Each sample counts as one iteration, and the tree size checked against the condition is
+    /// the number of milestones in the roadmap.
+    pub fn construct_roadmap_with(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<(), PlanningError> {
+        if !self.begin_roadmap()? {
+            return Ok(());
+        }
+        self.grow_roadmap(condition)
+    }
+
+    /// Adds milestones to the existing roadmap for `additional_timeout`, e.g. after a query
+    /// failed because the roadmap was too sparse.
+    ///
+    /// New milestones are connected to all milestones within `connection_radius`, old and new
+    /// alike. If there is no roadmap yet, or it is stale, a new one is constructed instead.
+    pub fn expand_roadmap(&mut self, additional_timeout: Duration) -> Result<(), PlanningError> {
+        self.expand_roadmap_with(TerminationCondition::Timeout(additional_timeout))
+    }
+
+    /// Adds milestones to the existing roadmap like `expand_roadmap`, sampling until `condition`
+    /// is met.
+    ///
+    /// Each sample counts as one iteration, and the tree size checked against the condition is
+    /// the number of milestones in the whole roadmap.
+    pub fn expand_roadmap_with(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<(), PlanningError> {
+        if self.roadmap.is_empty() || self.is_roadmap_stale() {
+            return self.construct_roadmap_with(condition);
+        }
+        self.grow_roadmap(condition)
+    }
+
+    /// Samples milestones and connects them to the roadmap until `condition` is met.
+    fn grow_roadmap(&mut self, condition: TerminationCondition) -> Result<(), PlanningError> {
         let pd = self
             .problem_def
-            .as_ref()
+            .clone()
             .ok_or(PlanningError::PlannerUninitialised)?;
         let vc = self
             .validity_checker
-            .as_ref()
+            .clone()
             .ok_or(PlanningError::PlannerUninitialised)?;
 
-        if !self.roadmap.is_empty() {
-            println!(
-                "PRM: Roadmap already constructed with {} milestones.",
-                self.roadmap.len()
-            );
-
-            return Ok(());
-        }
-
-        let mut rng = rand::rng();
         let start_time = Instant::now();
+        let mut iteration = 0;
         loop {
-            if start_time.elapsed().as_secs_f64() > self.timeout {
+            // Abort if the validity checker failed while adding the previous sample, discarding
+            // the partial roadmap so that it is rebuilt on the next call.
+            if let Err(err) = planner::check_validity_error(&vc) {
+                self.clear_roadmap();
+                return Err(err);
+            }
+            if condition
+                .check(start_time.elapsed(), iteration, self.roadmap.len())
+                .is_some()
+            {
                 break;
             }
+            iteration += 1;
 
-            let q_rand = pd.space.sample_uniform(&mut rng).unwrap();
+            let q_rand = self.sample_state(&pd.space)?;
             if vc.is_valid(&q_rand) {
                 let mut new_node = Node {
                     state: q_rand.clone(),
                     edges: Vec::new(),
+                    edge_costs: Vec::new(),
+                    edge_checked: Vec::new(),
                 };
 
-                let mut to_update: Vec<usize> = Vec::new();
+                let mut to_update: Vec<(usize, f64)> = Vec::new();
 
-                for i in 0..self.roadmap.len() {
+                for i in self.candidate_neighbours(&pd.space, &q_rand) {
                     let other_state = self.roadmap[i].state.clone();
-                    let dist = pd.space.distance(&q_rand, &other_state);
-                    if dist < self.connection_radius && self.check_motion(&q_rand, &other_state) {
+                    if self.lazy || self.check_edge(&q_rand, &other_state) {
+                        let cost = self.edge_cost(&q_rand, &other_state);
                         new_node.edges.push(i);
-                        to_update.push(i);
+                        new_node.edge_costs.push(cost);
+                        new_node.edge_checked.push(!self.lazy);
+                        to_update.push((i, cost));
                     }
                 }
 
                 let new_node_idx = self.roadmap.len();
                 self.roadmap.push(new_node);
+                logging::node_milestone("PRM", self.roadmap.len());
+                if let Some(nn) = self.nn.as_mut() {
+                    nn.add(new_node_idx, q_rand);
+                }
 
-                for i in to_update {
+                for (i, cost) in to_update {
                     self.roadmap[i].edges.push(new_node_idx);
+                    self.roadmap[i].edge_costs.push(cost);
+                    self.roadmap[i].edge_checked.push(!self.lazy);
                 }
             }
         }
-        println!(
-            "PRM: Roadmap constructed with {} milestones.",
-            self.roadmap.len()
-        );
+        logging::roadmap_constructed("PRM", self.roadmap.len(), false);
 
         Ok(())
     }
 
-    /// An internal helper function to check if the motion between two states is valid.
+    /// Prepares to build the roadmap, first discarding it if it is stale.
     ///
-    /// It works by discretizing the straight-line path between `from` and `to` into small steps
-    /// and calling the `StateValidityChecker` on each intermediate state. If any intermediate
-    /// state is invalid, the entire motion is considered invalid.
+    /// Returns `false` if an up-to-date roadmap already exists, so there is nothing to build.
+    fn begin_roadmap(&mut self) -> Result<bool, PlanningError> {
+        if self.is_roadmap_stale() {
+            self.clear_roadmap();
+        }
+        if self.problem_def.is_none() || self.validity_checker.is_none() {
+            return Err(PlanningError::PlannerUninitialised);
+        }
+
+        if !self.roadmap.is_empty() {
+            logging::roadmap_constructed("PRM", self.roadmap.len(), true);
+            return Ok(false);
+        }
+
+        self.roadmap_generation = self
+            .generation_source
+            .as_ref()
+            .map(|source| source.generation());
+        Ok(true)
+    }
+
+    /// Removes every node from the roadmap and the nearest-neighbour structure.
+    fn clear_roadmap(&mut self) {
+        self.roadmap.clear();
+        if let Some(nn) = self.nn.as_mut() {
+            nn.clear();
+        }
+    }
+
+    /// Draws a candidate milestone with the sampler, or uniformly from `space` without one.
+    fn sample_state(&mut self, space: &SP) -> Result<S, PlanningError> {
+        match &self.sampler {
+            Some(sampler) => sampler.sample(&mut *self.rng),
+            None => space.sample_uniform(&mut self.rng),
+        }
+        .map_err(PlanningError::SamplingFailed)
+    }
+
+    /// Checks the motion between two states with the motion validator created in `setup`.
     fn check_motion(&self, from: &S, to: &S) -> bool {
-        // We need access to the space and checker from our stored setup info.
-        if let (Some(pd), Some(vc)) = (&self.problem_def, &self.validity_checker) {
-            let space = &pd.space;
+        self.motion_validator
+            .as_ref()
+            .is_some_and(|validator| validator.check_motion(from, to))
+    }
 
-            let dist = space.distance(from, to);
-            let num_steps =
-                (dist / (space.get_longest_valid_segment_length() * 0.1)).ceil() as usize;
+    /// Checks the roadmap edge between `a` and `b`.
+    ///
+    /// The search may follow an edge either way, so in a space whose metric is not symmetric both
+    /// directions are checked, since they are different motions.
+    fn check_edge(&self, a: &S, b: &S) -> bool {
+        let symmetric = self
+            .problem_def
+            .as_ref()
+            .is_none_or(|pd| pd.space.is_metric_symmetric());
+        self.check_motion(a, b) && (symmetric || self.check_motion(b, a))
+    }
 
-            if num_steps <= 1 {
-                return vc.is_valid(to);
+    /// Computes the cost of the edge between `from` and `to`.
+    ///
+    /// Without clearance weighting this is the edge length. Otherwise the length is scaled by
+    /// `1 + weight / average_clearance`, where the clearance is averaged over states sampled at
+    /// the motion-checking resolution.
+    fn edge_cost(&self, from: &S, to: &S) -> f64 {
+        let Some(pd) = &self.problem_def else {
+            return f64::INFINITY;
+        };
+        let space = &pd.space;
+        match &self.clearance {
+            Some((clearance, weight)) => {
+                objective::clearance_weighted_cost(&**space, &**clearance, *weight, from, to)
             }
+            None => space.distance(from, to),
+        }
+    }
 
-            let mut interpolated_state = from.clone();
-            for i in 1..=num_steps {
-                let t = i as f64 / num_steps as f64;
-                space.interpolate(from, to, t, &mut interpolated_state);
-                if !vc.is_valid(&interpolated_state) {
+    /// Checks the not yet checked edges along the path ending at `goal_idx`, marking valid edges
+    /// as checked and removing invalid ones from the roadmap.
+    ///
+    /// Returns whether every edge along the path is valid. Checking stops at the first invalid
+    /// edge, since the path has to be searched for again anyway.
+    fn validate_path(
+        &mut self,
+        parent_map: &HashMap<usize, Option<usize>>,
+        goal_idx: usize,
+    ) -> bool {
+        let mut current = goal_idx;
+        while let Some(parent) = parent_map[&current] {
+            let Some(position) = self.roadmap[parent]
+                .edges
+                .iter()
+                .position(|&i| i == current)
+            else {
+                return false;
+            };
+            if !self.roadmap[parent].edge_checked[position] {
+                let valid =
+                    self.check_edge(&self.roadmap[parent].state, &self.roadmap[current].state);
+                for (from, to) in [(parent, current), (current, parent)] {
+                    let node = &mut self.roadmap[from];
+                    if let Some(position) = node.edges.iter().position(|&i| i == to) {
+                        if valid {
+                            node.edge_checked[position] = true;
+                        } else {
+                            node.edges.remove(position);
+                            node.edge_costs.remove(position);
+                            node.edge_checked.remove(position);
+                        }
+                    }
+                }
+                if !valid {
                     return false;
                 }
             }
-
-            true
-        } else {
-            false
+            current = parent;
         }
+        true
     }
 
     fn reconstruct_path(
@@ -208,10 +818,162 @@ where
     }
 }
 
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+impl<S, SP, G> PRM<S, SP, G>
+where
+    S: State + Send + Sync + 'static,
+    SP: StateSpace<StateType = S> + Send + Sync + 'static,
+    G: Goal<S> + GoalSampleableRegion<S>,
+{
+    /// Sets a thread-safe motion validator, used to check the motions between states both by
+    /// `construct_roadmap_parallel` and, like `set_motion_validator`, by everything else.
+    ///
+    /// It takes effect in `setup_parallel`.
+    pub fn set_parallel_motion_validator(
+        &mut self,
+        validator: Arc<dyn MotionValidator<S> + Send + Sync>,
+    ) {
+        self.custom_motion_validator = Some(validator.clone());
+        self.parallel_motion_validator = Some(validator);
+    }
+
+    /// Sets the planner up like `setup`, with a thread-safe validity checker that
+    /// `construct_roadmap_parallel` can share between threads.
+    pub fn setup_parallel(
+        &mut self,
+        problem_def: Arc<ProblemDefinition<S, SP, G>>,
+        validity_checker: Arc<dyn StateValidityChecker<S> + Send + Sync>,
+    ) {
+        self.setup(problem_def, validity_checker.clone());
+        self.parallel_checker = self
+            .validity_checker
+            .as_ref()
+            .map(|counting| Arc::new(counting.with_inner(validity_checker)));
+    }
+
+    /// Constructs the probabilistic roadmap like `construct_roadmap_with`, checking the samples
+    /// and motions of each batch in parallel on `rayon`'s thread pool.
+    ///
+    /// Each round draws `batch_size` samples, keeps those the validity checker finds valid, adds
+    /// them to the roadmap and then checks the motions to all of their neighbours at once,
+    /// including those added earlier in the same batch. Sampling stays sequential, so with the
+    /// same seed and an iteration condition this builds the same roadmap as
+    /// `construct_roadmap_with`.
+    ///
+    /// The planner must have been set up with `setup_parallel`, or this returns
+    /// `PlanningError::PlannerUninitialised`. States are checked with the checker given to it, and
+    /// motions with the validator set by `set_parallel_motion_validator` or, without one, with
+    /// the same checker at the space's resolution. Their checks count towards
+    /// `set_max_validity_checks` as usual.
+    pub fn construct_roadmap_parallel(
+        &mut self,
+        condition: TerminationCondition,
+        batch_size: usize,
+    ) -> Result<(), PlanningError> {
+        use rayon::prelude::*;
+
+        let checker = self
+            .parallel_checker
+            .clone()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        if !self.begin_roadmap()? {
+            return Ok(());
+        }
+        let pd = self
+            .problem_def
+            .clone()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        let validator = self.parallel_motion_validator.clone();
+
+        let start_time = Instant::now();
+        let mut iteration = 0;
+        'construction: loop {
+            let mut batch = Vec::with_capacity(batch_size);
+            for _ in 0..batch_size.max(1) {
+                if condition
+                    .check(start_time.elapsed(), iteration, self.roadmap.len())
+                    .is_some()
+                {
+                    break;
+                }
+                iteration += 1;
+                batch.push(self.sample_state(&pd.space)?);
+            }
+            if batch.is_empty() {
+                break 'construction;
+            }
+
+            let valid_samples: Vec<S> = batch
+                .into_par_iter()
+                .filter(|state| checker.is_valid(state))
+                .collect();
+            if let Err(err) = planner::check_validity_error(&checker) {
+                self.clear_roadmap();
+                return Err(err);
+            }
+
+            // Add the batch's milestones first, so that later ones also connect to earlier ones.
+            let mut pairs = Vec::new();
+            for state in valid_samples {
+                let new_node_idx = self.roadmap.len();
+                for i in self.candidate_neighbours(&pd.space, &state) {
+                    pairs.push((new_node_idx, i));
+                }
+                if let Some(nn) = self.nn.as_mut() {
+                    nn.add(new_node_idx, state.clone());
+                }
+                self.roadmap.push(Node {
+                    state,
+                    edges: Vec::new(),
+                    edge_costs: Vec::new(),
+                    edge_checked: Vec::new(),
+                });
+                logging::node_milestone("PRM", self.roadmap.len());
+            }
+
+            let (space, roadmap, lazy) = (&*pd.space, &self.roadmap, self.lazy);
+            let symmetric = space.is_metric_symmetric();
+            let check_motion = |from: &S, to: &S| match &validator {
+                Some(validator) => validator.check_motion(from, to),
+                None => planner::check_motion(space, &*checker, from, to),
+            };
+            // As in `check_edge`, both directions of an edge are checked if they differ.
+            let valid_edges: Vec<bool> = pairs
+                .par_iter()
+                .map(|&(a, b)| {
+                    let (from, to) = (&roadmap[a].state, &roadmap[b].state);
+                    lazy || (check_motion(from, to) && (symmetric || check_motion(to, from)))
+                })
+                .collect();
+            if let Err(err) = planner::check_validity_error(&checker) {
+                self.clear_roadmap();
+                return Err(err);
+            }
+
+            for ((a, b), _) in pairs
+                .into_iter()
+                .zip(valid_edges)
+                .filter(|(_, valid)| *valid)
+            {
+                let cost = self.edge_cost(&self.roadmap[a].state, &self.roadmap[b].state);
+                for (from, to) in [(a, b), (b, a)] {
+                    let node = &mut self.roadmap[from];
+                    node.edges.push(to);
+                    node.edge_costs.push(cost);
+                    node.edge_checked.push(!lazy);
+                }
+            }
+        }
+        logging::roadmap_constructed("PRM", self.roadmap.len(), false);
+
+        Ok(())
+    }
+}
+
 impl<S, SP, G> Planner<S, SP, G> for PRM<S, SP, G>
 where
-    S: State + Clone,
-    SP: StateSpace<StateType = S>,
+    S: State + Clone + 'static,
+    SP: StateSpace<StateType = S> + 'static,
     G: Goal<S> + GoalSampleableRegion<S>,
 {
     fn setup(
@@ -219,41 +981,109 @@ where
         problem_def: Arc<ProblemDefinition<S, SP, G>>,
         validity_checker: Arc<dyn StateValidityChecker<S>>,
     ) {
+        let validity_checker =
+            planner::count_validity_checks(validity_checker, self.max_validity_checks);
+        if cfg!(debug_assertions) && self.check_axioms {
+            crate::base::space::debug_check_axioms(&*problem_def.space, &mut self.rng);
+        }
+
+        self.nn = self
+            .nn_factory
+            .as_ref()
+            .map(|factory| factory(problem_def.space.clone()));
+        self.motion_validator = Some(motion_validator::motion_validator_or_default(
+            &self.custom_motion_validator,
+            problem_def.space.clone(),
+            validity_checker.clone(),
+        ));
         self.problem_def = Some(problem_def);
         self.validity_checker = Some(validity_checker);
+        #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+        {
+            self.parallel_checker = None;
+        }
         self.roadmap.clear();
     }
 
-    fn solve(&mut self, timeout: Duration) -> Result<Path<S>, PlanningError> {
-        // Ensure setup has been called.
+    /// Searches the already constructed roadmap, giving up once `condition` is met.
+    ///
+    /// Each node expanded by the search counts as one iteration, and the tree size checked
+    /// against the condition is the number of nodes the search has settled.
+    fn solve_with(&mut self, condition: TerminationCondition) -> Result<Path<S>, PlanningError> {
+        self.query(&condition)
+    }
+
+    fn solve_detailed_with(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<Solution<S>, PlanningError> {
+        let start_time = Instant::now();
+        let path = self.query(&condition)?;
+        let stats = PlannerStats {
+            planning_time: start_time.elapsed(),
+            validity_checks: self.validity_check_count(),
+        };
         let pd = self
             .problem_def
             .as_ref()
             .ok_or(PlanningError::PlannerUninitialised)?;
+        Ok(Solution::exact("PRM", path, &*pd.space, stats))
+    }
+}
+
+impl<S, SP, G> PRM<S, SP, G>
+where
+    S: State + Clone,
+    SP: StateSpace<StateType = S>,
+    G: Goal<S> + GoalSampleableRegion<S>,
+{
+    /// Builds the roadmap from exactly `iterations` samples and then queries it without a time
+    /// limit.
+    ///
+    /// If an up-to-date roadmap has already been constructed it is reused as is. Returns
+    /// `PlanningError::NoSolutionFound` if the roadmap does not connect the start to the goal.
+    pub fn solve_iterations(&mut self, iterations: usize) -> Result<Path<S>, PlanningError> {
+        if self.roadmap.is_empty() || self.is_roadmap_stale() {
+            self.construct_roadmap_iterations(iterations)?;
+        }
+        // An empty `Any` is never met, so the query runs to completion.
+        self.query(&TerminationCondition::Any(Vec::new()))
+    }
+
+    /// Searches the roadmap for a path from the start state to the goal region, giving up once
+    /// `condition` is met.
+    ///
+    /// In lazy mode, the edges along each path found are checked, and the roadmap is searched
+    /// again without the invalid ones until a valid path is found.
+    fn query(&mut self, condition: &TerminationCondition) -> Result<Path<S>, PlanningError> {
+        // Ensure setup has been called.
+        let pd = self
+            .problem_def
+            .clone()
+            .ok_or(PlanningError::PlannerUninitialised)?;
         let vc = self
             .validity_checker
-            .as_ref()
+            .clone()
             .ok_or(PlanningError::PlannerUninitialised)?;
         let goal = &pd.goal;
 
-        if self.roadmap.is_empty() {
+        // A stale roadmap may contain edges through obstacles that have since moved.
+        if self.roadmap.is_empty() || self.is_roadmap_stale() {
             return Err(PlanningError::UnsampledStateSpace);
         }
 
-        let start_state = &pd.start_states[0];
+        let start_state = &planner::enforce_bounds(&*pd.space, pd.start_states[0].clone());
         if !vc.is_valid(start_state) {
             return Err(PlanningError::InvalidStartState);
         }
 
         // Connect start state to the roadmap
-        let mut start_connections = Vec::new();
-        for i in 0..self.roadmap.len() {
-            if pd.space.distance(start_state, &self.roadmap[i].state) < self.connection_radius
-                && self.check_motion(start_state, &self.roadmap[i].state)
-            {
-                start_connections.push(i);
-            }
-        }
+        let start_connections: Vec<usize> = self
+            .candidate_neighbours(&pd.space, start_state)
+            .into_iter()
+            .filter(|&i| self.check_motion(start_state, &self.roadmap[i].state))
+            .collect();
+        planner::check_validity_error(&*vc)?;
 
         // Find goal nodes in the roadmap
         let mut goal_indices = Vec::new();
@@ -267,35 +1097,123 @@ where
             return Err(PlanningError::NoSolutionFound);
         }
 
-        // Graph Search (Breadth-First Search)
-        let mut queue: VecDeque<usize> = start_connections.clone().into_iter().collect();
+        let start_time = Instant::now();
+        let mut iteration = 0;
+        loop {
+            let (parent_map, goal_node_idx) = self.search(
+                start_state,
+                &start_connections,
+                &goal_indices,
+                condition,
+                start_time,
+                &mut iteration,
+            )?;
+            if !self.lazy || self.validate_path(&parent_map, goal_node_idx) {
+                logging::solution_found("PRM", self.roadmap.len());
+                return Ok(self.reconstruct_path(start_state, parent_map, goal_node_idx));
+            }
+            planner::check_validity_error(&*vc)?;
+        }
+    }
+
+    /// Runs Dijkstra's algorithm (or A*) over the roadmap from the nodes in `start_connections` to
+    /// the nearest of `goal_indices`, returning the search's parent map and the goal node reached.
+    ///
+    /// `iteration` counts expanded nodes across searches, so that repeated lazy searches share
+    /// one termination condition.
+    fn search(
+        &self,
+        start_state: &S,
+        start_connections: &[usize],
+        goal_indices: &[usize],
+        condition: &TerminationCondition,
+        start_time: Instant,
+        iteration: &mut usize,
+    ) -> Result<(HashMap<usize, Option<usize>>, usize), PlanningError> {
+        let goal = &self
+            .problem_def
+            .as_ref()
+            .ok_or(PlanningError::PlannerUninitialised)?
+            .goal;
+
+        // The A* heuristic; zero for Dijkstra.
+        let heuristic = |index: usize| {
+            if self.astar {
+                goal.distance_goal(&self.roadmap[index].state)
+            } else {
+                0.0
+            }
+        };
+
+        // Graph Search (Dijkstra or A*)
+        let mut queue: BinaryHeap<QueueEntry> = BinaryHeap::new();
         let mut parent_map: HashMap<usize, Option<usize>> = HashMap::new();
+        let mut best_cost = vec![f64::INFINITY; self.roadmap.len()];
         let mut visited = vec![false; self.roadmap.len()];
 
-        for idx in &start_connections {
-            queue.push_back(*idx);
-            parent_map.insert(*idx, None);
-            visited[*idx] = true;
+        for &idx in start_connections {
+            let cost = self.edge_cost(start_state, &self.roadmap[idx].state);
+            if cost < best_cost[idx] {
+                best_cost[idx] = cost;
+                parent_map.insert(idx, None);
+                queue.push(QueueEntry {
+                    priority: cost + heuristic(idx),
+                    cost,
+                    index: idx,
+                });
+            }
         }
 
         let mut goal_reached = None;
 
-        let start_time = Instant::now();
-        while let Some(current_idx) = queue.pop_front() {
-            if start_time.elapsed() > timeout {
-                return Err(PlanningError::Timeout);
+        let mut settled = 0;
+        while let Some(QueueEntry {
+            cost,
+            index: current_idx,
+            ..
+        }) = queue.pop()
+        {
+            match condition.check(start_time.elapsed(), *iteration, settled) {
+                Some(TerminationReason::Timeout) => {
+                    // Report progress over the part of the roadmap the search has reached.
+                    let best_goal_distance = (0..self.roadmap.len())
+                        .filter(|&i| visited[i])
+                        .map(|i| goal.distance_goal(&self.roadmap[i].state))
+                        .fold(f64::INFINITY, f64::min);
+                    let nodes = self.roadmap.len();
+                    logging::timeout("PRM", nodes, best_goal_distance);
+                    return Err(PlanningError::Timeout {
+                        best_goal_distance,
+                        nodes,
+                    });
+                }
+                Some(_) => return Err(PlanningError::NoSolutionFound),
+                None => {}
+            }
+            *iteration += 1;
+
+            if visited[current_idx] {
+                continue;
             }
+            visited[current_idx] = true;
+            settled += 1;
 
             if goal_indices.contains(&current_idx) {
                 goal_reached = Some(current_idx);
                 break;
             }
 
-            for &neighbor_idx in &self.roadmap[current_idx].edges {
-                if !visited[neighbor_idx] {
-                    visited[neighbor_idx] = true;
+            let node = &self.roadmap[current_idx];
+            for (&neighbor_idx, &edge_cost) in node.edges.iter().zip(&node.edge_costs) {
+                let new_cost = cost + edge_cost;
+                if !visited[neighbor_idx] && new_cost < best_cost[neighbor_idx] {
+                    best_cost[neighbor_idx] = new_cost;
                     parent_map.insert(neighbor_idx, Some(current_idx));
-                    queue.push_back(neighbor_idx);
+                    queue.push(QueueEntry {
+                        priority: new_cost + heuristic(neighbor_idx),
+                        cost: new_cost,
+                        index: neighbor_idx,
+                    });
                 }
             }
         }
@@ -303,6 +1221,33 @@ where
         // If no goal was reached, no path exists
         let goal_node_idx = goal_reached.ok_or(PlanningError::NoSolutionFound)?;
 
-        Ok(self.reconstruct_path(start_state, parent_map, goal_node_idx))
+        Ok((parent_map, goal_node_idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{space::RealVectorStateSpace, state::RealVectorState};
+    use crate::test_fixtures::{AlwaysValid, PointGoal};
+
+    #[test]
+    fn test_reserve_presizes_the_roadmap() {
+        let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0); 2])).unwrap());
+        let problem_def = Arc::new(ProblemDefinition {
+            space,
+            start_states: vec![RealVectorState::new(vec![1.0, 1.0])],
+            goal: Arc::new(PointGoal(RealVectorState::new(vec![9.0, 9.0]))),
+            start_sampler: None,
+        });
+
+        let mut planner = PRM::new(1.0, 2.0);
+        planner.set_seed(0);
+        planner.setup(problem_def, Arc::new(AlwaysValid));
+        planner.reserve(500);
+        assert!(planner.roadmap.capacity() >= 500);
+
+        planner.construct_roadmap_iterations(500).unwrap();
+        assert_eq!(planner.roadmap.len(), 500);
     }
 }
@@ -4,18 +4,25 @@
 
 use std::sync::Arc;
 
-use crate::time::{Duration, Instant};
+use crate::{
+    logging,
+    time::{Duration, Instant},
+};
 
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
 
 use crate::base::{
     error::PlanningError,
     goal::{Goal, GoalSampleableRegion},
-    planner::{Path, Planner},
+    motion_validator::{self, MotionValidator},
+    nearest_neighbors::{LinearNN, NearestNeighbors, NearestNeighborsFactory},
+    planner::{self, Path, Planner, PlannerStats, Solution},
     problem_definition::ProblemDefinition,
-    space::StateSpace,
+    sampler::StateSampler,
+    space::{StateSpace, SuggestedParams},
     state::State,
-    validity::StateValidityChecker,
+    termination::{TerminationCondition, TerminationReason},
+    validity::{CountingValidityChecker, StateValidityChecker},
 };
 
 // A helper struct to build the tree. Each node stores its state and the index of its parent in the
@@ -26,6 +33,9 @@ struct Node<S: State> {
     parent_index: Option<usize>,
 }
 
+/// The default for `RRT::max_stagnant_iterations`.
+const DEFAULT_MAX_STAGNANT_ITERATIONS: usize = 1000;
+
 /// An implementation of the Rapidly-exploring Random Tree (RRT) algorithm.
 ///
 /// RRT is a randomized, sampling-based algorithm designed to efficiently search high-dimensional
@@ -50,15 +60,45 @@ struct Node<S: State> {
 /// To use this planner, the following trait bounds must be met:
 /// - The `State` type (`S`) must be `Clone`.
 /// - The `Goal` type (`G`) must implement `GoalSampleableRegion` to support goal-biasing.
+///
+/// # Giving up early
+///
+/// The search stops early with `PlanningError::NoSolutionFound`, instead of running until its
+/// termination condition, if too many consecutive iterations fail to extend the tree. The limit is
+/// `max_stagnant_iterations` per node in the tree, so it grows with the tree: a start enclosed by
+/// obstacles is given up on after 1000 iterations by default, while a large tree searching for a
+/// narrow passage is given many more. Set `max_stagnant_iterations` to `usize::MAX` to always run
+/// until the termination condition.
 pub struct RRT<S: State, SP: StateSpace<StateType = S>, G: Goal<S>> {
     /// The maximum distance between nodes in the tree. This is the "step size".
     pub max_distance: f64,
     /// The probability of sampling the goal region instead of the whole space (e.g., 0.05 for 5%).
     pub goal_bias: f64,
+    /// The number of consecutive iterations, per node in the tree, that may fail to add a node
+    /// before the search gives up with `PlanningError::NoSolutionFound`, e.g. because the start
+    /// is enclosed by obstacles. Defaults to 1000.
+    pub max_stagnant_iterations: usize,
+    /// The largest number of nodes the tree may grow to, or `None` for no limit (the default).
+    ///
+    /// Once the tree reaches it, the search stops with `PlanningError::NoSolutionFound` rather
+    /// than allocating more nodes, whatever the termination condition.
+    pub max_nodes: Option<usize>,
 
     problem_def: Option<Arc<ProblemDefinition<S, SP, G>>>,
-    validity_checker: Option<Arc<dyn StateValidityChecker<S>>>,
+    validity_checker: Option<Arc<CountingValidityChecker<S>>>,
+    max_validity_checks: Option<usize>,
+    check_axioms: bool,
+    motion_validator: Option<Arc<dyn MotionValidator<S>>>,
+    custom_motion_validator: Option<Arc<dyn MotionValidator<S>>>,
     tree: Vec<Node<S>>,
+    nn_factory: Option<NearestNeighborsFactory<S, SP>>,
+    nn: Option<Box<dyn NearestNeighbors<S>>>,
+    sampler: Option<Arc<dyn StateSampler<S>>>,
+    rng: Box<dyn RngCore>,
+    goal_zoom: bool,
+    // The index of the node nearest the goal region and its `distance_goal`, kept up to date in
+    // goal-zoom mode.
+    closest_to_goal: Option<(usize, f64)>,
 }
 
 impl<S, SP, G> RRT<S, SP, G>
@@ -76,45 +116,135 @@ where
         RRT {
             max_distance,
             goal_bias,
+            max_stagnant_iterations: DEFAULT_MAX_STAGNANT_ITERATIONS,
+            max_nodes: None,
             problem_def: None,
             validity_checker: None,
+            max_validity_checks: None,
+            check_axioms: false,
+            motion_validator: None,
+            custom_motion_validator: None,
             tree: Vec::new(),
+            nn_factory: None,
+            nn: None,
+            sampler: None,
+            rng: Box::new(StdRng::from_os_rng()),
+            goal_zoom: false,
+            closest_to_goal: None,
         }
     }
 
-    /// An internal helper function to check if the motion between two states is valid.
+    /// Creates a new `RRT` planner using the parameters suggested by `space`.
     ///
-    /// It works by discretizing the straight-line path between `from` and `to` into small steps
-    /// and calling the `StateValidityChecker` on each intermediate state. If any intermediate
-    /// state is invalid, the entire motion is considered invalid.
-    fn check_motion(&self, from: &S, to: &S) -> bool {
-        // We need access to the space and checker from our stored setup info.
-        if let (Some(pd), Some(vc)) = (&self.problem_def, &self.validity_checker) {
-            let space = &pd.space;
+    /// See `StateSpace::suggested_planner_params`.
+    pub fn with_defaults(space: &SP) -> Self {
+        let SuggestedParams {
+            max_distance,
+            goal_bias,
+        } = space.suggested_planner_params();
+        Self::new(max_distance, goal_bias)
+    }
 
-            let dist = space.distance(from, to);
-            let num_steps =
-                (dist / (space.get_longest_valid_segment_length() * 0.1)).ceil() as usize;
+    /// Sets the sampler that draws the states the tree grows towards, e.g. a `HaltonSampler` or
+    /// a `BridgeTestSampler`, in the iterations that do not sample the goal. Without it, the
+    /// space is sampled uniformly.
+    pub fn set_sampler(&mut self, sampler: Arc<dyn StateSampler<S>>) {
+        self.sampler = Some(sampler);
+    }
 
-            if num_steps <= 1 {
-                return vc.is_valid(to);
-            }
+    /// Sets the nearest-neighbour structure used to search the tree.
+    ///
+    /// `factory` is called in `setup` with the problem's space. Without it, a `LinearNN` is used.
+    pub fn set_nearest_neighbors(&mut self, factory: NearestNeighborsFactory<S, SP>) {
+        self.nn_factory = Some(factory);
+    }
 
-            let mut interpolated_state = from.clone();
-            for i in 1..=num_steps {
-                let t = i as f64 / num_steps as f64;
-                space.interpolate(from, to, t, &mut interpolated_state);
-                if !vc.is_valid(&interpolated_state) {
-                    return false;
-                }
-            }
+    /// Sets whether goal-biased iterations "zoom" towards the goal from the tree's closest node.
+    ///
+    /// With goal zoom, a goal-biased iteration extends the node with the smallest
+    /// `distance_goal`, rather than the node nearest the goal sample, towards the goal sample.
+    /// The frontier closest to the goal then keeps advancing, instead of goal samples being spent
+    /// on extending whichever node happens to be nearest to them. Iterations that are not
+    /// goal-biased sample the space uniformly as usual.
+    pub fn set_goal_zoom(&mut self, goal_zoom: bool) {
+        self.goal_zoom = goal_zoom;
+    }
+
+    /// Sets the motion validator used to check the motions between states.
+    ///
+    /// It takes effect in `setup`. Without it, `setup` creates a `DiscreteMotionValidator` from
+    /// the problem's space and validity checker.
+    pub fn set_motion_validator(&mut self, validator: Arc<dyn MotionValidator<S>>) {
+        self.custom_motion_validator = Some(validator);
+    }
+
+    /// Sets the largest number of validity checks the planner may make, or removes the limit.
+    ///
+    /// It takes effect in `setup`, and counts every check made from then on, including those of
+    /// the start states and of motions checked by the default motion validator. A planner that
+    /// needs more checks stops with `PlanningError::CheckBudgetExhausted`.
+    pub fn set_max_validity_checks(&mut self, max_checks: Option<usize>) {
+        self.max_validity_checks = max_checks;
+    }
+
+    /// Sets whether `setup` checks the problem's space with `StateSpace::check_axioms`, panicking
+    /// if the space breaks one of them.
+    ///
+    /// The check only runs in builds with `debug_assertions`, and is off by default. It draws its
+    /// three states from the planner's random number generator, so a failure reproduces under
+    /// `set_seed`.
+    pub fn set_check_axioms(&mut self, check: bool) {
+        self.check_axioms = check;
+    }
+
+    /// Returns the number of validity checks made since `setup`.
+    pub fn validity_check_count(&self) -> usize {
+        self.validity_checker
+            .as_ref()
+            .map_or(0, |checker| checker.count())
+    }
+
+    /// Seeds the planner's random number generator, used for both uniform and goal sampling.
+    ///
+    /// Combined with `solve_iterations`, this makes planning runs reproducible. Since RRT returns
+    /// its first solution, a seeded `solve` is reproducible too, as long as it finishes before the
+    /// timeout.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Box::new(StdRng::seed_from_u64(seed));
+    }
+
+    /// Replaces the planner's random number generator, used for both uniform and goal sampling.
+    ///
+    /// Like `set_seed`, this makes runs reproducible with a generator of the caller's choosing,
+    /// e.g. a `RecordingRng` or `ReplayRng` from `oxmpl::testing` to debug a nondeterministic run.
+    pub fn set_rng(&mut self, rng: impl RngCore + 'static) {
+        self.rng = Box::new(rng);
+    }
+
+    /// Returns the number of nodes currently in the tree.
+    pub fn tree_size(&self) -> usize {
+        self.tree.len()
+    }
 
-            true
-        } else {
-            false
+    /// Reserves capacity for at least `n` more nodes, so that growing a large tree does not
+    /// repeatedly reallocate.
+    ///
+    /// `setup` keeps the tree's capacity but creates a fresh nearest-neighbour structure, so call
+    /// this after `setup` for the reservation to cover both.
+    pub fn reserve(&mut self, n: usize) {
+        self.tree.reserve(n);
+        if let Some(nn) = self.nn.as_mut() {
+            nn.reserve(n);
         }
     }
 
+    /// Checks the motion between two states with the motion validator created in `setup`.
+    fn check_motion(&self, from: &S, to: &S) -> bool {
+        self.motion_validator
+            .as_ref()
+            .is_some_and(|validator| validator.check_motion(from, to))
+    }
+
     fn reconstruct_path(&self, start_node_idx: usize) -> Path<S> {
         let mut path_states = Vec::new();
         let mut current_index = Some(start_node_idx);
@@ -132,8 +262,8 @@ where
 impl<S, SP, G> Planner<S, SP, G> for RRT<S, SP, G>
 where
     // RRT needs to clone states to store them in its tree.
-    S: State + Clone,
-    SP: StateSpace<StateType = S>,
+    S: State + Clone + 'static,
+    SP: StateSpace<StateType = S> + 'static,
     // For goal biasing, the Goal type must be sampleable.
     G: Goal<S> + GoalSampleableRegion<S>,
 {
@@ -142,20 +272,181 @@ where
         problem_def: Arc<ProblemDefinition<S, SP, G>>,
         validity_checker: Arc<dyn StateValidityChecker<S>>,
     ) {
+        let validity_checker =
+            planner::count_validity_checks(validity_checker, self.max_validity_checks);
+        if cfg!(debug_assertions) && self.check_axioms {
+            crate::base::space::debug_check_axioms(&*problem_def.space, &mut self.rng);
+        }
+
+        let space = problem_def.space.clone();
+        self.nn = Some(match &self.nn_factory {
+            Some(factory) => factory(space),
+            None => Box::new(LinearNN::new(space)),
+        });
+
+        self.motion_validator = Some(motion_validator::motion_validator_or_default(
+            &self.custom_motion_validator,
+            problem_def.space.clone(),
+            validity_checker.clone(),
+        ));
         self.problem_def = Some(problem_def);
         self.validity_checker = Some(validity_checker);
         self.tree.clear();
 
-        // Initialise the tree with the start state.
-        let start_state = self.problem_def.as_ref().unwrap().start_states[0].clone();
-        let start_node = Node {
-            state: start_state,
-            parent_index: None,
+        // Initialise the tree with the start states, plus the states drawn from the start
+        // sampler, brought within the bounds of the space. Sampled states are checked for
+        // validity once they are within the bounds, as that is the state the tree holds.
+        let pd = self.problem_def.as_ref().unwrap();
+        let vc = self.validity_checker.as_ref().unwrap();
+        let nn = self.nn.as_mut().unwrap();
+        let sampled_starts = pd
+            .sample_start_states(&mut self.rng)
+            .into_iter()
+            .map(|state| planner::enforce_bounds(&*pd.space, state))
+            .filter(|state| vc.is_valid(state));
+        let start_states = pd
+            .start_states
+            .iter()
+            .map(|state| planner::enforce_bounds(&*pd.space, state.clone()));
+        for start_state in start_states.chain(sampled_starts) {
+            nn.add(self.tree.len(), start_state.clone());
+            self.tree.push(Node {
+                state: start_state,
+                parent_index: None,
+            });
+        }
+    }
+
+    fn solve_with(&mut self, condition: TerminationCondition) -> Result<Path<S>, PlanningError> {
+        self.run(&condition)
+    }
+
+    fn solve_detailed_with(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<Solution<S>, PlanningError> {
+        let start_time = Instant::now();
+        let path = self.run(&condition)?;
+        let stats = PlannerStats {
+            planning_time: start_time.elapsed(),
+            validity_checks: self.validity_check_count(),
         };
-        self.tree.push(start_node);
+        let pd = self
+            .problem_def
+            .as_ref()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        Ok(Solution::exact("RRT", path, &*pd.space, stats))
+    }
+}
+
+impl<S, SP, G> RRT<S, SP, G>
+where
+    S: State + Clone,
+    SP: StateSpace<StateType = S>,
+    G: Goal<S> + GoalSampleableRegion<S>,
+{
+    /// Runs at most `iterations` iterations of the main loop, regardless of elapsed time.
+    ///
+    /// Unlike `solve`, the amount of work done does not depend on the speed of the machine, so
+    /// seeded runs are reproducible. Returns `PlanningError::NoSolutionFound` if no solution was
+    /// found within the iteration budget.
+    pub fn solve_iterations(&mut self, iterations: usize) -> Result<Path<S>, PlanningError> {
+        self.run(&TerminationCondition::MaxIterations(iterations))
     }
 
-    fn solve(&mut self, timeout: Duration) -> Result<Path<S>, PlanningError> {
+    /// Like `solve_with`, but if the goal has not been reached when `condition` is met, returns
+    /// the path from the start to the node of the tree closest to the goal instead of an error.
+    ///
+    /// The flag is `true` if the path reaches the goal, and `false` if it is partial. Closeness is
+    /// measured by `GoalRegion::distance_goal`. Errors other than running out of time or
+    /// iterations, e.g. `PlanningError::InvalidStartState`, are still returned.
+    pub fn solve_partial(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<(Path<S>, bool), PlanningError> {
+        match self.run(&condition) {
+            Ok(path) => Ok((path, true)),
+            Err(PlanningError::Timeout { .. } | PlanningError::NoSolutionFound) => {
+                let pd = self
+                    .problem_def
+                    .as_ref()
+                    .ok_or(PlanningError::PlannerUninitialised)?;
+                let closest =
+                    planner::closest_to_goal(&*pd.goal, self.tree.iter().map(|node| &node.state))
+                        .ok_or(PlanningError::InvalidStartState)?;
+                Ok((self.reconstruct_path(closest), false))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Grows the tree through `seed_states` before running the main loop for at most `timeout`.
+    ///
+    /// The states are added in order, each connected to the previously added seed state if the
+    /// motion between them is valid, and otherwise to its nearest node in the tree. States that
+    /// are invalid or cannot be connected either way are skipped, so the seeds need not form a
+    /// contiguous valid path. This lets a known partial plan, e.g. from a previous planning
+    /// query, jump-start the search.
+    ///
+    /// `setup` must be called first.
+    pub fn solve_from_states(
+        &mut self,
+        seed_states: &[S],
+        timeout: Duration,
+    ) -> Result<Path<S>, PlanningError> {
+        let pd = self
+            .problem_def
+            .clone()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        let vc = self
+            .validity_checker
+            .clone()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+
+        planner::check_start_states(&*pd.space, &*vc, &pd.start_states)?;
+        if self.tree.is_empty() {
+            return Err(PlanningError::InvalidStartState);
+        }
+
+        let mut previous_seed_index: Option<usize> = None;
+        for state in seed_states {
+            if !vc.is_valid(state) {
+                continue;
+            }
+
+            let parent_index = previous_seed_index
+                .filter(|&index| self.check_motion(&self.tree[index].state, state))
+                .or_else(|| {
+                    self.nn
+                        .as_ref()
+                        .and_then(|nn| nn.nearest(state))
+                        .filter(|&index| self.check_motion(&self.tree[index].state, state))
+                });
+            let Some(parent_index) = parent_index else {
+                continue;
+            };
+
+            let new_node_index = self.tree.len();
+            self.tree.push(Node {
+                state: state.clone(),
+                parent_index: Some(parent_index),
+            });
+            if let Some(nn) = self.nn.as_mut() {
+                nn.add(new_node_index, state.clone());
+            }
+            previous_seed_index = Some(new_node_index);
+
+            if pd.goal.is_satisfied(state) {
+                logging::solution_found("RRT", self.tree.len());
+                return Ok(self.reconstruct_path(new_node_index));
+            }
+        }
+
+        self.run(&TerminationCondition::Timeout(timeout))
+    }
+
+    /// The main RRT loop, stopping once `condition` is met.
+    fn run(&mut self, condition: &TerminationCondition) -> Result<Path<S>, PlanningError> {
         // Ensure setup has been called.
         let pd = self
             .problem_def
@@ -163,38 +454,93 @@ where
             .ok_or(PlanningError::PlannerUninitialised)?;
         let goal = &pd.goal;
 
+        let vc = self
+            .validity_checker
+            .as_ref()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        planner::check_start_states(&*pd.space, &**vc, &pd.start_states)?;
+        if self.tree.is_empty() {
+            return Err(PlanningError::InvalidStartState);
+        }
+
+        if self.goal_zoom {
+            self.closest_to_goal = self
+                .tree
+                .iter()
+                .map(|node| goal.distance_goal(&node.state))
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        }
+
+        let condition = &*planner::with_max_nodes(condition, self.max_nodes);
         let start_time = Instant::now();
-        let mut rng = rand::rng();
+        let mut iteration = 0;
+        let mut stagnant_iterations = 0;
 
         // Main Loop
         loop {
-            // 1. Check for timeout
-            if start_time.elapsed() > timeout {
-                return Err(PlanningError::Timeout);
+            // Abort if the validity checker failed during the previous iteration.
+            if let Some(vc) = &self.validity_checker {
+                planner::check_validity_error(&**vc)?;
             }
+            // 1. Check the termination condition
+            match condition.check(start_time.elapsed(), iteration, self.tree.len()) {
+                Some(TerminationReason::Timeout) => {
+                    let best_goal_distance = self
+                        .tree
+                        .iter()
+                        .map(|node| goal.distance_goal(&node.state))
+                        .fold(f64::INFINITY, f64::min);
+                    let nodes = self.tree.len();
+                    logging::timeout("RRT", nodes, best_goal_distance);
+                    return Err(PlanningError::Timeout {
+                        best_goal_distance,
+                        nodes,
+                    });
+                }
+                Some(_) => return Err(PlanningError::NoSolutionFound),
+                None => {}
+            }
+            // Give up early if every motion out of the tree keeps being rejected. Larger trees are
+            // allowed longer runs of failures, as they take longer to find a narrow passage.
+            if stagnant_iterations >= self.max_stagnant_iterations.saturating_mul(self.tree.len()) {
+                return Err(PlanningError::NoSolutionFound);
+            }
+            iteration += 1;
 
-            // 2. Sample a state (q_rand)
-            let q_rand = if rng.random_bool(self.goal_bias) {
-                // TODO: assume sample_goal can't fail here for simplicity, but a real
-                // implementation would handle the Result.
-                goal.sample_goal(&mut rng).unwrap()
+            // 2. Sample a state (q_rand). In goal-zoom mode a goal sample is steered towards from
+            //    the node closest to the goal, falling back to uniform sampling if it fails.
+            let zoom_target = if self.goal_zoom && self.rng.random_bool(self.goal_bias) {
+                goal.sample_goal(&mut self.rng)
+                    .ok()
+                    .map(|state| planner::enforce_bounds(&*pd.space, state))
+                    .zip(self.closest_to_goal)
             } else {
-                // TODO: assume uniform sampling can't fail if bounds are set correctly.
-                pd.space.sample_uniform(&mut rng).unwrap()
+                None
             };
+            let (q_rand, nearest_node_index) = match zoom_target {
+                Some((q_rand, (closest_index, _))) => (q_rand, closest_index),
+                None => {
+                    let goal_bias = if self.goal_zoom { 0.0 } else { self.goal_bias };
+                    let q_rand = planner::sample_target(
+                        &*pd.space,
+                        &**goal,
+                        goal_bias,
+                        self.sampler.as_deref(),
+                        &mut self.rng,
+                    )?;
 
-            // 3. Find the nearest node in the tree (q_near)
-            let mut nearest_node_index = 0;
-            let mut min_dist = pd.space.distance(&self.tree[0].state, &q_rand);
-
-            for i in 1..self.tree.len() {
-                let dist = pd.space.distance(&self.tree[i].state, &q_rand);
-                if dist < min_dist {
-                    min_dist = dist;
-                    nearest_node_index = i;
+                    // 3. Find the nearest node in the tree (q_near)
+                    let nearest_node_index = self
+                        .nn
+                        .as_ref()
+                        .and_then(|nn| nn.nearest(&q_rand))
+                        .ok_or(PlanningError::PlannerUninitialised)?;
+                    (q_rand, nearest_node_index)
                 }
-            }
+            };
             let q_near = &self.tree[nearest_node_index].state;
+            let min_dist = pd.space.distance(q_near, &q_rand);
 
             // 4. Steer from q_near towards q_rand to get q_new
             let mut q_new = q_near.clone();
@@ -202,27 +548,172 @@ where
                 // If q_rand is too far, interpolate to a point at max_distance
                 let t = self.max_distance / min_dist;
                 pd.space.interpolate(q_near, &q_rand, t, &mut q_new);
+                // Interpolation may leave the bounds, through rounding or a curved path.
+                pd.space.enforce_bounds(&mut q_new);
             } else {
                 // If q_rand is close enough, just use it as q_new
                 q_new = q_rand;
             }
 
             // 5. Check if the motion to q_new is valid
-            if self.check_motion(q_near, &q_new) {
+            if !self.check_motion(q_near, &q_new) {
+                stagnant_iterations += 1;
+            } else {
+                stagnant_iterations = 0;
+
                 // 6. Add q_new to the tree
                 let new_node = Node {
                     state: q_new.clone(),
                     parent_index: Some(nearest_node_index),
                 };
                 self.tree.push(new_node);
+                if let Some(nn) = self.nn.as_mut() {
+                    nn.add(self.tree.len() - 1, q_new.clone());
+                }
+                logging::node_milestone("RRT", self.tree.len());
+                if self.goal_zoom {
+                    let goal_distance = goal.distance_goal(&q_new);
+                    if self
+                        .closest_to_goal
+                        .is_none_or(|(_, closest)| goal_distance < closest)
+                    {
+                        self.closest_to_goal = Some((self.tree.len() - 1, goal_distance));
+                    }
+                }
 
                 // 7. Check if the new node satisfies the goal
                 if goal.is_satisfied(&q_new) {
-                    println!("Solution found after {} nodes.", self.tree.len());
+                    logging::solution_found("RRT", self.tree.len());
                     return Ok(self.reconstruct_path(self.tree.len() - 1));
                 }
             }
         }
-        // TODO: Limit iteration counts and add Err(PlanningError::NoSolutionFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{
+        error::StateSamplingError,
+        nearest_neighbors::KdTreeNN,
+        space::{DubinsStateSpace, RealVectorStateSpace},
+        state::{RealVectorState, SE2State},
+    };
+    use crate::test_fixtures::{
+        AlwaysValid, BrokenSpace, OverhangingSampler, PointGoal, UnreachableGoal,
+    };
+    use rand::Rng;
+    use std::f64::consts::PI;
+
+    /// Samples uniformly from the left half, `x < 5`, of the 10 x 10 square.
+    struct LeftHalfSampler;
+
+    impl StateSampler<RealVectorState> for LeftHalfSampler {
+        fn sample(&self, rng: &mut dyn RngCore) -> Result<RealVectorState, StateSamplingError> {
+            Ok(RealVectorState::new(vec![
+                rng.random_range(0.0..5.0),
+                rng.random_range(0.0..10.0),
+            ]))
+        }
+    }
+
+    #[test]
+    fn test_reserve_presizes_the_tree() {
+        let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0); 2])).unwrap());
+        let problem_def = Arc::new(ProblemDefinition {
+            space,
+            start_states: vec![RealVectorState::new(vec![1.0, 1.0])],
+            goal: Arc::new(PointGoal(RealVectorState::new(vec![9.0, 9.0]))),
+            start_sampler: None,
+        });
+
+        let mut planner = RRT::new(0.5, 0.1);
+        planner.set_nearest_neighbors(Arc::new(|space| Box::new(KdTreeNN::new(space))));
+        planner.set_seed(0);
+        planner.setup(problem_def, Arc::new(AlwaysValid));
+        planner.reserve(10_000);
+        assert!(planner.tree.capacity() >= 10_000);
+
+        let path = planner.solve_iterations(10_000).unwrap();
+        assert_eq!(path.0.last(), Some(&RealVectorState::new(vec![9.0, 9.0])));
+    }
+
+    #[test]
+    fn test_sampler_keeps_the_tree_in_the_sampled_region() {
+        let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0); 2])).unwrap());
+        let problem_def = Arc::new(ProblemDefinition {
+            space,
+            start_states: vec![RealVectorState::new(vec![1.0, 5.0])],
+            goal: Arc::new(PointGoal(RealVectorState::new(vec![9.0, 5.0]))),
+            start_sampler: None,
+        });
+        let grow = |sampler: Option<Arc<dyn StateSampler<RealVectorState>>>| {
+            let mut planner = RRT::new(0.5, 0.0);
+            planner.set_seed(0);
+            if let Some(sampler) = sampler {
+                planner.set_sampler(sampler);
+            }
+            planner.setup(problem_def.clone(), Arc::new(AlwaysValid));
+            // Without goal biasing, the goal point is never sampled, so the search runs out.
+            assert!(planner.solve_iterations(500).is_err());
+            planner
+                .tree
+                .iter()
+                .map(|node| node.state.values[0])
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+
+        assert!(grow(None) > 5.0);
+        assert!(grow(Some(Arc::new(LeftHalfSampler))) < 5.0);
+    }
+
+    #[test]
+    fn test_tree_stays_within_bounds() {
+        // Dubins paths between states in a box this small swing well outside it.
+        let space = Arc::new(DubinsStateSpace::new(Some(vec![(0.0, 2.0); 2]), 1.0).unwrap());
+        let problem_def = Arc::new(ProblemDefinition {
+            space: space.clone(),
+            start_states: vec![SE2State::new(1.0, 1.0, 0.0)],
+            goal: Arc::new(UnreachableGoal(SE2State::new(1.5, 1.5, PI))),
+            start_sampler: None,
+        });
+
+        // A sampler may also return states outside the bounds, which the tree can reach directly.
+        let samplers: [Option<Arc<dyn StateSampler<SE2State>>>; 2] =
+            [None, Some(Arc::new(OverhangingSampler))];
+        for sampler in samplers {
+            let mut planner = RRT::new(1.0, 0.05);
+            planner.set_seed(0);
+            if let Some(sampler) = sampler {
+                planner.set_sampler(sampler);
+            }
+            planner.setup(problem_def.clone(), Arc::new(AlwaysValid));
+            assert!(planner.solve_iterations(300).is_err());
+
+            assert!(planner.tree.len() > 100);
+            assert!(planner
+                .tree
+                .iter()
+                .all(|node| space.satisfies_bounds(&node.state)));
+        }
+    }
+
+    #[test]
+    fn test_axiom_check_is_opt_in() {
+        let problem_def = Arc::new(ProblemDefinition::new(
+            Arc::new(BrokenSpace),
+            vec![RealVectorState::new(vec![0.5])],
+            Arc::new(PointGoal(RealVectorState::new(vec![1.0]))),
+        ));
+        let mut planner = RRT::new(0.1, 0.05);
+        planner.set_seed(0);
+        planner.setup(problem_def.clone(), Arc::new(AlwaysValid));
+
+        planner.set_check_axioms(true);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            planner.setup(problem_def, Arc::new(AlwaysValid))
+        }));
+        assert_eq!(result.is_err(), cfg!(debug_assertions));
     }
 }
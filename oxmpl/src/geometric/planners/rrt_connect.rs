@@ -4,20 +4,29 @@
 
 use std::sync::Arc;
 
-use crate::time::{Duration, Instant};
+use crate::{
+    logging,
+    time::{Duration, Instant},
+};
 
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
 
 use crate::base::{
-    error::PlanningError,
+    error::{PlanningError, StateSamplingError},
     goal::{Goal, GoalSampleableRegion},
-    planner::{Path, Planner},
+    motion_validator::{self, MotionValidator},
+    nearest_neighbors::{LinearNN, NearestNeighbors, NearestNeighborsFactory},
+    planner::{self, Path, Planner, PlannerStats, Solution},
     problem_definition::ProblemDefinition,
     space::StateSpace,
     state::State,
-    validity::StateValidityChecker,
+    termination::{TerminationCondition, TerminationReason},
+    validity::{CountingValidityChecker, StateValidityChecker},
 };
 
+/// The distance below which a start state and the goal tree's root are treated as the same state.
+const COINCIDENT_ROOT_DISTANCE: f64 = 1e-9;
+
 // A helper struct to build the tree. Each node stores its state and the index of its parent in the
 #[derive(Clone)]
 struct Node<S: State> {
@@ -60,16 +69,34 @@ enum ExtendResult {
 /// To use this planner, the following trait bounds must be met:
 /// - The `State` type (`S`) must be `Clone`.
 /// - The `Goal` type (`G`) must implement `GoalSampleableRegion` to initialize the goal tree.
+///
+/// For wide goal regions, a single goal root may land somewhere the start tree cannot reach.
+/// `set_goal_root_interval` makes the goal tree a forest, adding further sampled goal states as
+/// roots while the trees grow.
 pub struct RRTConnect<S: State, SP: StateSpace<StateType = S>, G: Goal<S>> {
     /// The maximum distance between nodes in the tree. This is the "step size".
     pub max_distance: f64,
     /// The probability of sampling the goal region instead of the whole space (e.g., 0.05 for 5%).
     pub goal_bias: f64,
+    /// The largest combined number of nodes the two trees may grow to, or `None` for no limit (the default).
+    ///
+    /// Once the trees reach it, the search stops with `PlanningError::NoSolutionFound` rather
+    /// than allocating more nodes, whatever the termination condition.
+    pub max_nodes: Option<usize>,
 
     problem_def: Option<Arc<ProblemDefinition<S, SP, G>>>,
-    validity_checker: Option<Arc<dyn StateValidityChecker<S>>>,
+    validity_checker: Option<Arc<CountingValidityChecker<S>>>,
+    max_validity_checks: Option<usize>,
+    check_axioms: bool,
+    motion_validator: Option<Arc<dyn MotionValidator<S>>>,
+    custom_motion_validator: Option<Arc<dyn MotionValidator<S>>>,
     start_tree: Vec<Node<S>>,
     goal_tree: Vec<Node<S>>,
+    goal_root_interval: Option<usize>,
+    nn_factory: Option<NearestNeighborsFactory<S, SP>>,
+    start_nn: Option<Box<dyn NearestNeighbors<S>>>,
+    goal_nn: Option<Box<dyn NearestNeighbors<S>>>,
+    rng: Box<dyn RngCore>,
 }
 
 impl<S, SP, G> RRTConnect<S, SP, G>
@@ -87,13 +114,95 @@ where
         RRTConnect {
             max_distance,
             goal_bias,
+            max_nodes: None,
             problem_def: None,
             validity_checker: None,
+            max_validity_checks: None,
+            check_axioms: false,
+            motion_validator: None,
+            custom_motion_validator: None,
             start_tree: Vec::new(),
             goal_tree: Vec::new(),
+            goal_root_interval: None,
+            nn_factory: None,
+            start_nn: None,
+            goal_nn: None,
+            rng: Box::new(StdRng::from_os_rng()),
         }
     }
 
+    /// Sets the nearest-neighbour structure used to search each of the two trees.
+    ///
+    /// `factory` is called twice in `setup` with the problem's space, once per tree. Without it,
+    /// a `LinearNN` is used.
+    pub fn set_nearest_neighbors(&mut self, factory: NearestNeighborsFactory<S, SP>) {
+        self.nn_factory = Some(factory);
+    }
+
+    /// Sets how often the goal tree gains another root.
+    ///
+    /// With `Some(interval)`, every `interval` iterations a valid state is sampled from the goal
+    /// region and added to the goal tree as a new root, so the goal tree grows from several parts
+    /// of the goal region rather than the single state sampled in `setup`. Failed samples are
+    /// skipped. With `None` (the default), the goal tree keeps its single root. An interval of 0
+    /// is treated as `None`.
+    pub fn set_goal_root_interval(&mut self, interval: Option<usize>) {
+        self.goal_root_interval = interval.filter(|&interval| interval > 0);
+    }
+
+    /// Sets the motion validator used to check the motions between states.
+    ///
+    /// It takes effect in `setup`. Without it, `setup` creates a `DiscreteMotionValidator` from
+    /// the problem's space and validity checker.
+    pub fn set_motion_validator(&mut self, validator: Arc<dyn MotionValidator<S>>) {
+        self.custom_motion_validator = Some(validator);
+    }
+
+    /// Sets the largest number of validity checks the planner may make, or removes the limit.
+    ///
+    /// It takes effect in `setup`, and counts every check made from then on, including those of
+    /// the start states and of motions checked by the default motion validator. A planner that
+    /// needs more checks stops with `PlanningError::CheckBudgetExhausted`.
+    pub fn set_max_validity_checks(&mut self, max_checks: Option<usize>) {
+        self.max_validity_checks = max_checks;
+    }
+
+    /// Sets whether `setup` checks the problem's space with `StateSpace::check_axioms`, panicking
+    /// if the space breaks one of them.
+    ///
+    /// The check only runs in builds with `debug_assertions`, and is off by default. It draws its
+    /// three states from the planner's random number generator, so a failure reproduces under
+    /// `set_seed`.
+    pub fn set_check_axioms(&mut self, check: bool) {
+        self.check_axioms = check;
+    }
+
+    /// Returns the number of validity checks made since `setup`.
+    pub fn validity_check_count(&self) -> usize {
+        self.validity_checker
+            .as_ref()
+            .map_or(0, |checker| checker.count())
+    }
+
+    /// Seeds the planner's random number generator.
+    ///
+    /// Call this before `setup`, since the goal tree's root is sampled there.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Box::new(StdRng::seed_from_u64(seed));
+    }
+
+    /// Replaces the planner's random number generator, used for both uniform and goal sampling.
+    ///
+    /// Like `set_seed`, this makes runs reproducible with a generator of the caller's choosing,
+    /// e.g. a `RecordingRng` or `ReplayRng` from `oxmpl::testing` to debug a nondeterministic run.
+    pub fn set_rng(&mut self, rng: impl RngCore + 'static) {
+        self.rng = Box::new(rng);
+    }
+
+    /// Returns the states from the root of `last_node_idx`'s tree to `last_node_idx`.
+    ///
+    /// A tree may have several roots, so the path starts at whichever root `last_node_idx`
+    /// descends from.
     fn reconstruct_path(&self, tree: &[Node<S>], last_node_idx: usize) -> Path<S> {
         let mut path_states = Vec::new();
         let mut current_index = Some(last_node_idx);
@@ -107,10 +216,16 @@ where
 
     /// Helper function to extend a tree towards a target state.
     ///
-    /// This function finds the node in the `tree` nearest to `q_target`. It then creates a new state
+    /// This function finds the node in the `tree` nearest to `q_target`, using `nn`, which must
+    /// index the same nodes as `tree`. It then creates a new state
     /// `q_new` by moving from the nearest node towards `q_target` by a distance of at most
     /// `max_distance`. If the motion to `q_new` is valid, it adds `q_new` to the tree.
     ///
+    /// The returned path follows the goal tree's edges towards its root, so if `is_goal_tree`
+    /// `q_new` is instead taken from the motion from `q_target` to the nearest node, and the motion
+    /// is checked from `q_new` to the nearest node. In a space whose metric is not symmetric, these
+    /// are different motions.
+    ///
     /// Returns a tuple `(ExtendResult, usize)` on success, where `usize` is the index of the new node.
     /// Returns `None` if the motion was invalid.
     ///
@@ -120,80 +235,85 @@ where
     /// > compiler was complaining.
     fn extend(
         tree: &mut Vec<Node<S>>,
+        nn: &mut dyn NearestNeighbors<S>,
         q_target: &S,
         pd: &ProblemDefinition<S, SP, G>,
-        vc: &Arc<dyn StateValidityChecker<S>>,
+        mv: &dyn MotionValidator<S>,
         max_distance: f64,
+        is_goal_tree: bool,
     ) -> Option<(ExtendResult, usize)> {
-        let mut nearest_node_index = 0;
-        let mut min_dist = pd.space.distance(&tree[0].state, q_target);
-        for (i, node) in tree.iter().enumerate().skip(1) {
-            let dist = pd.space.distance(&node.state, q_target);
-            if dist < min_dist {
-                min_dist = dist;
-                nearest_node_index = i;
-            }
-        }
-
+        let nearest_node_index = nn.nearest(q_target)?;
         let q_near = tree[nearest_node_index].state.clone();
+        let min_dist = if is_goal_tree {
+            pd.space.distance(q_target, &q_near)
+        } else {
+            pd.space.distance(&q_near, q_target)
+        };
         let mut q_new = q_near.clone();
         let result = if min_dist > max_distance {
             let t = max_distance / min_dist;
-            pd.space.interpolate(&q_near, q_target, t, &mut q_new);
+            if is_goal_tree {
+                pd.space.interpolate(q_target, &q_near, 1.0 - t, &mut q_new);
+            } else {
+                pd.space.interpolate(&q_near, q_target, t, &mut q_new);
+            }
+            // Interpolation may leave the bounds, through rounding or a curved path.
+            pd.space.enforce_bounds(&mut q_new);
             ExtendResult::Advanced
         } else {
             q_new = q_target.clone();
             ExtendResult::Reached
         };
 
-        if Self::check_motion(&q_near, &q_new, pd, vc) {
+        let valid = if is_goal_tree {
+            mv.check_motion(&q_new, &q_near)
+        } else {
+            mv.check_motion(&q_near, &q_new)
+        };
+        if valid {
             let new_node_idx = tree.len();
+            nn.add(new_node_idx, q_new.clone());
             tree.push(Node {
                 state: q_new,
                 parent_index: Some(nearest_node_index),
             });
+            logging::node_milestone("RRTConnect", tree.len());
             Some((result, new_node_idx))
         } else {
             None
         }
     }
 
-    /// An internal helper function to check if the motion between two states is valid.
+    /// Samples a valid state from the goal region to root the goal tree at.
+    ///
+    /// The motion checks only test the states a motion moves through, so a tree rooted at an
+    /// invalid state could otherwise grow out of an obstacle, or never grow at all.
     ///
-    /// It works by discretizing the straight-line path between `from` and `to` into small steps and
-    /// calling the `StateValidityChecker` on each intermediate state. If any intermediate state is
-    /// invalid, the entire motion is considered invalid.
-    fn check_motion(
-        from: &S,
-        to: &S,
+    /// # Errors
+    /// Returns the goal's sampling error, or `StateSamplingError::GoalSamplingTimeout` if no valid
+    /// sample was found within `MAX_SAMPLING_ATTEMPTS` attempts.
+    fn sample_goal_root(
         pd: &ProblemDefinition<S, SP, G>,
-        vc: &Arc<dyn StateValidityChecker<S>>,
-    ) -> bool {
-        let space = &pd.space;
-        let dist = space.distance(from, to);
-        let num_steps = (dist / (space.get_longest_valid_segment_length() * 0.1)).ceil() as usize;
-
-        if num_steps <= 1 {
-            return vc.is_valid(to);
-        }
-
-        let mut interpolated_state = from.clone();
-        for i in 1..=num_steps {
-            let t = i as f64 / num_steps as f64;
-            space.interpolate(from, to, t, &mut interpolated_state);
-            if !vc.is_valid(&interpolated_state) {
-                return false;
+        vc: &dyn StateValidityChecker<S>,
+        rng: &mut impl Rng,
+    ) -> Result<S, StateSamplingError> {
+        for _ in 0..planner::MAX_SAMPLING_ATTEMPTS {
+            let state = planner::enforce_bounds(&*pd.space, pd.goal.sample_goal(rng)?);
+            if vc.is_valid(&state) {
+                return Ok(state);
             }
         }
-        true
+        Err(StateSamplingError::GoalSamplingTimeout {
+            attempts: planner::MAX_SAMPLING_ATTEMPTS as u32,
+        })
     }
 }
 
 /// The main implementation of the Planner trait for RRTConnect.
 impl<S, SP, G> Planner<S, SP, G> for RRTConnect<S, SP, G>
 where
-    S: State + Clone,
-    SP: StateSpace<StateType = S>,
+    S: State + Clone + 'static,
+    SP: StateSpace<StateType = S> + 'static,
     G: Goal<S> + GoalSampleableRegion<S>,
 {
     fn setup(
@@ -201,32 +321,181 @@ where
         problem_def: Arc<ProblemDefinition<S, SP, G>>,
         validity_checker: Arc<dyn StateValidityChecker<S>>,
     ) {
+        let validity_checker =
+            planner::count_validity_checks(validity_checker, self.max_validity_checks);
+        if cfg!(debug_assertions) && self.check_axioms {
+            crate::base::space::debug_check_axioms(&*problem_def.space, &mut self.rng);
+        }
+
+        let make_nn = |space: Arc<SP>| -> Box<dyn NearestNeighbors<S>> {
+            match &self.nn_factory {
+                Some(factory) => factory(space),
+                None => Box::new(LinearNN::new(space)),
+            }
+        };
+        let mut start_nn = make_nn(problem_def.space.clone());
+        let mut goal_nn = make_nn(problem_def.space.clone());
+
+        self.motion_validator = Some(motion_validator::motion_validator_or_default(
+            &self.custom_motion_validator,
+            problem_def.space.clone(),
+            validity_checker.clone(),
+        ));
         self.problem_def = Some(problem_def);
         self.validity_checker = Some(validity_checker);
         self.start_tree.clear();
         self.goal_tree.clear();
         let pd = self.problem_def.as_ref().unwrap();
+        let vc = self.validity_checker.as_ref().unwrap();
 
-        // Initialise the trees beginning from start and goal states.
-        let start_state = pd.start_states[0].clone();
-        let start_node = Node {
-            state: start_state,
-            parent_index: None,
+        // Initialise the start tree with the start states, plus the states drawn from the start
+        // sampler, brought within the bounds of the space. Sampled states are checked for
+        // validity once they are within the bounds, as that is the state the tree holds.
+        let sampled_starts = pd
+            .sample_start_states(&mut self.rng)
+            .into_iter()
+            .map(|state| planner::enforce_bounds(&*pd.space, state))
+            .filter(|state| vc.is_valid(state));
+        let start_states = pd
+            .start_states
+            .iter()
+            .map(|state| planner::enforce_bounds(&*pd.space, state.clone()));
+        for start_state in start_states.chain(sampled_starts) {
+            start_nn.add(self.start_tree.len(), start_state.clone());
+            self.start_tree.push(Node {
+                state: start_state,
+                parent_index: None,
+            });
+        }
+
+        // Initialise the goal tree with a valid state sampled from the goal region. If that
+        // fails, the goal tree is left empty and `solve` retries the sampling, reporting the
+        // error.
+        if let Ok(goal_state) = Self::sample_goal_root(pd, &**vc, &mut self.rng) {
+            goal_nn.add(0, goal_state.clone());
+            let goal_node = Node {
+                state: goal_state,
+                parent_index: None,
+            };
+            self.goal_tree.push(goal_node);
+        }
+
+        self.start_nn = Some(start_nn);
+        self.goal_nn = Some(goal_nn);
+    }
+
+    fn solve_with(&mut self, condition: TerminationCondition) -> Result<Path<S>, PlanningError> {
+        self.run(&condition)
+    }
+
+    fn solve_detailed_with(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<Solution<S>, PlanningError> {
+        let start_time = Instant::now();
+        let path = self.run(&condition)?;
+        let stats = PlannerStats {
+            planning_time: start_time.elapsed(),
+            validity_checks: self.validity_check_count(),
         };
-        self.start_tree.push(start_node);
+        let pd = self
+            .problem_def
+            .as_ref()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        Ok(Solution::exact("RRTConnect", path, &*pd.space, stats))
+    }
+}
+
+impl<S, SP, G> RRTConnect<S, SP, G>
+where
+    S: State + Clone,
+    SP: StateSpace<StateType = S>,
+    G: Goal<S> + GoalSampleableRegion<S>,
+{
+    /// Runs at most `iterations` iterations of the main loop, regardless of elapsed time.
+    ///
+    /// Returns `PlanningError::NoSolutionFound` if the trees were not connected within the
+    /// iteration budget.
+    pub fn solve_iterations(&mut self, iterations: usize) -> Result<Path<S>, PlanningError> {
+        self.run(&TerminationCondition::MaxIterations(iterations))
+    }
+
+    /// Answers whether the problem is feasible, growing the trees for at most `timeout`.
+    ///
+    /// This runs the same search as `solve`, but returns as soon as the trees connect without
+    /// reconstructing the path, for callers that only need a yes/no answer.
+    ///
+    /// # Errors
+    ///
+    /// Running out of time does not prove that no path exists, so if the trees have not connected
+    /// within `timeout` this returns `PlanningError::Timeout` rather than `Ok(false)`. Other
+    /// errors are as for `solve`.
+    pub fn is_feasible(&mut self, timeout: Duration) -> Result<bool, PlanningError> {
+        self.grow(&TerminationCondition::Timeout(timeout))
+            .map(|_| true)
+    }
 
-        let mut rng = rand::rng();
-        let goal_state = pd.goal.sample_goal(&mut rng).unwrap();
-        let goal_node = Node {
-            state: goal_state,
-            parent_index: None,
+    /// Like `solve_with`, but if the goal has not been reached when `condition` is met, returns
+    /// the path from the start to the node of the start tree closest to the goal instead of an
+    /// error.
+    ///
+    /// The flag is `true` if the path reaches the goal, and `false` if it is partial. Closeness is
+    /// measured by `GoalRegion::distance_goal`. Errors other than running out of time or
+    /// iterations, e.g. `PlanningError::InvalidStartState`, are still returned.
+    pub fn solve_partial(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<(Path<S>, bool), PlanningError> {
+        match self.run(&condition) {
+            Ok(path) => Ok((path, true)),
+            Err(PlanningError::Timeout { .. } | PlanningError::NoSolutionFound) => {
+                let pd = self
+                    .problem_def
+                    .as_ref()
+                    .ok_or(PlanningError::PlannerUninitialised)?;
+                let closest = planner::closest_to_goal(
+                    &*pd.goal,
+                    self.start_tree.iter().map(|node| &node.state),
+                )
+                .ok_or(PlanningError::InvalidStartState)?;
+                Ok((self.reconstruct_path(&self.start_tree, closest), false))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Grows the trees until they connect and reconstructs the path through the connection.
+    fn run(&mut self, condition: &TerminationCondition) -> Result<Path<S>, PlanningError> {
+        let (start_idx, goal_idx) = self.grow(condition)?;
+        let mut start_path = self.reconstruct_path(&self.start_tree, start_idx).0;
+        let Some(goal_idx) = goal_idx else {
+            return Ok(Path(start_path));
         };
-        self.goal_tree.push(goal_node);
+
+        // The goal path is from goal to connection, so it needs to be reversed. Append it
+        // (skipping the first element, which is the duplicate connection point) to the start
+        // path.
+        let mut goal_path = self.reconstruct_path(&self.goal_tree, goal_idx).0;
+        goal_path.reverse();
+        start_path.extend(goal_path.into_iter().skip(1));
+
+        Ok(Path(start_path))
     }
 
-    fn solve(&mut self, timeout: Duration) -> Result<Path<S>, PlanningError> {
+    /// The main RRT-Connect loop, stopping once the trees connect or `condition` is met.
+    ///
+    /// The tree size checked against the condition is the combined size of both trees.
+    ///
+    /// Returns the index of the start-tree node where the search succeeded, together with the
+    /// index of the goal-tree node it connects to, or `None` if the start-tree node itself lies
+    /// in the goal region.
+    fn grow(
+        &mut self,
+        condition: &TerminationCondition,
+    ) -> Result<(usize, Option<usize>), PlanningError> {
+        let condition = &*planner::with_max_nodes(condition, self.max_nodes);
         let start_time = Instant::now();
-        let mut rng = rand::rng();
+        let mut iteration = 0;
         let pd = self
             .problem_def
             .as_ref()
@@ -235,53 +504,152 @@ where
             .validity_checker
             .as_ref()
             .ok_or(PlanningError::PlannerUninitialised)?;
+        let mv = self
+            .motion_validator
+            .as_deref()
+            .ok_or(PlanningError::PlannerUninitialised)?;
         let goal = &pd.goal;
+        let (Some(start_nn), Some(goal_nn)) = (self.start_nn.as_mut(), self.goal_nn.as_mut())
+        else {
+            return Err(PlanningError::PlannerUninitialised);
+        };
+
+        planner::check_start_states(&*pd.space, &**vc, &pd.start_states)?;
+        if self.start_tree.is_empty() {
+            return Err(PlanningError::InvalidStartState);
+        }
+        if self.goal_tree.is_empty() {
+            let goal_state = Self::sample_goal_root(pd, &**vc, &mut self.rng)
+                .map_err(PlanningError::SamplingFailed)?;
+            goal_nn.add(0, goal_state.clone());
+            self.goal_tree.push(Node {
+                state: goal_state,
+                parent_index: None,
+            });
+        }
+
+        // The trees may start out connected: a start state can already lie in the goal region, or
+        // coincide with the goal tree's root if the goal sampler returned it. The start state on
+        // its own is then the solution, so return it rather than growing the trees.
+        let goal_root = &self.goal_tree[0].state;
+        let connected = self
+            .start_tree
+            .iter()
+            .enumerate()
+            .find_map(|(start_idx, node)| {
+                if goal.is_satisfied(&node.state) {
+                    Some((start_idx, None))
+                } else if pd.space.distance(&node.state, goal_root) <= COINCIDENT_ROOT_DISTANCE {
+                    Some((start_idx, Some(0)))
+                } else {
+                    None
+                }
+            });
+        if let Some(solution) = connected {
+            logging::solution_found("RRTConnect", self.start_tree.len() + self.goal_tree.len());
+            return Ok(solution);
+        }
 
         // Main loop
         loop {
-            // 1. Check for timeout
-            if start_time.elapsed() > timeout {
-                return Err(PlanningError::Timeout);
+            // Abort if the validity checker failed during the previous iteration.
+            planner::check_validity_error(&**vc)?;
+            // 1. Check the termination condition
+            let nodes = self.start_tree.len() + self.goal_tree.len();
+            match condition.check(start_time.elapsed(), iteration, nodes) {
+                Some(TerminationReason::Timeout) => {
+                    // Only the start tree says anything about progress towards the goal region.
+                    let best_goal_distance = self
+                        .start_tree
+                        .iter()
+                        .map(|node| goal.distance_goal(&node.state))
+                        .fold(f64::INFINITY, f64::min);
+                    logging::timeout("RRTConnect", nodes, best_goal_distance);
+                    return Err(PlanningError::Timeout {
+                        best_goal_distance,
+                        nodes,
+                    });
+                }
+                Some(_) => return Err(PlanningError::NoSolutionFound),
+                None => {}
+            }
+            iteration += 1;
+
+            // Periodically root the goal tree at another state from the goal region.
+            if self
+                .goal_root_interval
+                .is_some_and(|interval| iteration % interval == 0)
+            {
+                if let Ok(goal_state) = Self::sample_goal_root(pd, &**vc, &mut self.rng) {
+                    goal_nn.add(self.goal_tree.len(), goal_state.clone());
+                    self.goal_tree.push(Node {
+                        state: goal_state,
+                        parent_index: None,
+                    });
+                }
             }
 
             // 2. Determine which tree to grow (tree_a) and which to connect to (tree_b). This
             //    balances the trees, which is more efficient.
-            let (tree_a, tree_b, is_growing_start_tree) =
+            let (tree_a, nn_a, tree_b, nn_b, is_growing_start_tree) =
                 if self.start_tree.len() <= self.goal_tree.len() {
-                    (&mut self.start_tree, &mut self.goal_tree, true)
+                    (
+                        &mut self.start_tree,
+                        &mut **start_nn,
+                        &mut self.goal_tree,
+                        &mut **goal_nn,
+                        true,
+                    )
                 } else {
-                    (&mut self.goal_tree, &mut self.start_tree, false)
+                    (
+                        &mut self.goal_tree,
+                        &mut **goal_nn,
+                        &mut self.start_tree,
+                        &mut **start_nn,
+                        false,
+                    )
                 };
 
             // 3. Sample a random target state `q_rand`, with goal biasing.
-            // TODO: Handle sampling failures.
-            let q_rand = if rng.random_bool(self.goal_bias) {
-                goal.sample_goal(&mut rng).unwrap()
-            } else {
-                pd.space.sample_uniform(&mut rng).unwrap()
-            };
+            let q_rand =
+                planner::sample_target(&*pd.space, &**goal, self.goal_bias, None, &mut self.rng)?;
 
             // 4. Try to extend tree_a towards q_rand.
-            if let Some((_extend_result, new_node_idx_a)) =
-                Self::extend(tree_a, &q_rand, pd, vc, self.max_distance)
-            {
+            if let Some((_extend_result, new_node_idx_a)) = Self::extend(
+                tree_a,
+                nn_a,
+                &q_rand,
+                pd,
+                mv,
+                self.max_distance,
+                !is_growing_start_tree,
+            ) {
                 let q_new = &tree_a[new_node_idx_a].state;
 
                 // If growing the start tree, check if the new node is already in the goal.
                 if is_growing_start_tree && goal.is_satisfied(q_new) {
-                    println!("Solution found by start tree reaching goal directly.");
-                    return Ok(self.reconstruct_path(&self.start_tree, new_node_idx_a));
+                    logging::solution_found(
+                        "RRTConnect",
+                        self.start_tree.len() + self.goal_tree.len(),
+                    );
+                    return Ok((new_node_idx_a, None));
                 }
 
                 // 5. Try to connect tree_b to the new state `q_new`.
-                if let Some((connect_result, new_node_idx_b)) =
-                    Self::extend(tree_b, q_new, pd, vc, self.max_distance)
-                {
+                if let Some((connect_result, new_node_idx_b)) = Self::extend(
+                    tree_b,
+                    nn_b,
+                    q_new,
+                    pd,
+                    mv,
+                    self.max_distance,
+                    is_growing_start_tree,
+                ) {
                     // 6. If the connection reached q_new, a solution is found.
                     if connect_result == ExtendResult::Reached {
-                        println!(
-                            "Solution found after {} total nodes.",
-                            self.start_tree.len() + self.goal_tree.len()
+                        logging::solution_found(
+                            "RRTConnect",
+                            self.start_tree.len() + self.goal_tree.len(),
                         );
 
                         // Identify which final node belongs to which tree.
@@ -290,21 +658,44 @@ where
                         } else {
                             (new_node_idx_b, new_node_idx_a)
                         };
-
-                        // 7. Reconstruct the path from both trees and merge them.
-                        let mut start_path = self.reconstruct_path(&self.start_tree, start_idx).0;
-                        let mut goal_path = self.reconstruct_path(&self.goal_tree, goal_idx).0;
-
-                        // The goal path is from goal to connection, so it needs to be reversed.
-                        goal_path.reverse();
-                        // Append the goal path (skipping the first element, which is the duplicate
-                        // connection point) to the start path.
-                        start_path.extend(goal_path.into_iter().skip(1));
-
-                        return Ok(Path(start_path));
+                        return Ok((start_idx, Some(goal_idx)));
                     }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{space::DubinsStateSpace, state::SE2State};
+    use crate::test_fixtures::{AlwaysValid, UnreachableGoal};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_trees_stay_within_bounds() {
+        // Dubins paths between states in a box this small swing well outside it.
+        let space = Arc::new(DubinsStateSpace::new(Some(vec![(0.0, 2.0); 2]), 1.0).unwrap());
+        let problem_def = Arc::new(ProblemDefinition {
+            space: space.clone(),
+            start_states: vec![SE2State::new(1.0, 1.0, 0.0)],
+            goal: Arc::new(UnreachableGoal(SE2State::new(1.5, 1.5, PI))),
+            start_sampler: None,
+        });
+
+        let mut planner = RRTConnect::new(1.0, 0.05);
+        planner.set_seed(0);
+        planner.setup(problem_def, Arc::new(AlwaysValid));
+        // Connecting the trees may solve the problem, but only once they have grown.
+        let _ = planner.solve_iterations(300);
+
+        let nodes: Vec<_> = planner
+            .start_tree
+            .iter()
+            .chain(&planner.goal_tree)
+            .collect();
+        assert!(nodes.len() > 20);
+        assert!(nodes.iter().all(|node| space.satisfies_bounds(&node.state)));
+    }
+}
@@ -2,31 +2,57 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
-use std::sync::Arc;
+use std::{cmp::Ordering, sync::Arc};
 
-use crate::time::{Duration, Instant};
+use crate::{logging, time::Instant};
 
-use rand::Rng;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
 
 use crate::base::{
     error::PlanningError,
     goal::{Goal, GoalSampleableRegion},
-    planner::{Path, Planner},
+    motion_validator::{self, MotionValidator},
+    nearest_neighbors::{LinearNN, NearestNeighbors, NearestNeighborsFactory},
+    objective::{OptimizationObjective, PathLengthObjective},
+    planner::{self, Path, Planner, PlannerStats, Solution},
     problem_definition::ProblemDefinition,
+    sampler::StateSampler,
     space::StateSpace,
     state::State,
-    validity::StateValidityChecker,
+    termination::{TerminationCondition, TerminationReason},
+    validity::{CountingValidityChecker, StateValidityChecker},
 };
 
 // A helper struct to build the tree. Each node stores its state and the index of its parent in
-// the. For RRT* you also need to know the cost to get to the node.
+// the. For RRT* you also need to know the cost to get to the node, and the indices of its
+// children so that a rewire can pass a lower cost on to them.
 #[derive(Clone)]
 struct Node<S: State> {
     state: S,
     parent_index: Option<usize>,
     cost: f64,
+    children: Vec<usize>,
 }
 
+/// A candidate parent considered during RRT*'s "Choose Parent" step.
+pub struct ParentCandidate<'a, S> {
+    /// The state of the candidate parent node.
+    pub state: &'a S,
+    /// The state of the candidate's own parent in the tree, if it has one.
+    pub parent: Option<&'a S>,
+}
+
+/// A comparator used to break ties between parents that give equal cost to a new state.
+///
+/// It is called as `tie_break(new_state, a, b)` and should return `Ordering::Less` if `a` is the
+/// preferable parent for `new_state`, `Ordering::Greater` if `b` is, and `Ordering::Equal` if
+/// there is no preference.
+pub type ParentTieBreak<S> =
+    Arc<dyn Fn(&S, &ParentCandidate<'_, S>, &ParentCandidate<'_, S>) -> Ordering>;
+
+/// A callback told the cost of the best solution each time it improves.
+pub type ProgressCallback = Arc<dyn Fn(f64)>;
+
 /// An implementation of the RRT* (RRT-star) algorithm.
 ///
 /// RRT* is a sampling-based algorithm that is asymptotically optimal, meaning it converges to the
@@ -37,17 +63,56 @@ struct Node<S: State> {
 ///     neighbors that will give it the lowest-cost path from the start.
 /// 2.  **"Rewire"**: After a new node is added, it checks if it can provide a shorter path for any
 ///     of its neighbors, and if so, it updates their parent connections.
+///
+/// When several parents give the same cost (common on lattices), the first one found is kept
+/// unless a secondary objective is supplied with `set_parent_tie_break`.
+///
+/// By default RRT* runs as an anytime planner: once the goal is reached, it keeps rewiring the tree
+/// until the termination condition is met and returns the lowest-cost solution found. With
+/// `set_anytime(false)`, it returns the first solution found instead.
+///
+/// In informed mode (`set_informed`), once a solution is found, samples are drawn only from the
+/// states that could lie on a shorter path (Informed RRT*), in spaces that support it.
+///
+/// Costs are path lengths unless another `OptimizationObjective` is set with
+/// `set_optimization_objective`, e.g. a `StateCostObjective` to keep paths away from obstacles.
 pub struct RRTStar<S: State, SP: StateSpace<StateType = S>, G: Goal<S>> {
     /// The maximum distance between nodes in the tree. This is the "step size".
     pub max_distance: f64,
     /// The probability of sampling the goal region instead of the whole space (e.g., 0.05 for 5%).
     pub goal_bias: f64,
     /// The radius to search for neighbors during the "Choose Parent" and "Rewire" steps.
+    ///
+    /// If a relative radius was set with `set_search_radius_fraction`, this is recomputed from
+    /// the space in `setup`.
     pub search_radius: f64,
+    /// The largest number of nodes the tree may grow to, or `None` for no limit (the default).
+    ///
+    /// Once the tree reaches it, the search stops with the best solution found so far, or with
+    /// `PlanningError::NoSolutionFound` if there is none, rather than allocating more nodes,
+    /// whatever the termination condition.
+    pub max_nodes: Option<usize>,
 
     problem_def: Option<Arc<ProblemDefinition<S, SP, G>>>,
-    validity_checker: Option<Arc<dyn StateValidityChecker<S>>>,
+    validity_checker: Option<Arc<CountingValidityChecker<S>>>,
+    max_validity_checks: Option<usize>,
+    check_axioms: bool,
+    motion_validator: Option<Arc<dyn MotionValidator<S>>>,
+    custom_motion_validator: Option<Arc<dyn MotionValidator<S>>>,
     tree: Vec<Node<S>>,
+    goal_node_indices: Vec<usize>,
+    tie_break: Option<ParentTieBreak<S>>,
+    objective: Option<Arc<dyn OptimizationObjective<S>>>,
+    custom_objective: Option<Arc<dyn OptimizationObjective<S>>>,
+    progress_callback: Option<ProgressCallback>,
+    reported_cost: f64,
+    search_radius_fraction: Option<f64>,
+    anytime: bool,
+    informed: bool,
+    nn_factory: Option<NearestNeighborsFactory<S, SP>>,
+    nn: Option<Box<dyn NearestNeighbors<S>>>,
+    sampler: Option<Arc<dyn StateSampler<S>>>,
+    rng: Box<dyn RngCore>,
 }
 
 impl<S, SP, G> RRTStar<S, SP, G>
@@ -67,67 +132,336 @@ where
             max_distance,
             goal_bias,
             search_radius,
+            max_nodes: None,
             problem_def: None,
             validity_checker: None,
+            max_validity_checks: None,
+            check_axioms: false,
+            motion_validator: None,
+            custom_motion_validator: None,
             tree: Vec::new(),
+            goal_node_indices: Vec::new(),
+            tie_break: None,
+            objective: None,
+            custom_objective: None,
+            progress_callback: None,
+            reported_cost: f64::INFINITY,
+            search_radius_fraction: None,
+            anytime: true,
+            informed: false,
+            nn_factory: None,
+            nn: None,
+            sampler: None,
+            rng: Box::new(StdRng::from_os_rng()),
         }
     }
 
-    fn check_motion(&self, from: &S, to: &S) -> bool {
-        // We need access to the space and checker from our stored setup info.
-        if let (Some(pd), Some(vc)) = (&self.problem_def, &self.validity_checker) {
-            let space = &pd.space;
-
-            // Determine the number of steps to check based on distance and resolution.
-            // A simple approach: one check per unit of distance (or a fraction thereof).
-            let dist = space.distance(from, to);
-            let num_steps =
-                (dist / (space.get_longest_valid_segment_length() * 0.1)).ceil() as usize;
-
-            if num_steps <= 1 {
-                return vc.is_valid(to);
-            }
+    /// Sets the sampler that draws the states the tree grows towards, e.g. a `HaltonSampler` or
+    /// a `BridgeTestSampler`, in the iterations that do not sample the goal. Without it, the
+    /// space is sampled uniformly. In informed mode, the informed
+    /// set takes over from it once a solution has been found.
+    pub fn set_sampler(&mut self, sampler: Arc<dyn StateSampler<S>>) {
+        self.sampler = Some(sampler);
+    }
 
-            let mut interpolated_state = from.clone();
-            for i in 1..=num_steps {
-                let t = i as f64 / num_steps as f64;
-                space.interpolate(from, to, t, &mut interpolated_state);
-                if !vc.is_valid(&interpolated_state) {
-                    return false;
-                }
-            }
+    /// Sets the nearest-neighbour structure used to search the tree, both for the nearest node
+    /// and for the neighbours considered when choosing a parent and rewiring.
+    ///
+    /// `factory` is called in `setup` with the problem's space. Without it, a `LinearNN` is used.
+    pub fn set_nearest_neighbors(&mut self, factory: NearestNeighborsFactory<S, SP>) {
+        self.nn_factory = Some(factory);
+    }
 
-            true
-        } else {
-            false
+    /// Sets the search radius relative to the size of the space instead of as an absolute
+    /// distance.
+    ///
+    /// In `setup`, `search_radius` becomes `fraction * space.get_maximum_extent()`. Distances in
+    /// SO2 and SO3 are bounded by PI and PI/2 respectively, so this lets the same value (e.g.
+    /// 0.1) work for angular and Euclidean spaces alike.
+    pub fn set_search_radius_fraction(&mut self, fraction: f64) {
+        self.search_radius_fraction = Some(fraction);
+    }
+
+    /// Sets whether solving keeps refining after the first solution. Defaults to `true`.
+    ///
+    /// This applies to every solve method. In anytime mode, they rewire the tree until the
+    /// termination condition is met and return the lowest-cost path found, failing only if the goal
+    /// was never reached. Otherwise they return as soon as the goal is next reached, which is
+    /// faster but usually far from optimal.
+    pub fn set_anytime(&mut self, anytime: bool) {
+        self.anytime = anytime;
+    }
+
+    /// Sets whether to sample from the informed set once a solution is found.
+    ///
+    /// The informed set is the prolate hyperspheroid of states `x` with
+    /// `distance(start, x) + distance(x, goal) < best_cost`, where `start` is the root and `goal`
+    /// the end of the best solution. Before the first solution, or in spaces without
+    /// `StateSpace::sample_informed`, sampling stays uniform.
+    pub fn set_informed(&mut self, informed: bool) {
+        self.informed = informed;
+    }
+
+    /// Sets the motion validator used to check the motions between states.
+    ///
+    /// It takes effect in `setup`. Without it, `setup` creates a `DiscreteMotionValidator` from
+    /// the problem's space and validity checker.
+    pub fn set_motion_validator(&mut self, validator: Arc<dyn MotionValidator<S>>) {
+        self.custom_motion_validator = Some(validator);
+    }
+
+    /// Sets the largest number of validity checks the planner may make, or removes the limit.
+    ///
+    /// It takes effect in `setup`, and counts every check made from then on, including those of
+    /// the start states and of motions checked by the default motion validator. A planner that
+    /// needs more checks stops with `PlanningError::CheckBudgetExhausted`.
+    pub fn set_max_validity_checks(&mut self, max_checks: Option<usize>) {
+        self.max_validity_checks = max_checks;
+    }
+
+    /// Sets whether `setup` checks the problem's space with `StateSpace::check_axioms`, panicking
+    /// if the space breaks one of them.
+    ///
+    /// The check only runs in builds with `debug_assertions`, and is off by default. It draws its
+    /// three states from the planner's random number generator, so a failure reproduces under
+    /// `set_seed`.
+    pub fn set_check_axioms(&mut self, check: bool) {
+        self.check_axioms = check;
+    }
+
+    /// Returns the number of validity checks made since `setup`.
+    pub fn validity_check_count(&self) -> usize {
+        self.validity_checker
+            .as_ref()
+            .map_or(0, |checker| checker.count())
+    }
+
+    /// Seeds the planner's random number generator.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Box::new(StdRng::seed_from_u64(seed));
+    }
+
+    /// Replaces the planner's random number generator, used for both uniform and goal sampling.
+    ///
+    /// Like `set_seed`, this makes runs reproducible with a generator of the caller's choosing,
+    /// e.g. a `RecordingRng` or `ReplayRng` from `oxmpl::testing` to debug a nondeterministic run.
+    pub fn set_rng(&mut self, rng: impl RngCore + 'static) {
+        self.rng = Box::new(rng);
+    }
+
+    /// Sets a secondary objective used to choose between parents of equal cost.
+    ///
+    /// Typical choices prefer the parent yielding the greater clearance or the smaller heading
+    /// change. Without a tie-break the first equal-cost parent found is kept.
+    pub fn set_parent_tie_break(&mut self, tie_break: ParentTieBreak<S>) {
+        self.tie_break = Some(tie_break);
+    }
+
+    /// Sets the objective that the costs of paths are measured by.
+    ///
+    /// It takes effect in `setup`. Without it, `setup` creates a `PathLengthObjective` for the
+    /// problem's space. Informed sampling assumes that no motion costs less than its length, so
+    /// only combine it with objectives that satisfy this.
+    pub fn set_optimization_objective(&mut self, objective: Arc<dyn OptimizationObjective<S>>) {
+        self.custom_objective = Some(objective);
+    }
+
+    /// Sets a callback to be told the cost of the best solution each time it improves, or removes
+    /// it.
+    ///
+    /// It is called from the planning loop when the goal is first reached and whenever a new goal
+    /// node or a rewire lowers `best_cost`, so the costs it is given never increase. In anytime
+    /// mode, this follows the refinement of the solution during `solve`.
+    pub fn set_progress_callback(&mut self, callback: Option<ProgressCallback>) {
+        self.progress_callback = callback;
+    }
+
+    /// Calls the progress callback if the best solution has improved since it was last called.
+    fn report_progress(&mut self) {
+        let Some(callback) = &self.progress_callback else {
+            return;
+        };
+        if let Some(cost) = self.best_cost() {
+            if cost < self.reported_cost {
+                self.reported_cost = cost;
+                callback(cost);
+            }
         }
     }
 
+    /// Checks the motion between two states with the motion validator created in `setup`.
+    fn check_motion(&self, from: &S, to: &S) -> bool {
+        self.motion_validator
+            .as_ref()
+            .is_some_and(|validator| validator.check_motion(from, to))
+    }
+
     /// Calculates the cost to reach `current_node` if it were parented by `neighbour_node`.
     fn cost(&self, current_node: &Node<S>, neighbour_node: &Node<S>) -> f64 {
-        if let Some(pd) = &self.problem_def {
-            neighbour_node.cost
-                + pd.space
-                    .distance(&current_node.state, &neighbour_node.state)
+        if let Some(objective) = &self.objective {
+            neighbour_node.cost + objective.motion_cost(&neighbour_node.state, &current_node.state)
         } else {
             f64::INFINITY
         }
     }
 
+    /// Selects the parent for `node` among its `neighbours`, starting from the nearest node.
+    ///
+    /// Returns the index of the chosen parent and the resulting cost of `node`. Costs within a
+    /// small tolerance of each other are treated as equal and resolved by the tie-break.
+    fn choose_parent(
+        &self,
+        node: &Node<S>,
+        nearest_node_index: usize,
+        neighbours: &[usize],
+    ) -> (usize, f64) {
+        let mut best_parent_index = nearest_node_index;
+        let mut min_cost = self.cost(node, &self.tree[nearest_node_index]);
+
+        // Iterate through neighbors to find a cheaper path. If a neighbor offers a cheaper
+        // path and the motion from that neighbor is collision-free we have found a new,
+        // better parent.
+        for &neighbour_idx in neighbours {
+            if neighbour_idx == best_parent_index {
+                continue;
+            }
+            let neighbour_node = &self.tree[neighbour_idx];
+            let cost_via_neighbour = self.cost(node, neighbour_node);
+            let tolerance = 1e-9 * (1.0 + min_cost.abs());
+
+            let is_better = if cost_via_neighbour < min_cost - tolerance {
+                true
+            } else if cost_via_neighbour <= min_cost + tolerance {
+                self.prefers_parent(node, neighbour_idx, best_parent_index)
+            } else {
+                false
+            };
+
+            if is_better && self.check_motion(&neighbour_node.state, &node.state) {
+                min_cost = cost_via_neighbour;
+                best_parent_index = neighbour_idx;
+            }
+        }
+
+        (best_parent_index, min_cost)
+    }
+
+    /// Makes `new_parent_idx` the parent of the node at `node_idx`, reached at `cost`, and passes
+    /// the lower cost on to every node below it.
+    ///
+    /// Without this, goal nodes below a rewired node would keep their old costs, and `best_cost`
+    /// would overstate the cost of the path returned.
+    fn rewire(&mut self, node_idx: usize, new_parent_idx: usize, cost: f64) {
+        if let Some(old_parent_idx) = self.tree[node_idx].parent_index {
+            self.tree[old_parent_idx]
+                .children
+                .retain(|&child| child != node_idx);
+        }
+        self.tree[new_parent_idx].children.push(node_idx);
+        let node = &mut self.tree[node_idx];
+        node.parent_index = Some(new_parent_idx);
+        node.cost = cost;
+
+        let mut stack = vec![node_idx];
+        while let Some(parent_idx) = stack.pop() {
+            for i in 0..self.tree[parent_idx].children.len() {
+                let child_idx = self.tree[parent_idx].children[i];
+                let child_cost = self.cost(&self.tree[child_idx], &self.tree[parent_idx]);
+                self.tree[child_idx].cost = child_cost;
+                stack.push(child_idx);
+            }
+        }
+    }
+
+    /// Returns `true` if the tie-break prefers `candidate_idx` over `current_idx` as the parent
+    /// of `node`.
+    fn prefers_parent(&self, node: &Node<S>, candidate_idx: usize, current_idx: usize) -> bool {
+        let Some(tie_break) = &self.tie_break else {
+            return false;
+        };
+        let as_candidate = |index: usize| ParentCandidate {
+            state: &self.tree[index].state,
+            parent: self.tree[index]
+                .parent_index
+                .map(|parent| &self.tree[parent].state),
+        };
+
+        tie_break(
+            &node.state,
+            &as_candidate(candidate_idx),
+            &as_candidate(current_idx),
+        ) == Ordering::Less
+    }
+
     /// Finds all nodes in the tree that are within the `search_radius` of a given node.
     ///
-    /// This is a simple linear scan;
-    /// TODO: Consider using kd-trees.
+    /// The search uses the nearest-neighbour structure set with `set_nearest_neighbors`, or a
+    /// linear scan by default.
     fn find_neighbours(&self, node: &Node<S>) -> Vec<usize> {
-        let mut neighbours: Vec<usize> = Vec::new();
-        if let Some(pd) = &self.problem_def {
-            for i in 0..self.tree.len() {
-                if pd.space.distance(&node.state, &self.tree[i].state) < self.search_radius {
-                    neighbours.push(i);
-                }
-            }
+        self.nn.as_ref().map_or_else(Vec::new, |nn| {
+            nn.nearest_within_radius(&node.state, self.search_radius)
+        })
+    }
+
+    /// Returns the number of nodes currently in the tree.
+    pub fn tree_size(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns whether the tree has reached the goal region, i.e. whether `best_solution` will
+    /// return a path.
+    pub fn has_solution(&self) -> bool {
+        !self.goal_node_indices.is_empty()
+    }
+
+    /// Returns the lowest-cost path to the goal region in the current tree, without planning any
+    /// further.
+    ///
+    /// Between calls to `continue_solve`, the cost of this path never increases.
+    pub fn best_solution(&self) -> Option<Path<S>> {
+        self.best_goal_node()
+            .map(|index| self.reconstruct_path(index))
+    }
+
+    /// Returns the cost of the lowest-cost path to the goal region in the current tree, or `None`
+    /// if the goal has not been reached.
+    ///
+    /// This is the cost recorded for the best goal node, i.e. the cost of `best_solution` under
+    /// the optimisation objective (by default its length) when the node was last (re)connected.
+    pub fn best_cost(&self) -> Option<f64> {
+        self.best_goal_node().map(|index| self.tree[index].cost)
+    }
+
+    /// Returns the index of the goal node with the lowest cost.
+    ///
+    /// Rewiring may have lowered costs since each goal node was added, so this is only decided
+    /// when asked.
+    fn best_goal_node(&self) -> Option<usize> {
+        self.goal_node_indices
+            .iter()
+            .copied()
+            .min_by(|&a, &b| self.tree[a].cost.total_cmp(&self.tree[b].cost))
+    }
+
+    /// Samples the informed set of the best solution, if informed mode is on and a solution
+    /// exists.
+    fn sample_informed(&mut self) -> Option<S> {
+        if !self.informed {
+            return None;
+        }
+        let pd = self.problem_def.clone()?;
+        let goal_index = self.best_goal_node()?;
+        let mut root_index = goal_index;
+        while let Some(parent_index) = self.tree[root_index].parent_index {
+            root_index = parent_index;
         }
-        neighbours
+
+        pd.space.sample_informed(
+            &self.tree[root_index].state,
+            &self.tree[goal_index].state,
+            self.tree[goal_index].cost,
+            &mut self.rng,
+        )
     }
 
     fn reconstruct_path(&self, start_node_idx: usize) -> Path<S> {
@@ -145,8 +479,8 @@ where
 
 impl<S, SP, G> Planner<S, SP, G> for RRTStar<S, SP, G>
 where
-    S: State + Clone,
-    SP: StateSpace<StateType = S>,
+    S: State + Clone + 'static,
+    SP: StateSpace<StateType = S> + 'static,
     G: Goal<S> + GoalSampleableRegion<S>,
 {
     fn setup(
@@ -154,65 +488,268 @@ where
         problem_def: Arc<ProblemDefinition<S, SP, G>>,
         validity_checker: Arc<dyn StateValidityChecker<S>>,
     ) {
+        let validity_checker =
+            planner::count_validity_checks(validity_checker, self.max_validity_checks);
+        if cfg!(debug_assertions) && self.check_axioms {
+            crate::base::space::debug_check_axioms(&*problem_def.space, &mut self.rng);
+        }
+
+        if let Some(fraction) = self.search_radius_fraction {
+            self.search_radius = fraction * problem_def.space.get_maximum_extent();
+        }
+
+        let space = problem_def.space.clone();
+        self.nn = Some(match &self.nn_factory {
+            Some(factory) => factory(space),
+            None => Box::new(LinearNN::new(space)),
+        });
+
+        self.motion_validator = Some(motion_validator::motion_validator_or_default(
+            &self.custom_motion_validator,
+            problem_def.space.clone(),
+            validity_checker.clone(),
+        ));
+        self.objective = Some(match &self.custom_objective {
+            Some(objective) => objective.clone(),
+            None => Arc::new(PathLengthObjective::new(problem_def.space.clone())),
+        });
         self.problem_def = Some(problem_def);
         self.validity_checker = Some(validity_checker);
         self.tree.clear();
+        self.goal_node_indices.clear();
+        self.reported_cost = f64::INFINITY;
+
+        // Initialise the tree with the start states, plus the states drawn from the start
+        // sampler, brought within the bounds of the space. Sampled states are checked for
+        // validity once they are within the bounds, as that is the state the tree holds.
+        let pd = self.problem_def.as_ref().unwrap();
+        let vc = self.validity_checker.as_ref().unwrap();
+        let nn = self.nn.as_mut().unwrap();
+        let sampled_starts = pd
+            .sample_start_states(&mut self.rng)
+            .into_iter()
+            .map(|state| planner::enforce_bounds(&*pd.space, state))
+            .filter(|state| vc.is_valid(state));
+        let start_states = pd
+            .start_states
+            .iter()
+            .map(|state| planner::enforce_bounds(&*pd.space, state.clone()));
+        for start_state in start_states.chain(sampled_starts) {
+            nn.add(self.tree.len(), start_state.clone());
+            self.tree.push(Node {
+                state: start_state,
+                parent_index: None,
+                cost: 0.0,
+                children: Vec::new(),
+            });
+        }
+    }
+
+    /// Refines the tree until `condition` is met and returns the lowest-cost solution, or returns
+    /// the first solution found if anytime mode is off (`set_anytime`).
+    ///
+    /// An error is only returned if the goal was never reached.
+    fn solve_with(&mut self, condition: TerminationCondition) -> Result<Path<S>, PlanningError> {
+        self.run(&condition, !self.anytime)
+    }
 
-        // Initialise the tree with the start state.
-        let start_state = self.problem_def.as_ref().unwrap().start_states[0].clone();
-        let start_node = Node {
-            state: start_state,
-            parent_index: None,
-            cost: 0.0,
+    fn solve_detailed_with(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<Solution<S>, PlanningError> {
+        let start_time = Instant::now();
+        let path = self.run(&condition, !self.anytime)?;
+        let stats = PlannerStats {
+            planning_time: start_time.elapsed(),
+            validity_checks: self.validity_check_count(),
         };
-        self.tree.push(start_node);
+        let pd = self
+            .problem_def
+            .as_ref()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        let mut solution = Solution::exact("RRT*", path, &*pd.space, stats);
+        if let Some(objective) = &self.objective {
+            solution.cost = solution.path.cost(&**objective);
+        }
+        Ok(solution)
+    }
+}
+
+impl<S, SP, G> RRTStar<S, SP, G>
+where
+    S: State + Clone,
+    SP: StateSpace<StateType = S>,
+    G: Goal<S> + GoalSampleableRegion<S>,
+{
+    /// Runs at most `iterations` iterations of the main loop, regardless of elapsed time.
+    ///
+    /// As with `solve`, in anytime mode the tree keeps being refined for the whole budget and the
+    /// lowest-cost path to the goal is returned. Returns `PlanningError::NoSolutionFound` if the
+    /// goal was never reached.
+    pub fn solve_iterations(&mut self, iterations: usize) -> Result<Path<S>, PlanningError> {
+        self.run(
+            &TerminationCondition::MaxIterations(iterations),
+            !self.anytime,
+        )
+    }
+
+    /// Resumes refining the current tree until `condition` is met, then returns the lowest-cost
+    /// path to the goal found so far.
+    ///
+    /// Neither the tree nor the solutions found by earlier calls are discarded, so repeated calls
+    /// refine the same tree: poll `best_solution` between calls to get the current best path,
+    /// whose cost never increases. `setup` starts a new tree from scratch. If anytime mode is off,
+    /// this instead returns once the goal is next reached.
+    pub fn continue_solve(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<Path<S>, PlanningError> {
+        self.run(&condition, !self.anytime)
     }
 
-    fn solve(&mut self, timeout: Duration) -> Result<Path<S>, PlanningError> {
+    /// Like `continue_solve`, refining the tree until `condition` is met, but if the goal was not
+    /// reached, returns the path from the start to the node of the tree closest to the goal
+    /// instead of an error.
+    ///
+    /// The flag is `true` if the path reaches the goal, and `false` if it is partial. Closeness is
+    /// measured by `GoalRegion::distance_goal`. Errors other than running out of time or
+    /// iterations, e.g. `PlanningError::InvalidStartState`, are still returned.
+    pub fn solve_partial(
+        &mut self,
+        condition: TerminationCondition,
+    ) -> Result<(Path<S>, bool), PlanningError> {
+        match self.run(&condition, !self.anytime) {
+            Ok(path) => Ok((path, true)),
+            Err(PlanningError::Timeout { .. } | PlanningError::NoSolutionFound) => {
+                let pd = self
+                    .problem_def
+                    .as_ref()
+                    .ok_or(PlanningError::PlannerUninitialised)?;
+                let closest =
+                    planner::closest_to_goal(&*pd.goal, self.tree.iter().map(|node| &node.state))
+                        .ok_or(PlanningError::InvalidStartState)?;
+                Ok((self.reconstruct_path(closest), false))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Replaces the goal of the problem while keeping the tree grown so far.
+    ///
+    /// Nodes already in the tree that satisfy the new goal become solutions straight away, so a
+    /// following `continue_solve` refines towards the new goal from the existing tree rather than
+    /// starting again. Unlike `setup`, the space, start states and validity checker are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PlanningError::PlannerUninitialised` if `setup` has not been called.
+    pub fn set_goal(&mut self, goal: Arc<G>) -> Result<(), PlanningError> {
         let pd = self
             .problem_def
             .as_ref()
             .ok_or(PlanningError::PlannerUninitialised)?;
+
+        self.goal_node_indices = self
+            .tree
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| goal.is_satisfied(&node.state))
+            .map(|(index, _)| index)
+            .collect();
+        self.reported_cost = f64::INFINITY;
+        self.problem_def = Some(Arc::new(ProblemDefinition {
+            space: pd.space.clone(),
+            start_states: pd.start_states.clone(),
+            goal,
+            start_sampler: pd.start_sampler.clone(),
+        }));
+        Ok(())
+    }
+
+    /// The main RRT* loop, stopping once `condition` is met.
+    ///
+    /// With `stop_at_first_solution`, the first solution found is returned. Otherwise, the best
+    /// solution found before `condition` was met is returned.
+    fn run(
+        &mut self,
+        condition: &TerminationCondition,
+        stop_at_first_solution: bool,
+    ) -> Result<Path<S>, PlanningError> {
+        let pd = self
+            .problem_def
+            .clone()
+            .ok_or(PlanningError::PlannerUninitialised)?;
         let goal = &pd.goal;
 
+        if let Some(vc) = &self.validity_checker {
+            planner::check_start_states(&*pd.space, &**vc, &pd.start_states)?;
+        }
+        if self.tree.is_empty() {
+            return Err(PlanningError::InvalidStartState);
+        }
+
+        let condition = &*planner::with_max_nodes(condition, self.max_nodes);
         let start_time = Instant::now();
-        let mut rng = rand::rng();
+        let mut iteration = 0;
 
         // Main Loop
         loop {
-            // 1. Check for timeout
-            if start_time.elapsed() > timeout {
-                return Err(PlanningError::Timeout);
+            // Abort if the validity checker failed during the previous iteration.
+            if let Some(vc) = &self.validity_checker {
+                planner::check_validity_error(&**vc)?;
             }
+            // 1. Check the termination condition
+            if let Some(reason) = condition.check(start_time.elapsed(), iteration, self.tree.len())
+            {
+                if let Some(best_goal_node) = self.best_goal_node() {
+                    logging::solution_found("RRT*", self.tree.len());
+                    return Ok(self.reconstruct_path(best_goal_node));
+                }
+                if reason != TerminationReason::Timeout {
+                    return Err(PlanningError::NoSolutionFound);
+                }
+                let best_goal_distance = self
+                    .tree
+                    .iter()
+                    .map(|node| goal.distance_goal(&node.state))
+                    .fold(f64::INFINITY, f64::min);
+                let nodes = self.tree.len();
+                logging::timeout("RRT*", nodes, best_goal_distance);
+                return Err(PlanningError::Timeout {
+                    best_goal_distance,
+                    nodes,
+                });
+            }
+            iteration += 1;
 
-            // 2. Sample a state (q_rand)
-            let q_rand = if rng.random_bool(self.goal_bias) {
-                // TODO: assume sample_goal can't fail here for simplicity, but a real
-                // implementation would handle the Result.
-                goal.sample_goal(&mut rng).unwrap()
-            } else {
-                // TODO: assume uniform sampling can't fail if bounds are set correctly.
-                pd.space.sample_uniform(&mut rng).unwrap()
+            // 2. Sample a state (q_rand), from the informed set once there is a solution
+            let q_rand = match self.sample_informed() {
+                Some(state) => state,
+                None => planner::sample_target(
+                    &*pd.space,
+                    &**goal,
+                    self.goal_bias,
+                    self.sampler.as_deref(),
+                    &mut self.rng,
+                )?,
             };
 
             // 3. Find the nearest node in the tree (q_near)
-            let mut nearest_node_index = 0;
-            let mut min_dist = pd.space.distance(&self.tree[0].state, &q_rand);
-
-            for i in 1..self.tree.len() {
-                let dist = pd.space.distance(&self.tree[i].state, &q_rand);
-                if dist < min_dist {
-                    min_dist = dist;
-                    nearest_node_index = i;
-                }
-            }
+            let nearest_node_index = self
+                .nn
+                .as_ref()
+                .and_then(|nn| nn.nearest(&q_rand))
+                .ok_or(PlanningError::PlannerUninitialised)?;
             let q_near = &self.tree[nearest_node_index].state;
+            let min_dist = pd.space.distance(q_near, &q_rand);
 
             // 4. Steer from q_near towards q_rand to get q_new
             let mut q_new = q_near.clone();
             if min_dist > self.max_distance {
                 let t = self.max_distance / min_dist;
                 pd.space.interpolate(q_near, &q_rand, t, &mut q_new);
+                // Interpolation may leave the bounds, through rounding or a curved path.
+                pd.space.enforce_bounds(&mut q_new);
             } else {
                 q_new = q_rand;
             }
@@ -226,36 +763,28 @@ where
                 state: q_new.clone(),
                 parent_index: None,
                 cost: 0.0,
+                children: Vec::new(),
             };
             let neighbours: Vec<usize> = self.find_neighbours(&temp_node);
 
             // 6. Choose parent
-            let mut best_parent_index = nearest_node_index;
-            let q_near_node = &self.tree[nearest_node_index];
-            let mut min_cost = self.cost(&temp_node, q_near_node);
-
-            // Iterate through neighbors to find a cheaper path. If a neighbor offers a cheaper
-            // path and the motion from that neighbor is collision-free we have found a new,
-            // better parent.
-            for &neighbour_idx in &neighbours {
-                let neighbour_node = &self.tree[neighbour_idx];
-                let cost_via_neighbour = self.cost(&temp_node, neighbour_node);
-
-                if cost_via_neighbour < min_cost && self.check_motion(&neighbour_node.state, &q_new)
-                {
-                    min_cost = cost_via_neighbour;
-                    best_parent_index = neighbour_idx;
-                }
-            }
+            let (best_parent_index, min_cost) =
+                self.choose_parent(&temp_node, nearest_node_index, &neighbours);
 
             // 7. Add the new node to the tree with the optimal parent and cost.
             let new_node = Node {
                 state: q_new.clone(),
                 parent_index: Some(best_parent_index),
                 cost: min_cost,
+                children: Vec::new(),
             };
             self.tree.push(new_node);
             let new_node_index = self.tree.len() - 1;
+            self.tree[best_parent_index].children.push(new_node_index);
+            if let Some(nn) = self.nn.as_mut() {
+                nn.add(new_node_index, q_new.clone());
+            }
+            logging::node_milestone("RRT*", self.tree.len());
 
             // 8. Rewire tree
             for &neighbour_idx in &neighbours {
@@ -275,17 +804,292 @@ where
                 if cost_via_new_node < neighbour_node.cost
                     && self.check_motion(&new_node_ref.state, &neighbour_node.state)
                 {
-                    let mutable_neighbour_node = &mut self.tree[neighbour_idx];
-                    mutable_neighbour_node.parent_index = Some(new_node_index);
-                    mutable_neighbour_node.cost = cost_via_new_node;
+                    self.rewire(neighbour_idx, new_node_index, cost_via_new_node);
                 }
             }
 
             // 9. Check if the new node satisfies the goal
-            if goal.is_satisfied(&q_new) {
-                println!("Solution found after {} nodes.", self.tree.len());
-                return Ok(self.reconstruct_path(self.tree.len() - 1));
+            let reached_goal = goal.is_satisfied(&q_new);
+            if reached_goal {
+                self.goal_node_indices.push(new_node_index);
+            }
+
+            // 10. Report the best solution if the new node or the rewiring improved it
+            self.report_progress();
+            if reached_goal && stop_at_first_solution {
+                logging::solution_found("RRT*", self.tree.len());
+                return Ok(self.reconstruct_path(new_node_index));
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{
+        error::StateSamplingError,
+        goal::GoalRegion,
+        space::{DubinsStateSpace, RealVectorStateSpace, SO2StateSpace},
+        state::{RealVectorState, SE2State, SO2State},
+    };
+    use crate::test_fixtures::{AlwaysValid, OverhangingSampler, PointGoal, UnreachableGoal};
+    use rand::Rng;
+    use std::f64::consts::PI;
+
+    /// Samples uniformly from the left half, `x < 5`, of the 10 x 10 square.
+    struct LeftHalfSampler;
+
+    impl StateSampler<RealVectorState> for LeftHalfSampler {
+        fn sample(&self, rng: &mut dyn RngCore) -> Result<RealVectorState, StateSamplingError> {
+            Ok(RealVectorState::new(vec![
+                rng.random_range(0.0..5.0),
+                rng.random_range(0.0..10.0),
+            ]))
+        }
+    }
+
+    struct AngleGoal(SO2State);
+
+    impl Goal<SO2State> for AngleGoal {
+        fn is_satisfied(&self, state: &SO2State) -> bool {
+            (state.value - self.0.value).abs() < 1e-9
+        }
+    }
+
+    impl GoalRegion<SO2State> for AngleGoal {
+        fn distance_goal(&self, _state: &SO2State) -> f64 {
+            0.0
+        }
+    }
+
+    impl GoalSampleableRegion<SO2State> for AngleGoal {
+        fn sample_goal(&self, _rng: &mut impl Rng) -> Result<SO2State, StateSamplingError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn node(x: f64, y: f64, parent_index: Option<usize>, cost: f64) -> Node<RealVectorState> {
+        Node {
+            state: RealVectorState::new(vec![x, y]),
+            parent_index,
+            cost,
+            children: Vec::new(),
+        }
+    }
+
+    /// Angle between the incoming direction `from -> via` and the outgoing one `via -> to`.
+    fn heading_change(from: &RealVectorState, via: &RealVectorState, to: &RealVectorState) -> f64 {
+        let a = (via.values[1] - from.values[1]).atan2(via.values[0] - from.values[0]);
+        let b = (to.values[1] - via.values[1]).atan2(to.values[0] - via.values[0]);
+        let diff = (b - a).abs() % (2.0 * std::f64::consts::PI);
+        diff.min(2.0 * std::f64::consts::PI - diff)
+    }
+
+    /// Builds a planner whose tree offers two equal-cost parents for the state `(3, 1)`:
+    /// `(2, 2)` reached heading north (a 135 degree turn) and `(2, 0)` reached heading east
+    /// (a 45 degree turn).
+    fn planner_with_equal_cost_parents() -> RRTStar<RealVectorState, RealVectorStateSpace, PointGoal>
+    {
+        let space =
+            Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 4.0), (0.0, 4.0)])).unwrap());
+        let problem_def = Arc::new(ProblemDefinition {
+            space,
+            start_states: vec![RealVectorState::new(vec![0.0, 0.0])],
+            goal: Arc::new(PointGoal(RealVectorState::new(vec![4.0, 4.0]))),
+            start_sampler: None,
+        });
+
+        let mut planner = RRTStar::new(1.0, 0.0, 2.0);
+        planner.setup(problem_def, Arc::new(AlwaysValid));
+        planner.tree.push(node(2.0, 1.0, Some(0), 3.0));
+        planner.tree.push(node(2.0, 2.0, Some(1), 4.0));
+        planner.tree.push(node(2.0, 0.0, Some(0), 4.0));
+        planner
+    }
+
+    #[test]
+    fn test_choose_parent_keeps_first_equal_cost_parent_by_default() {
+        let planner = planner_with_equal_cost_parents();
+        let new_node = node(3.0, 1.0, None, 0.0);
+
+        let (parent, cost) = planner.choose_parent(&new_node, 2, &[2, 3]);
+        assert_eq!(parent, 2);
+        assert!((cost - (4.0 + 2.0f64.sqrt())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_choose_parent_tie_break_prefers_smoother_parent() {
+        let mut planner = planner_with_equal_cost_parents();
+        planner.set_parent_tie_break(Arc::new(|new_state, a, b| {
+            let turn = |c: &ParentCandidate<'_, RealVectorState>| {
+                c.parent
+                    .map_or(0.0, |p| heading_change(p, c.state, new_state))
+            };
+            turn(a).total_cmp(&turn(b))
+        }));
+        let new_node = node(3.0, 1.0, None, 0.0);
+
+        let (parent, cost) = planner.choose_parent(&new_node, 2, &[2, 3]);
+        assert_eq!(
+            parent, 3,
+            "The parent with the smaller heading change should win."
+        );
+        assert!((cost - (4.0 + 2.0f64.sqrt())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_search_radius_fraction_scales_with_so2_extent() {
+        let space = Arc::new(SO2StateSpace::new(None).unwrap());
+        let problem_def = Arc::new(ProblemDefinition {
+            space,
+            start_states: vec![SO2State::new(0.0)],
+            goal: Arc::new(AngleGoal(SO2State::new(PI / 2.0))),
+            start_sampler: None,
+        });
+
+        // A radius of 1.0 would cover a third of the circle; relative to the extent of SO2 the
+        // same planner settings give a local neighbourhood.
+        let mut planner = RRTStar::new(0.2, 0.0, 1.0);
+        planner.set_search_radius_fraction(0.1);
+        planner.set_seed(5);
+        planner.setup(problem_def, Arc::new(AlwaysValid));
+        assert!((planner.search_radius - 0.1 * PI).abs() < 1e-12);
+
+        let _ = planner.solve_iterations(300);
+        assert!(planner.tree.len() > 100);
+
+        // Every node past the first few should have other nodes to rewire against, but not the
+        // whole tree.
+        for node in planner.tree.iter().skip(20) {
+            let neighbours = planner.find_neighbours(node);
+            assert!(neighbours.len() > 1, "Rewiring neighbourhood is empty");
+            assert!(neighbours.len() < planner.tree.len() / 2);
+        }
+    }
+
+    #[test]
+    fn test_informed_samples_lie_within_the_informed_set() {
+        let space =
+            Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+        let start = RealVectorState::new(vec![1.0, 1.0]);
+        let goal = RealVectorState::new(vec![9.0, 7.0]);
+        let problem_def = Arc::new(ProblemDefinition {
+            space: space.clone(),
+            start_states: vec![start.clone()],
+            goal: Arc::new(PointGoal(goal.clone())),
+            start_sampler: None,
+        });
+
+        let mut planner = RRTStar::new(1.0, 0.1, 2.0);
+        planner.set_seed(0);
+        planner.set_informed(true);
+        planner.setup(problem_def, Arc::new(AlwaysValid));
+        assert!(planner.sample_informed().is_none());
+
+        planner.solve_iterations(10_000).unwrap();
+        let best_cost = planner.best_cost().unwrap();
+        for _ in 0..1000 {
+            let state = planner.sample_informed().unwrap();
+            let cost = space.distance(&start, &state) + space.distance(&state, &goal);
+            assert!(
+                cost <= best_cost + 1e-9,
+                "{state:?} costs {cost} > {best_cost}"
+            );
+            assert!(space.satisfies_bounds(&state));
+        }
+
+        planner.set_informed(false);
+        assert!(planner.sample_informed().is_none());
+    }
+
+    #[test]
+    fn test_progress_callback_reports_non_increasing_costs() {
+        let space =
+            Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+        let problem_def = Arc::new(ProblemDefinition {
+            space,
+            start_states: vec![RealVectorState::new(vec![1.0, 1.0])],
+            goal: Arc::new(PointGoal(RealVectorState::new(vec![9.0, 7.0]))),
+            start_sampler: None,
+        });
+        let costs = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let mut planner = RRTStar::new(1.0, 0.1, 2.0);
+        planner.set_seed(0);
+        let reported = costs.clone();
+        planner.set_progress_callback(Some(Arc::new(move |cost| {
+            reported.lock().unwrap().push(cost)
+        })));
+        planner.setup(problem_def, Arc::new(AlwaysValid));
+        planner.solve_iterations(5000).unwrap();
+
+        let costs = costs.lock().unwrap();
+        assert!(
+            costs.len() > 1,
+            "expected the solution to improve, got {costs:?}"
+        );
+        assert!(costs.windows(2).all(|pair| pair[1] < pair[0]));
+        assert_eq!(costs.last().copied(), planner.best_cost());
+    }
+
+    #[test]
+    fn test_sampler_keeps_the_tree_in_the_sampled_region() {
+        let space = Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0); 2])).unwrap());
+        let problem_def = Arc::new(ProblemDefinition {
+            space,
+            start_states: vec![RealVectorState::new(vec![1.0, 5.0])],
+            goal: Arc::new(PointGoal(RealVectorState::new(vec![9.0, 5.0]))),
+            start_sampler: None,
+        });
+        let grow = |sampler: Option<Arc<dyn StateSampler<RealVectorState>>>| {
+            let mut planner = RRTStar::new(0.5, 0.0, 1.0);
+            planner.set_seed(0);
+            if let Some(sampler) = sampler {
+                planner.set_sampler(sampler);
+            }
+            planner.setup(problem_def.clone(), Arc::new(AlwaysValid));
+            // Without goal biasing, the goal point is never sampled, so the search runs out.
+            assert!(planner.solve_iterations(500).is_err());
+            planner
+                .tree
+                .iter()
+                .map(|node| node.state.values[0])
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+
+        assert!(grow(None) > 5.0);
+        assert!(grow(Some(Arc::new(LeftHalfSampler))) < 5.0);
+    }
+
+    #[test]
+    fn test_tree_stays_within_bounds() {
+        // Dubins paths between states in a box this small swing well outside it.
+        let space = Arc::new(DubinsStateSpace::new(Some(vec![(0.0, 2.0); 2]), 1.0).unwrap());
+        let problem_def = Arc::new(ProblemDefinition {
+            space: space.clone(),
+            start_states: vec![SE2State::new(1.0, 1.0, 0.0)],
+            goal: Arc::new(UnreachableGoal(SE2State::new(1.5, 1.5, PI))),
+            start_sampler: None,
+        });
+
+        // A sampler may also return states outside the bounds, which the tree can reach directly.
+        let samplers: [Option<Arc<dyn StateSampler<SE2State>>>; 2] =
+            [None, Some(Arc::new(OverhangingSampler))];
+        for sampler in samplers {
+            let mut planner = RRTStar::new(1.0, 0.05, 1.0);
+            planner.set_seed(0);
+            if let Some(sampler) = sampler {
+                planner.set_sampler(sampler);
+            }
+            planner.setup(problem_def.clone(), Arc::new(AlwaysValid));
+            assert!(planner.solve_iterations(300).is_err());
+
+            assert!(planner.tree.len() > 100);
+            assert!(planner
+                .tree
+                .iter()
+                .all(|node| space.satisfies_bounds(&node.state)));
+        }
+    }
+}
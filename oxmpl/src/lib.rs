@@ -1,3 +1,7 @@
 pub mod base;
 pub mod geometric;
+pub(crate) mod logging;
+#[cfg(test)]
+mod test_fixtures;
+pub mod testing;
 pub mod time;
@@ -0,0 +1,62 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Planner diagnostics routed through the `log` crate facade.
+//!
+//! With the `logging` feature enabled, planners emit the events below under the `oxmpl` target,
+//! with the details attached as structured key-values. Applications choose where they end up by
+//! installing a logger, e.g. `env_logger` natively or `console_log` on wasm. Without the feature
+//! the events compile to nothing.
+
+/// Emit a `debug` milestone event every time a tree grows by this many nodes.
+pub(crate) const NODE_MILESTONE_INTERVAL: usize = 1000;
+
+/// A planner found a solution after growing `nodes` nodes.
+pub(crate) fn solution_found(planner: &'static str, nodes: usize) {
+    #[cfg(feature = "logging")]
+    log::info!(target: "oxmpl", planner, nodes; "{planner}: solution found after {nodes} nodes");
+    #[cfg(not(feature = "logging"))]
+    let _ = (planner, nodes);
+}
+
+/// A planner ran out of time after growing `nodes` nodes, the closest of which was
+/// `best_goal_distance` from the goal.
+pub(crate) fn timeout(planner: &'static str, nodes: usize, best_goal_distance: f64) {
+    #[cfg(feature = "logging")]
+    log::info!(
+        target: "oxmpl",
+        planner,
+        nodes,
+        best_goal_distance;
+        "{planner}: timed out after {nodes} nodes, closest was {best_goal_distance} from the goal"
+    );
+    #[cfg(not(feature = "logging"))]
+    let _ = (planner, nodes, best_goal_distance);
+}
+
+/// A planner's tree or roadmap has grown to `nodes` nodes. Only emitted every
+/// `NODE_MILESTONE_INTERVAL` nodes.
+pub(crate) fn node_milestone(planner: &'static str, nodes: usize) {
+    if !nodes.is_multiple_of(NODE_MILESTONE_INTERVAL) {
+        return;
+    }
+    #[cfg(feature = "logging")]
+    log::debug!(target: "oxmpl", planner, nodes; "{planner}: grown to {nodes} nodes");
+    #[cfg(not(feature = "logging"))]
+    let _ = planner;
+}
+
+/// A roadmap of `nodes` milestones is ready, either newly constructed or reused as is.
+pub(crate) fn roadmap_constructed(planner: &'static str, nodes: usize, reused: bool) {
+    #[cfg(feature = "logging")]
+    log::info!(
+        target: "oxmpl",
+        planner,
+        nodes,
+        reused;
+        "{planner}: roadmap with {nodes} milestones ready"
+    );
+    #[cfg(not(feature = "logging"))]
+    let _ = (planner, nodes, reused);
+}
@@ -0,0 +1,128 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Goals, validity checkers, samplers and spaces shared by the crate's unit tests.
+
+use std::f64::consts::PI;
+
+use rand::{Rng, RngCore};
+
+use crate::base::{
+    error::StateSamplingError,
+    goal::{Goal, GoalRegion, GoalSampleableRegion},
+    sampler::StateSampler,
+    space::StateSpace,
+    state::{RealVectorState, SE2State, State},
+    validity::StateValidityChecker,
+};
+
+/// A goal satisfied only by one exact state.
+pub(crate) struct PointGoal(pub(crate) RealVectorState);
+
+impl Goal<RealVectorState> for PointGoal {
+    fn is_satisfied(&self, state: &RealVectorState) -> bool {
+        state == &self.0
+    }
+}
+
+impl GoalRegion<RealVectorState> for PointGoal {
+    fn distance_goal(&self, state: &RealVectorState) -> f64 {
+        state
+            .values
+            .iter()
+            .zip(&self.0.values)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+impl GoalSampleableRegion<RealVectorState> for PointGoal {
+    fn sample_goal(&self, _rng: &mut impl Rng) -> Result<RealVectorState, StateSamplingError> {
+        Ok(self.0.clone())
+    }
+}
+
+/// A goal that is never satisfied, so the planner keeps growing its tree.
+pub(crate) struct UnreachableGoal<S>(pub(crate) S);
+
+impl<S: State> Goal<S> for UnreachableGoal<S> {
+    fn is_satisfied(&self, _state: &S) -> bool {
+        false
+    }
+}
+
+impl<S: State> GoalRegion<S> for UnreachableGoal<S> {
+    fn distance_goal(&self, _state: &S) -> f64 {
+        1.0
+    }
+}
+
+impl<S: State> GoalSampleableRegion<S> for UnreachableGoal<S> {
+    fn sample_goal(&self, _rng: &mut impl Rng) -> Result<S, StateSamplingError> {
+        Ok(self.0.clone())
+    }
+}
+
+/// A validity checker that accepts every state.
+pub(crate) struct AlwaysValid;
+
+impl<S: State> StateValidityChecker<S> for AlwaysValid {
+    fn is_valid(&self, _state: &S) -> bool {
+        true
+    }
+}
+
+/// Samples SE(2) states uniformly from `[-0.5, 2.5]^2`, overhanging a space bounded by `[0, 2]^2`.
+pub(crate) struct OverhangingSampler;
+
+impl StateSampler<SE2State> for OverhangingSampler {
+    fn sample(&self, rng: &mut dyn RngCore) -> Result<SE2State, StateSamplingError> {
+        Ok(SE2State::new(
+            rng.random_range(-0.5..2.5),
+            rng.random_range(-0.5..2.5),
+            rng.random_range(-PI..PI),
+        ))
+    }
+}
+
+/// A deliberately broken 1D space whose distance is biased and whose interpolation ignores
+/// its `from` endpoint.
+pub(crate) struct BrokenSpace;
+
+impl StateSpace for BrokenSpace {
+    type StateType = RealVectorState;
+
+    fn distance(&self, state1: &Self::StateType, state2: &Self::StateType) -> f64 {
+        state1.values[0] - state2.values[0] + 1.0
+    }
+
+    fn interpolate(
+        &self,
+        _from: &Self::StateType,
+        to: &Self::StateType,
+        _t: f64,
+        state: &mut Self::StateType,
+    ) {
+        state.values[0] = to.values[0];
+    }
+
+    fn enforce_bounds(&self, _state: &mut Self::StateType) {}
+
+    fn satisfies_bounds(&self, _state: &Self::StateType) -> bool {
+        true
+    }
+
+    fn sample_uniform(&self, rng: &mut impl Rng) -> Result<Self::StateType, StateSamplingError> {
+        Ok(RealVectorState::new(vec![rng.random_range(0.0..1.0)]))
+    }
+
+    fn get_longest_valid_segment_length(&self) -> f64 {
+        0.05
+    }
+
+    fn get_maximum_extent(&self) -> f64 {
+        1.0
+    }
+}
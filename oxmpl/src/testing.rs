@@ -0,0 +1,250 @@
+// Copyright (c) 2025 Junior Sundar
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Assertions for testing planners against each other.
+//!
+//! With the `testing` feature enabled, this module also provides `RecordingRng` and `ReplayRng`
+//! for recording the random draws of a planning run and replaying them exactly.
+
+use crate::base::{planner::Path, space::StateSpace};
+
+#[cfg(feature = "testing")]
+pub use self::rng::{RecordingRng, ReplayRng, RngDraw, RngLog};
+
+/// Asserts that `path_a` is no longer than `ratio` times the length of `path_b`.
+///
+/// Path lengths are measured with `space.distance`. This encodes expectations such as an
+/// optimising planner's path being no worse than a non-optimising one's, e.g. with `path_a` from
+/// RRT* and `path_b` from RRT. A `ratio` slightly above `1.0` leaves room for sampling noise.
+///
+/// # Panics
+///
+/// Panics if `path_a` is longer than `ratio` times `path_b`, reporting both lengths.
+///
+/// # Examples
+///
+/// ```
+/// use oxmpl::base::{planner::Path, space::RealVectorStateSpace, state::RealVectorState};
+/// use oxmpl::testing::assert_path_cost_within;
+///
+/// let space = RealVectorStateSpace::new(2, None).unwrap();
+/// let straight = Path(vec![
+///     RealVectorState::new(vec![0.0, 0.0]),
+///     RealVectorState::new(vec![2.0, 0.0]),
+/// ]);
+/// let detour = Path(vec![
+///     RealVectorState::new(vec![0.0, 0.0]),
+///     RealVectorState::new(vec![1.0, 0.5]),
+///     RealVectorState::new(vec![2.0, 0.0]),
+/// ]);
+///
+/// assert_path_cost_within(&straight, &detour, &space, 1.0);
+/// assert_path_cost_within(&detour, &straight, &space, 1.2);
+/// ```
+pub fn assert_path_cost_within<SP: StateSpace>(
+    path_a: &Path<SP::StateType>,
+    path_b: &Path<SP::StateType>,
+    space: &SP,
+    ratio: f64,
+) {
+    let length_a = path_a.length(space);
+    let length_b = path_b.length(space);
+    assert!(
+        length_a <= ratio * length_b,
+        "Path length {length_a} exceeds {ratio} times the reference path length {length_b}."
+    );
+}
+
+#[cfg(feature = "testing")]
+mod rng {
+    use rand::RngCore;
+    use std::sync::{Arc, Mutex};
+
+    /// A single draw from a random number generator, as recorded by `RecordingRng`.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum RngDraw {
+        /// A call to `next_u32` and the value it returned.
+        U32(u32),
+        /// A call to `next_u64` and the value it returned.
+        U64(u64),
+        /// A call to `fill_bytes` and the bytes it filled in.
+        Bytes(Vec<u8>),
+    }
+
+    /// A handle to the draws recorded by a `RecordingRng`, which stays readable after the
+    /// generator has been handed to a planner.
+    #[derive(Clone, Debug, Default)]
+    pub struct RngLog(Arc<Mutex<Vec<RngDraw>>>);
+
+    impl RngLog {
+        /// Returns a copy of the draws recorded so far, in order.
+        pub fn draws(&self) -> Vec<RngDraw> {
+            self.0.lock().unwrap().clone()
+        }
+
+        /// Returns the number of draws recorded so far.
+        pub fn len(&self) -> usize {
+            self.0.lock().unwrap().len()
+        }
+
+        /// Returns `true` if nothing has been drawn yet.
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        fn push(&self, draw: RngDraw) {
+            self.0.lock().unwrap().push(draw);
+        }
+    }
+
+    /// Wraps a random number generator, recording every draw made from it.
+    ///
+    /// Pass it to a planner's `set_rng` and keep its `log` to replay the run later with
+    /// `ReplayRng`, even if the wrapped generator was seeded from the operating system.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use oxmpl::testing::{RecordingRng, ReplayRng};
+    /// use rand::{rngs::StdRng, Rng, SeedableRng};
+    ///
+    /// let mut recording = RecordingRng::new(StdRng::from_os_rng());
+    /// let log = recording.log();
+    /// let original: Vec<f64> = (0..3).map(|_| recording.random()).collect();
+    ///
+    /// let mut replay = ReplayRng::new(log.draws());
+    /// let replayed: Vec<f64> = (0..3).map(|_| replay.random()).collect();
+    /// assert_eq!(original, replayed);
+    /// ```
+    pub struct RecordingRng<R: RngCore> {
+        inner: R,
+        log: RngLog,
+    }
+
+    impl<R: RngCore> RecordingRng<R> {
+        /// Creates a `RecordingRng` drawing from `inner`.
+        pub fn new(inner: R) -> Self {
+            Self {
+                inner,
+                log: RngLog::default(),
+            }
+        }
+
+        /// Returns a handle to the draws recorded by this generator.
+        pub fn log(&self) -> RngLog {
+            self.log.clone()
+        }
+    }
+
+    impl<R: RngCore> RngCore for RecordingRng<R> {
+        fn next_u32(&mut self) -> u32 {
+            let value = self.inner.next_u32();
+            self.log.push(RngDraw::U32(value));
+            value
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let value = self.inner.next_u64();
+            self.log.push(RngDraw::U64(value));
+            value
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            self.inner.fill_bytes(dst);
+            self.log.push(RngDraw::Bytes(dst.to_vec()));
+        }
+    }
+
+    /// Replays draws recorded by a `RecordingRng`, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics as soon as the replayed run diverges from the recorded one, i.e. when it makes a
+    /// different kind of draw than was recorded at that point, or more draws than were recorded.
+    /// The message gives the index of the offending draw.
+    pub struct ReplayRng {
+        draws: Vec<RngDraw>,
+        next: usize,
+    }
+
+    impl ReplayRng {
+        /// Creates a `ReplayRng` that replays `draws`.
+        pub fn new(draws: Vec<RngDraw>) -> Self {
+            Self { draws, next: 0 }
+        }
+
+        /// Returns the number of recorded draws not replayed yet.
+        pub fn remaining(&self) -> usize {
+            self.draws.len() - self.next
+        }
+
+        fn take(&mut self, requested: &str) -> &RngDraw {
+            let index = self.next;
+            let Some(draw) = self.draws.get(index) else {
+                panic!(
+                    "ReplayRng diverged at draw {index}: {requested} was requested, but only \
+                     {} draws were recorded",
+                    self.draws.len()
+                );
+            };
+            self.next += 1;
+            draw
+        }
+    }
+
+    impl RngCore for ReplayRng {
+        fn next_u32(&mut self) -> u32 {
+            let index = self.next;
+            match self.take("next_u32") {
+                RngDraw::U32(value) => *value,
+                other => panic!("ReplayRng diverged at draw {index}: next_u32 was requested, but {other:?} was recorded"),
+            }
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let index = self.next;
+            match self.take("next_u64") {
+                RngDraw::U64(value) => *value,
+                other => panic!("ReplayRng diverged at draw {index}: next_u64 was requested, but {other:?} was recorded"),
+            }
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            let index = self.next;
+            match self.take("fill_bytes") {
+                RngDraw::Bytes(bytes) if bytes.len() == dst.len() => dst.copy_from_slice(bytes),
+                other => panic!(
+                    "ReplayRng diverged at draw {index}: fill_bytes of {} bytes was requested, \
+                     but {other:?} was recorded",
+                    dst.len()
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{space::RealVectorStateSpace, state::RealVectorState};
+
+    fn path(points: &[[f64; 2]]) -> Path<RealVectorState> {
+        Path(
+            points
+                .iter()
+                .map(|p| RealVectorState::new(p.to_vec()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds 1.1 times the reference path length")]
+    fn test_assert_path_cost_within_rejects_longer_path() {
+        let space = RealVectorStateSpace::new(2, None).unwrap();
+        let straight = path(&[[0.0, 0.0], [3.0, 0.0]]);
+        let detour = path(&[[0.0, 0.0], [0.0, 4.0], [3.0, 0.0]]);
+
+        assert_path_cost_within(&detour, &straight, &space, 1.1);
+    }
+}
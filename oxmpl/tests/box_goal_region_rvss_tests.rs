@@ -0,0 +1,110 @@
+use std::{sync::Arc, time::Duration};
+
+use oxmpl::base::{
+    error::StateSpaceError,
+    goal::{BoxGoalRegion, Goal, GoalRegion, GoalSampleableRegion},
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+};
+use oxmpl::geometric::RRT;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+mod common;
+use common::AlwaysValid;
+
+/// A box that is tight in x and y but loose in z.
+fn landing_box() -> BoxGoalRegion {
+    BoxGoalRegion::new(
+        RealVectorState::new(vec![5.0, 5.0, 2.0]),
+        vec![0.1, 0.2, 1.5],
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_box_goal_is_satisfied_inside_the_box() {
+    let goal = landing_box();
+
+    assert!(goal.is_satisfied(&RealVectorState::new(vec![5.0, 5.0, 2.0])));
+    assert!(goal.is_satisfied(&RealVectorState::new(vec![5.09, 4.81, 3.49])));
+    assert_eq!(
+        goal.distance_goal(&RealVectorState::new(vec![4.95, 5.1, 0.6])),
+        0.0
+    );
+}
+
+#[test]
+fn test_box_goal_is_not_satisfied_outside_one_axis() {
+    let goal = landing_box();
+
+    // Well within the loose z tolerance, but just outside the tight x one.
+    assert!(!goal.is_satisfied(&RealVectorState::new(vec![5.15, 5.0, 2.0])));
+    // Within x and y, but outside z.
+    assert!(!goal.is_satisfied(&RealVectorState::new(vec![5.0, 5.0, 3.6])));
+}
+
+#[test]
+fn test_box_goal_distance_to_nearest_face_and_corner() {
+    let goal = landing_box();
+
+    // Outside one face: the distance is along that axis only.
+    let beyond_x_face = RealVectorState::new(vec![5.6, 5.0, 2.0]);
+    assert!((goal.distance_goal(&beyond_x_face) - 0.5).abs() < 1e-9);
+    let below_z_face = RealVectorState::new(vec![5.0, 5.1, -0.5]);
+    assert!((goal.distance_goal(&below_z_face) - 1.0).abs() < 1e-9);
+
+    // Outside along two axes: the distance is to the nearest edge.
+    let beyond_edge = RealVectorState::new(vec![5.4, 5.6, 2.0]);
+    assert!((goal.distance_goal(&beyond_edge) - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_box_goal_samples_lie_in_the_box() {
+    let goal = landing_box();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..1000 {
+        let sample = goal.sample_goal(&mut rng).unwrap();
+        assert!(goal.is_satisfied(&sample), "{sample:?} is outside the box");
+    }
+}
+
+#[test]
+fn test_box_goal_rejects_malformed_half_widths() {
+    let center = RealVectorState::new(vec![0.0, 0.0]);
+
+    assert_eq!(
+        BoxGoalRegion::new(center.clone(), vec![1.0]).err(),
+        Some(StateSpaceError::DimensionMismatch {
+            expected: 2,
+            found: 1
+        })
+    );
+    assert!(matches!(
+        BoxGoalRegion::new(center, vec![1.0, -1.0]),
+        Err(StateSpaceError::InvalidBound { .. })
+    ));
+}
+
+#[test]
+fn test_rrt_reaches_box_goal() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(3, Some(vec![(0.0, 10.0), (0.0, 10.0), (0.0, 4.0)])).unwrap(),
+    );
+    let goal = Arc::new(landing_box());
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space,
+        vec![RealVectorState::new(vec![1.0, 1.0, 1.0])],
+        goal.clone(),
+    ));
+
+    let mut planner = RRT::new(0.5, 0.1);
+    planner.set_seed(0);
+    planner.setup(problem_definition, Arc::new(AlwaysValid));
+    let path = planner.solve(Duration::from_secs(5)).unwrap();
+
+    assert!(goal.is_satisfied(path.0.last().unwrap()));
+}
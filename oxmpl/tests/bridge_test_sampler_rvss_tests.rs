@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    error::StateSamplingError,
+    sampler::{BridgeTestSampler, StateSampler},
+    space::{RealVectorStateSpace, StateSpace},
+    state::RealVectorState,
+    validity::StateValidityChecker,
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+mod common;
+use common::AlwaysValid;
+
+/// Two blocks between x = 3 and x = 7, leaving a thin corridor between y = 4.8 and y = 5.2.
+struct CorridorChecker;
+
+impl CorridorChecker {
+    fn in_corridor(state: &RealVectorState) -> bool {
+        let (x, y) = (state.values[0], state.values[1]);
+        (3.0..=7.0).contains(&x) && (4.8..=5.2).contains(&y)
+    }
+}
+
+impl StateValidityChecker<RealVectorState> for CorridorChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let x = state.values[0];
+        !(3.0..=7.0).contains(&x) || Self::in_corridor(state)
+    }
+}
+
+fn space() -> Arc<RealVectorStateSpace> {
+    Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap())
+}
+
+#[test]
+fn test_bridge_samples_concentrate_in_thin_corridor() {
+    let space = space();
+    let checker = Arc::new(CorridorChecker);
+    let sampler = BridgeTestSampler::new(space.clone(), checker.clone(), 1.0);
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let num_samples = 200;
+    let bridge_hits = (0..num_samples)
+        .map(|_| sampler.sample(&mut rng).unwrap())
+        .inspect(|state| assert!(checker.is_valid(state)))
+        .filter(CorridorChecker::in_corridor)
+        .count();
+
+    // The corridor is 1.6 of the 61.6 units of free area, so uniform sampling puts about 2.6% of
+    // the valid samples there.
+    let uniform_hits = (0..num_samples)
+        .map(|_| {
+            std::iter::repeat_with(|| space.sample_uniform(&mut rng).unwrap())
+                .find(|state| checker.is_valid(state))
+                .unwrap()
+        })
+        .filter(CorridorChecker::in_corridor)
+        .count();
+
+    assert!(
+        bridge_hits > num_samples * 3 / 4,
+        "Only {bridge_hits} of {num_samples} bridge samples were in the corridor"
+    );
+    assert!(
+        uniform_hits < num_samples / 10,
+        "{uniform_hits} of {num_samples} uniform samples were in the corridor"
+    );
+}
+
+#[test]
+fn test_bridge_sampler_times_out_without_narrow_passages() {
+    let mut sampler = BridgeTestSampler::new(space(), Arc::new(AlwaysValid), 1.0);
+    sampler.max_attempts = 50;
+    let mut rng = StdRng::seed_from_u64(0);
+
+    assert_eq!(
+        sampler.sample(&mut rng),
+        Err(StateSamplingError::GoalSamplingTimeout { attempts: 50 })
+    );
+}
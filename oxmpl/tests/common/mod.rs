@@ -0,0 +1,94 @@
+//! Fixtures shared by the integration tests.
+//!
+//! Each test binary compiles this module separately and uses only some of it.
+#![allow(dead_code)]
+
+use std::f64::consts::PI;
+
+use oxmpl::base::{
+    error::StateSamplingError,
+    goal::{Goal, GoalRegion, GoalSampleableRegion},
+    state::{RealVectorState, SE2State, State},
+    validity::StateValidityChecker,
+};
+use rand::Rng;
+
+/// A StateValidityChecker for an open space without obstacles.
+pub struct AlwaysValid;
+
+impl<S: State> StateValidityChecker<S> for AlwaysValid {
+    fn is_valid(&self, _state: &S) -> bool {
+        true
+    }
+}
+
+/// A StateValidityChecker that defines a simple vertical wall obstacle.
+pub struct WallObstacleChecker {
+    pub wall_x_pos: f64,
+    pub wall_y_min: f64,
+    pub wall_y_max: f64,
+    pub wall_thickness: f64,
+}
+
+impl StateValidityChecker<RealVectorState> for WallObstacleChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let x = state.values[0];
+        let y = state.values[1];
+
+        let is_in_wall = x >= self.wall_x_pos - self.wall_thickness / 2.0
+            && x <= self.wall_x_pos + self.wall_thickness / 2.0
+            && y >= self.wall_y_min
+            && y <= self.wall_y_max;
+
+        !is_in_wall
+    }
+}
+
+/// A square obstacle in the middle of a 10x10 room. Dubins paths can swing outside the bounds of
+/// the space, so the walls of the room are obstacles too.
+pub struct RoomChecker;
+
+impl StateValidityChecker<SE2State> for RoomChecker {
+    fn is_valid(&self, state: &SE2State) -> bool {
+        let [x, y] = state.translation;
+        let in_room = (0.0..=10.0).contains(&x) && (0.0..=10.0).contains(&y);
+        let in_obstacle = (4.0..=6.0).contains(&x) && (4.0..=6.0).contains(&y);
+        in_room && !in_obstacle
+    }
+}
+
+/// A goal reached once the car is within `radius` of `target`, with any heading.
+pub struct PositionGoal {
+    pub target: [f64; 2],
+    pub radius: f64,
+}
+
+impl PositionGoal {
+    fn centre_distance(&self, state: &SE2State) -> f64 {
+        (state.translation[0] - self.target[0]).hypot(state.translation[1] - self.target[1])
+    }
+}
+
+impl Goal<SE2State> for PositionGoal {
+    fn is_satisfied(&self, state: &SE2State) -> bool {
+        self.centre_distance(state) <= self.radius
+    }
+}
+
+impl GoalRegion<SE2State> for PositionGoal {
+    fn distance_goal(&self, state: &SE2State) -> f64 {
+        (self.centre_distance(state) - self.radius).max(0.0)
+    }
+}
+
+impl GoalSampleableRegion<SE2State> for PositionGoal {
+    fn sample_goal(&self, rng: &mut impl Rng) -> Result<SE2State, StateSamplingError> {
+        let angle = rng.random_range(-PI..PI);
+        let radius = self.radius * rng.random::<f64>().sqrt();
+        Ok(SE2State::new(
+            self.target[0] + radius * angle.cos(),
+            self.target[1] + radius * angle.sin(),
+            rng.random_range(-PI..PI),
+        ))
+    }
+}
@@ -0,0 +1,80 @@
+use std::{sync::Arc, time::Duration};
+
+use oxmpl::base::{
+    error::PlanningError,
+    goal::{Goal, GoalState},
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+};
+use oxmpl::geometric::{FMTStar, RRT};
+use oxmpl::testing::assert_path_cost_within;
+
+mod common;
+use common::AlwaysValid;
+
+#[test]
+fn test_fmt_star_path_is_near_optimal_and_no_longer_than_rrt_in_open_space() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+
+    let goal_definition =
+        Arc::new(GoalState::new(space.clone(), RealVectorState::new(vec![9.0, 9.0]), 0.5).unwrap());
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 1.0])],
+        goal_definition.clone(),
+    ));
+    let validity_checker = Arc::new(AlwaysValid);
+
+    // The straight line from the start to the edge of the goal region.
+    let optimal_cost = 128.0_f64.sqrt() - 0.5;
+
+    for seed in 0..5 {
+        let mut rrt = RRT::new(0.5, 0.05);
+        rrt.set_seed(seed);
+        rrt.setup(problem_definition.clone(), validity_checker.clone());
+        let rrt_path = rrt
+            .solve_iterations(5_000)
+            .expect("RRT failed to find a solution in open space");
+
+        let mut fmt_star = FMTStar::new(2_000, 1.1);
+        fmt_star.set_seed(seed);
+        fmt_star.setup(problem_definition.clone(), validity_checker.clone());
+        let fmt_star_path = fmt_star
+            .solve(Duration::from_secs(10))
+            .expect("FMT* failed to find a solution in open space");
+
+        assert!(goal_definition.is_satisfied(fmt_star_path.0.last().unwrap()));
+        assert_path_cost_within(&fmt_star_path, &rrt_path, &*space, 1.0);
+        let cost = fmt_star_path.length(&*space);
+        assert!(
+            cost <= optimal_cost * 1.05,
+            "FMT* path cost {cost} is not within 5% of the optimal {optimal_cost}"
+        );
+    }
+}
+
+#[test]
+fn test_fmt_star_rejects_problem_without_start_states() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![],
+        Arc::new(GoalState::new(space, RealVectorState::new(vec![9.0, 9.0]), 0.5).unwrap()),
+    ));
+
+    let mut fmt_star = FMTStar::new(200, 1.1);
+    fmt_star.set_seed(0);
+    fmt_star.setup(problem_definition, Arc::new(AlwaysValid));
+    assert!(matches!(
+        fmt_star.solve(Duration::from_secs(1)),
+        Err(PlanningError::InvalidProblemDefinition(_))
+    ));
+}
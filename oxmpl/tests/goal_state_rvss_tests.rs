@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    error::StateSpaceError,
+    goal::{Goal, GoalRegion, GoalSampleableRegion, GoalState},
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{path_utils::validate_path, RRT};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+/// A StateValidityChecker with a wall across the middle of the space, leaving a gap at the top.
+struct WallChecker;
+
+impl StateValidityChecker<RealVectorState> for WallChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let (x, y) = (state.values[0], state.values[1]);
+        !((4.5..=5.5).contains(&x) && y < 8.0)
+    }
+}
+
+fn space() -> Arc<RealVectorStateSpace> {
+    Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap())
+}
+
+#[test]
+fn test_goal_state_rejects_invalid_threshold() {
+    let target = RealVectorState::new(vec![5.0, 5.0]);
+    for threshold in [-0.1, f64::NAN, f64::INFINITY] {
+        assert!(matches!(
+            GoalState::new(space(), target.clone(), threshold),
+            Err(StateSpaceError::InvalidThreshold { .. })
+        ));
+    }
+    assert_eq!(
+        GoalState::new(space(), target, -0.1).err(),
+        Some(StateSpaceError::InvalidThreshold { threshold: -0.1 })
+    );
+}
+
+#[test]
+fn test_goal_state_samples_within_threshold() {
+    let target = RealVectorState::new(vec![9.0, 1.0]);
+    let goal = GoalState::new(space(), target.clone(), 0.5).unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..100 {
+        let sample = goal.sample_goal(&mut rng).unwrap();
+        assert!(goal.is_satisfied(&sample), "{sample:?}");
+        assert_eq!(goal.distance_goal(&sample), 0.0);
+    }
+
+    // With no threshold, the target is the only goal state.
+    let point_goal = GoalState::new(space(), target.clone(), 0.0).unwrap();
+    assert_eq!(point_goal.sample_goal(&mut rng).unwrap(), target);
+}
+
+#[test]
+fn test_goal_state_samples_uniformly_within_threshold() {
+    let goal = GoalState::new(space(), RealVectorState::new(vec![5.0, 5.0]), 1.0).unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+    let num_samples = 4_000;
+    let samples: Vec<RealVectorState> = (0..num_samples)
+        .map(|_| goal.sample_goal(&mut rng).unwrap())
+        .collect();
+
+    // A quarter of the area of the disc lies within half its radius.
+    let inner = samples
+        .iter()
+        .filter(|s| {
+            goal.distance_goal(s) == 0.0 && (s.values[0] - 5.0).hypot(s.values[1] - 5.0) < 0.5
+        })
+        .count();
+    let inner_fraction = inner as f64 / num_samples as f64;
+    assert!(
+        (0.2..0.3).contains(&inner_fraction),
+        "{inner_fraction} of the samples lie within half the threshold"
+    );
+
+    // Next to a bound, samples beyond it are clamped onto it rather than skewed away from it.
+    let edge_goal = GoalState::new(space(), RealVectorState::new(vec![0.2, 5.0]), 1.0).unwrap();
+    let left = (0..num_samples)
+        .map(|_| edge_goal.sample_goal(&mut rng).unwrap())
+        .inspect(|s| assert!(edge_goal.is_satisfied(s) && s.values[0] >= 0.0))
+        .filter(|s| s.values[0] < 0.2)
+        .count();
+    let left_fraction = left as f64 / num_samples as f64;
+    assert!(
+        (0.45..0.55).contains(&left_fraction),
+        "{left_fraction} of the samples lie towards the bound"
+    );
+}
+
+#[test]
+fn test_rrt_reaches_goal_state() {
+    let space = space();
+    let goal =
+        Arc::new(GoalState::new(space.clone(), RealVectorState::new(vec![9.0, 1.0]), 0.5).unwrap());
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 1.0])],
+        goal.clone(),
+    ));
+
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(0);
+    planner.setup(problem_definition.clone(), Arc::new(WallChecker));
+    let path = planner
+        .solve_iterations(10_000)
+        .expect("RRT failed to reach the goal state");
+
+    assert_eq!(path.0.first(), problem_definition.start_states.first());
+    assert!(goal.is_satisfied(path.0.last().unwrap()));
+    assert!(validate_path(&path, &*space, &WallChecker).is_ok());
+}
@@ -0,0 +1,84 @@
+use std::{f64::consts::PI, sync::Arc, time::Duration};
+
+use oxmpl::base::{
+    goal::{Goal, GoalRegion, GoalSampleableRegion, GoalState},
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::SO2StateSpace,
+    state::SO2State,
+};
+use oxmpl::geometric::RRT;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+mod common;
+use common::AlwaysValid;
+
+/// A goal straddling the seam at `+PI`/`-PI`.
+fn seam_goal() -> GoalState<SO2State, SO2StateSpace> {
+    let space = Arc::new(SO2StateSpace::new(None).unwrap());
+    GoalState::new(space, SO2State::new(PI - 0.05), 0.1).unwrap()
+}
+
+#[test]
+fn test_goal_state_samples_near_seam_are_normalised_and_satisfy_goal() {
+    let goal = seam_goal();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let samples: Vec<SO2State> = (0..1000)
+        .map(|_| goal.sample_goal(&mut rng).unwrap())
+        .collect();
+
+    for sample in &samples {
+        assert!((-PI..PI).contains(&sample.value), "{sample:?}");
+        assert!(goal.is_satisfied(sample), "{sample:?}");
+    }
+    // The goal covers both sides of the seam, so samples wrap round to just above -PI.
+    assert!(samples.iter().any(|sample| sample.value > 0.0));
+    assert!(samples.iter().any(|sample| sample.value < 0.0));
+    // Samples are jittered rather than all at the target.
+    assert!(samples.iter().any(|sample| sample != goal.target()));
+}
+
+#[test]
+fn test_goal_state_distance_uses_shortest_arc() {
+    let goal = seam_goal();
+
+    // Inside the goal, on either side of the seam.
+    assert_eq!(goal.distance_goal(&SO2State::new(PI - 0.1)), 0.0);
+    assert_eq!(goal.distance_goal(&SO2State::new(-PI + 0.04)), 0.0);
+
+    // Just across the seam is much closer than the long way round.
+    assert!((goal.distance_goal(&SO2State::new(-PI + 0.25)) - 0.2).abs() < 1e-9);
+    assert!((goal.distance_goal(&SO2State::new(0.0)) - (PI - 0.15)).abs() < 1e-9);
+    assert!(!goal.is_satisfied(&SO2State::new(-PI + 0.25)));
+}
+
+#[test]
+fn test_goal_state_accepts_unnormalised_target() {
+    let space = Arc::new(SO2StateSpace::new(None).unwrap());
+    let goal = GoalState::new(space, SO2State { value: 3.0 * PI }, 0.1).unwrap();
+
+    assert!(goal.is_satisfied(&SO2State::new(PI - 0.05)));
+    assert!(goal.is_satisfied(&SO2State::new(-PI + 0.05)));
+}
+
+#[test]
+fn test_rrt_reaches_goal_state_across_seam() {
+    let space = Arc::new(SO2StateSpace::new(None).unwrap());
+    let goal = Arc::new(seam_goal());
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space,
+        vec![SO2State::new(-PI / 2.0)],
+        goal.clone(),
+    ));
+
+    let mut planner = RRT::new(0.3, 0.1);
+    planner.set_seed(0);
+    planner.setup(problem_definition, Arc::new(AlwaysValid));
+    let path = planner
+        .solve(Duration::from_secs(5))
+        .expect("RRT should reach the goal");
+
+    assert!(goal.is_satisfied(path.0.last().unwrap()));
+}
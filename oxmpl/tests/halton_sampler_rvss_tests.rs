@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    error::StateSamplingError,
+    sampler::{HaltonSampler, StateSampler},
+    space::RealVectorStateSpace,
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn test_halton_samples_hit_every_grid_cell() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(-4.0, 4.0), (0.0, 18.0)])).unwrap());
+    let sampler = HaltonSampler::new(space);
+    let mut rng = StdRng::seed_from_u64(0);
+
+    // An 8 x 9 grid of cells matches the bases 2 and 3 of the two dimensions, so the first 72
+    // points hit every cell exactly once. Uniform random sampling would leave about 26 cells
+    // empty on average.
+    let mut hits = [[0; 8]; 9];
+    for _ in 0..72 {
+        let state = sampler.sample(&mut rng).unwrap();
+        // Points can fall on the lower edge of a cell, so allow for rounding below it.
+        let col = (state.values[0] + 4.0 + 1e-9) as usize;
+        let row = (state.values[1] / 2.0 + 1e-9) as usize;
+        hits[row][col] += 1;
+    }
+
+    for (row, cells) in hits.iter().enumerate() {
+        for (col, &count) in cells.iter().enumerate() {
+            assert_eq!(count, 1, "Cell ({row}, {col}) was sampled {count} times");
+        }
+    }
+}
+
+#[test]
+fn test_halton_sampler_rejects_unbounded_space() {
+    let space = Arc::new(RealVectorStateSpace::new(2, None).unwrap());
+    let sampler = HaltonSampler::new(space);
+    let mut rng = StdRng::seed_from_u64(0);
+
+    assert_eq!(
+        sampler.sample(&mut rng),
+        Err(StateSamplingError::UnboundedDimension { dimension_index: 0 })
+    );
+}
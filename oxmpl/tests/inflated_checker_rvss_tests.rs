@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+    validity::{InflatedChecker, StateValidityChecker},
+};
+
+mod common;
+use common::WallObstacleChecker;
+
+#[test]
+fn test_inflated_checker_rejects_states_near_the_wall() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let wall = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+    let inflated = InflatedChecker::new(space, wall.clone(), 1.0, 32);
+
+    // Within the inflation radius of the wall's faces, ends and corners, but outside the wall.
+    for near in [
+        [4.5, 5.0],
+        [5.5, 5.0],
+        [4.3, 3.0],
+        [5.0, 8.5],
+        [5.0, 1.6],
+        [4.5, 8.3],
+    ] {
+        let state = RealVectorState::new(near.to_vec());
+        assert!(wall.is_valid(&state));
+        assert!(
+            !inflated.is_valid(&state),
+            "{near:?} is within the inflation radius of the wall"
+        );
+    }
+
+    // Well clear of the wall, or inside it.
+    for far in [[2.0, 5.0], [8.0, 5.0], [5.0, 9.5], [5.0, 0.5]] {
+        assert!(inflated.is_valid(&RealVectorState::new(far.to_vec())));
+    }
+    assert!(!inflated.is_valid(&RealVectorState::new(vec![5.0, 5.0])));
+}
+
+/// A StateValidityChecker whose only obstacle is the strip `x > 9.8` along the right bound.
+struct RightEdgeChecker;
+
+impl StateValidityChecker<RealVectorState> for RightEdgeChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        state.values[0] <= 9.8
+    }
+}
+
+#[test]
+fn test_inflated_checker_probes_towards_the_bounds() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let inflated = InflatedChecker::new(space, Arc::new(RightEdgeChecker), 1.0, 8);
+
+    // The probe towards the right bound is clamped to it, inside the obstacle.
+    assert!(!inflated.is_valid(&RealVectorState::new(vec![9.5, 5.0])));
+    assert!(!inflated.is_valid(&RealVectorState::new(vec![9.5, 9.9])));
+    assert!(inflated.is_valid(&RealVectorState::new(vec![8.5, 5.0])));
+}
@@ -0,0 +1,140 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use log::{
+    kv::{Key, Value, VisitSource},
+    Level, Log, Metadata, Record,
+};
+
+use oxmpl::base::{
+    error::StateSamplingError,
+    goal::{Goal, GoalRegion, GoalSampleableRegion},
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+};
+use oxmpl::geometric::RRT;
+
+use rand::Rng;
+
+mod common;
+use common::AlwaysValid;
+
+/// A single captured log record, with its key-values rendered as strings.
+struct Event {
+    level: Level,
+    target: String,
+    message: String,
+    fields: HashMap<String, String>,
+}
+
+/// A logger that stores every record it receives.
+struct CapturingLogger {
+    events: Mutex<Vec<Event>>,
+}
+
+struct FieldCollector<'a>(&'a mut HashMap<String, String>);
+
+impl<'kvs> VisitSource<'kvs> for FieldCollector<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), log::kv::Error> {
+        self.0.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let mut fields = HashMap::new();
+        record
+            .key_values()
+            .visit(&mut FieldCollector(&mut fields))
+            .unwrap();
+        self.events.lock().unwrap().push(Event {
+            level: record.level(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+            fields,
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    events: Mutex::new(Vec::new()),
+};
+
+/// A goal satisfied by any state with `x` of at least 9.
+struct RightEdgeGoal;
+
+impl Goal<RealVectorState> for RightEdgeGoal {
+    fn is_satisfied(&self, state: &RealVectorState) -> bool {
+        state.values[0] >= 9.0
+    }
+}
+
+impl GoalRegion<RealVectorState> for RightEdgeGoal {
+    fn distance_goal(&self, state: &RealVectorState) -> f64 {
+        (9.0 - state.values[0]).max(0.0)
+    }
+}
+
+impl GoalSampleableRegion<RealVectorState> for RightEdgeGoal {
+    fn sample_goal(&self, rng: &mut impl Rng) -> Result<RealVectorState, StateSamplingError> {
+        Ok(RealVectorState {
+            values: vec![rng.random_range(9.0..10.0), rng.random_range(0.0..10.0)],
+        })
+    }
+}
+
+#[test]
+fn test_rrt_emits_solution_found_event() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space,
+        vec![RealVectorState {
+            values: vec![1.0, 5.0],
+        }],
+        Arc::new(RightEdgeGoal),
+    ));
+
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(1);
+    planner.setup(problem_definition, Arc::new(AlwaysValid));
+    planner
+        .solve_iterations(10_000)
+        .expect("Planner failed to find a solution");
+
+    let events = LOGGER.events.lock().unwrap();
+    let solution_events: Vec<_> = events
+        .iter()
+        .filter(|event| event.message.contains("solution found"))
+        .collect();
+    assert_eq!(
+        solution_events.len(),
+        1,
+        "Expected exactly one solution found event"
+    );
+    let solution_event = solution_events[0];
+
+    assert_eq!(solution_event.level, Level::Info);
+    assert_eq!(solution_event.target, "oxmpl");
+    assert_eq!(solution_event.fields["planner"], "RRT");
+    assert_eq!(
+        solution_event.fields["nodes"],
+        planner.tree_size().to_string()
+    );
+}
@@ -0,0 +1,302 @@
+use std::{
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use oxmpl::base::{
+    error::PlanningError,
+    goal::GoalState,
+    motion_validator::{BisectionMotionValidator, DiscreteMotionValidator, MotionValidator},
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::{RealVectorStateSpace, StateSpace},
+    state::RealVectorState,
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{path_utils::validate_path, RRTConnect, RRT};
+
+mod common;
+use common::WallObstacleChecker;
+
+/// Wraps a wall checker and counts how many states it has checked.
+struct CountingWallChecker {
+    checked: AtomicUsize,
+    wall: WallObstacleChecker,
+}
+
+impl StateValidityChecker<RealVectorState> for CountingWallChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        self.checked.fetch_add(1, Ordering::Relaxed);
+        self.wall.is_valid(state)
+    }
+}
+
+/// A motion validator that discretises motions itself, recording how many sub-states it checked.
+struct RecordingMotionValidator {
+    space: Arc<RealVectorStateSpace>,
+    checker: Arc<CountingWallChecker>,
+    substates_checked: AtomicUsize,
+}
+
+impl MotionValidator<RealVectorState> for RecordingMotionValidator {
+    fn check_motion(&self, from: &RealVectorState, to: &RealVectorState) -> bool {
+        let num_steps = self
+            .space
+            .segments_for_distance(self.space.distance(from, to))
+            .max(1);
+        let mut state = from.clone();
+        for i in 1..=num_steps {
+            self.space
+                .interpolate(from, to, i as f64 / num_steps as f64, &mut state);
+            self.substates_checked.fetch_add(1, Ordering::Relaxed);
+            if !self.checker.is_valid(&state) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Rejects every motion.
+struct RejectAllMotions;
+
+impl MotionValidator<RealVectorState> for RejectAllMotions {
+    fn check_motion(&self, _from: &RealVectorState, _to: &RealVectorState) -> bool {
+        false
+    }
+}
+
+fn wall_checker() -> Arc<CountingWallChecker> {
+    Arc::new(CountingWallChecker {
+        checked: AtomicUsize::new(0),
+        wall: WallObstacleChecker {
+            wall_x_pos: 5.0,
+            wall_y_min: 2.0,
+            wall_y_max: 8.0,
+            wall_thickness: 0.5,
+        },
+    })
+}
+
+type WallProblem = ProblemDefinition<
+    RealVectorState,
+    RealVectorStateSpace,
+    GoalState<RealVectorState, RealVectorStateSpace>,
+>;
+
+fn problem() -> (Arc<RealVectorStateSpace>, Arc<WallProblem>) {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let goal =
+        Arc::new(GoalState::new(space.clone(), RealVectorState::new(vec![9.0, 5.0]), 0.5).unwrap());
+    let problem_def = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        goal,
+    ));
+    (space, problem_def)
+}
+
+#[test]
+fn test_custom_motion_validator_checks_the_same_substates_as_the_default() {
+    let (space, problem_def) = problem();
+
+    let default_checker = wall_checker();
+    let mut default_rrt = RRT::new(0.5, 0.05);
+    default_rrt.set_seed(7);
+    default_rrt.setup(problem_def.clone(), default_checker.clone());
+    let default_path = default_rrt
+        .solve_iterations(10_000)
+        .expect("RRT with the default motion validator failed to find a solution");
+
+    let custom_checker = wall_checker();
+    let validator = Arc::new(RecordingMotionValidator {
+        space: space.clone(),
+        checker: custom_checker.clone(),
+        substates_checked: AtomicUsize::new(0),
+    });
+    let mut custom_rrt = RRT::new(0.5, 0.05);
+    custom_rrt.set_seed(7);
+    custom_rrt.set_motion_validator(validator.clone());
+    custom_rrt.setup(problem_def, custom_checker.clone());
+    let custom_path = custom_rrt
+        .solve_iterations(10_000)
+        .expect("RRT with a custom motion validator failed to find a solution");
+
+    // The recording validator discretises motions exactly like the default one, so the planner
+    // grows the same tree and checks the same states. Besides the motions, each planner checks
+    // its start state once before planning.
+    assert_eq!(custom_path.0, default_path.0);
+    let substates_checked = validator.substates_checked.load(Ordering::Relaxed);
+    assert!(substates_checked > 0);
+    assert_eq!(
+        substates_checked + 1,
+        default_checker.checked.load(Ordering::Relaxed)
+    );
+    assert_eq!(
+        substates_checked + 1,
+        custom_checker.checked.load(Ordering::Relaxed)
+    );
+}
+
+#[test]
+fn test_planners_use_the_custom_motion_validator() {
+    let (_, problem_def) = problem();
+
+    let mut rrt = RRT::new(0.5, 0.05);
+    rrt.set_seed(0);
+    rrt.set_motion_validator(Arc::new(RejectAllMotions));
+    rrt.setup(problem_def.clone(), wall_checker());
+    assert_eq!(
+        rrt.solve_iterations(1_000).err(),
+        Some(PlanningError::NoSolutionFound)
+    );
+    assert_eq!(rrt.tree_size(), 1);
+
+    let mut rrt_connect = RRTConnect::new(0.5, 0.05);
+    rrt_connect.set_seed(0);
+    rrt_connect.set_motion_validator(Arc::new(RejectAllMotions));
+    rrt_connect.setup(problem_def, wall_checker());
+    assert_eq!(
+        rrt_connect.solve_iterations(1_000).err(),
+        Some(PlanningError::NoSolutionFound)
+    );
+}
+
+/// A StateValidityChecker that records the x coordinate of each state it checks, rejecting those
+/// within `invalid_x` and accepting every other state.
+#[derive(Default)]
+struct RecordingChecker {
+    xs: Mutex<Vec<f64>>,
+    invalid_x: Option<RangeInclusive<f64>>,
+}
+
+impl RecordingChecker {
+    fn with_invalid_x(invalid_x: RangeInclusive<f64>) -> Self {
+        Self {
+            xs: Mutex::default(),
+            invalid_x: Some(invalid_x),
+        }
+    }
+}
+
+impl StateValidityChecker<RealVectorState> for RecordingChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let x = state.values[0];
+        self.xs.lock().unwrap().push(x);
+        !self
+            .invalid_x
+            .as_ref()
+            .is_some_and(|invalid_x| invalid_x.contains(&x))
+    }
+}
+
+/// `problem`, in a space whose longest valid segment is coarse, about 0.7.
+fn coarse_problem() -> (Arc<RealVectorStateSpace>, Arc<WallProblem>) {
+    let (space, problem_def) = problem();
+    let mut space = (*space).clone();
+    space.set_longest_valid_segment_fraction(0.05);
+    let space = Arc::new(space);
+    let problem_def = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        problem_def.start_states.clone(),
+        Arc::new(GoalState::new(space.clone(), RealVectorState::new(vec![9.0, 5.0]), 0.5).unwrap()),
+    ));
+    (space, problem_def)
+}
+
+/// A wall across the whole space, much thinner than the longest valid segment of
+/// `coarse_problem`'s space.
+fn thin_wall_checker() -> Arc<CountingWallChecker> {
+    Arc::new(CountingWallChecker {
+        checked: AtomicUsize::new(0),
+        wall: WallObstacleChecker {
+            wall_x_pos: 2.25,
+            wall_y_min: 0.0,
+            wall_y_max: 10.0,
+            wall_thickness: 0.1,
+        },
+    })
+}
+
+#[test]
+fn test_bisection_validator_finds_thin_walls_in_fewer_checks_at_the_same_resolution() {
+    let (space, _) = problem();
+    let mut space = (*space).clone();
+    space.set_longest_valid_segment_fraction(0.05 / space.get_maximum_extent());
+    let space = Arc::new(space);
+    let resolution = space.get_longest_valid_segment_length();
+    let from = RealVectorState::new(vec![0.0, 5.0]);
+    let to = RealVectorState::new(vec![4.0, 5.0]);
+
+    let discrete_checker = Arc::new(RecordingChecker::with_invalid_x(2.21..=2.31));
+    let discrete = DiscreteMotionValidator::new(space.clone(), discrete_checker.clone());
+    assert!(!discrete.check_motion(&from, &to));
+
+    let bisection_checker = Arc::new(RecordingChecker::with_invalid_x(2.21..=2.31));
+    let bisection = BisectionMotionValidator::new(space, bisection_checker.clone(), resolution);
+    assert!(!bisection.check_motion(&from, &to));
+
+    let discrete_xs = discrete_checker.xs.lock().unwrap().clone();
+    let bisection_xs = bisection_checker.xs.lock().unwrap().clone();
+    // Both stop at the same state, 2.25, at `t = 0.5625` along the motion. The discrete validator
+    // walks there from the start, while the bisection validator checks the end state and the
+    // first three levels of midpoints (2.0; 1.0, 3.0; 0.5, ..., 3.5) and then hits the wall with
+    // the fifth midpoint of the fourth level.
+    let first_invalid_t = |xs: &[f64]| xs.last().unwrap() / to.values[0];
+    assert!((first_invalid_t(&discrete_xs) - 0.5625).abs() < 1e-9);
+    assert!((first_invalid_t(&bisection_xs) - 0.5625).abs() < 1e-9);
+    assert_eq!(discrete_xs.len(), 45);
+    assert_eq!(bisection_xs.len(), 1 + 1 + 2 + 4 + 5);
+}
+
+#[test]
+fn test_bisection_validator_checks_valid_motions_at_its_resolution() {
+    let (space, _) = problem();
+    let checker = Arc::new(RecordingChecker::default());
+    let bisection = BisectionMotionValidator::new(space, checker.clone(), 0.3);
+
+    let from = RealVectorState::new(vec![1.0, 5.0]);
+    let to = RealVectorState::new(vec![4.0, 5.0]);
+    assert!(bisection.check_motion(&from, &to));
+
+    let mut xs = checker.xs.lock().unwrap().clone();
+    // The end state is checked first, then the midpoint.
+    assert_eq!(&xs[..2], &[4.0, 2.5]);
+    xs.push(from.values[0]);
+    xs.sort_by(f64::total_cmp);
+    xs.dedup();
+    assert!(xs.windows(2).all(|pair| pair[1] - pair[0] <= 0.3));
+}
+
+#[test]
+// Planners are single-threaded, so the validator need not be `Send` or `Sync`.
+#[allow(clippy::arc_with_non_send_sync)]
+fn test_rrt_with_bisection_validator_does_not_pass_through_thin_walls() {
+    let (space, problem_def) = coarse_problem();
+    let checker = thin_wall_checker();
+
+    let mut rrt = RRT::new(0.5, 0.05);
+    rrt.set_seed(0);
+    rrt.set_motion_validator(Arc::new(BisectionMotionValidator::new(
+        space.clone(),
+        checker.clone(),
+        0.05,
+    )));
+    rrt.setup(problem_def, checker.clone());
+    assert!(rrt.solve_iterations(2_000).is_err());
+
+    // With the default validator, the tree steps over the wall and reaches the goal.
+    let (_, problem_def) = coarse_problem();
+    let mut rrt = RRT::new(0.5, 0.05);
+    rrt.set_seed(0);
+    rrt.setup(problem_def, checker.clone());
+    let path = rrt.solve_iterations(2_000).unwrap();
+    assert!(validate_path(&path, &*space, &*checker).is_ok());
+    assert!(path.0.iter().any(|state| state.values[0] > 2.3));
+}
@@ -0,0 +1,26 @@
+use nalgebra::{dvector, DVector};
+
+use oxmpl::base::{
+    space::{RealVectorStateSpace, StateSpace},
+    state::RealVectorState,
+};
+
+#[test]
+fn test_real_vector_state_round_trips_through_dvector() {
+    let vector = dvector![1.5, -2.25, 3.0];
+
+    let state = RealVectorState::from(vector.clone());
+    assert_eq!(state.values, vec![1.5, -2.25, 3.0]);
+
+    let restored: DVector<f64> = state.into();
+    assert_eq!(restored, vector);
+}
+
+#[test]
+fn test_dvector_states_can_be_used_with_a_space() {
+    let space = RealVectorStateSpace::new(2, None).unwrap();
+    let a: RealVectorState = dvector![0.0, 0.0].into();
+    let b: RealVectorState = dvector![3.0, 4.0].into();
+
+    assert_eq!(space.distance(&a, &b), 5.0);
+}
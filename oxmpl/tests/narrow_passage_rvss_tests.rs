@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    error::PlanningError,
+    goal::{Goal, GoalState},
+    planner::{Path, Planner},
+    problem_definition::ProblemDefinition,
+    projection::RealVectorProjection,
+    space::{RealVectorStateSpace, StateSpace},
+    state::RealVectorState,
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{RRTConnect, RRTStar, KPIECE, PRM, RRT};
+
+/// An axis-aligned rectangle of free space.
+struct Rect {
+    x_min: f64,
+    x_max: f64,
+    y_min: f64,
+    y_max: f64,
+}
+
+impl Rect {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x_min && x <= self.x_max && y >= self.y_min && y <= self.y_max
+    }
+}
+
+/// A world made of two free rooms joined by a thin corridor. Everything else is an obstacle.
+struct TwoRoomsWithGap {
+    left_room: Rect,
+    right_room: Rect,
+    corridor: Rect,
+}
+
+impl StateValidityChecker<RealVectorState> for TwoRoomsWithGap {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let x = state.values[0];
+        let y = state.values[1];
+
+        self.left_room.contains(x, y)
+            || self.right_room.contains(x, y)
+            || self.corridor.contains(x, y)
+    }
+}
+
+/// Builds a 10x10 world with a room on each side and a 0.6 wide, 2.0 long corridor between them.
+fn two_rooms_with_gap() -> TwoRoomsWithGap {
+    TwoRoomsWithGap {
+        left_room: Rect {
+            x_min: 0.0,
+            x_max: 4.0,
+            y_min: 0.0,
+            y_max: 10.0,
+        },
+        right_room: Rect {
+            x_min: 6.0,
+            x_max: 10.0,
+            y_min: 0.0,
+            y_max: 10.0,
+        },
+        corridor: Rect {
+            x_min: 4.0,
+            x_max: 6.0,
+            y_min: 4.7,
+            y_max: 5.3,
+        },
+    }
+}
+
+type NarrowPassageProblem = ProblemDefinition<
+    RealVectorState,
+    RealVectorStateSpace,
+    GoalState<RealVectorState, RealVectorStateSpace>,
+>;
+
+/// Sets up a problem from the middle of the left room to the middle of the right room.
+fn narrow_passage_problem() -> (
+    Arc<RealVectorStateSpace>,
+    Arc<NarrowPassageProblem>,
+    Arc<TwoRoomsWithGap>,
+) {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState {
+            values: vec![2.0, 2.0],
+        }],
+        Arc::new(
+            GoalState::new(
+                space.clone(),
+                RealVectorState {
+                    values: vec![8.0, 8.0],
+                },
+                0.5,
+            )
+            .unwrap(),
+        ),
+    ));
+
+    (space, problem_definition, Arc::new(two_rooms_with_gap()))
+}
+
+/// Checks that the path is collision-free, runs from the start to the goal and crosses the
+/// corridor.
+fn assert_path_through_corridor(
+    result: Result<Path<RealVectorState>, PlanningError>,
+    space: &RealVectorStateSpace,
+    problem_definition: &NarrowPassageProblem,
+    world: &TwoRoomsWithGap,
+) {
+    let path = match result {
+        Ok(path) => path,
+        Err(e) => panic!("Planner failed to find a path through the corridor: {e:?}"),
+    };
+
+    assert!(
+        space.distance(&path.0[0], &problem_definition.start_states[0]) < 1e-9,
+        "Path should start at the start state"
+    );
+    assert!(
+        problem_definition.goal.is_satisfied(path.0.last().unwrap()),
+        "Path should end in the goal region"
+    );
+
+    let mut crossed_corridor = false;
+    for pair in path.0.windows(2) {
+        let dist = space.distance(&pair[0], &pair[1]);
+        let num_steps = ((dist / space.get_longest_valid_segment_length()).ceil() as usize).max(1);
+        let mut interpolated_state = pair[0].clone();
+        for j in 0..=num_steps {
+            let t = j as f64 / num_steps as f64;
+            space.interpolate(&pair[0], &pair[1], t, &mut interpolated_state);
+            assert!(
+                world.is_valid(&interpolated_state),
+                "Path is in collision at {interpolated_state:?}"
+            );
+            let (x, y) = (interpolated_state.values[0], interpolated_state.values[1]);
+            if x > world.left_room.x_max && x < world.right_room.x_min {
+                assert!(world.corridor.contains(x, y));
+                crossed_corridor = true;
+            }
+        }
+    }
+    assert!(crossed_corridor, "Path should pass through the corridor");
+}
+
+#[test]
+fn test_rrt_finds_path_through_narrow_passage() {
+    let (space, problem_definition, world) = narrow_passage_problem();
+
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(1);
+    planner.setup(problem_definition.clone(), world.clone());
+
+    let result = planner.solve_iterations(20_000);
+    assert_path_through_corridor(result, &space, &problem_definition, &world);
+}
+
+#[test]
+fn test_rrt_connect_finds_path_through_narrow_passage() {
+    let (space, problem_definition, world) = narrow_passage_problem();
+
+    let mut planner = RRTConnect::new(0.5, 0.05);
+    planner.set_seed(1);
+    planner.setup(problem_definition.clone(), world.clone());
+
+    let result = planner.solve_iterations(20_000);
+    assert_path_through_corridor(result, &space, &problem_definition, &world);
+}
+
+#[test]
+fn test_rrt_star_finds_path_through_narrow_passage() {
+    let (space, problem_definition, world) = narrow_passage_problem();
+
+    let mut planner = RRTStar::new(0.5, 0.05, 1.0);
+    planner.set_seed(1);
+    planner.setup(problem_definition.clone(), world.clone());
+
+    let result = planner.solve_iterations(5_000);
+    assert_path_through_corridor(result, &space, &problem_definition, &world);
+}
+
+#[test]
+fn test_prm_finds_path_through_narrow_passage() {
+    let (space, problem_definition, world) = narrow_passage_problem();
+
+    let mut planner = PRM::new(5.0, 1.0);
+    planner.set_seed(1);
+    planner.setup(problem_definition.clone(), world.clone());
+
+    let result = planner.solve_iterations(3_000);
+    assert_path_through_corridor(result, &space, &problem_definition, &world);
+}
+
+#[test]
+fn test_kpiece_finds_path_through_narrow_passage() {
+    let (space, problem_definition, world) = narrow_passage_problem();
+
+    let mut planner = KPIECE::new(0.5, 0.05, Arc::new(RealVectorProjection::new(2)));
+    planner.set_seed(1);
+    planner.setup(problem_definition.clone(), world.clone());
+
+    let result = planner.solve_iterations(20_000);
+    assert_path_through_corridor(result, &space, &problem_definition, &world);
+}
+
+#[test]
+fn test_kpiece_expands_fewer_nodes_than_rrt_through_narrow_passage() {
+    // Compare the tree sizes at the first solution, summed over several seeds, since either
+    // planner can get lucky on a single run.
+    let (_, problem_definition, world) = narrow_passage_problem();
+    let mut rrt_nodes = 0;
+    let mut kpiece_nodes = 0;
+    for seed in 0..10 {
+        let mut rrt = RRT::new(0.5, 0.05);
+        rrt.set_seed(seed);
+        rrt.setup(problem_definition.clone(), world.clone());
+        rrt.solve_iterations(20_000)
+            .expect("RRT failed to find a path through the corridor");
+        rrt_nodes += rrt.tree_size();
+
+        let mut kpiece = KPIECE::new(0.5, 0.05, Arc::new(RealVectorProjection::new(2)));
+        kpiece.set_seed(seed);
+        kpiece.setup(problem_definition.clone(), world.clone());
+        kpiece
+            .solve_iterations(20_000)
+            .expect("KPIECE failed to find a path through the corridor");
+        kpiece_nodes += kpiece.tree_size();
+    }
+
+    assert!(
+        kpiece_nodes < rrt_nodes,
+        "KPIECE expanded {kpiece_nodes} nodes, RRT {rrt_nodes}"
+    );
+}
@@ -0,0 +1,26 @@
+use ndarray::{array, Array1};
+
+use oxmpl::base::{
+    space::{RealVectorStateSpace, StateSpace},
+    state::RealVectorState,
+};
+
+#[test]
+fn test_real_vector_state_round_trips_through_array1() {
+    let array = array![1.5, -2.25, 3.0];
+
+    let state = RealVectorState::from(array.clone());
+    assert_eq!(state.values, vec![1.5, -2.25, 3.0]);
+
+    let restored: Array1<f64> = state.into();
+    assert_eq!(restored, array);
+}
+
+#[test]
+fn test_array1_states_can_be_used_with_a_space() {
+    let space = RealVectorStateSpace::new(2, None).unwrap();
+    let a: RealVectorState = array![0.0, 0.0].into();
+    let b: RealVectorState = array![3.0, 4.0].into();
+
+    assert_eq!(space.distance(&a, &b), 5.0);
+}
@@ -0,0 +1,141 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use oxmpl::base::{
+    error::StateSamplingError,
+    goal::GoalState,
+    nearest_neighbors::{KdTreeNN, NearestNeighbors},
+    planner::{Path, Planner},
+    problem_definition::ProblemDefinition,
+    space::{RealVectorStateSpace, StateSpace},
+    state::RealVectorState,
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::RRT;
+
+use rand::Rng;
+
+/// A `RealVectorStateSpace` that counts how many times `distance` is called.
+struct CountingSpace {
+    inner: RealVectorStateSpace,
+    distance_calls: AtomicUsize,
+}
+
+impl CountingSpace {
+    fn distance_calls(&self) -> usize {
+        self.distance_calls.load(Ordering::Relaxed)
+    }
+}
+
+impl StateSpace for CountingSpace {
+    type StateType = RealVectorState;
+
+    fn distance(&self, state1: &RealVectorState, state2: &RealVectorState) -> f64 {
+        self.distance_calls.fetch_add(1, Ordering::Relaxed);
+        self.inner.distance(state1, state2)
+    }
+
+    fn interpolate(
+        &self,
+        from: &RealVectorState,
+        to: &RealVectorState,
+        t: f64,
+        state: &mut RealVectorState,
+    ) {
+        self.inner.interpolate(from, to, t, state);
+    }
+
+    fn enforce_bounds(&self, state: &mut RealVectorState) {
+        self.inner.enforce_bounds(state);
+    }
+
+    fn satisfies_bounds(&self, state: &RealVectorState) -> bool {
+        self.inner.satisfies_bounds(state)
+    }
+
+    fn sample_uniform(&self, rng: &mut impl Rng) -> Result<RealVectorState, StateSamplingError> {
+        self.inner.sample_uniform(rng)
+    }
+
+    fn get_longest_valid_segment_length(&self) -> f64 {
+        self.inner.get_longest_valid_segment_length()
+    }
+
+    fn get_maximum_extent(&self) -> f64 {
+        self.inner.get_maximum_extent()
+    }
+}
+
+/// A 10x10 maze with two staggered walls, forcing an S-shaped path from the bottom-left corner to
+/// the top-right corner.
+struct MazeChecker;
+
+impl StateValidityChecker<RealVectorState> for MazeChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let x = state.values[0];
+        let y = state.values[1];
+
+        let in_first_wall = (3.0..=3.5).contains(&x) && y <= 8.0;
+        let in_second_wall = (6.5..=7.0).contains(&x) && y >= 2.0;
+
+        !in_first_wall && !in_second_wall
+    }
+}
+
+/// Solves the maze with a seeded RRT and returns the path together with the number of distance
+/// calls made while planning.
+fn solve_maze(use_kd_tree: bool) -> (Path<RealVectorState>, usize) {
+    let space = Arc::new(CountingSpace {
+        inner: RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+        distance_calls: AtomicUsize::new(0),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState {
+            values: vec![1.0, 1.0],
+        }],
+        Arc::new(
+            // The goal measures distances in a space of its own, so they are not counted.
+            GoalState::new(
+                Arc::new(space.inner.clone()),
+                RealVectorState {
+                    values: vec![9.0, 9.0],
+                },
+                0.5,
+            )
+            .unwrap(),
+        ),
+    ));
+
+    let mut planner = RRT::new(0.2, 0.05);
+    planner.set_seed(3);
+    if use_kd_tree {
+        planner.set_nearest_neighbors(Arc::new(|space| {
+            Box::new(KdTreeNN::new(space)) as Box<dyn NearestNeighbors<RealVectorState>>
+        }));
+    }
+    planner.setup(problem_definition, Arc::new(MazeChecker));
+
+    let path = planner
+        .solve_iterations(50_000)
+        .expect("Planner failed to solve the maze within 50000 iterations");
+    (path, space.distance_calls())
+}
+
+#[test]
+fn test_kd_tree_finds_same_path_with_fewer_distance_calls() {
+    let (linear_path, linear_calls) = solve_maze(false);
+    let (kd_path, kd_calls) = solve_maze(true);
+
+    assert_eq!(
+        linear_path.0, kd_path.0,
+        "The kd-tree should find exactly the same nearest nodes as the linear scan"
+    );
+    assert!(
+        kd_calls * 5 < linear_calls,
+        "Expected the kd-tree to need far fewer distance calls, got {kd_calls} vs {linear_calls}"
+    );
+}
@@ -0,0 +1,143 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use oxmpl::base::{
+    goal::{BoxGoalRegion, Goal},
+    planner::{self, Path, Planner},
+    problem_definition::ProblemDefinition,
+    space::{RealVectorStateSpace, StateSpace},
+    state::RealVectorState,
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{path_utils::validate_path, RRTConnect, RRTStar, RRT};
+
+mod common;
+use common::WallObstacleChecker;
+
+/// The Manhattan, Euclidean and Chebyshev norm orders.
+const NORM_ORDERS: [f64; 3] = [1.0, 2.0, f64::INFINITY];
+
+/// A StateValidityChecker that accepts every state and counts how many it was asked about.
+#[derive(Default)]
+struct CountingChecker {
+    checked: AtomicUsize,
+}
+
+impl StateValidityChecker<RealVectorState> for CountingChecker {
+    fn is_valid(&self, _state: &RealVectorState) -> bool {
+        self.checked.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+}
+
+fn space_with_norm_order(bounds: Vec<(f64, f64)>, p: f64) -> RealVectorStateSpace {
+    let mut space = RealVectorStateSpace::new(bounds.len(), Some(bounds)).unwrap();
+    space.set_norm_order(p).unwrap();
+    space.set_longest_valid_segment_fraction(0.1);
+    space
+}
+
+fn count_motion_checks(space: &RealVectorStateSpace, from: &[f64], to: &[f64]) -> usize {
+    let checker = CountingChecker::default();
+    let from = RealVectorState::new(from.to_vec());
+    let to = RealVectorState::new(to.to_vec());
+    assert!(planner::check_motion(space, &checker, &from, &to));
+    checker.checked.load(Ordering::Relaxed)
+}
+
+#[test]
+fn test_distances_follow_norm_order() {
+    let (a, b) = (
+        RealVectorState::new(vec![1.0, 2.0]),
+        RealVectorState::new(vec![4.0, 6.0]),
+    );
+    let expected = [7.0, 5.0, 4.0];
+
+    for (p, expected) in NORM_ORDERS.into_iter().zip(expected) {
+        let space = space_with_norm_order(vec![(0.0, 10.0), (0.0, 10.0)], p);
+        assert_eq!(space.distance(&a, &b), expected, "p = {p}");
+    }
+}
+
+#[test]
+fn test_motion_check_resolution_follows_norm_order() {
+    // The extent of the 3 x 4 box is 7, 5 and 4 under the three norms, so with a fraction of 0.1
+    // motions are checked every 0.7, 0.5 and 0.4 respectively.
+    let expected_extents = [7.0, 5.0, 4.0];
+    let expected_checks_along_x = [5, 6, 8];
+
+    for ((p, extent), checks) in NORM_ORDERS
+        .into_iter()
+        .zip(expected_extents)
+        .zip(expected_checks_along_x)
+    {
+        let space = space_with_norm_order(vec![(0.0, 3.0), (0.0, 4.0)], p);
+        assert_eq!(space.get_maximum_extent(), extent, "p = {p}");
+
+        // The diagonal is as long as the extent, so it is always split into 1 / 0.1 segments.
+        assert_eq!(count_motion_checks(&space, &[0.0, 0.0], &[3.0, 4.0]), 10);
+        // A motion of length 3 along x needs ceil(3 / segment length) checks.
+        assert_eq!(
+            count_motion_checks(&space, &[0.0, 0.0], &[3.0, 0.0]),
+            checks,
+            "p = {p}"
+        );
+    }
+}
+
+#[test]
+fn test_planners_find_valid_paths_under_each_norm_order() {
+    let wall = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+    let goal =
+        Arc::new(BoxGoalRegion::new(RealVectorState::new(vec![9.0, 5.0]), vec![0.5, 0.5]).unwrap());
+    let timeout = Duration::from_secs(5);
+
+    for p in NORM_ORDERS {
+        let space = Arc::new(space_with_norm_order(vec![(0.0, 10.0), (0.0, 10.0)], p));
+        let problem_definition = Arc::new(ProblemDefinition::new(
+            space.clone(),
+            vec![RealVectorState::new(vec![1.0, 5.0])],
+            goal.clone(),
+        ));
+
+        let mut rrt = RRT::new(0.5, 0.05);
+        rrt.set_seed(0);
+        rrt.setup(problem_definition.clone(), wall.clone());
+        let mut rrt_connect = RRTConnect::new(0.5, 0.05);
+        rrt_connect.set_seed(0);
+        rrt_connect.setup(problem_definition.clone(), wall.clone());
+        let mut rrt_star = RRTStar::new(0.5, 0.05, 1.0);
+        rrt_star.set_seed(0);
+        // Any valid path will do, so there is no need to refine it until the timeout.
+        rrt_star.set_anytime(false);
+        rrt_star.setup(problem_definition.clone(), wall.clone());
+
+        let paths: [(&str, Path<RealVectorState>); 3] = [
+            ("RRT", rrt.solve(timeout).unwrap()),
+            ("RRTConnect", rrt_connect.solve(timeout).unwrap()),
+            ("RRT*", rrt_star.solve(timeout).unwrap()),
+        ];
+        for (name, path) in paths {
+            assert_eq!(path.0.first(), problem_definition.start_states.first());
+            assert!(
+                goal.is_satisfied(path.0.last().unwrap()),
+                "{name} path under p = {p} must end in the goal"
+            );
+            assert_eq!(
+                validate_path(&path, &*space, &*wall),
+                Ok(()),
+                "{name} path under p = {p} must be valid"
+            );
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use oxmpl::base::{
+    error::PlanningError,
+    goal::{BoxGoalRegion, Goal},
+    motion_validator::MotionValidator,
+    planner::{check_motion, check_motion_parallel, solve_parallel, Planner},
+    problem_definition::ProblemDefinition,
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+    termination::TerminationCondition,
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{PRM, RRT};
+
+mod common;
+use common::{AlwaysValid, WallObstacleChecker};
+
+#[test]
+fn test_parallel_motion_check_agrees_with_sequential() {
+    let mut space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+    // A fine resolution, so that long motions are split into many sub-states.
+    space.set_longest_valid_segment_fraction(0.001);
+    let checker = WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    };
+
+    let motions = [
+        // Passes below the wall.
+        (vec![1.0, 1.0], vec![9.0, 1.5], true),
+        // Runs alongside the wall without touching it.
+        (vec![4.0, 0.5], vec![4.0, 9.5], true),
+        // Crosses the wall.
+        (vec![1.0, 5.0], vec![9.0, 5.0], false),
+        // Only clips the wall's top corner.
+        (vec![4.5, 8.2], vec![5.5, 7.9], false),
+        // Short enough to be a single segment, ending inside the wall.
+        (vec![5.0, 5.0], vec![5.001, 5.0], false),
+    ];
+    for (from, to, expected) in motions {
+        let from = RealVectorState::new(from);
+        let to = RealVectorState::new(to);
+        let sequential = check_motion(&space, &checker, &from, &to);
+        let parallel = check_motion_parallel(&space, &checker, &from, &to);
+
+        assert_eq!(sequential, expected, "{from:?} -> {to:?}");
+        assert_eq!(parallel, sequential, "{from:?} -> {to:?}");
+    }
+}
+
+type WallPRM = PRM<RealVectorState, RealVectorStateSpace, BoxGoalRegion>;
+
+/// A PRM set up to plan past the wall of `WallObstacleChecker` in a 10x10 space.
+fn wall_prm(seed: u64) -> WallPRM {
+    wall_prm_with(seed, |_| {})
+}
+
+/// A problem from the left to the right of the wall of `WallObstacleChecker` in a 10x10 space.
+fn wall_problem() -> Arc<ProblemDefinition<RealVectorState, RealVectorStateSpace, BoxGoalRegion>> {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    Arc::new(ProblemDefinition::new(
+        space,
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(BoxGoalRegion::new(RealVectorState::new(vec![9.0, 5.0]), vec![0.5, 0.5]).unwrap()),
+    ))
+}
+
+/// Like `wall_prm`, calling `configure` on the planner before `setup_parallel`.
+fn wall_prm_with(seed: u64, configure: impl FnOnce(&mut WallPRM)) -> WallPRM {
+    let problem_definition = wall_problem();
+    let checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut planner = PRM::new(0.2, 1.5);
+    planner.set_seed(seed);
+    configure(&mut planner);
+    planner.setup_parallel(problem_definition, checker);
+    planner
+}
+
+#[test]
+fn test_parallel_roadmap_matches_sequential_for_same_samples() {
+    let mut sequential = wall_prm(3);
+    sequential.construct_roadmap_iterations(300).unwrap();
+
+    let mut parallel = wall_prm(3);
+    parallel
+        .construct_roadmap_parallel(TerminationCondition::MaxIterations(300), 32)
+        .unwrap();
+
+    let sequential = sequential.get_roadmap();
+    let parallel = parallel.get_roadmap();
+    assert_eq!(parallel.len(), sequential.len());
+    for (p, s) in parallel.iter().zip(&sequential) {
+        assert_eq!(p.state(), s.state());
+        assert_eq!(p.neighbors(), s.neighbors());
+    }
+}
+
+#[test]
+fn test_parallel_roadmap_grows_as_large_as_sequential_within_timeout() {
+    let timeout = Duration::from_millis(200);
+
+    let mut sequential = wall_prm(5);
+    sequential
+        .construct_roadmap_with(TerminationCondition::Timeout(timeout))
+        .unwrap();
+    let sequential_nodes = sequential.get_roadmap().len();
+
+    let mut parallel = wall_prm(5);
+    parallel
+        .construct_roadmap_parallel(TerminationCondition::Timeout(timeout), 64)
+        .unwrap();
+    let parallel_nodes = parallel.get_roadmap().len();
+
+    assert!(
+        2 * parallel_nodes >= sequential_nodes,
+        "The parallel roadmap should be of a similar size: {parallel_nodes} vs {sequential_nodes}"
+    );
+    // Both roadmaps connect the start to the goal around the wall.
+    sequential.solve(Duration::from_secs(1)).unwrap();
+    parallel.solve(Duration::from_secs(1)).unwrap();
+}
+
+/// A MotionValidator that rejects every motion.
+struct RejectAllMotions;
+
+impl MotionValidator<RealVectorState> for RejectAllMotions {
+    fn check_motion(&self, _from: &RealVectorState, _to: &RealVectorState) -> bool {
+        false
+    }
+}
+
+#[test]
+fn test_parallel_roadmap_checks_edges_with_motion_validator() {
+    let mut planner = wall_prm_with(3, |planner| {
+        planner.set_parallel_motion_validator(Arc::new(RejectAllMotions));
+    });
+    planner
+        .construct_roadmap_parallel(TerminationCondition::MaxIterations(300), 32)
+        .unwrap();
+
+    let roadmap = planner.get_roadmap();
+    assert!(!roadmap.is_empty());
+    assert!(roadmap.iter().all(|node| node.neighbors().is_empty()));
+}
+
+#[test]
+fn test_parallel_roadmap_requires_setup_parallel() {
+    let mut planner: WallPRM = PRM::new(0.2, 1.5);
+    planner.setup(wall_problem(), Arc::new(AlwaysValid));
+
+    assert_eq!(
+        planner
+            .construct_roadmap_parallel(TerminationCondition::MaxIterations(300), 32)
+            .err(),
+        Some(PlanningError::PlannerUninitialised)
+    );
+}
+
+#[test]
+fn test_parallel_roadmap_respects_validity_check_budget() {
+    let mut planner = wall_prm_with(3, |planner| planner.set_max_validity_checks(Some(100)));
+
+    assert_eq!(
+        planner
+            .construct_roadmap_parallel(TerminationCondition::MaxIterations(10_000), 32)
+            .err(),
+        Some(PlanningError::CheckBudgetExhausted { max_checks: 100 })
+    );
+    assert_eq!(planner.validity_check_count(), 100);
+    assert!(planner.get_roadmap().is_empty());
+}
+
+/// A StateValidityChecker under which only a single state is valid, so no motion ever is.
+struct SingleValidState(RealVectorState);
+
+impl StateValidityChecker<RealVectorState> for SingleValidState {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        state == &self.0
+    }
+}
+
+/// A problem from one side of a 10x10 space to the other, to plan past a wall.
+fn crossing_problem() -> Arc<ProblemDefinition<RealVectorState, RealVectorStateSpace, BoxGoalRegion>>
+{
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    Arc::new(ProblemDefinition::new(
+        space,
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(BoxGoalRegion::new(RealVectorState::new(vec![9.0, 5.0]), vec![0.5, 0.5]).unwrap()),
+    ))
+}
+
+#[test]
+fn test_solve_parallel_returns_first_path_of_seeded_rrts() {
+    let problem = crossing_problem();
+    // A wall across almost the whole space, leaving thin gaps at either end.
+    let checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 0.3,
+        wall_y_max: 9.7,
+        wall_thickness: 0.5,
+    });
+
+    let (winner, path) = solve_parallel(
+        4,
+        |i| {
+            let mut planner = RRT::new(0.3, 0.05);
+            planner.set_seed(i as u64);
+            planner.setup(problem.clone(), checker.clone());
+            planner
+        },
+        TerminationCondition::Timeout(Duration::from_secs(30)),
+    )
+    .expect("One of the RRTs should find a path");
+
+    assert!(winner < 4);
+    assert!(problem.goal.is_satisfied(path.0.last().unwrap()));
+    assert!(path.0.windows(2).all(|pair| check_motion(
+        &*problem.space,
+        &*checker,
+        &pair[0],
+        &pair[1]
+    )));
+}
+
+#[test]
+fn test_solve_parallel_cancels_remaining_planners() {
+    let problem = crossing_problem();
+    let start = problem.start_states[0].clone();
+    let timeout = Duration::from_secs(30);
+
+    // Only planner 2 can leave its start state. The others would keep trying until the timeout,
+    // so returning early shows they were cancelled once planner 2 succeeded.
+    let started = Instant::now();
+    let (winner, _) = solve_parallel(
+        4,
+        |i| {
+            let mut planner = RRT::new(0.5, 0.05);
+            planner.set_seed(i as u64);
+            planner.max_stagnant_iterations = usize::MAX;
+            if i == 2 {
+                planner.setup(problem.clone(), Arc::new(AlwaysValid));
+            } else {
+                planner.setup(problem.clone(), Arc::new(SingleValidState(start.clone())));
+            }
+            planner
+        },
+        TerminationCondition::Timeout(timeout),
+    )
+    .expect("Planner 2 should find a path");
+
+    assert_eq!(winner, 2);
+    assert!(started.elapsed() < timeout / 3);
+}
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    error::PlanningError,
+    goal::{BoxGoalRegion, Goal, GoalRegion},
+    planner::{Path, Planner},
+    problem_definition::ProblemDefinition,
+    projection::RealVectorProjection,
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+    termination::TerminationCondition,
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{path_utils::validate_path, RRTConnect, RRTStar, KPIECE, RRT};
+
+/// A StateValidityChecker with a wall across the whole height of the space at `x = 5`, so the
+/// right-hand side cannot be reached from the left.
+struct FullWallChecker;
+
+/// The x coordinate of the left face of the wall.
+const WALL_LEFT: f64 = 4.75;
+
+impl StateValidityChecker<RealVectorState> for FullWallChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        !(WALL_LEFT..=5.25).contains(&state.values[0])
+    }
+}
+
+type PartialSolver =
+    Box<dyn FnMut(TerminationCondition) -> Result<(Path<RealVectorState>, bool), PlanningError>>;
+
+/// Sets up each tree planner on `problem_definition` and returns its `solve_partial`.
+fn partial_solvers(
+    problem_definition: Arc<
+        ProblemDefinition<RealVectorState, RealVectorStateSpace, BoxGoalRegion>,
+    >,
+) -> Vec<(&'static str, PartialSolver)> {
+    let checker = Arc::new(FullWallChecker);
+
+    let mut rrt = RRT::new(0.5, 0.05);
+    rrt.set_seed(0);
+    rrt.setup(problem_definition.clone(), checker.clone());
+    let mut rrt_connect = RRTConnect::new(0.5, 0.05);
+    rrt_connect.set_seed(0);
+    rrt_connect.setup(problem_definition.clone(), checker.clone());
+    let mut rrt_star = RRTStar::new(0.5, 0.05, 1.0);
+    rrt_star.set_seed(0);
+    rrt_star.setup(problem_definition.clone(), checker.clone());
+    let mut kpiece = KPIECE::new(0.5, 0.05, Arc::new(RealVectorProjection::new(2)));
+    kpiece.set_seed(0);
+    kpiece.setup(problem_definition, checker);
+
+    vec![
+        ("RRT", Box::new(move |c| rrt.solve_partial(c))),
+        (
+            "RRTConnect",
+            Box::new(move |c| rrt_connect.solve_partial(c)),
+        ),
+        ("RRT*", Box::new(move |c| rrt_star.solve_partial(c))),
+        ("KPIECE", Box::new(move |c| kpiece.solve_partial(c))),
+    ]
+}
+
+fn problem_with_goal(
+    center: Vec<f64>,
+) -> Arc<ProblemDefinition<RealVectorState, RealVectorStateSpace, BoxGoalRegion>> {
+    let space = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+    Arc::new(ProblemDefinition::new(
+        Arc::new(space),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(BoxGoalRegion::new(RealVectorState::new(center), vec![0.5, 0.5]).unwrap()),
+    ))
+}
+
+#[test]
+fn test_solve_partial_ends_next_to_the_wall_when_goal_is_unreachable() {
+    let problem_definition = problem_with_goal(vec![9.0, 5.0]);
+    let goal = problem_definition.goal.clone();
+    // The closest reachable states lie against the wall, 3.75 from the edge of the goal box.
+    let closest_reachable = 9.0 - 0.5 - WALL_LEFT;
+
+    for (name, mut solve_partial) in partial_solvers(problem_definition.clone()) {
+        let (path, reached) = solve_partial(TerminationCondition::MaxIterations(3000))
+            .unwrap_or_else(|err| panic!("{name} failed: {err:?}"));
+
+        assert!(!reached, "{name} cannot have reached the goal");
+        assert_eq!(path.0.first(), problem_definition.start_states.first());
+        let end = path.0.last().unwrap();
+        assert!(!goal.is_satisfied(end));
+        assert!(
+            end.values[0] < WALL_LEFT,
+            "{name} path must stay left of the wall"
+        );
+        let distance = goal.distance_goal(end);
+        assert!(
+            distance < closest_reachable + 0.25,
+            "{name} path should end next to the wall, but is {distance} from the goal"
+        );
+        assert_eq!(
+            validate_path(&path, &*problem_definition.space, &FullWallChecker),
+            Ok(())
+        );
+    }
+}
+
+#[test]
+fn test_solve_partial_reports_reaching_a_reachable_goal() {
+    let problem_definition = problem_with_goal(vec![3.0, 8.0]);
+    let goal = problem_definition.goal.clone();
+
+    for (name, mut solve_partial) in partial_solvers(problem_definition) {
+        let (path, reached) = solve_partial(TerminationCondition::MaxIterations(3000))
+            .unwrap_or_else(|err| panic!("{name} failed: {err:?}"));
+
+        assert!(reached, "{name} should have reached the goal");
+        assert!(goal.is_satisfied(path.0.last().unwrap()));
+    }
+}
@@ -0,0 +1,87 @@
+use std::{sync::Arc, time::Duration};
+
+use oxmpl::base::{
+    goal::GoalState,
+    planner::{Path, Planner},
+    problem_definition::ProblemDefinition,
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::PRM;
+
+/// Axis-aligned boxes `(x_min, x_max, y_min, y_max)` forming a block in the middle of the map.
+///
+/// The block is split by a narrow horizontal gap at `4.6 < y < 5.4` (the shortcut), while a wide
+/// free corridor runs above it for `7 < y <= 10` (the safe route).
+const OBSTACLES: [(f64, f64, f64, f64); 2] = [(3.0, 7.0, 0.0, 4.6), (3.0, 7.0, 5.4, 7.0)];
+
+fn distance_to_box(x: f64, y: f64, (x_min, x_max, y_min, y_max): (f64, f64, f64, f64)) -> f64 {
+    let dx = (x_min - x).max(0.0).max(x - x_max);
+    let dy = (y_min - y).max(0.0).max(y - y_max);
+    (dx * dx + dy * dy).sqrt()
+}
+
+fn clearance(state: &RealVectorState) -> f64 {
+    OBSTACLES
+        .iter()
+        .map(|&b| distance_to_box(state.values[0], state.values[1], b))
+        .fold(f64::INFINITY, f64::min)
+}
+
+struct BlockWithGapChecker;
+
+impl StateValidityChecker<RealVectorState> for BlockWithGapChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        clearance(state) > 0.0
+    }
+}
+
+/// Returns `true` if any waypoint of the path lies inside the narrow gap between the blocks.
+fn uses_shortcut(path: &Path<RealVectorState>) -> bool {
+    path.0
+        .iter()
+        .any(|s| (3.0..=7.0).contains(&s.values[0]) && s.values[1] < 7.0)
+}
+
+fn solve_with_weighting(clearance_weight: Option<f64>) -> Path<RealVectorState> {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+
+    let start_state = RealVectorState::new(vec![1.0, 5.0]);
+    let goal_definition =
+        Arc::new(GoalState::new(space.clone(), RealVectorState::new(vec![9.0, 5.0]), 0.5).unwrap());
+
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state],
+        goal_definition,
+    ));
+
+    let mut planner = PRM::new(2.0, 1.0);
+    if let Some(weight) = clearance_weight {
+        planner.set_clearance_weighting(Arc::new(clearance), weight);
+    }
+    planner.setup(problem_definition, Arc::new(BlockWithGapChecker));
+    planner.construct_roadmap().unwrap();
+
+    planner.solve(Duration::from_secs(5)).unwrap()
+}
+
+#[test]
+fn test_prm_length_weighting_takes_shortcut() {
+    let path = solve_with_weighting(None);
+    assert!(
+        uses_shortcut(&path),
+        "Length-weighted query should take the narrow shortcut."
+    );
+}
+
+#[test]
+fn test_prm_clearance_weighting_takes_wide_route() {
+    let path = solve_with_weighting(Some(5.0));
+    assert!(
+        !uses_shortcut(&path),
+        "Clearance-weighted query should avoid the narrow shortcut."
+    );
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    goal::Goal, planner::Planner, problem_definition::ProblemDefinition, space::DubinsStateSpace,
+    state::SE2State,
+};
+use oxmpl::geometric::{path_utils::validate_path, PRM};
+
+mod common;
+use common::{PositionGoal, RoomChecker};
+
+#[test]
+fn test_prm_finds_valid_path_in_dubinsss() {
+    let space = Arc::new(DubinsStateSpace::new(Some(vec![(0.0, 10.0), (0.0, 10.0)]), 1.0).unwrap());
+
+    let start_state = SE2State::new(1.0, 5.0, 0.0);
+    let goal_definition = Arc::new(PositionGoal {
+        target: [9.0, 5.0],
+        radius: 0.5,
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
+
+    // The roadmap's edges are searched in both directions, which are different Dubins curves, so
+    // the path is only valid if both were checked.
+    for lazy in [false, true] {
+        let mut planner = PRM::new(5.0, 2.0);
+        planner.set_seed(0);
+        planner.set_lazy(lazy);
+        planner.setup(problem_definition.clone(), Arc::new(RoomChecker));
+
+        let path = planner
+            .solve_iterations(1_000)
+            .expect("Planner failed to find a solution when one should exist");
+
+        assert_eq!(path.0.first(), Some(&start_state));
+        assert!(goal_definition.is_satisfied(path.0.last().unwrap()));
+        assert_eq!(validate_path(&path, &*space, &RoomChecker), Ok(()));
+    }
+}
@@ -1,18 +1,30 @@
-use std::{f64::consts::PI, sync::Arc, time::Duration};
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    f64::consts::PI,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use oxmpl::base::{
-    error::StateSamplingError,
+    error::{PlanningError, StateSamplingError},
     goal::{Goal, GoalRegion, GoalSampleableRegion},
     planner::{Path, Planner},
     problem_definition::ProblemDefinition,
+    sampler::HaltonSampler,
     space::{RealVectorStateSpace, StateSpace},
     state::RealVectorState,
-    validity::StateValidityChecker,
+    validity::{IncrementalValidityChecker, StateValidityChecker},
 };
-use oxmpl::geometric::PRM;
+use oxmpl::geometric::{path_utils::validate_path, PrefilterFn, PRM};
 
 use rand::Rng;
 
+mod common;
+use common::AlwaysValid;
+
 /// A StateValidityChecker that defines a simple vertical wall obstacle.
 struct WallObstacleChecker {
     wall_x_pos: f64,
@@ -68,44 +80,6 @@ impl GoalSampleableRegion<RealVectorState> for CircularGoalRegion {
     }
 }
 
-/// A helper function to validate the entire solution path.
-fn is_path_valid(
-    path: &Path<RealVectorState>,
-    space: &RealVectorStateSpace,
-    checker: &dyn StateValidityChecker<RealVectorState>,
-) -> bool {
-    for i in 0..path.0.len() - 1 {
-        let state_a = &path.0[i];
-        let state_b = &path.0[i + 1];
-
-        if !checker.is_valid(state_a) {
-            println!("Path invalid: State {state_a:?} is in collision.");
-            return false;
-        }
-        if (i + 1 == path.0.len() - 1) && !checker.is_valid(state_b) {
-            println!("Path invalid: Final state {state_b:?} is in collision.");
-            return false;
-        }
-
-        let dist = space.distance(state_a, state_b);
-        let num_steps = (dist / space.get_longest_valid_segment_length()).ceil() as usize;
-        if num_steps > 1 {
-            let mut interpolated_state = state_a.clone();
-            for j in 1..=num_steps {
-                let t = j as f64 / num_steps as f64;
-                space.interpolate(state_a, state_b, t, &mut interpolated_state);
-                if !checker.is_valid(&interpolated_state) {
-                    println!(
-                        "Path invalid: Motion between {state_a:?} and {state_b:?} is in collision at {interpolated_state:?}."
-                    );
-                    return false;
-                }
-            }
-        }
-    }
-    true
-}
-
 #[test]
 fn test_prm_finds_path_in_rvss() {
     let new_rvss_result = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]));
@@ -129,11 +103,11 @@ fn test_prm_finds_path_in_rvss() {
         space: space.clone(),
     });
 
-    let problem_definition = Arc::new(ProblemDefinition {
-        space: space.clone(),
-        start_states: vec![start_state.clone()],
-        goal: goal_definition.clone(),
-    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
 
     let validity_checker = Arc::new(WallObstacleChecker {
         wall_x_pos: 5.0,
@@ -187,9 +161,623 @@ fn test_prm_finds_path_in_rvss() {
     );
 
     assert!(
-        is_path_valid(&path, &space, &*validity_checker),
+        validate_path(&path, &*space, &*validity_checker).is_ok(),
         "The returned path was found to be invalid."
     );
 
     println!("PRM planner test passed!");
 }
+
+#[test]
+fn test_prm_connection_prefilter_does_not_change_roadmap() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState {
+            values: vec![1.0, 5.0],
+        }],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState {
+                values: vec![9.0, 5.0],
+            },
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let connection_radius = 1.0;
+    let build_roadmap = |prefilter: Option<PrefilterFn<RealVectorState>>| {
+        let mut planner = PRM::new(5.0, connection_radius);
+        planner.set_seed(11);
+        if let Some(prefilter) = prefilter {
+            planner.set_connection_prefilter(prefilter);
+        }
+        planner.setup(problem_definition.clone(), validity_checker.clone());
+        planner
+            .construct_roadmap_iterations(500)
+            .expect("Issue constructing roadmap!");
+        planner.get_roadmap()
+    };
+
+    // Reject pairs whose bounding box is larger than the connection radius.
+    let bounding_box: PrefilterFn<RealVectorState> = Arc::new(move |a, b| {
+        a.values
+            .iter()
+            .zip(&b.values)
+            .all(|(x, y)| (x - y).abs() < connection_radius)
+    });
+
+    let exact = build_roadmap(None);
+    let prefiltered = build_roadmap(Some(bounding_box));
+
+    assert_eq!(exact.len(), prefiltered.len());
+    for (a, b) in exact.iter().zip(&prefiltered) {
+        assert_eq!(a.state(), b.state());
+        assert_eq!(a.neighbors(), b.neighbors());
+    }
+    assert!(
+        exact.iter().any(|node| !node.neighbors().is_empty()),
+        "Roadmap should contain edges"
+    );
+}
+
+/// A wall with a gap that can be closed, bumping the generation when it changes.
+struct ClosableGapWall {
+    gap_closed: AtomicBool,
+    generation: AtomicU64,
+}
+
+impl StateValidityChecker<RealVectorState> for ClosableGapWall {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let x = state.values[0];
+        let y = state.values[1];
+        let in_wall = (4.75..=5.25).contains(&x) && (2.0..=8.0).contains(&y);
+        let in_gap = (4.0..=6.0).contains(&y);
+
+        !in_wall || (in_gap && !self.gap_closed.load(Ordering::SeqCst))
+    }
+}
+
+impl IncrementalValidityChecker<RealVectorState> for ClosableGapWall {
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+}
+
+#[test]
+fn test_prm_roadmap_from_halton_sampler_is_reproducible() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState::new(vec![9.0, 5.0]),
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    // The Halton sequence ignores the planner's random number generator, so differently seeded
+    // planners build the same roadmap.
+    let build_roadmap = |seed: u64| {
+        let mut planner = PRM::new(5.0, 1.0);
+        planner.set_seed(seed);
+        planner.set_sampler(Arc::new(HaltonSampler::new(space.clone())));
+        planner.setup(problem_definition.clone(), validity_checker.clone());
+        planner
+            .construct_roadmap_iterations(300)
+            .expect("Issue constructing roadmap!");
+        planner.get_roadmap()
+    };
+
+    let first = build_roadmap(1);
+    let second = build_roadmap(2);
+    assert!(!first.is_empty());
+    assert_eq!(first.len(), second.len());
+    for (a, b) in first.iter().zip(&second) {
+        assert_eq!(a.state(), b.state());
+        assert_eq!(a.neighbors(), b.neighbors());
+    }
+}
+
+#[test]
+fn test_prm_rebuilds_stale_roadmap_after_environment_change() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+    let start_state = RealVectorState {
+        values: vec![1.0, 5.0],
+    };
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState {
+                values: vec![9.0, 5.0],
+            },
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+    let validity_checker = Arc::new(ClosableGapWall {
+        gap_closed: AtomicBool::new(false),
+        generation: AtomicU64::new(0),
+    });
+
+    let mut planner = PRM::new(5.0, 1.0);
+    planner.set_seed(4);
+    planner.setup(problem_definition, validity_checker.clone());
+    planner.track_validity_generation(validity_checker.clone());
+    planner
+        .construct_roadmap_iterations(1000)
+        .expect("Issue constructing roadmap!");
+    assert!(!planner.is_roadmap_stale());
+    assert!(planner.solve(Duration::from_secs(5)).is_ok());
+
+    // Closing the gap invalidates edges through it.
+    validity_checker.gap_closed.store(true, Ordering::SeqCst);
+    validity_checker.generation.fetch_add(1, Ordering::SeqCst);
+    assert!(planner.is_roadmap_stale());
+    assert!(matches!(
+        planner.solve(Duration::from_secs(5)),
+        Err(PlanningError::UnsampledStateSpace)
+    ));
+
+    planner
+        .construct_roadmap_iterations(1000)
+        .expect("Issue constructing roadmap!");
+    assert!(!planner.is_roadmap_stale());
+    let path = planner
+        .solve(Duration::from_secs(5))
+        .expect("Planner failed to find a path around the closed wall");
+    assert!(
+        validate_path(&path, &*space, &*validity_checker).is_ok(),
+        "The returned path was found to be invalid."
+    );
+}
+
+/// Wraps a checker and counts the states it is asked to check.
+struct CountingChecker<C> {
+    inner: C,
+    checks: AtomicU64,
+}
+
+impl<C: StateValidityChecker<RealVectorState>> StateValidityChecker<RealVectorState>
+    for CountingChecker<C>
+{
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        self.checks.fetch_add(1, Ordering::Relaxed);
+        self.inner.is_valid(state)
+    }
+}
+
+#[test]
+fn test_lazy_prm_checks_fewer_motions_than_eager_prm() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let start_state = RealVectorState::new(vec![1.0, 5.0]);
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![9.0, 5.0]),
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
+    let wall = WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    };
+
+    // Both planners see the same samples, so any difference in the number of checks comes from
+    // the motions checked along roadmap edges.
+    let mut checks = Vec::new();
+    for lazy in [false, true] {
+        let checker = Arc::new(CountingChecker {
+            inner: WallObstacleChecker { ..wall },
+            checks: AtomicU64::new(0),
+        });
+        let mut planner = PRM::new(5.0, 1.0);
+        planner.set_seed(0);
+        planner.set_lazy(lazy);
+        planner.setup(problem_definition.clone(), checker.clone());
+
+        let path = planner.solve_iterations(500).unwrap_or_else(|e| {
+            panic!("PRM (lazy: {lazy}) failed to find a path: {e:?}");
+        });
+        assert!(goal_definition.is_satisfied(path.0.last().unwrap()));
+        assert!(
+            validate_path(&path, &*space, &wall).is_ok(),
+            "PRM (lazy: {lazy}) returned an invalid path"
+        );
+        checks.push(checker.checks.load(Ordering::Relaxed));
+        assert_eq!(
+            planner.validity_check_count() as u64,
+            checks[checks.len() - 1]
+        );
+
+        // Only lazy mode leaves edges that no query has needed unchecked.
+        let has_unchecked_edges = planner
+            .get_roadmap()
+            .iter()
+            .any(|node| (0..node.neighbors().len()).any(|index| !node.is_edge_validated(index)));
+        assert_eq!(has_unchecked_edges, lazy);
+    }
+
+    assert!(
+        checks[1] < checks[0] / 2,
+        "Lazy PRM made {} validity checks, eager PRM made {}",
+        checks[1],
+        checks[0]
+    );
+}
+
+#[test]
+fn test_prm_query_minimises_length_rather_than_edge_count() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let start_state = RealVectorState::new(vec![1.0, 5.0]);
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![9.0, 5.0]),
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let connection_radius = 1.0;
+    let mut planner = PRM::new(5.0, connection_radius);
+    planner.set_seed(0);
+    planner.setup(problem_definition, validity_checker.clone());
+    let dijkstra_path = planner.solve_iterations(500).unwrap();
+
+    // Find the path with the fewest edges by BFS over the same roadmap. The start is far enough
+    // from the wall that every connection to it is collision-free, so the BFS sees the same graph
+    // as the query.
+    let roadmap = planner.get_roadmap();
+    let mut parents: HashMap<usize, Option<usize>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for (i, node) in roadmap.iter().enumerate() {
+        if space.distance(&start_state, node.state()) < connection_radius {
+            parents.insert(i, None);
+            queue.push_back(i);
+        }
+    }
+    let mut goal_node = None;
+    while let Some(current) = queue.pop_front() {
+        if goal_definition.is_satisfied(roadmap[current].state()) {
+            goal_node = Some(current);
+            break;
+        }
+        for &neighbour in roadmap[current].neighbors() {
+            if let Entry::Vacant(entry) = parents.entry(neighbour) {
+                entry.insert(Some(current));
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    let mut index = goal_node.expect("The roadmap should connect the start to the goal");
+    let mut states = vec![roadmap[index].state().clone()];
+    while let Some(parent) = parents[&index] {
+        states.push(roadmap[parent].state().clone());
+        index = parent;
+    }
+    states.push(start_state.clone());
+    states.reverse();
+    let bfs_path = Path(states);
+    assert!(validate_path(&dijkstra_path, &*space, &*validity_checker).is_ok());
+    assert!(
+        dijkstra_path.length(&*space) < bfs_path.length(&*space) - 1e-3,
+        "Dijkstra path ({}) should be shorter than the fewest-edges path ({})",
+        dijkstra_path.length(&*space),
+        bfs_path.length(&*space)
+    );
+
+    // A* searches the same roadmap, and its heuristic is admissible, so it finds an equally short
+    // path.
+    planner.set_astar(true);
+    let astar_path = planner.solve_iterations(500).unwrap();
+    assert!((astar_path.length(&*space) - dijkstra_path.length(&*space)).abs() < 1e-9);
+}
+
+#[test]
+fn test_prm_imported_roadmap_answers_new_queries_without_construction() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+    let problem = |start: Vec<f64>, target: Vec<f64>| {
+        Arc::new(ProblemDefinition::new(
+            space.clone(),
+            vec![RealVectorState::new(start)],
+            Arc::new(CircularGoalRegion {
+                target: RealVectorState::new(target),
+                radius: 0.5,
+                space: space.clone(),
+            }),
+        ))
+    };
+
+    let mut builder = PRM::new(5.0, 1.0);
+    builder.set_seed(0);
+    builder.setup(
+        problem(vec![1.0, 5.0], vec![9.0, 5.0]),
+        validity_checker.clone(),
+    );
+    builder.construct_roadmap_iterations(1000).unwrap();
+    let data = builder.export_roadmap();
+    assert_eq!(data.states.len(), builder.get_roadmap().len());
+
+    // A fresh planner answers a different query from the imported roadmap.
+    let new_problem = problem(vec![9.0, 1.0], vec![1.0, 9.0]);
+    let mut planner = PRM::new(5.0, 1.0);
+    planner.setup(new_problem.clone(), validity_checker.clone());
+    planner.import_roadmap(data.clone()).unwrap();
+    let path = planner.solve(Duration::from_secs(5)).unwrap();
+
+    assert!(space.distance(&path.0[0], &new_problem.start_states[0]) < 1e-9);
+    assert!(new_problem.goal.is_satisfied(path.0.last().unwrap()));
+    assert!(validate_path(&path, &*space, &*validity_checker).is_ok());
+
+    // Only the motions connecting the start were checked, not those of a new roadmap.
+    let roadmap = planner.get_roadmap();
+    assert_eq!(roadmap.len(), data.states.len());
+    assert!(planner.validity_check_count() < 1000);
+    for (node, edges) in roadmap.iter().zip(&data.edges) {
+        let mut imported = node.neighbors().to_vec();
+        let mut exported = edges.clone();
+        imported.sort_unstable();
+        exported.sort_unstable();
+        assert_eq!(imported, exported);
+    }
+
+    let mut malformed = data;
+    malformed.edges[0].push(malformed.states.len());
+    assert!(matches!(
+        planner.import_roadmap(malformed),
+        Err(PlanningError::InvalidProblemDefinition(_))
+    ));
+}
+
+#[test]
+fn test_prm_roadmap_edges_are_symmetric() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState::new(vec![9.0, 5.0]),
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut planner = PRM::new(5.0, 1.0);
+    planner.set_seed(0);
+    planner.setup(problem_definition, validity_checker.clone());
+    planner.construct_roadmap_iterations(500).unwrap();
+
+    let roadmap = planner.get_roadmap();
+    assert_eq!(planner.roadmap_size(), roadmap.len());
+    assert!(roadmap.iter().any(|node| !node.neighbors().is_empty()));
+    for (i, node) in roadmap.iter().enumerate() {
+        assert!(validity_checker.is_valid(node.state()));
+        for &j in node.neighbors() {
+            assert_ne!(i, j, "milestone {i} is connected to itself");
+            assert!(
+                roadmap[j].neighbors().contains(&i),
+                "milestone {i} lists {j}, but {j} does not list {i}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_prm_expanded_roadmap_answers_query_the_initial_one_could_not() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState::new(vec![9.0, 5.0]),
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut planner = PRM::new(5.0, 1.0);
+    planner.set_seed(0);
+    planner.setup(problem_definition.clone(), validity_checker.clone());
+    planner.construct_roadmap_iterations(10).unwrap();
+    let initial = planner.get_roadmap();
+    assert!(planner.solve(Duration::from_secs(1)).is_err());
+
+    planner.expand_roadmap(Duration::from_secs(1)).unwrap();
+    let expanded = planner.get_roadmap();
+    assert!(expanded.len() > initial.len());
+    for (before, after) in initial.iter().zip(&expanded) {
+        assert_eq!(before.state(), after.state());
+    }
+    // Some of the initial milestones are now connected to new ones.
+    assert!(initial.iter().zip(&expanded).any(|(before, after)| after
+        .neighbors()
+        .iter()
+        .any(|&j| j >= initial.len())
+        && before.neighbors().len() < after.neighbors().len()));
+
+    let path = planner
+        .solve(Duration::from_secs(1))
+        .expect("the expanded roadmap should connect the start to the goal");
+    assert!(problem_definition.goal.is_satisfied(path.0.last().unwrap()));
+    assert!(validate_path(&path, &*space, &*validity_checker).is_ok());
+}
+
+#[test]
+fn test_prm_star_approaches_optimal_cost_in_open_space() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let target = RealVectorState::new(vec![9.0, 9.0]);
+    let start = RealVectorState::new(vec![1.0, 1.0]);
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start.clone()],
+        Arc::new(CircularGoalRegion {
+            target: target.clone(),
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+    let optimal = space.distance(&start, &target) - 0.5;
+    // Above the threshold 2 * (1 + 1/d)^(1/d) * (mu / zeta_d)^(1/d) for the 10 x 10 square.
+    let gamma = 2.0 * 1.5_f64.sqrt() * (100.0 / PI).sqrt();
+
+    let mean_cost = |star: bool, samples: usize| {
+        let seeds = 0..3_u64;
+        let total: f64 = seeds
+            .clone()
+            .map(|seed| {
+                let mut planner = PRM::new(5.0, if star { 10.0 } else { 0.6 });
+                planner.set_seed(seed);
+                if star {
+                    planner.set_star(gamma, 2).unwrap();
+                }
+                planner.setup(problem_definition.clone(), Arc::new(AlwaysValid));
+                let path = planner.solve_iterations(samples).unwrap();
+                path.length(&*space)
+            })
+            .sum();
+        total / seeds.count() as f64
+    };
+
+    let star_small = mean_cost(true, 100);
+    let star_large = mean_cost(true, 1000);
+    let fixed_large = mean_cost(false, 1000);
+    assert!(
+        star_large < star_small,
+        "PRM* should improve with more samples: {star_large} vs {star_small}"
+    );
+    assert!(
+        star_large < fixed_large,
+        "PRM* should beat fixed-radius PRM: {star_large} vs {fixed_large}"
+    );
+    assert!(
+        star_large < 1.03 * optimal,
+        "PRM* cost {star_large} should be near the optimal {optimal}"
+    );
+}
+
+#[test]
+fn test_prm_star_radius_shrinks_as_roadmap_grows() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 1.0])],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState::new(vec![9.0, 9.0]),
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+
+    let mut planner = PRM::new(5.0, 3.0);
+    assert_eq!(planner.current_connection_radius(), 3.0);
+    planner.set_star(10.0, 2).unwrap();
+    planner.set_seed(0);
+    planner.setup(problem_definition, Arc::new(AlwaysValid));
+    // Capped at the connection radius while the roadmap is small.
+    assert_eq!(planner.current_connection_radius(), 3.0);
+
+    planner.construct_roadmap_iterations(999).unwrap();
+    let n = 1000.0_f64;
+    let expected = 10.0 * (n.ln() / n).sqrt();
+    assert!((planner.current_connection_radius() - expected).abs() < 1e-12);
+}
+
+#[test]
+fn test_prm_star_rejects_invalid_parameters() {
+    let mut planner: PRM<RealVectorState, RealVectorStateSpace, CircularGoalRegion> =
+        PRM::new(5.0, 3.0);
+
+    assert_eq!(
+        planner.set_star(10.0, 0),
+        Err(PlanningError::InvalidParameter {
+            name: "dimension",
+            value: 0.0
+        })
+    );
+    assert!(matches!(
+        planner.set_star(-1.0, 2),
+        Err(PlanningError::InvalidParameter { name: "gamma", .. })
+    ));
+    assert!(planner.set_star(f64::NAN, 2).is_err());
+    // A rejected call leaves the planner in fixed-radius mode.
+    assert_eq!(planner.current_connection_radius(), 3.0);
+
+    // Without a finite cap, the PRM* radius is used unchanged.
+    planner.connection_radius = f64::INFINITY;
+    planner.set_star(10.0, 2).unwrap();
+    let n = 2.0_f64;
+    assert_eq!(
+        planner.current_connection_radius(),
+        10.0 * (n.ln() / n).sqrt()
+    );
+}
@@ -21,7 +21,7 @@ struct ForbiddenAngleChecker {
 
 impl StateValidityChecker<SO2State> for ForbiddenAngleChecker {
     fn is_valid(&self, state: &SO2State) -> bool {
-        let val = state.clone().normalise().value;
+        let val = state.normalised().value;
         !(val >= self.invalid_min && val <= self.invalid_max)
     }
 }
@@ -103,11 +103,11 @@ fn test_prm_finds_path_in_so2ss() {
         space: space.clone(),
     });
 
-    let problem_definition = Arc::new(ProblemDefinition {
-        space: space.clone(),
-        start_states: vec![start_state.clone()],
-        goal: goal_definition.clone(),
-    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
 
     let validity_checker = Arc::new(ForbiddenAngleChecker {
         invalid_min: -0.5,
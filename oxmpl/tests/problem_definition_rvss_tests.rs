@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    error::{PlanningError, ProblemError},
+    goal::GoalState,
+    problem_definition::ProblemDefinition,
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+mod common;
+use common::WallObstacleChecker;
+
+fn wall() -> WallObstacleChecker {
+    WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    }
+}
+
+fn problem(
+    start_states: Vec<RealVectorState>,
+    goal_target: RealVectorState,
+) -> ProblemDefinition<
+    RealVectorState,
+    RealVectorStateSpace,
+    GoalState<RealVectorState, RealVectorStateSpace>,
+> {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    ProblemDefinition::new(
+        space.clone(),
+        start_states,
+        Arc::new(GoalState::new(space, goal_target, 0.5).unwrap()),
+    )
+}
+
+#[test]
+fn test_validate_accepts_a_well_formed_problem() {
+    let pd = problem(
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        RealVectorState::new(vec![9.0, 5.0]),
+    );
+    let mut rng = StdRng::seed_from_u64(0);
+
+    assert_eq!(pd.validate(&wall()), Ok(()));
+    assert_eq!(pd.probe_goal(&wall(), &mut rng), Ok(()));
+}
+
+#[test]
+fn test_validate_rejects_a_start_of_the_wrong_dimension() {
+    let pd = problem(
+        vec![RealVectorState::new(vec![1.0, 5.0, 0.0])],
+        RealVectorState::new(vec![9.0, 5.0]),
+    );
+
+    assert_eq!(
+        pd.validate(&wall()),
+        Err(ProblemError::IncompatibleStart { index: 0 })
+    );
+}
+
+#[test]
+fn test_validate_rejects_missing_out_of_bounds_and_invalid_starts() {
+    let goal = RealVectorState::new(vec![9.0, 5.0]);
+
+    assert_eq!(
+        problem(vec![], goal.clone()).validate(&wall()),
+        Err(ProblemError::NoStartStates)
+    );
+    assert_eq!(
+        problem(vec![RealVectorState::new(vec![-1.0, 5.0])], goal.clone()).validate(&wall()),
+        Err(ProblemError::OutOfBounds { index: 0 })
+    );
+    // The index identifies which of several start states is at fault.
+    let starts = vec![
+        RealVectorState::new(vec![1.0, 5.0]),
+        RealVectorState::new(vec![5.0, 5.0]),
+    ];
+    assert_eq!(
+        problem(starts, goal).validate(&wall()),
+        Err(ProblemError::InvalidStart { index: 1 })
+    );
+}
+
+#[test]
+fn test_probe_goal_fails_for_a_goal_inside_an_obstacle() {
+    let pd = problem(
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        RealVectorState::new(vec![5.0, 5.0]),
+    );
+    let checker = WallObstacleChecker {
+        wall_thickness: 2.0,
+        ..wall()
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+
+    assert!(pd.validate(&checker).is_ok());
+    assert!(matches!(
+        pd.probe_goal(&checker, &mut rng),
+        Err(PlanningError::InvalidProblemDefinition(_))
+    ));
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    goal::GoalState, planner::Planner, problem_definition::ProblemDefinition,
+    space::RealVectorStateSpace, state::RealVectorState,
+};
+use oxmpl::geometric::RRT;
+use oxmpl::testing::{RecordingRng, ReplayRng, RngDraw};
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+mod common;
+use common::WallObstacleChecker;
+
+fn setup_rrt(
+    rng: impl RngCore + 'static,
+) -> RRT<RealVectorState, RealVectorStateSpace, GoalState<RealVectorState, RealVectorStateSpace>> {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let problem_def = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(GoalState::new(space, RealVectorState::new(vec![9.0, 5.0]), 0.5).unwrap()),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut rrt = RRT::new(0.5, 0.05);
+    rrt.set_rng(rng);
+    rrt.setup(problem_def, validity_checker);
+    rrt
+}
+
+#[test]
+fn test_replaying_a_recorded_rrt_run_reproduces_its_tree() {
+    // An unseeded run, as when chasing a failure that only shows up sometimes.
+    let recording = RecordingRng::new(StdRng::from_os_rng());
+    let log = recording.log();
+    let mut recorded_rrt = setup_rrt(recording);
+    let recorded_path = recorded_rrt
+        .solve_iterations(10_000)
+        .expect("Recorded RRT run failed to find a solution");
+    assert!(!log.is_empty());
+
+    let mut replayed_rrt = setup_rrt(ReplayRng::new(log.draws()));
+    let replayed_path = replayed_rrt
+        .solve_iterations(10_000)
+        .expect("Replayed RRT run failed to find a solution");
+
+    assert_eq!(replayed_path.0, recorded_path.0);
+    assert_eq!(replayed_rrt.tree_size(), recorded_rrt.tree_size());
+}
+
+#[test]
+#[should_panic(expected = "ReplayRng diverged at draw 0: next_u64 was requested")]
+fn test_replay_panics_when_the_run_diverges() {
+    let mut replay = ReplayRng::new(vec![RngDraw::U32(7)]);
+    replay.next_u64();
+}
+
+#[test]
+#[should_panic(expected = "ReplayRng diverged at draw 1")]
+fn test_replay_panics_when_the_recording_runs_out() {
+    let mut replay = ReplayRng::new(vec![RngDraw::U32(7)]);
+    assert_eq!(replay.next_u32(), 7);
+    assert_eq!(replay.remaining(), 0);
+    replay.next_u32();
+}
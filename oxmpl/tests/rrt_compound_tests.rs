@@ -0,0 +1,140 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use oxmpl::base::{
+    error::StateSamplingError,
+    goal::{Goal, GoalRegion, GoalSampleableRegion},
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::{CompoundStateSpace, RealVectorStateSpace, SO2StateSpace, StateSpace},
+    state::{CompoundState, RealVectorState, SO2State},
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{path_utils::validate_path, RRT};
+
+use rand::Rng;
+
+fn pose(x: f64, y: f64, theta: f64) -> CompoundState {
+    CompoundState::new(vec![
+        Box::new(RealVectorState::new(vec![x, y])),
+        Box::new(SO2State::new(theta)),
+    ])
+}
+
+fn position(state: &CompoundState) -> &[f64] {
+    &state.component::<RealVectorState>(0).unwrap().values
+}
+
+fn heading(state: &CompoundState) -> f64 {
+    state.component::<SO2State>(1).unwrap().value
+}
+
+/// A rod of length `2 * half_length` centred on the position and pointing along the heading,
+/// which must stay clear of a vertical wall at x = 5 with a gap at the top.
+struct WallChecker {
+    half_length: f64,
+    gap_min_y: f64,
+}
+
+impl StateValidityChecker<CompoundState> for WallChecker {
+    fn is_valid(&self, state: &CompoundState) -> bool {
+        let (x, y) = (position(state)[0], position(state)[1]);
+        let (dx, dy) = (heading(state).cos(), heading(state).sin());
+        (-10..=10).all(|i| {
+            let s = self.half_length * i as f64 / 10.0;
+            let (px, py) = (x + s * dx, y + s * dy);
+            !((4.5..=5.5).contains(&px) && py < self.gap_min_y)
+        })
+    }
+}
+
+/// A goal reached once the position is within `radius` of `target`, at any heading.
+struct PositionGoal {
+    target: [f64; 2],
+    radius: f64,
+}
+
+impl PositionGoal {
+    fn centre_distance(&self, state: &CompoundState) -> f64 {
+        let p = position(state);
+        ((p[0] - self.target[0]).powi(2) + (p[1] - self.target[1]).powi(2)).sqrt()
+    }
+}
+
+impl Goal<CompoundState> for PositionGoal {
+    fn is_satisfied(&self, state: &CompoundState) -> bool {
+        self.centre_distance(state) <= self.radius
+    }
+}
+
+impl GoalRegion<CompoundState> for PositionGoal {
+    fn distance_goal(&self, state: &CompoundState) -> f64 {
+        (self.centre_distance(state) - self.radius).max(0.0)
+    }
+}
+
+impl GoalSampleableRegion<CompoundState> for PositionGoal {
+    fn sample_goal(&self, rng: &mut impl Rng) -> Result<CompoundState, StateSamplingError> {
+        let angle = rng.random_range(0.0..2.0 * PI);
+        let radius = self.radius * rng.random::<f64>().sqrt();
+        Ok(pose(
+            self.target[0] + radius * angle.cos(),
+            self.target[1] + radius * angle.sin(),
+            rng.random_range(-PI..PI),
+        ))
+    }
+}
+
+#[test]
+fn test_rrt_finds_path_in_real_vector_so2_compound_space() {
+    let space = Arc::new(
+        CompoundStateSpace::new(
+            vec![
+                Box::new(
+                    RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap(),
+                ),
+                Box::new(SO2StateSpace::new(None).unwrap()),
+            ],
+            vec![1.0, 0.5],
+        )
+        .unwrap(),
+    );
+
+    let start_state = pose(1.0, 1.0, 0.0);
+    let goal_definition = Arc::new(PositionGoal {
+        target: [9.0, 1.0],
+        radius: 0.5,
+    });
+
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
+
+    // A wall between the start and the goal, which the rod has to go round through the gap.
+    let validity_checker = Arc::new(WallChecker {
+        half_length: 1.0,
+        gap_min_y: 8.0,
+    });
+    assert!(
+        validity_checker.is_valid(&start_state),
+        "Start state should be valid!"
+    );
+    assert!(
+        !validity_checker.is_valid(&pose(5.0, 1.0, 0.0)),
+        "The straight line from start to goal should be blocked!"
+    );
+
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(0);
+    planner.setup(problem_definition, validity_checker.clone());
+
+    let path = planner
+        .solve_iterations(50_000)
+        .expect("Planner failed to find a solution when one should exist");
+
+    assert_eq!(position(&path.0[0]), position(&start_state));
+    assert!(goal_definition.is_satisfied(path.0.last().unwrap()));
+    assert!(path.0.iter().all(|state| space.satisfies_bounds(state)));
+    assert!(validate_path(&path, &*space, &*validity_checker).is_ok());
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    goal::Goal,
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::{DubinsStateSpace, StateSpace},
+    state::SE2State,
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{path_utils::validate_path, RRTConnect};
+
+mod common;
+use common::{PositionGoal, RoomChecker};
+
+#[test]
+fn test_rrt_connect_finds_valid_path_in_dubinsss() {
+    let space = Arc::new(DubinsStateSpace::new(Some(vec![(0.0, 10.0), (0.0, 10.0)]), 1.0).unwrap());
+    assert!(!space.is_metric_symmetric());
+
+    let start_state = SE2State::new(1.0, 5.0, 0.0);
+    let goal_definition = Arc::new(PositionGoal {
+        target: [9.0, 5.0],
+        radius: 0.5,
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
+
+    assert!(
+        !RoomChecker.is_valid(&SE2State::new(5.0, 5.0, 0.0)),
+        "The straight line from start to goal should be blocked!"
+    );
+
+    // The goal tree is grown away from the goal, but its motions are driven towards it, so every
+    // motion of the returned path must be valid in the order the path follows it.
+    for seed in 0..10 {
+        let mut planner = RRTConnect::new(1.0, 0.05);
+        planner.set_seed(seed);
+        planner.setup(problem_definition.clone(), Arc::new(RoomChecker));
+
+        let path = planner
+            .solve_iterations(20_000)
+            .expect("Planner failed to find a solution when one should exist");
+
+        assert_eq!(path.0.first(), Some(&start_state));
+        assert!(goal_definition.is_satisfied(path.0.last().unwrap()));
+        assert_eq!(validate_path(&path, &*space, &RoomChecker), Ok(()));
+    }
+}
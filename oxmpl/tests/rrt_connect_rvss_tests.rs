@@ -1,15 +1,15 @@
 use std::{f64::consts::PI, sync::Arc, time::Duration};
 
 use oxmpl::base::{
-    error::StateSamplingError,
+    error::{PlanningError, StateSamplingError},
     goal::{Goal, GoalRegion, GoalSampleableRegion},
-    planner::{Path, Planner},
+    planner::Planner,
     problem_definition::ProblemDefinition,
     space::{RealVectorStateSpace, StateSpace},
     state::RealVectorState,
     validity::StateValidityChecker,
 };
-use oxmpl::geometric::RRTConnect;
+use oxmpl::geometric::{path_utils::validate_path, RRTConnect};
 
 use rand::Rng;
 
@@ -68,42 +68,86 @@ impl GoalSampleableRegion<RealVectorState> for CircularGoalRegion {
     }
 }
 
-/// A helper function to validate the entire solution path.
-fn is_path_valid(
-    path: &Path<RealVectorState>,
-    space: &RealVectorStateSpace,
-    checker: &dyn StateValidityChecker<RealVectorState>,
-) -> bool {
-    for i in 0..path.0.len() - 1 {
-        let state_a = &path.0[i];
-        let state_b = &path.0[i + 1];
-
-        if !checker.is_valid(state_a) {
-            println!("Path invalid: State {state_a:?} is in collision.");
-            return false;
-        }
-        if (i + 1 == path.0.len() - 1) && !checker.is_valid(state_b) {
-            println!("Path invalid: Final state {state_b:?} is in collision.");
-            return false;
-        }
-
-        let dist = space.distance(state_a, state_b);
-        let num_steps = (dist / space.get_longest_valid_segment_length()).ceil() as usize;
-        if num_steps > 1 {
-            let mut interpolated_state = state_a.clone();
-            for j in 1..=num_steps {
-                let t = j as f64 / num_steps as f64;
-                space.interpolate(state_a, state_b, t, &mut interpolated_state);
-                if !checker.is_valid(&interpolated_state) {
-                    println!(
-                        "Path invalid: Motion between {state_a:?} and {state_b:?} is in collision at {interpolated_state:?}."
-                    );
-                    return false;
-                }
-            }
-        }
+/// A goal region that can never be sampled, e.g. because its constraints contradict each other.
+struct UnsatisfiableGoal;
+
+impl Goal<RealVectorState> for UnsatisfiableGoal {
+    fn is_satisfied(&self, _state: &RealVectorState) -> bool {
+        false
+    }
+}
+
+impl GoalRegion<RealVectorState> for UnsatisfiableGoal {
+    fn distance_goal(&self, _state: &RealVectorState) -> f64 {
+        f64::INFINITY
+    }
+}
+
+impl GoalSampleableRegion<RealVectorState> for UnsatisfiableGoal {
+    fn sample_goal(&self, _rng: &mut impl Rng) -> Result<RealVectorState, StateSamplingError> {
+        Err(StateSamplingError::GoalRegionUnsatisfiable)
+    }
+}
+
+/// A goal region around a single state, whose sampler always returns that exact state.
+struct ExactStateGoal {
+    target: RealVectorState,
+    space: Arc<RealVectorStateSpace>,
+}
+
+impl Goal<RealVectorState> for ExactStateGoal {
+    fn is_satisfied(&self, state: &RealVectorState) -> bool {
+        self.space.distance(state, &self.target) <= 1e-6
+    }
+}
+
+impl GoalRegion<RealVectorState> for ExactStateGoal {
+    fn distance_goal(&self, state: &RealVectorState) -> f64 {
+        (self.space.distance(state, &self.target) - 1e-6).max(0.0)
+    }
+}
+
+impl GoalSampleableRegion<RealVectorState> for ExactStateGoal {
+    fn sample_goal(&self, _rng: &mut impl Rng) -> Result<RealVectorState, StateSamplingError> {
+        Ok(self.target.clone())
+    }
+}
+
+/// A goal region covering the strip `9 <= y <= 9.5` across the whole width of a 10x10 space.
+struct StripGoalRegion;
+
+impl Goal<RealVectorState> for StripGoalRegion {
+    fn is_satisfied(&self, state: &RealVectorState) -> bool {
+        (9.0..=9.5).contains(&state.values[1])
+    }
+}
+
+impl GoalRegion<RealVectorState> for StripGoalRegion {
+    fn distance_goal(&self, state: &RealVectorState) -> f64 {
+        (9.0 - state.values[1]).max(state.values[1] - 9.5).max(0.0)
+    }
+}
+
+impl GoalSampleableRegion<RealVectorState> for StripGoalRegion {
+    fn sample_goal(&self, rng: &mut impl Rng) -> Result<RealVectorState, StateSamplingError> {
+        Ok(RealVectorState::new(vec![
+            rng.random_range(0.0..10.0),
+            rng.random_range(9.0..9.5),
+        ]))
+    }
+}
+
+/// A StateValidityChecker that walls off the top-left of a 10x10 space, leaving only the part of
+/// `StripGoalRegion` with `x > 9` reachable from below.
+struct SealedPocketChecker;
+
+impl StateValidityChecker<RealVectorState> for SealedPocketChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let (x, y) = (state.values[0], state.values[1]);
+        let in_floor = (8.5..=8.8).contains(&y) && x <= 9.0;
+        let in_side = y >= 8.5 && (8.7..=9.0).contains(&x);
+        !in_floor && !in_side
     }
-    true
 }
 
 #[test]
@@ -124,11 +168,11 @@ fn test_rrt_connect_finds_path_in_rvss() {
         space: space.clone(),
     });
 
-    let problem_definition = Arc::new(ProblemDefinition {
-        space: space.clone(),
-        start_states: vec![start_state.clone()],
-        goal: goal_definition.clone(),
-    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
 
     let validity_checker = Arc::new(WallObstacleChecker {
         wall_x_pos: 5.0,
@@ -174,9 +218,206 @@ fn test_rrt_connect_finds_path_in_rvss() {
     );
 
     assert!(
-        is_path_valid(&path, &space, &*validity_checker),
+        validate_path(&path, &*space, &*validity_checker).is_ok(),
         "The returned path was found to be invalid."
     );
 
     println!("RRT-Connect planner test passed!");
 }
+
+#[test]
+fn test_rrt_connect_reports_unsatisfiable_goal() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space,
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(UnsatisfiableGoal),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    // The goal tree cannot be rooted, which must surface as an error rather than a panic.
+    let mut planner = RRTConnect::new(0.5, 0.05);
+    planner.setup(problem_definition, validity_checker);
+
+    let result = planner.solve(Duration::from_secs(1));
+    assert_eq!(
+        result.err(),
+        Some(PlanningError::SamplingFailed(
+            StateSamplingError::GoalRegionUnsatisfiable
+        ))
+    );
+}
+
+#[test]
+fn test_rrt_connect_is_feasible() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![9.0, 5.0]),
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space,
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        goal_definition,
+    ));
+
+    let mut planner = RRTConnect::new(0.5, 0.05);
+    planner.setup(
+        problem_definition.clone(),
+        Arc::new(WallObstacleChecker {
+            wall_x_pos: 5.0,
+            wall_y_min: 2.0,
+            wall_y_max: 8.0,
+            wall_thickness: 0.5,
+        }),
+    );
+    assert_eq!(planner.is_feasible(Duration::from_secs(5)), Ok(true));
+
+    // A wall across the whole space separates the start from the goal.
+    let mut planner = RRTConnect::new(0.5, 0.05);
+    planner.setup(
+        problem_definition,
+        Arc::new(WallObstacleChecker {
+            wall_x_pos: 5.0,
+            wall_y_min: 0.0,
+            wall_y_max: 10.0,
+            wall_thickness: 0.5,
+        }),
+    );
+    let result = planner.is_feasible(Duration::from_millis(200));
+    assert!(
+        matches!(result, Err(PlanningError::Timeout { .. })),
+        "Expected Timeout, got {result:?}"
+    );
+}
+
+#[test]
+fn test_rrt_connect_returns_trivial_path_when_goal_sample_equals_start() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let start_state = RealVectorState::new(vec![1.0, 5.0]);
+    let goal_definition = Arc::new(ExactStateGoal {
+        target: start_state.clone(),
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut planner = RRTConnect::new(0.5, 0.05);
+    planner.setup(problem_definition, validity_checker.clone());
+
+    // The trees start out connected, so no iterations are needed.
+    let path = planner
+        .solve_iterations(400)
+        .expect("The start state is already a solution");
+    assert_eq!(path.0, vec![start_state]);
+    assert!(goal_definition.is_satisfied(path.0.last().unwrap()));
+    assert!(validity_checker.is_valid(&path.0[0]));
+
+    assert_eq!(planner.is_feasible(Duration::from_secs(1)), Ok(true));
+}
+
+#[test]
+fn test_rrt_connect_goal_roots_reach_wide_goal_region() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 1.0])],
+        Arc::new(StripGoalRegion),
+    ));
+    let validity_checker = Arc::new(SealedPocketChecker);
+
+    // The number of seeds for which the planner finds a path within 400 iterations.
+    let successes = |goal_root_interval: Option<usize>| {
+        (0..20)
+            .filter(|&seed| {
+                let mut planner = RRTConnect::new(0.5, 0.05);
+                planner.set_seed(seed);
+                planner.set_goal_root_interval(goal_root_interval);
+                planner.setup(problem_definition.clone(), validity_checker.clone());
+                match planner.solve_iterations(400) {
+                    Ok(path) => {
+                        assert!(StripGoalRegion.is_satisfied(path.0.last().unwrap()));
+                        assert!(validate_path(&path, &*space, &*validity_checker).is_ok());
+                        true
+                    }
+                    Err(_) => false,
+                }
+            })
+            .count()
+    };
+
+    // A single goal root usually lands in the sealed pocket, where the goal tree can never meet
+    // the start tree. Adding roots every 10 iterations soon puts one in the reachable part.
+    let single_root = successes(None);
+    let multi_root = successes(Some(10));
+    assert!(
+        multi_root >= 15,
+        "Most runs with extra goal roots should succeed: {multi_root}/20"
+    );
+    assert!(
+        multi_root > 2 * single_root,
+        "Extra goal roots should succeed far more often: {multi_root} vs {single_root}"
+    );
+}
+
+#[test]
+fn test_rrt_connect_rejects_start_in_obstacle_or_out_of_bounds() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let goal = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![9.0, 5.0]),
+        radius: 0.5,
+        space: space.clone(),
+    });
+
+    // The first start is inside the wall, the second left of the space.
+    for start in [vec![5.0, 5.0], vec![-1.0, 5.0]] {
+        let problem_definition = Arc::new(ProblemDefinition::new(
+            space.clone(),
+            vec![RealVectorState::new(start.clone())],
+            goal.clone(),
+        ));
+        let validity_checker = Arc::new(WallObstacleChecker {
+            wall_x_pos: 5.0,
+            wall_y_min: 2.0,
+            wall_y_max: 8.0,
+            wall_thickness: 0.5,
+        });
+
+        let mut planner = RRTConnect::new(0.5, 0.05);
+        planner.setup(problem_definition, validity_checker);
+
+        assert_eq!(
+            planner.solve(Duration::from_secs(1)).err(),
+            Some(PlanningError::InvalidStartState),
+            "start {start:?} should be rejected"
+        );
+    }
+}
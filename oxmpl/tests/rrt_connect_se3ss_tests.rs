@@ -0,0 +1,144 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use oxmpl::base::{
+    error::StateSamplingError,
+    goal::{Goal, GoalRegion, GoalSampleableRegion},
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::{SE3StateSpace, StateSpace},
+    state::{SE3State, SO3State},
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{path_utils::validate_path, RRTConnect};
+
+use rand::Rng;
+
+/// Rotates `v` by the unit quaternion `q`.
+fn rotate(q: &SO3State, v: [f64; 3]) -> [f64; 3] {
+    // t = 2 * (q_vec x v); v' = v + w * t + q_vec x t
+    let cross = |a: [f64; 3], b: [f64; 3]| {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    };
+    let q_vec = [q.x, q.y, q.z];
+    let t = cross(q_vec, v).map(|c| 2.0 * c);
+    let u = cross(q_vec, t);
+    [
+        v[0] + q.w * t[0] + u[0],
+        v[1] + q.w * t[1] + u[1],
+        v[2] + q.w * t[2] + u[2],
+    ]
+}
+
+/// A free-flying rod of length `2 * half_length` along its local x-axis, which must stay clear of
+/// an axis-aligned box obstacle.
+struct RodBoxChecker {
+    half_length: f64,
+    box_min: [f64; 3],
+    box_max: [f64; 3],
+}
+
+impl StateValidityChecker<SE3State> for RodBoxChecker {
+    fn is_valid(&self, state: &SE3State) -> bool {
+        let axis = rotate(&state.rotation, [1.0, 0.0, 0.0]);
+        (-10..=10).all(|i| {
+            let s = self.half_length * i as f64 / 10.0;
+            let point: Vec<f64> = (0..3).map(|k| state.translation[k] + s * axis[k]).collect();
+            !(0..3).all(|k| point[k] >= self.box_min[k] && point[k] <= self.box_max[k])
+        })
+    }
+}
+
+/// A goal reached once the rod's centre is within `radius` of `target`, in any orientation.
+struct PositionGoal {
+    target: [f64; 3],
+    radius: f64,
+    space: Arc<SE3StateSpace>,
+}
+
+impl PositionGoal {
+    fn centre_distance(&self, state: &SE3State) -> f64 {
+        (0..3)
+            .map(|k| (state.translation[k] - self.target[k]).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+impl Goal<SE3State> for PositionGoal {
+    fn is_satisfied(&self, state: &SE3State) -> bool {
+        self.centre_distance(state) <= self.radius
+    }
+}
+
+impl GoalRegion<SE3State> for PositionGoal {
+    fn distance_goal(&self, state: &SE3State) -> f64 {
+        (self.centre_distance(state) - self.radius).max(0.0)
+    }
+}
+
+impl GoalSampleableRegion<SE3State> for PositionGoal {
+    fn sample_goal(&self, rng: &mut impl Rng) -> Result<SE3State, StateSamplingError> {
+        let azimuth = rng.random_range(0.0..2.0 * PI);
+        let inclination = rng.random_range(0.0..PI);
+        let radius = self.radius * rng.random::<f64>().cbrt();
+        let translation = [
+            self.target[0] + radius * inclination.sin() * azimuth.cos(),
+            self.target[1] + radius * inclination.sin() * azimuth.sin(),
+            self.target[2] + radius * inclination.cos(),
+        ];
+        let rotation = self.space.rotation.sample_uniform(rng)?;
+
+        Ok(SE3State::new(translation, rotation))
+    }
+}
+
+#[test]
+fn test_rrt_connect_finds_path_in_se3ss() {
+    let space = Arc::new(
+        SE3StateSpace::new(Some(vec![(0.0, 10.0), (0.0, 10.0), (0.0, 10.0)]), None).unwrap(),
+    );
+
+    let start_state = SE3State::new([1.0, 5.0, 5.0], SO3State::identity());
+    let goal_definition = Arc::new(PositionGoal {
+        target: [9.0, 5.0, 5.0],
+        radius: 0.5,
+        space: space.clone(),
+    });
+
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
+
+    // A box in the middle of the space, directly between the start and the goal.
+    let validity_checker = Arc::new(RodBoxChecker {
+        half_length: 1.0,
+        box_min: [3.5, 3.5, 3.5],
+        box_max: [6.5, 6.5, 6.5],
+    });
+    assert!(
+        validity_checker.is_valid(&start_state),
+        "Start state should be valid!"
+    );
+    assert!(
+        !validity_checker.is_valid(&SE3State::new([5.0, 5.0, 5.0], SO3State::identity())),
+        "The straight line from start to goal should be blocked!"
+    );
+
+    let mut planner = RRTConnect::new(0.5, 0.05);
+    planner.set_seed(0);
+    planner.setup(problem_definition, validity_checker.clone());
+
+    let path = planner
+        .solve_iterations(20_000)
+        .expect("Planner failed to find a solution when one should exist");
+
+    assert_eq!(path.0.first(), Some(&start_state));
+    assert!(goal_definition.is_satisfied(path.0.last().unwrap()));
+    assert!(validate_path(&path, &*space, &*validity_checker).is_ok());
+}
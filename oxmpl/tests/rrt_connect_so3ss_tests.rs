@@ -1,17 +1,17 @@
 use std::{f64::consts::PI, sync::Arc, time::Duration};
 
 use oxmpl::base::{
-    error::StateSamplingError,
-    goal::{Goal, GoalRegion, GoalSampleableRegion},
-    planner::{Path, Planner},
+    error::{PlanningError, StateSamplingError},
+    goal::{sample_goal_with_budget, Goal, GoalRegion, GoalSampleableRegion},
+    planner::Planner,
     problem_definition::ProblemDefinition,
     space::{SO3StateSpace, StateSpace},
     state::SO3State,
     validity::StateValidityChecker,
 };
-use oxmpl::geometric::RRTConnect;
+use oxmpl::geometric::{path_utils::validate_path, RRTConnect};
 
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 /// Utility function to create Quaternions
 fn quaternion_from_axis_angle(axis: [f64; 3], angle: f64) -> SO3State {
@@ -74,10 +74,13 @@ impl GoalRegion<SO3State> for SO3GoalRegion {
     }
 }
 
+/// The number of draws `SO3GoalRegion::sample_goal` makes before giving up.
+const GOAL_SAMPLING_ATTEMPTS: u32 = 100_000;
+
 impl GoalSampleableRegion<SO3State> for SO3GoalRegion {
     /// Samples a state uniformly from within the goal's cone of freedom.
     fn sample_goal(&self, rng: &mut impl Rng) -> Result<SO3State, StateSamplingError> {
-        loop {
+        sample_goal_with_budget(rng, GOAL_SAMPLING_ATTEMPTS, |rng| {
             let x: f64 = rng.random_range(-1.0..1.0);
             let y: f64 = rng.random_range(-1.0..1.0);
             let z: f64 = rng.random_range(-1.0..1.0);
@@ -96,49 +99,12 @@ impl GoalSampleableRegion<SO3State> for SO3GoalRegion {
 
                 let distance = self.space.distance(&self.target, &random_quat);
                 if distance <= self.radius {
-                    return Ok(random_quat);
-                }
-            }
-        }
-    }
-}
-
-/// A helper function to validate the entire solution path.
-fn is_path_valid(
-    path: &Path<SO3State>,
-    space: &SO3StateSpace,
-    checker: &dyn StateValidityChecker<SO3State>,
-) -> bool {
-    for i in 0..path.0.len() - 1 {
-        let state_a = &path.0[i];
-        let state_b = &path.0[i + 1];
-
-        if !checker.is_valid(state_a) {
-            println!("Path invalid: State {state_a:?} is in collision.");
-            return false;
-        }
-        if (i + 1 == path.0.len() - 1) && !checker.is_valid(state_b) {
-            println!("Path invalid: Final state {state_b:?} is in collision.");
-            return false;
-        }
-
-        let dist = space.distance(state_a, state_b);
-        let num_steps = (dist / space.get_longest_valid_segment_length()).ceil() as usize;
-        if num_steps > 1 {
-            let mut interpolated_state = state_a.clone();
-            for j in 1..=num_steps {
-                let t = j as f64 / num_steps as f64;
-                space.interpolate(state_a, state_b, t, &mut interpolated_state);
-                if !checker.is_valid(&interpolated_state) {
-                    println!(
-                        "Path invalid: Motion between {state_a:?} and {state_b:?} is in collision at {interpolated_state:?}."
-                    );
-                    return false;
+                    return Ok(Some(random_quat));
                 }
             }
-        }
+            Ok(None)
+        })
     }
-    true
 }
 
 #[test]
@@ -155,11 +121,11 @@ fn test_rrt_connect_finds_path_in_so3ss() {
         space: space.clone(),
     });
 
-    let problem_definition = Arc::new(ProblemDefinition {
-        space: space.clone(),
-        start_states: vec![start_state.clone()],
-        goal: goal_definition.clone(),
-    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
 
     let validity_checker = Arc::new(ForbiddenConeChecker {
         center: SO3State::identity(),
@@ -205,9 +171,51 @@ fn test_rrt_connect_finds_path_in_so3ss() {
     );
 
     assert!(
-        is_path_valid(&path, &space, &*validity_checker),
+        validate_path(&path, &*space, &*validity_checker).is_ok(),
         "The returned path was found to be invalid."
     );
 
     println!("RRT planner test passed!");
 }
+
+#[test]
+fn test_rrt_connect_reports_goal_sampling_timeout_for_unhittable_goal() {
+    let space = Arc::new(SO3StateSpace::new(None).unwrap());
+    // Rejection sampling all of SO(3) practically never lands this close to the target.
+    let goal_definition = Arc::new(SO3GoalRegion {
+        target: quaternion_from_axis_angle([0.0, 1.0, 0.0], -PI / 2.0),
+        radius: 1e-12,
+        space: space.clone(),
+    });
+
+    let mut rng = StdRng::seed_from_u64(0);
+    assert_eq!(
+        goal_definition.sample_goal(&mut rng).err(),
+        Some(StateSamplingError::GoalSamplingTimeout {
+            attempts: GOAL_SAMPLING_ATTEMPTS
+        })
+    );
+
+    // RRTConnect roots its goal tree with a goal sample, so the error surfaces from `solve`.
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![quaternion_from_axis_angle([0.0, 1.0, 0.0], PI / 2.0)],
+        goal_definition,
+    ));
+    let mut planner = RRTConnect::new(0.2, 0.05);
+    planner.set_seed(0);
+    planner.setup(
+        problem_definition,
+        Arc::new(ForbiddenConeChecker {
+            center: SO3State::identity(),
+            radius: 40.0f64.to_radians(),
+            space,
+        }),
+    );
+    assert!(matches!(
+        planner.solve(Duration::from_secs(10)),
+        Err(PlanningError::SamplingFailed(
+            StateSamplingError::GoalSamplingTimeout { .. }
+        ))
+    ));
+}
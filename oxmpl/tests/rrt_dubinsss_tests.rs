@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    goal::Goal,
+    planner::{Path, Planner},
+    problem_definition::ProblemDefinition,
+    space::{DubinsStateSpace, StateSpace},
+    state::{SE2State, SO2State},
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{path_utils::validate_path, RRT};
+
+mod common;
+use common::{PositionGoal, RoomChecker};
+
+/// Checks that the car always drives in the direction it is heading along every motion of
+/// `path`, which a straight-line interpolation between different headings would not.
+fn drives_forwards(path: &Path<SE2State>, space: &DubinsStateSpace) -> bool {
+    path.0.windows(2).all(|pair| {
+        let steps = 100;
+        let mut previous = pair[0].clone();
+        let mut next = pair[0].clone();
+        (1..=steps).all(|i| {
+            space.interpolate(&pair[0], &pair[1], i as f64 / steps as f64, &mut next);
+            let dx = next.translation[0] - previous.translation[0];
+            let dy = next.translation[1] - previous.translation[1];
+            let direction = SO2State::new(dy.atan2(dx)).value;
+            let heading_error = SO2State::new(direction - previous.rotation.value).value;
+            previous.clone_from(&next);
+            // Short steps have a negligible direction; on a turn, the direction of travel lags the
+            // heading by half the angle turned.
+            dx.hypot(dy) < 1e-9 || heading_error.abs() < 0.1
+        })
+    })
+}
+
+#[test]
+fn test_rrt_finds_drivable_path_in_dubinsss() {
+    let space = Arc::new(DubinsStateSpace::new(Some(vec![(0.0, 10.0), (0.0, 10.0)]), 1.0).unwrap());
+
+    let start_state = SE2State::new(1.0, 5.0, 0.0);
+    let goal_definition = Arc::new(PositionGoal {
+        target: [9.0, 5.0],
+        radius: 0.5,
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
+
+    assert!(
+        !RoomChecker.is_valid(&SE2State::new(5.0, 5.0, 0.0)),
+        "The straight line from start to goal should be blocked!"
+    );
+
+    let mut planner = RRT::new(1.0, 0.05);
+    planner.set_seed(0);
+    planner.setup(problem_definition, Arc::new(RoomChecker));
+
+    let path = planner
+        .solve_iterations(20_000)
+        .expect("Planner failed to find a solution when one should exist");
+
+    assert_eq!(path.0.first(), Some(&start_state));
+    assert!(goal_definition.is_satisfied(path.0.last().unwrap()));
+    assert_eq!(validate_path(&path, &*space, &RoomChecker), Ok(()));
+    assert!(drives_forwards(&path, &space));
+}
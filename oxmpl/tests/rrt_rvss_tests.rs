@@ -1,17 +1,55 @@
-use std::{f64::consts::PI, sync::Arc, time::Duration};
+use std::{
+    f64::consts::PI,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use oxmpl::base::{
-    error::StateSamplingError,
-    goal::{Goal, GoalRegion, GoalSampleableRegion},
+    error::{PlanningError, StateSamplingError},
+    goal::{BoxGoalRegion, Goal, GoalRegion, GoalSampleableRegion},
     planner::{Path, Planner},
-    problem_definition::ProblemDefinition,
+    problem_definition::{ProblemDefinition, StartSampler},
     space::{RealVectorStateSpace, StateSpace},
     state::RealVectorState,
+    termination::TerminationCondition,
     validity::StateValidityChecker,
 };
-use oxmpl::geometric::RRT;
+use oxmpl::geometric::{path_utils::validate_path, RRT};
+
+use rand::{Rng, RngCore};
+
+mod common;
+use common::AlwaysValid;
 
-use rand::Rng;
+/// A StateValidityChecker with a thick square wall around a small free cell centred on `centre`.
+struct EnclosureChecker {
+    centre: RealVectorState,
+}
+
+impl StateValidityChecker<RealVectorState> for EnclosureChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let offset = state
+            .values
+            .iter()
+            .zip(&self.centre.values)
+            .map(|(value, centre)| (value - centre).abs())
+            .fold(0.0, f64::max);
+        !(0.05..=1.0).contains(&offset)
+    }
+}
+
+/// A StateValidityChecker with a small room in the corner of the space at `[0, 1.2]^2`, whose
+/// only exit is a narrow gap in its right wall.
+struct NarrowExitChecker;
+
+impl StateValidityChecker<RealVectorState> for NarrowExitChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let (x, y) = (state.values[0], state.values[1]);
+        let in_wall = (1.2..=1.5).contains(&x.max(y));
+        let in_gap = (1.2..=1.5).contains(&x) && (0.5..=0.7).contains(&y);
+        !in_wall || in_gap
+    }
+}
 
 /// A StateValidityChecker that defines a simple vertical wall obstacle.
 struct WallObstacleChecker {
@@ -68,42 +106,49 @@ impl GoalSampleableRegion<RealVectorState> for CircularGoalRegion {
     }
 }
 
-/// A helper function to validate the entire solution path.
-fn is_path_valid(
-    path: &Path<RealVectorState>,
-    space: &RealVectorStateSpace,
-    checker: &dyn StateValidityChecker<RealVectorState>,
-) -> bool {
-    for i in 0..path.0.len() - 1 {
-        let state_a = &path.0[i];
-        let state_b = &path.0[i + 1];
+/// A goal region that can never be sampled, e.g. because its constraints contradict each other.
+struct UnsatisfiableGoal;
 
-        if !checker.is_valid(state_a) {
-            println!("Path invalid: State {state_a:?} is in collision.");
-            return false;
-        }
-        if (i + 1 == path.0.len() - 1) && !checker.is_valid(state_b) {
-            println!("Path invalid: Final state {state_b:?} is in collision.");
+impl Goal<RealVectorState> for UnsatisfiableGoal {
+    fn is_satisfied(&self, _state: &RealVectorState) -> bool {
+        false
+    }
+}
+
+impl GoalRegion<RealVectorState> for UnsatisfiableGoal {
+    fn distance_goal(&self, _state: &RealVectorState) -> f64 {
+        f64::INFINITY
+    }
+}
+
+impl GoalSampleableRegion<RealVectorState> for UnsatisfiableGoal {
+    fn sample_goal(&self, _rng: &mut impl Rng) -> Result<RealVectorState, StateSamplingError> {
+        Err(StateSamplingError::GoalRegionUnsatisfiable)
+    }
+}
+
+/// A StateValidityChecker that fails to check states right of `x = 5`, e.g. because its
+/// collision model is unavailable there, and records the failure instead.
+#[derive(Default)]
+struct FailingChecker {
+    error: Mutex<Option<String>>,
+}
+
+impl StateValidityChecker<RealVectorState> for FailingChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        if state.values[0] > 5.0 {
+            self.error
+                .lock()
+                .unwrap()
+                .get_or_insert_with(|| "collision model unavailable".to_string());
             return false;
         }
+        true
+    }
 
-        let dist = space.distance(state_a, state_b);
-        let num_steps = (dist / space.get_longest_valid_segment_length()).ceil() as usize;
-        if num_steps > 1 {
-            let mut interpolated_state = state_a.clone();
-            for j in 1..=num_steps {
-                let t = j as f64 / num_steps as f64;
-                space.interpolate(state_a, state_b, t, &mut interpolated_state);
-                if !checker.is_valid(&interpolated_state) {
-                    println!(
-                        "Path invalid: Motion between {state_a:?} and {state_b:?} is in collision at {interpolated_state:?}."
-                    );
-                    return false;
-                }
-            }
-        }
+    fn take_error(&self) -> Option<String> {
+        self.error.lock().unwrap().take()
     }
-    true
 }
 
 #[test]
@@ -129,11 +174,11 @@ fn test_rrt_finds_path_in_rvss() {
         space: space.clone(),
     });
 
-    let problem_definition = Arc::new(ProblemDefinition {
-        space: space.clone(),
-        start_states: vec![start_state.clone()],
-        goal: goal_definition.clone(),
-    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
 
     let validity_checker = Arc::new(WallObstacleChecker {
         wall_x_pos: 5.0,
@@ -180,9 +225,814 @@ fn test_rrt_finds_path_in_rvss() {
     );
 
     assert!(
-        is_path_valid(&path, &space, &*validity_checker),
+        validate_path(&path, &*space, &*validity_checker).is_ok(),
         "The returned path was found to be invalid."
     );
 
     println!("RRT planner test passed!");
 }
+
+#[test]
+fn test_rrt_solve_iterations_is_deterministic_with_seed() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+
+    let start_state = RealVectorState {
+        values: vec![1.0, 5.0],
+    };
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState {
+            values: vec![9.0, 5.0],
+        },
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state],
+        goal_definition,
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let run = |seed: u64| {
+        let mut planner = RRT::new(0.5, 0.05);
+        planner.set_seed(seed);
+        planner.setup(problem_definition.clone(), validity_checker.clone());
+        let result = planner.solve_iterations(5000);
+        (result, planner.tree_size())
+    };
+
+    let (first_result, first_tree_size) = run(42);
+    let (second_result, second_tree_size) = run(42);
+
+    let first_path =
+        first_result.expect("Planner failed to find a solution within 5000 iterations");
+    let second_path =
+        second_result.expect("Planner failed to find a solution within 5000 iterations");
+    assert_eq!(
+        first_path.0, second_path.0,
+        "Seeded runs should return identical paths"
+    );
+    assert_eq!(
+        first_tree_size, second_tree_size,
+        "Seeded runs should grow identical trees"
+    );
+}
+
+#[test]
+fn test_rrt_solve_iterations_reports_no_solution_when_budget_exhausted() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState {
+            values: vec![9.0, 5.0],
+        },
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState {
+            values: vec![1.0, 5.0],
+        }],
+        goal_definition,
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    // With a step size of 0.5 the goal cannot be reached in 3 iterations.
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(7);
+    planner.setup(problem_definition, validity_checker);
+
+    let result = planner.solve_iterations(3);
+    assert!(
+        matches!(result, Err(PlanningError::NoSolutionFound)),
+        "Expected NoSolutionFound, got {:?}",
+        result.err()
+    );
+    assert!(planner.tree_size() <= 4);
+}
+
+#[test]
+fn test_rrt_solve_detailed_describes_exact_solution() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![9.0, 5.0]),
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        goal_definition.clone(),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(0);
+    planner.setup(problem_definition, validity_checker.clone());
+    let solution = planner.solve_detailed(Duration::from_secs(5)).unwrap();
+
+    assert_eq!(solution.planner_name, "RRT");
+    assert!(!solution.approximate);
+    assert!(solution.cost.is_finite());
+    assert!((solution.cost - solution.path.length(&*space)).abs() < 1e-9);
+    assert!(goal_definition.is_satisfied(solution.path.0.last().unwrap()));
+    assert!(validate_path(&solution.path, &*space, &*validity_checker).is_ok());
+    assert_eq!(
+        solution.stats.validity_checks,
+        planner.validity_check_count()
+    );
+    assert!(solution.stats.validity_checks > 0);
+}
+
+/// A planner implementing only the required methods of `Planner`, as one outside the crate
+/// might. It plans a straight line from the start to the centre of the goal region.
+#[derive(Default)]
+struct StraightLinePlanner {
+    problem_def:
+        Option<Arc<ProblemDefinition<RealVectorState, RealVectorStateSpace, CircularGoalRegion>>>,
+}
+
+impl Planner<RealVectorState, RealVectorStateSpace, CircularGoalRegion> for StraightLinePlanner {
+    fn setup(
+        &mut self,
+        problem_def: Arc<
+            ProblemDefinition<RealVectorState, RealVectorStateSpace, CircularGoalRegion>,
+        >,
+        _validity_checker: Arc<dyn StateValidityChecker<RealVectorState>>,
+    ) {
+        self.problem_def = Some(problem_def);
+    }
+
+    fn solve_with(
+        &mut self,
+        _condition: TerminationCondition,
+    ) -> Result<Path<RealVectorState>, PlanningError> {
+        let pd = self
+            .problem_def
+            .as_ref()
+            .ok_or(PlanningError::PlannerUninitialised)?;
+        Ok(Path(vec![
+            pd.start_states[0].clone(),
+            pd.goal.target.clone(),
+        ]))
+    }
+}
+
+#[test]
+fn test_solve_detailed_wraps_solve_with_by_default() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState::new(vec![9.0, 5.0]),
+            radius: 0.5,
+            space,
+        }),
+    ));
+
+    let mut planner = StraightLinePlanner::default();
+    planner.setup(problem_definition, Arc::new(AlwaysValid));
+    let solution = planner.solve_detailed(Duration::from_secs(1)).unwrap();
+
+    assert_eq!(solution.path.0.len(), 2);
+    assert!(solution.planner_name.ends_with("StraightLinePlanner"));
+    assert!(!solution.approximate);
+    assert!(solution.cost.is_nan());
+    assert_eq!(solution.stats.validity_checks, 0);
+}
+
+#[test]
+fn test_rrt_stops_when_validity_check_budget_is_exhausted() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState::new(vec![9.0, 5.0]),
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    // Every motion of the 0.5 step is checked in several segments, so 20 checks cannot reach the
+    // goal, however many iterations are allowed.
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(0);
+    planner.set_max_validity_checks(Some(20));
+    planner.setup(problem_definition.clone(), validity_checker.clone());
+
+    let result = planner.solve_iterations(10_000);
+    assert_eq!(
+        result.err(),
+        Some(PlanningError::CheckBudgetExhausted { max_checks: 20 })
+    );
+    assert_eq!(planner.validity_check_count(), 20);
+
+    // Without the budget, the same run succeeds and reports how many checks it needed.
+    planner.set_max_validity_checks(None);
+    planner.setup(problem_definition, validity_checker);
+    planner.solve_iterations(10_000).unwrap();
+    assert!(planner.validity_check_count() > 20);
+}
+
+#[test]
+fn test_rrt_gives_up_when_start_is_enclosed() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let start = RealVectorState::new(vec![5.0, 5.0]);
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start.clone()],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState::new(vec![9.0, 9.0]),
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(0);
+    planner.setup(
+        problem_definition,
+        Arc::new(EnclosureChecker { centre: start }),
+    );
+
+    // Every motion out of the free cell crosses the wall, so the tree stops growing and the
+    // search gives up long before the timeout.
+    let timeout = Duration::from_secs(10);
+    let started = Instant::now();
+    let result = planner.solve(timeout);
+    assert_eq!(result.err(), Some(PlanningError::NoSolutionFound));
+    assert!(started.elapsed() < timeout / 10);
+}
+
+#[test]
+fn test_rrt_default_stagnation_limit_allows_narrow_passage() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 30.0), (0.0, 30.0)])).unwrap());
+    let goal = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![28.0, 28.0]),
+        radius: 1.0,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![0.6, 0.6])],
+        goal.clone(),
+    ));
+
+    // Almost every sample lies outside the small starting room, so once the room is explored,
+    // thousands of iterations in a row fail before one finds the gap.
+    for seed in 0..5 {
+        let mut planner = RRT::new(0.5, 0.05);
+        planner.set_seed(seed);
+        planner.setup(problem_definition.clone(), Arc::new(NarrowExitChecker));
+        let path = planner
+            .solve(Duration::from_secs(30))
+            .unwrap_or_else(|err| panic!("Seed {seed} failed to leave the room: {err}"));
+
+        assert!(goal.is_satisfied(path.0.last().unwrap()));
+        assert!(validate_path(&path, &*space, &NarrowExitChecker).is_ok());
+    }
+}
+
+#[test]
+fn test_rrt_falls_back_to_uniform_sampling_when_goal_sampling_fails() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space,
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(UnsatisfiableGoal),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    // Every goal sample fails, so every iteration has to fall back to uniform sampling.
+    let mut planner = RRT::new(0.5, 1.0);
+    planner.set_seed(0);
+    planner.setup(problem_definition, validity_checker);
+
+    let result = planner.solve_iterations(100);
+    assert_eq!(result.err(), Some(PlanningError::NoSolutionFound));
+    assert!(planner.tree_size() > 1, "The tree should still have grown");
+}
+
+#[test]
+fn test_rrt_reports_sampling_failure_in_unbounded_space() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, None).expect("Error creating new RealVectorState!"));
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space,
+        vec![RealVectorState::new(vec![0.0, 0.0])],
+        Arc::new(UnsatisfiableGoal),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.setup(problem_definition, validity_checker);
+
+    let result = planner.solve(Duration::from_secs(1));
+    assert_eq!(
+        result.err(),
+        Some(PlanningError::SamplingFailed(
+            StateSamplingError::UnboundedDimension { dimension_index: 0 }
+        ))
+    );
+}
+
+#[test]
+fn test_rrt_plans_from_start_distribution() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState {
+            values: vec![9.0, 5.0],
+        },
+        radius: 0.5,
+        space: space.clone(),
+    });
+
+    // Start anywhere in a small square around (1, 5) instead of at a fixed state.
+    let start_region = |state: &RealVectorState| {
+        (0.5..=1.5).contains(&state.values[0]) && (4.5..=5.5).contains(&state.values[1])
+    };
+    let problem_definition = Arc::new(
+        ProblemDefinition::new(space.clone(), vec![], goal_definition.clone()).with_start_sampler(
+            StartSampler {
+                sample: Arc::new(|rng: &mut dyn RngCore| RealVectorState {
+                    values: vec![rng.random_range(0.5..1.5), rng.random_range(4.5..5.5)],
+                }),
+                num_samples: 5,
+            },
+        ),
+    );
+
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(3);
+    planner.setup(problem_definition, validity_checker.clone());
+    assert_eq!(
+        planner.tree_size(),
+        5,
+        "The tree should be seeded with every sampled start"
+    );
+
+    let result = planner.solve(Duration::from_secs(5));
+    assert!(
+        result.is_ok(),
+        "Planner failed to find a solution from the start distribution. Error: {:?}",
+        result.err()
+    );
+
+    let path = result.unwrap();
+    assert!(
+        start_region(path.0.first().unwrap()),
+        "Path should start inside the start distribution"
+    );
+    assert!(
+        goal_definition.is_satisfied(path.0.last().unwrap()),
+        "Path should end in the goal region"
+    );
+    assert!(
+        validate_path(&path, &*space, &*validity_checker).is_ok(),
+        "The returned path was found to be invalid."
+    );
+}
+
+/// A StateValidityChecker rejecting a strip along the left edge of the space, `0 <= x <= 0.5`,
+/// and accepting everything else, including states beyond the edge.
+struct LeftEdgeChecker;
+
+impl StateValidityChecker<RealVectorState> for LeftEdgeChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        !(0.0..=0.5).contains(&state.values[0])
+    }
+}
+
+#[test]
+fn test_rrt_checks_sampled_starts_once_within_bounds() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    // The sampled start is valid where it is, but not once clamped onto the edge of the space.
+    let problem_definition = Arc::new(
+        ProblemDefinition::new(
+            space.clone(),
+            vec![RealVectorState::new(vec![5.0, 5.0])],
+            Arc::new(CircularGoalRegion {
+                target: RealVectorState::new(vec![9.0, 5.0]),
+                radius: 0.5,
+                space,
+            }),
+        )
+        .with_start_sampler(StartSampler {
+            sample: Arc::new(|_: &mut dyn RngCore| RealVectorState::new(vec![-1.0, 5.0])),
+            num_samples: 3,
+        }),
+    );
+
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(0);
+    planner.setup(problem_definition, Arc::new(LeftEdgeChecker));
+    assert_eq!(planner.tree_size(), 1);
+}
+
+#[test]
+fn test_seeded_rrt_solve_returns_identical_paths() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![9.0, 5.0]),
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space,
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        goal_definition,
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let run = || {
+        let mut planner = RRT::new(0.5, 0.05);
+        planner.set_seed(1234);
+        planner.setup(problem_definition.clone(), validity_checker.clone());
+        planner
+            .solve(Duration::from_secs(5))
+            .expect("Planner failed to find a solution when one should exist")
+    };
+
+    assert_eq!(
+        run().0,
+        run().0,
+        "Seeded runs should return identical paths"
+    );
+}
+
+#[test]
+fn test_rrt_timeout_reports_partial_progress() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+
+    let start_state = RealVectorState {
+        values: vec![1.0, 5.0],
+    };
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState {
+            values: vec![9.0, 5.0],
+        },
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
+
+    // The wall spans the whole space, so the goal is unreachable.
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 0.0,
+        wall_y_max: 10.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(9);
+    planner.setup(problem_definition, validity_checker);
+
+    match planner.solve(Duration::from_millis(100)) {
+        Err(PlanningError::Timeout {
+            best_goal_distance,
+            nodes,
+        }) => {
+            let start_goal_distance = goal_definition.distance_goal(&start_state);
+            assert!(best_goal_distance.is_finite());
+            assert!(
+                best_goal_distance < start_goal_distance,
+                "Best goal distance {best_goal_distance} should improve on the start's {start_goal_distance}"
+            );
+            assert_eq!(nodes, planner.tree_size());
+            assert!(nodes > 1);
+        }
+        Err(e) => panic!("Expected a timeout, got {e:?}"),
+        Ok(_) => panic!("Planner should not find a path through a closed wall"),
+    }
+}
+
+#[test]
+fn test_rrt_solve_from_states_jump_starts_search() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState {
+            values: vec![9.0, 5.0],
+        },
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState {
+            values: vec![1.0, 5.0],
+        }],
+        goal_definition,
+    ));
+
+    // The wall leaves only a narrow gap at the top of the space.
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 0.0,
+        wall_y_max: 9.6,
+        wall_thickness: 0.5,
+    });
+
+    // A scattered partial plan through the gap. The states are far apart and one of them lies
+    // inside the wall, so they do not form a valid path on their own.
+    let seed_states: Vec<RealVectorState> =
+        [[2.0, 9.0], [5.0, 5.0], [4.0, 9.8], [6.0, 9.8], [8.0, 8.0]]
+            .iter()
+            .map(|values| RealVectorState {
+                values: values.to_vec(),
+            })
+            .collect();
+
+    let mut hot_nodes = 0;
+    let mut cold_nodes = 0;
+    for seed in 0..5 {
+        let mut hot_planner = RRT::new(0.5, 0.05);
+        hot_planner.set_seed(seed);
+        hot_planner.setup(problem_definition.clone(), validity_checker.clone());
+        let path = hot_planner
+            .solve_from_states(&seed_states, Duration::from_secs(5))
+            .expect("Hotstarted planner failed to find a solution");
+        assert!(validate_path(&path, &*space, &*validity_checker).is_ok());
+        assert!(
+            !path.0.contains(&seed_states[1]),
+            "The invalid seed state must not be part of the path"
+        );
+        hot_nodes += hot_planner.tree_size();
+
+        let mut cold_planner = RRT::new(0.5, 0.05);
+        cold_planner.set_seed(seed);
+        cold_planner.setup(problem_definition.clone(), validity_checker.clone());
+        let _ = cold_planner.solve(Duration::from_secs(5));
+        cold_nodes += cold_planner.tree_size();
+    }
+
+    assert!(
+        hot_nodes < cold_nodes,
+        "Hotstarted planner grew {hot_nodes} nodes, cold-started planner grew {cold_nodes}"
+    );
+}
+
+#[test]
+fn test_rrt_with_defaults_uses_suggested_params() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+    let params = space.suggested_planner_params();
+
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState {
+            values: vec![9.0, 5.0],
+        },
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState {
+            values: vec![1.0, 5.0],
+        }],
+        goal_definition,
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut planner = RRT::with_defaults(&*space);
+    assert_eq!(planner.max_distance, params.max_distance);
+    assert_eq!(planner.goal_bias, params.goal_bias);
+
+    planner.set_seed(5);
+    planner.setup(problem_definition, validity_checker.clone());
+    let path = planner
+        .solve_iterations(5000)
+        .expect("Planner with suggested parameters failed to find a solution");
+    assert!(validate_path(&path, &*space, &*validity_checker).is_ok());
+}
+
+#[test]
+fn test_rrt_aborts_when_validity_check_fails() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Error creating new RealVectorState!"),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState::new(vec![9.0, 5.0]),
+            radius: 0.5,
+            space,
+        }),
+    ));
+
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(0);
+    planner.setup(problem_definition, Arc::new(FailingChecker::default()));
+
+    let result = planner.solve(Duration::from_secs(5));
+    assert_eq!(
+        result.err(),
+        Some(PlanningError::ValidityCheckFailed(
+            "collision model unavailable".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_rrt_never_crosses_thin_wall_regardless_of_max_distance() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 1.0])],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState::new(vec![9.0, 1.0]),
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+    // A wall much thinner than the longest steps, leaving a gap only above y = 8.
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 0.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.1,
+    });
+
+    for max_distance in [0.25, 1.0, 4.0, 15.0] {
+        for seed in 0..3 {
+            let mut planner = RRT::new(max_distance, 0.05);
+            planner.set_seed(seed);
+            planner.setup(problem_definition.clone(), validity_checker.clone());
+            let path = planner.solve_iterations(20_000).unwrap_or_else(|e| {
+                panic!("RRT with max_distance {max_distance} failed to find a path: {e:?}")
+            });
+
+            // Every segment that crosses the wall's centre line does so through the gap, up to
+            // the wall's thickness.
+            for pair in path.0.windows(2) {
+                let (a, b) = (&pair[0].values, &pair[1].values);
+                if (a[0] - 5.0) * (b[0] - 5.0) < 0.0 {
+                    let t = (5.0 - a[0]) / (b[0] - a[0]);
+                    let y = a[1] + t * (b[1] - a[1]);
+                    assert!(
+                        y > 8.0 - 0.1,
+                        "Segment {a:?} -> {b:?} crosses the wall at y = {y} with max_distance {max_distance}"
+                    );
+                }
+            }
+            assert!(validate_path(&path, &*space, &*validity_checker).is_ok());
+        }
+    }
+}
+
+#[test]
+fn test_rrt_goal_zoom_reaches_distant_goal_in_fewer_iterations() {
+    // A goal strip along the far side of an open space. Goal samples are spread along the whole
+    // strip, so without zoom they mostly extend whichever node is level with them, rather than
+    // the one already closest to the strip.
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 100.0), (0.0, 100.0)])).unwrap());
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 1.0])],
+        Arc::new(
+            BoxGoalRegion::new(RealVectorState::new(vec![97.5, 50.0]), vec![2.5, 50.0]).unwrap(),
+        ),
+    ));
+
+    // Every iteration adds a node in open space, so the tree size counts the iterations.
+    let iterations_to_solve = |goal_zoom: bool, seed: u64| {
+        let mut planner = RRT::new(1.0, 0.05);
+        planner.set_seed(seed);
+        planner.set_goal_zoom(goal_zoom);
+        planner.setup(problem_definition.clone(), Arc::new(AlwaysValid));
+        planner.solve_iterations(100_000).unwrap();
+        planner.tree_size()
+    };
+
+    let standard: usize = (0..20).map(|seed| iterations_to_solve(false, seed)).sum();
+    let zoom: usize = (0..20).map(|seed| iterations_to_solve(true, seed)).sum();
+    assert!(
+        (zoom as f64) < 0.9 * standard as f64,
+        "Goal zoom took {zoom} iterations over 20 seeds, against {standard} without it"
+    );
+}
+
+#[test]
+fn test_rrt_rejects_start_in_obstacle_or_out_of_bounds() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let goal = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![9.0, 5.0]),
+        radius: 0.5,
+        space: space.clone(),
+    });
+
+    // The first start is inside the wall, the second left of the space.
+    for start in [vec![5.0, 5.0], vec![-1.0, 5.0]] {
+        let problem_definition = Arc::new(ProblemDefinition::new(
+            space.clone(),
+            vec![RealVectorState::new(start.clone())],
+            goal.clone(),
+        ));
+        let validity_checker = Arc::new(WallObstacleChecker {
+            wall_x_pos: 5.0,
+            wall_y_min: 2.0,
+            wall_y_max: 8.0,
+            wall_thickness: 0.5,
+        });
+
+        let mut planner = RRT::new(0.5, 0.05);
+        planner.setup(problem_definition, validity_checker);
+
+        assert_eq!(
+            planner.solve(Duration::from_secs(1)).err(),
+            Some(PlanningError::InvalidStartState),
+            "start {start:?} should be rejected"
+        );
+    }
+}
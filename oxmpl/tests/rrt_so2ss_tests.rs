@@ -21,7 +21,7 @@ struct ForbiddenAngleChecker {
 
 impl StateValidityChecker<SO2State> for ForbiddenAngleChecker {
     fn is_valid(&self, state: &SO2State) -> bool {
-        let val = state.clone().normalise().value;
+        let val = state.normalised().value;
         !(val >= self.invalid_min && val <= self.invalid_max)
     }
 }
@@ -103,11 +103,11 @@ fn test_rrt_finds_path_in_so2ss() {
         space: space.clone(),
     });
 
-    let problem_definition = Arc::new(ProblemDefinition {
-        space: space.clone(),
-        start_states: vec![start_state.clone()],
-        goal: goal_definition.clone(),
-    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
 
     let validity_checker = Arc::new(ForbiddenAngleChecker {
         invalid_min: -0.5,
@@ -157,3 +157,45 @@ fn test_rrt_finds_path_in_so2ss() {
 
     println!("RRT planner test for SO(2) passed!");
 }
+
+#[test]
+fn test_rrt_normalises_raw_start_angle_in_so2ss() {
+    let space = Arc::new(SO2StateSpace::new(None).expect("Failed to create state space"));
+
+    // Built directly rather than with `SO2State::new`, so the angle is not wrapped into range.
+    let raw_start_state = SO2State { value: 3.0 * PI };
+    let goal_definition = Arc::new(AngleGoalRegion {
+        target: SO2State::new(PI / 2.0),
+        radius: 0.1,
+        space: space.clone(),
+    });
+
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![raw_start_state.clone()],
+        goal_definition.clone(),
+    ));
+
+    let validity_checker = Arc::new(ForbiddenAngleChecker {
+        invalid_min: -0.5,
+        invalid_max: 0.5,
+    });
+
+    let mut planner = RRT::new(0.2, 0.05);
+    planner.set_seed(0);
+    planner.setup(problem_definition, validity_checker.clone());
+
+    let path = planner
+        .solve_iterations(10_000)
+        .expect("Planner failed to find a solution when one should exist");
+
+    let first = path.0.first().unwrap();
+    assert!(
+        (-PI..PI).contains(&first.value),
+        "Start state {first:?} should have been normalised into [-PI, PI)"
+    );
+    assert!(space.distance(first, &raw_start_state) < 1e-9);
+    assert!(path.0.iter().all(|state| space.satisfies_bounds(state)));
+    assert!(goal_definition.is_satisfied(path.0.last().unwrap()));
+    assert!(is_so2_path_valid(&path, &space, &*validity_checker));
+}
@@ -1,15 +1,18 @@
 use std::{f64::consts::PI, sync::Arc, time::Duration};
 
 use oxmpl::base::{
-    error::StateSamplingError,
+    error::{PlanningError, StateSamplingError},
     goal::{Goal, GoalRegion, GoalSampleableRegion},
+    objective::{OptimizationObjective, StateCostObjective},
     planner::{Path, Planner},
     problem_definition::ProblemDefinition,
     space::{RealVectorStateSpace, StateSpace},
     state::RealVectorState,
+    termination::TerminationCondition,
     validity::StateValidityChecker,
 };
-use oxmpl::geometric::RRTStar;
+use oxmpl::geometric::{path_utils::validate_path, RRTStar};
+use oxmpl::testing::assert_path_cost_within;
 
 use rand::Rng;
 
@@ -68,44 +71,6 @@ impl GoalSampleableRegion<RealVectorState> for CircularGoalRegion {
     }
 }
 
-/// A helper function to validate the entire solution path.
-fn is_path_valid(
-    path: &Path<RealVectorState>,
-    space: &RealVectorStateSpace,
-    checker: &dyn StateValidityChecker<RealVectorState>,
-) -> bool {
-    for i in 0..path.0.len() - 1 {
-        let state_a = &path.0[i];
-        let state_b = &path.0[i + 1];
-
-        if !checker.is_valid(state_a) {
-            println!("Path invalid: State {state_a:?} is in collision.");
-            return false;
-        }
-        if (i + 1 == path.0.len() - 1) && !checker.is_valid(state_b) {
-            println!("Path invalid: Final state {state_b:?} is in collision.");
-            return false;
-        }
-
-        let dist = space.distance(state_a, state_b);
-        let num_steps = (dist / space.get_longest_valid_segment_length()).ceil() as usize;
-        if num_steps > 1 {
-            let mut interpolated_state = state_a.clone();
-            for j in 1..=num_steps {
-                let t = j as f64 / num_steps as f64;
-                space.interpolate(state_a, state_b, t, &mut interpolated_state);
-                if !checker.is_valid(&interpolated_state) {
-                    println!(
-                        "Path invalid: Motion between {state_a:?} and {state_b:?} is in collision at {interpolated_state:?}."
-                    );
-                    return false;
-                }
-            }
-        }
-    }
-    true
-}
-
 #[test]
 fn test_rrt_star_finds_path_in_rvss() {
     let space = Arc::new(
@@ -124,11 +89,11 @@ fn test_rrt_star_finds_path_in_rvss() {
         space: space.clone(),
     });
 
-    let problem_definition = Arc::new(ProblemDefinition {
-        space: space.clone(),
-        start_states: vec![start_state.clone()],
-        goal: goal_definition.clone(),
-    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![start_state.clone()],
+        goal_definition.clone(),
+    ));
 
     let validity_checker = Arc::new(WallObstacleChecker {
         wall_x_pos: 5.0,
@@ -174,9 +139,413 @@ fn test_rrt_star_finds_path_in_rvss() {
     );
 
     assert!(
-        is_path_valid(&path, &space, &*validity_checker),
+        validate_path(&path, &*space, &*validity_checker).is_ok(),
         "The returned path was found to be invalid."
     );
 
     println!("RRT* planner test passed!");
 }
+
+#[test]
+fn test_rrt_star_best_solution_improves_across_continue_solve() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState {
+            values: vec![9.0, 5.0],
+        },
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState {
+            values: vec![1.0, 5.0],
+        }],
+        goal_definition.clone(),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut planner = RRTStar::new(0.5, 0.05, 1.5);
+    planner.set_seed(3);
+    planner.setup(problem_definition, validity_checker.clone());
+    assert!(!planner.has_solution());
+    assert!(planner.best_solution().is_none());
+
+    let mut previous_best: Option<Path<RealVectorState>> = None;
+    for budget in [500, 1000, 2000] {
+        let result = planner.continue_solve(TerminationCondition::MaxIterations(budget));
+        if result.is_err() {
+            assert!(
+                previous_best.is_none(),
+                "A found solution should not be lost"
+            );
+            continue;
+        }
+
+        assert!(planner.has_solution());
+        let best = planner
+            .best_solution()
+            .expect("A solution was returned, so one should be available");
+        assert!(goal_definition.is_satisfied(best.0.last().unwrap()));
+        assert!(validate_path(&best, &*space, &*validity_checker).is_ok());
+        if let Some(previous_best) = &previous_best {
+            assert_path_cost_within(&best, previous_best, &*space, 1.0 + 1e-9);
+        }
+        previous_best = Some(best);
+    }
+    assert!(
+        previous_best.is_some(),
+        "RRT* should find a solution within the total budget"
+    );
+}
+
+#[test]
+fn test_rrt_star_set_goal_reuses_the_tree() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let goal_a = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![9.0, 5.0]),
+        radius: 0.5,
+        space: space.clone(),
+    });
+    // Goal B lies between the start and the wall, where the tree towards goal A has already grown.
+    let goal_b = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![3.0, 5.0]),
+        radius: 1.0,
+        space: space.clone(),
+    });
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+    let problem_definition = |goal: Arc<CircularGoalRegion>| {
+        Arc::new(ProblemDefinition::new(
+            space.clone(),
+            vec![RealVectorState::new(vec![1.0, 5.0])],
+            goal,
+        ))
+    };
+
+    let mut planner = RRTStar::new(0.5, 0.05, 1.5);
+    assert!(matches!(
+        planner.set_goal(goal_b.clone()),
+        Err(PlanningError::PlannerUninitialised)
+    ));
+    planner.set_seed(1);
+    planner.setup(problem_definition(goal_a.clone()), validity_checker.clone());
+    let path_a = planner
+        .continue_solve(TerminationCondition::MaxIterations(2000))
+        .expect("RRT* should reach goal A");
+    assert!(goal_a.is_satisfied(path_a.0.last().unwrap()));
+    let tree_size = planner.tree_size();
+
+    planner.set_goal(goal_b.clone()).unwrap();
+    assert_eq!(planner.tree_size(), tree_size, "The tree should be kept");
+    assert!(planner.has_solution());
+    let path_b = planner
+        .continue_solve(TerminationCondition::MaxIterations(0))
+        .expect("The retained tree already reaches goal B");
+    assert!(goal_b.is_satisfied(path_b.0.last().unwrap()));
+    assert!(validate_path(&path_b, &*space, &*validity_checker).is_ok());
+
+    // Without the retained tree, the same budget is not enough.
+    let mut fresh = RRTStar::new(0.5, 0.05, 1.5);
+    fresh.set_seed(1);
+    fresh.setup(problem_definition(goal_b), validity_checker);
+    assert!(fresh
+        .continue_solve(TerminationCondition::MaxIterations(0))
+        .is_err());
+}
+
+#[test]
+fn test_rrt_star_anytime_solve_improves_on_first_solution() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![9.0, 5.0]),
+        radius: 0.5,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        goal_definition.clone(),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    let mut planner = RRTStar::new(0.5, 0.05, 1.5);
+    planner.set_seed(7);
+    planner.set_anytime(false);
+    planner.setup(problem_definition, validity_checker.clone());
+    assert_eq!(planner.best_cost(), None);
+
+    // Without anytime mode, `solve` stops at the first solution.
+    let first_path = planner
+        .solve(Duration::from_secs(5))
+        .expect("RRT* should find a first solution");
+    let first_cost = planner.best_cost().expect("A solution was found");
+    assert!(first_path.length(&*space) <= first_cost + 1e-9);
+
+    // In anytime mode, the default, `solve` keeps rewiring the same tree until the timeout.
+    planner.set_anytime(true);
+    let final_path = planner
+        .solve(Duration::from_millis(500))
+        .expect("A found solution should not be lost");
+    let final_cost = planner.best_cost().expect("A solution was found");
+
+    assert!(goal_definition.is_satisfied(final_path.0.last().unwrap()));
+    assert!(validate_path(&final_path, &*space, &*validity_checker).is_ok());
+    assert!(
+        final_cost < first_cost,
+        "Refining should improve on the first solution: {final_cost} vs {first_cost}"
+    );
+    assert_path_cost_within(&final_path, &first_path, &*space, 1.0);
+}
+
+#[test]
+fn test_rrt_star_anytime_is_respected_by_every_solve_method() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState::new(vec![9.0, 5.0]),
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+    let planner = |anytime: bool| {
+        let mut planner = RRTStar::new(0.5, 0.05, 1.5);
+        planner.set_seed(7);
+        planner.set_anytime(anytime);
+        planner.setup(problem_definition.clone(), validity_checker.clone());
+        planner
+    };
+    const ITERATIONS: usize = 2_000;
+    let condition = || TerminationCondition::MaxIterations(ITERATIONS);
+    type SolveMethod = fn(
+        &mut RRTStar<RealVectorState, RealVectorStateSpace, CircularGoalRegion>,
+    ) -> Vec<RealVectorState>;
+    let methods: [(&str, SolveMethod); 4] = [
+        ("solve_with", |p| {
+            p.solve_with(TerminationCondition::MaxIterations(ITERATIONS))
+                .unwrap()
+                .0
+        }),
+        ("solve_iterations", |p| {
+            p.solve_iterations(ITERATIONS).unwrap().0
+        }),
+        ("continue_solve", |p| {
+            p.continue_solve(TerminationCondition::MaxIterations(ITERATIONS))
+                .unwrap()
+                .0
+        }),
+        ("solve_partial", |p| {
+            let (path, _) = p
+                .solve_partial(TerminationCondition::MaxIterations(ITERATIONS))
+                .unwrap();
+            path.0
+        }),
+    ];
+
+    // Without anytime mode, every method stops at the first solution, like `solve`.
+    let mut timed = planner(false);
+    let first_path = timed.solve(Duration::from_secs(5)).unwrap();
+    for (name, method) in methods {
+        let mut first = planner(false);
+        assert_eq!(method(&mut first), first_path.0, "{name}");
+        assert_eq!(first.tree_size(), timed.tree_size(), "{name}");
+    }
+
+    // In anytime mode, the default, every method refines for the whole budget.
+    let mut refined = planner(true);
+    let refined_path = refined.solve_with(condition()).unwrap();
+    assert!(refined.tree_size() > timed.tree_size());
+    assert!(refined.best_cost().unwrap() < timed.best_cost().unwrap());
+    for (name, method) in methods {
+        let mut iterated = RRTStar::new(0.5, 0.05, 1.5);
+        iterated.set_seed(7);
+        iterated.setup(problem_definition.clone(), validity_checker.clone());
+        assert_eq!(method(&mut iterated), refined_path.0, "{name}");
+        assert_eq!(iterated.tree_size(), refined.tree_size(), "{name}");
+    }
+}
+
+#[test]
+fn test_rrt_star_best_cost_matches_path_length_after_rewiring() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        Arc::new(CircularGoalRegion {
+            target: RealVectorState::new(vec![9.0, 5.0]),
+            radius: 0.5,
+            space: space.clone(),
+        }),
+    ));
+    let validity_checker = Arc::new(WallObstacleChecker {
+        wall_x_pos: 5.0,
+        wall_y_min: 2.0,
+        wall_y_max: 8.0,
+        wall_thickness: 0.5,
+    });
+
+    // Long runs rewire nodes above goal nodes, whose costs must follow.
+    for seed in 0..4 {
+        let mut planner = RRTStar::new(0.5, 0.05, 1.5);
+        planner.set_seed(seed);
+        planner.setup(problem_definition.clone(), validity_checker.clone());
+        let path = planner
+            .solve_with(TerminationCondition::MaxIterations(5_000))
+            .expect("RRT* should find a solution");
+        let best_cost = planner.best_cost().expect("A solution was found");
+        assert!(
+            (best_cost - path.length(&*space)).abs() < 1e-9,
+            "Seed {seed}: best_cost {best_cost} vs path length {}",
+            path.length(&*space)
+        );
+    }
+}
+
+/// A StateValidityChecker for a corridor between walls at y = 1 and y = 3.
+struct CorridorChecker;
+
+impl StateValidityChecker<RealVectorState> for CorridorChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        (1.0..=3.0).contains(&state.values[1])
+    }
+}
+
+/// The distance from a state to the nearer wall of `CorridorChecker`.
+fn corridor_clearance(state: &RealVectorState) -> f64 {
+    (state.values[1] - 1.0).min(3.0 - state.values[1])
+}
+
+#[test]
+fn test_rrt_star_clearance_objective_keeps_path_in_middle_of_corridor() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 4.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let goal_definition = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![9.0, 1.2]),
+        radius: 0.3,
+        space: space.clone(),
+    });
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 1.2])],
+        goal_definition.clone(),
+    ));
+    let validity_checker = Arc::new(CorridorChecker);
+
+    // The mean clearance of the waypoints between the start and the goal.
+    let mean_interior_clearance = |path: &Path<RealVectorState>| {
+        let interior = &path.0[1..path.0.len() - 1];
+        assert!(
+            !interior.is_empty(),
+            "The path should have interior waypoints"
+        );
+        interior.iter().map(corridor_clearance).sum::<f64>() / interior.len() as f64
+    };
+
+    let mut length_planner = RRTStar::new(0.5, 0.05, 1.5);
+    length_planner.set_seed(3);
+    length_planner.setup(problem_definition.clone(), validity_checker.clone());
+    let length_path = length_planner
+        .solve_iterations(3000)
+        .expect("RRT* should find a path along the corridor");
+
+    let mut clearance_planner = RRTStar::new(0.5, 0.05, 1.5);
+    clearance_planner.set_seed(3);
+    let objective: Box<dyn OptimizationObjective<RealVectorState>> = Box::new(
+        StateCostObjective::new(space.clone(), Arc::new(corridor_clearance), 1.0),
+    );
+    clearance_planner.set_optimization_objective(Arc::from(objective));
+    clearance_planner.setup(problem_definition, validity_checker.clone());
+    let clearance_path = clearance_planner
+        .solve_iterations(3000)
+        .expect("RRT* should find a path along the corridor");
+
+    assert!(goal_definition.is_satisfied(clearance_path.0.last().unwrap()));
+    assert!(validate_path(&clearance_path, &*space, &*validity_checker).is_ok());
+
+    // The shortest path hugs the lower wall, while the clearance objective pulls it towards the
+    // middle of the corridor (clearance 1.0).
+    let length_clearance = mean_interior_clearance(&length_path);
+    let clearance_clearance = mean_interior_clearance(&clearance_path);
+    assert!(
+        length_clearance < 0.5,
+        "The shortest path should stay near the wall: {length_clearance}"
+    );
+    assert!(
+        clearance_clearance > 0.7,
+        "The clearance objective should route through the middle: {clearance_clearance}"
+    );
+}
+
+#[test]
+fn test_rrt_star_rejects_start_in_obstacle_or_out_of_bounds() {
+    let space =
+        Arc::new(RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap());
+    let goal = Arc::new(CircularGoalRegion {
+        target: RealVectorState::new(vec![9.0, 5.0]),
+        radius: 0.5,
+        space: space.clone(),
+    });
+
+    // The first start is inside the wall, the second left of the space.
+    for start in [vec![5.0, 5.0], vec![-1.0, 5.0]] {
+        let problem_definition = Arc::new(ProblemDefinition::new(
+            space.clone(),
+            vec![RealVectorState::new(start.clone())],
+            goal.clone(),
+        ));
+        let validity_checker = Arc::new(WallObstacleChecker {
+            wall_x_pos: 5.0,
+            wall_y_min: 2.0,
+            wall_y_max: 8.0,
+            wall_thickness: 0.5,
+        });
+
+        let mut planner = RRTStar::new(0.5, 0.05, 1.0);
+        planner.setup(problem_definition, validity_checker);
+
+        assert_eq!(
+            planner.solve(Duration::from_secs(1)).err(),
+            Some(PlanningError::InvalidStartState),
+            "start {start:?} should be rejected"
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    goal::{Goal, GoalState},
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+};
+use oxmpl::geometric::{RRTStar, RRT};
+use oxmpl::testing::assert_path_cost_within;
+
+mod common;
+use common::AlwaysValid;
+
+#[test]
+fn test_rrt_star_path_is_no_longer_than_rrt_in_open_space() {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+
+    let goal_definition =
+        Arc::new(GoalState::new(space.clone(), RealVectorState::new(vec![9.0, 9.0]), 0.5).unwrap());
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 1.0])],
+        goal_definition.clone(),
+    ));
+    let validity_checker = Arc::new(AlwaysValid);
+
+    for seed in 0..5 {
+        let mut rrt = RRT::new(0.5, 0.05);
+        rrt.set_seed(seed);
+        rrt.setup(problem_definition.clone(), validity_checker.clone());
+        let rrt_path = rrt
+            .solve_iterations(5_000)
+            .expect("RRT failed to find a solution in open space");
+
+        let mut rrt_star = RRTStar::new(0.5, 0.05, 2.0);
+        rrt_star.set_seed(seed);
+        rrt_star.setup(problem_definition.clone(), validity_checker.clone());
+        let rrt_star_path = rrt_star
+            .solve_iterations(2_000)
+            .expect("RRT* failed to find a solution in open space");
+
+        assert!(goal_definition.is_satisfied(rrt_star_path.0.last().unwrap()));
+        assert_path_cost_within(&rrt_star_path, &rrt_path, &*space, 1.05);
+    }
+}
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use oxmpl::base::{
+    error::StateSamplingError,
+    goal::{Goal, GoalRegion, GoalSampleableRegion},
+    motion_validator::TimeMotionValidator,
+    planner::{Path, Planner},
+    problem_definition::ProblemDefinition,
+    space::{RealVectorStateSpace, TimeStateSpace},
+    state::{RealVectorState, TimedState},
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{RRTConnect, RRT};
+
+use rand::Rng;
+
+type State = TimedState<RealVectorState>;
+type Space = TimeStateSpace<RealVectorStateSpace>;
+
+/// The times during which the gate in the wall is open.
+const GATE_OPEN: (f64, f64) = (6.0, 9.0);
+
+/// A wall across the space at `x = 5`, with a gate at `4 <= y <= 6` that is only open during
+/// `GATE_OPEN`.
+struct GatedWallChecker;
+
+impl StateValidityChecker<State> for GatedWallChecker {
+    fn is_valid(&self, state: &State) -> bool {
+        let (x, y) = (state.state.values[0], state.state.values[1]);
+        let in_wall = (4.5..=5.5).contains(&x);
+        let in_open_gate =
+            (4.0..=6.0).contains(&y) && (GATE_OPEN.0..=GATE_OPEN.1).contains(&state.time);
+        !in_wall || in_open_gate
+    }
+}
+
+/// Reaching `x >= 9`, at any time.
+struct RightEdgeGoal;
+
+impl Goal<State> for RightEdgeGoal {
+    fn is_satisfied(&self, state: &State) -> bool {
+        state.state.values[0] >= 9.0
+    }
+}
+
+impl GoalRegion<State> for RightEdgeGoal {
+    fn distance_goal(&self, state: &State) -> f64 {
+        (9.0 - state.state.values[0]).max(0.0)
+    }
+}
+
+impl GoalSampleableRegion<State> for RightEdgeGoal {
+    fn sample_goal(&self, rng: &mut impl Rng) -> Result<State, StateSamplingError> {
+        Ok(TimedState::new(
+            RealVectorState::new(vec![
+                rng.random_range(9.0..10.0),
+                rng.random_range(0.0..10.0),
+            ]),
+            rng.random_range(0.0..20.0),
+        ))
+    }
+}
+
+fn problem() -> (
+    Arc<Space>,
+    Arc<ProblemDefinition<State, Space, RightEdgeGoal>>,
+) {
+    let inner = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)])).unwrap();
+    let mut space = TimeStateSpace::new(inner, (0.0, 20.0)).unwrap();
+    space.max_speed = Some(2.0);
+    let space = Arc::new(space);
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![TimedState::new(RealVectorState::new(vec![1.0, 5.0]), 0.0)],
+        Arc::new(RightEdgeGoal),
+    ));
+    (space, problem_definition)
+}
+
+/// Returns the time at which `path` crosses the middle of the wall.
+fn wall_crossing_time(path: &Path<State>) -> f64 {
+    let crossing = path
+        .0
+        .windows(2)
+        .find(|pair| pair[0].state.values[0] < 5.0 && pair[1].state.values[0] >= 5.0)
+        .expect("The path must cross the wall");
+    let (a, b) = (&crossing[0], &crossing[1]);
+    let t = (5.0 - a.state.values[0]) / (b.state.values[0] - a.state.values[0]);
+    a.time + t * (b.time - a.time)
+}
+
+#[test]
+// Planners are single-threaded, so the validator need not be `Send` or `Sync`.
+#[allow(clippy::arc_with_non_send_sync)]
+fn test_rrt_passes_moving_obstacle_by_respecting_time() {
+    let (space, problem_definition) = problem();
+    let checker = Arc::new(GatedWallChecker);
+
+    let mut planner = RRT::new(1.0, 0.05);
+    planner.set_seed(0);
+    planner.set_motion_validator(Arc::new(TimeMotionValidator::new(
+        space.clone(),
+        checker.clone(),
+    )));
+    planner.setup(problem_definition.clone(), checker.clone());
+    let path = planner
+        .solve_iterations(50_000)
+        .expect("RRT failed to find a path through the gate");
+
+    assert_eq!(path.0.first(), problem_definition.start_states.first());
+    assert!(RightEdgeGoal.is_satisfied(path.0.last().unwrap()));
+    for pair in path.0.windows(2) {
+        assert!(
+            space.is_motion_admissible(&pair[0], &pair[1]),
+            "Motion from {:?} to {:?} goes back in time or too fast",
+            pair[0],
+            pair[1]
+        );
+        assert!(oxmpl::base::planner::check_motion(
+            &*space, &*checker, &pair[0], &pair[1]
+        ));
+    }
+    let crossing_time = wall_crossing_time(&path);
+    assert!((GATE_OPEN.0..=GATE_OPEN.1).contains(&crossing_time));
+}
+
+#[test]
+#[allow(clippy::arc_with_non_send_sync)]
+fn test_rrt_connect_paths_move_forwards_in_time() {
+    let (space, problem_definition) = problem();
+    let checker = Arc::new(GatedWallChecker);
+
+    // The goal tree grows backwards in time from the goal, so its motions are only admissible if
+    // they are checked in the order the path follows them. Goal states sampled too early cannot
+    // be reached at all, so the goal tree is given further roots.
+    let mut planner = RRTConnect::new(1.0, 0.05);
+    planner.set_seed(0);
+    planner.set_goal_root_interval(Some(100));
+    planner.set_motion_validator(Arc::new(TimeMotionValidator::new(
+        space.clone(),
+        checker.clone(),
+    )));
+    planner.setup(problem_definition.clone(), checker.clone());
+    let path = planner
+        .solve_iterations(50_000)
+        .expect("RRTConnect failed to find a path through the gate");
+
+    assert_eq!(path.0.first(), problem_definition.start_states.first());
+    assert!(RightEdgeGoal.is_satisfied(path.0.last().unwrap()));
+    for pair in path.0.windows(2) {
+        assert!(
+            space.is_motion_admissible(&pair[0], &pair[1]),
+            "Motion from {:?} to {:?} goes back in time or too fast",
+            pair[0],
+            pair[1]
+        );
+    }
+    let crossing_time = wall_crossing_time(&path);
+    assert!((GATE_OPEN.0..=GATE_OPEN.1).contains(&crossing_time));
+}
+
+#[test]
+fn test_rrt_without_time_ordering_travels_back_in_time() {
+    let (_, problem_definition) = problem();
+
+    let mut planner = RRT::new(1.0, 0.05);
+    planner.set_seed(0);
+    planner.setup(problem_definition, Arc::new(GatedWallChecker));
+    let path = planner.solve_iterations(50_000).unwrap();
+
+    // Only the default motion validator was used, so every state along the path is valid, but
+    // the path cannot be executed.
+    assert!(path.0.windows(2).any(|pair| pair[1].time < pair[0].time));
+}
@@ -0,0 +1,94 @@
+use std::f64::consts::PI;
+
+use oxmpl::base::{
+    planner::Path,
+    space::{RealVectorStateSpace, SE3StateSpace, SO2StateSpace, SO3StateSpace, StateSpace},
+    state::{RealVectorState, SE3State, SO2State, SO3State},
+};
+use oxmpl::geometric::RoadmapData;
+
+#[test]
+fn test_real_vector_path_round_trips_through_json() {
+    let path = Path(vec![
+        RealVectorState::new(vec![0.0, 0.0]),
+        RealVectorState::new(vec![1.5, -2.25]),
+        RealVectorState::new(vec![3.0, 4.0]),
+    ]);
+
+    let json = serde_json::to_string(&path).unwrap();
+    let restored: Path<RealVectorState> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.0, path.0);
+}
+
+#[test]
+fn test_states_round_trip_through_json() {
+    let so2 = SO2State::new(PI / 3.0);
+    let restored: SO2State = serde_json::from_str(&serde_json::to_string(&so2).unwrap()).unwrap();
+    assert_eq!(restored, so2);
+
+    // Deliberately not a unit quaternion: the raw components must survive unchanged.
+    let so3 = SO3State::new(1.0, 2.0, 3.0, 4.0);
+    let restored: SO3State = serde_json::from_str(&serde_json::to_string(&so3).unwrap()).unwrap();
+    assert_eq!(restored, so3);
+
+    let se3 = SE3State {
+        translation: [1.0, 2.0, 3.0],
+        rotation: SO3State::identity(),
+    };
+    let restored: SE3State = serde_json::from_str(&serde_json::to_string(&se3).unwrap()).unwrap();
+    assert_eq!(restored, se3);
+}
+
+#[test]
+fn test_state_spaces_round_trip_bounds_and_segment_fraction() {
+    let mut rv = RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (-5.0, 5.0)])).unwrap();
+    rv.set_longest_valid_segment_fraction(0.2);
+    let restored: RealVectorStateSpace =
+        serde_json::from_str(&serde_json::to_string(&rv).unwrap()).unwrap();
+    assert_eq!(restored.dimension, rv.dimension);
+    assert_eq!(restored.bounds, rv.bounds);
+    assert_eq!(
+        restored.get_longest_valid_segment_length(),
+        rv.get_longest_valid_segment_length()
+    );
+
+    let so2 = SO2StateSpace::new(Some((0.0, PI))).unwrap();
+    let restored: SO2StateSpace =
+        serde_json::from_str(&serde_json::to_string(&so2).unwrap()).unwrap();
+    assert_eq!(restored.bounds, so2.bounds);
+
+    let so3 = SO3StateSpace::new(Some((SO3State::identity(), PI / 4.0))).unwrap();
+    let restored: SO3StateSpace =
+        serde_json::from_str(&serde_json::to_string(&so3).unwrap()).unwrap();
+    assert_eq!(restored.bounds, so3.bounds);
+
+    let se3 = SE3StateSpace::new(
+        Some(vec![(0.0, 1.0), (0.0, 2.0), (0.0, 3.0)]),
+        Some((SO3State::identity(), PI / 2.0)),
+    )
+    .unwrap();
+    let restored: SE3StateSpace =
+        serde_json::from_str(&serde_json::to_string(&se3).unwrap()).unwrap();
+    assert_eq!(restored.translation.bounds, se3.translation.bounds);
+    assert_eq!(restored.rotation.bounds, se3.rotation.bounds);
+    assert_eq!(restored.rotation_weight, se3.rotation_weight);
+}
+
+#[test]
+fn test_prm_roadmap_round_trips_through_json() {
+    let data = RoadmapData {
+        states: vec![
+            RealVectorState::new(vec![0.0, 0.0]),
+            RealVectorState::new(vec![1.0, 0.5]),
+            RealVectorState::new(vec![2.0, 1.0]),
+        ],
+        edges: vec![vec![1], vec![0, 2], vec![1]],
+    };
+
+    let json = serde_json::to_string(&data).unwrap();
+    let restored: RoadmapData<RealVectorState> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.states, data.states);
+    assert_eq!(restored.edges, data.edges);
+}
@@ -0,0 +1,192 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use oxmpl::base::{
+    error::{PlanningError, StateSamplingError},
+    goal::{Goal, GoalRegion, GoalSampleableRegion},
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+    termination::TerminationCondition,
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::{RRTConnect, RRTStar, RRT};
+
+use rand::Rng;
+
+/// A StateValidityChecker with a closed box around `[8, 10] x [8, 10]`, so the goal inside it can
+/// never be reached from outside.
+struct BoxedInChecker;
+
+impl StateValidityChecker<RealVectorState> for BoxedInChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let (x, y) = (state.values[0], state.values[1]);
+        let in_box = (7.0..=11.0).contains(&x) && (7.0..=11.0).contains(&y);
+        let in_cavity = (8.0..=10.0).contains(&x) && (8.0..=10.0).contains(&y);
+        !in_box || in_cavity
+    }
+}
+
+/// A goal at the centre of the closed box.
+struct BoxedInGoal;
+
+impl Goal<RealVectorState> for BoxedInGoal {
+    fn is_satisfied(&self, state: &RealVectorState) -> bool {
+        (state.values[0] - 9.0).abs() <= 0.5 && (state.values[1] - 9.0).abs() <= 0.5
+    }
+}
+
+impl GoalRegion<RealVectorState> for BoxedInGoal {
+    fn distance_goal(&self, state: &RealVectorState) -> f64 {
+        ((state.values[0] - 9.0).powi(2) + (state.values[1] - 9.0).powi(2)).sqrt()
+    }
+}
+
+impl GoalSampleableRegion<RealVectorState> for BoxedInGoal {
+    fn sample_goal(&self, rng: &mut impl Rng) -> Result<RealVectorState, StateSamplingError> {
+        Ok(RealVectorState::new(vec![
+            rng.random_range(8.5..9.5),
+            rng.random_range(8.5..9.5),
+        ]))
+    }
+}
+
+fn boxed_in_problem() -> Arc<ProblemDefinition<RealVectorState, RealVectorStateSpace, BoxedInGoal>>
+{
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 12.0), (0.0, 12.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    Arc::new(ProblemDefinition::new(
+        space,
+        vec![RealVectorState::new(vec![1.0, 1.0])],
+        Arc::new(BoxedInGoal),
+    ))
+}
+
+/// Sets `flag` after `delay` on another thread.
+fn cancel_after(flag: Arc<AtomicBool>, delay: Duration) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        thread::sleep(delay);
+        flag.store(true, Ordering::Relaxed);
+    })
+}
+
+/// Runs `planner` on the boxed-in problem until it is cancelled, asserting that it stops promptly.
+fn assert_cancellation_stops_planner<P>(mut planner: P)
+where
+    P: Planner<RealVectorState, RealVectorStateSpace, BoxedInGoal>,
+{
+    planner.setup(boxed_in_problem(), Arc::new(BoxedInChecker));
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let canceller = cancel_after(cancel.clone(), Duration::from_millis(100));
+
+    let start = Instant::now();
+    let result = planner.solve_with(
+        TerminationCondition::Timeout(Duration::from_secs(30))
+            .or(TerminationCondition::Cancelled(cancel)),
+    );
+    let elapsed = start.elapsed();
+    canceller.join().unwrap();
+
+    assert_eq!(result.err(), Some(PlanningError::NoSolutionFound));
+    assert!(
+        elapsed < Duration::from_secs(5),
+        "Planner took {elapsed:?} to notice the cancellation"
+    );
+}
+
+#[test]
+fn test_cancellation_stops_rrt() {
+    assert_cancellation_stops_planner(RRT::new(0.5, 0.05));
+}
+
+#[test]
+fn test_cancellation_stops_rrt_connect() {
+    assert_cancellation_stops_planner(RRTConnect::new(0.5, 0.05));
+}
+
+#[test]
+fn test_cancellation_stops_rrt_star() {
+    assert_cancellation_stops_planner(RRTStar::new(0.5, 0.05, 1.0));
+}
+
+#[test]
+fn test_max_tree_size_stops_rrt() {
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(0);
+    planner.setup(boxed_in_problem(), Arc::new(BoxedInChecker));
+
+    let result = planner.solve_with(
+        TerminationCondition::MaxTreeSize(200).or(TerminationCondition::MaxIterations(100_000)),
+    );
+
+    assert_eq!(result.err(), Some(PlanningError::NoSolutionFound));
+    assert_eq!(planner.tree_size(), 200);
+}
+
+#[test]
+fn test_solve_still_reports_timeout() {
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.setup(boxed_in_problem(), Arc::new(BoxedInChecker));
+
+    let result = planner.solve(Duration::from_millis(50));
+    assert!(
+        matches!(result, Err(PlanningError::Timeout { .. })),
+        "Expected Timeout, got {:?}",
+        result.err()
+    );
+}
+
+/// Runs `planner` on the boxed-in problem with a long timeout, asserting that its `max_nodes` cap
+/// stops it first.
+fn assert_max_nodes_stops_planner<P>(planner: &mut P)
+where
+    P: Planner<RealVectorState, RealVectorStateSpace, BoxedInGoal>,
+{
+    planner.setup(boxed_in_problem(), Arc::new(BoxedInChecker));
+
+    let start = Instant::now();
+    let result = planner.solve(Duration::from_secs(30));
+    let elapsed = start.elapsed();
+
+    assert_eq!(result.err(), Some(PlanningError::NoSolutionFound));
+    assert!(
+        elapsed < Duration::from_secs(5),
+        "Planner took {elapsed:?} to stop at the node cap"
+    );
+}
+
+#[test]
+fn test_max_nodes_stops_rrt() {
+    let mut planner = RRT::new(0.5, 0.05);
+    planner.set_seed(0);
+    planner.max_nodes = Some(20);
+    assert_max_nodes_stops_planner(&mut planner);
+    assert_eq!(planner.tree_size(), 20);
+}
+
+#[test]
+fn test_max_nodes_stops_rrt_connect() {
+    let mut planner = RRTConnect::new(0.5, 0.05);
+    planner.set_seed(0);
+    planner.max_nodes = Some(20);
+    assert_max_nodes_stops_planner(&mut planner);
+}
+
+#[test]
+fn test_max_nodes_stops_rrt_star() {
+    let mut planner = RRTStar::new(0.5, 0.05, 1.0);
+    planner.set_seed(0);
+    planner.max_nodes = Some(20);
+    assert_max_nodes_stops_planner(&mut planner);
+    assert_eq!(planner.tree_size(), 20);
+}
@@ -0,0 +1,116 @@
+use std::{sync::Arc, time::Duration};
+
+use oxmpl::base::{
+    error::StateSamplingError,
+    goal::{Goal, GoalRegion, GoalSampleableRegion, GoalState, ValidGoalRegion},
+    planner::Planner,
+    problem_definition::ProblemDefinition,
+    space::RealVectorStateSpace,
+    state::RealVectorState,
+    validity::StateValidityChecker,
+};
+use oxmpl::geometric::RRT;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+/// A StateValidityChecker that defines a box obstacle.
+struct BoxObstacleChecker {
+    x_min: f64,
+    x_max: f64,
+    y_min: f64,
+    y_max: f64,
+}
+
+impl StateValidityChecker<RealVectorState> for BoxObstacleChecker {
+    fn is_valid(&self, state: &RealVectorState) -> bool {
+        let x = state.values[0];
+        let y = state.values[1];
+        !(x >= self.x_min && x <= self.x_max && y >= self.y_min && y <= self.y_max)
+    }
+}
+
+/// Sets up a goal region centred at (9, 5) whose left half is covered by an obstacle.
+fn overlapping_goal_and_obstacle() -> (
+    Arc<RealVectorStateSpace>,
+    GoalState<RealVectorState, RealVectorStateSpace>,
+    Arc<BoxObstacleChecker>,
+) {
+    let space = Arc::new(
+        RealVectorStateSpace::new(2, Some(vec![(0.0, 10.0), (0.0, 10.0)]))
+            .expect("Failed to create state space for test."),
+    );
+    let goal = GoalState::new(space.clone(), RealVectorState::new(vec![9.0, 5.0]), 0.5).unwrap();
+    let checker = Arc::new(BoxObstacleChecker {
+        x_min: 8.0,
+        x_max: 9.0,
+        y_min: 4.0,
+        y_max: 6.0,
+    });
+    (space, goal, checker)
+}
+
+#[test]
+fn test_valid_goal_region_only_accepts_collision_free_goal_states() {
+    let (_, goal, checker) = overlapping_goal_and_obstacle();
+
+    let in_collision = RealVectorState::new(vec![8.8, 5.0]);
+    let collision_free = RealVectorState::new(vec![9.2, 5.0]);
+    assert!(goal.is_satisfied(&in_collision));
+    assert!(goal.is_satisfied(&collision_free));
+
+    let valid_goal = ValidGoalRegion::new(goal, checker.clone());
+    assert!(!valid_goal.is_satisfied(&in_collision));
+    assert!(valid_goal.is_satisfied(&collision_free));
+    assert!(!valid_goal.is_satisfied(&RealVectorState::new(vec![5.0, 5.0])));
+    assert_eq!(valid_goal.distance_goal(&in_collision), 0.0);
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..200 {
+        let sample = valid_goal
+            .sample_goal(&mut rng)
+            .expect("Half of the goal region is valid, so sampling should succeed");
+        assert!(checker.is_valid(&sample));
+        assert!(valid_goal.inner().is_satisfied(&sample));
+    }
+}
+
+#[test]
+fn test_valid_goal_region_sampling_fails_when_goal_is_fully_blocked() {
+    let (_, goal, _) = overlapping_goal_and_obstacle();
+    let checker = Arc::new(BoxObstacleChecker {
+        x_min: 8.0,
+        x_max: 10.0,
+        y_min: 4.0,
+        y_max: 6.0,
+    });
+    let valid_goal = ValidGoalRegion::new(goal, checker);
+
+    let mut rng = StdRng::seed_from_u64(0);
+    assert!(matches!(
+        valid_goal.sample_goal(&mut rng),
+        Err(StateSamplingError::GoalSamplingTimeout { .. })
+    ));
+}
+
+#[test]
+fn test_rrt_with_valid_goal_region_ends_in_a_collision_free_state() {
+    let (space, goal, checker) = overlapping_goal_and_obstacle();
+    let valid_goal = Arc::new(ValidGoalRegion::new(goal, checker.clone()));
+
+    let problem_definition = Arc::new(ProblemDefinition::new(
+        space.clone(),
+        vec![RealVectorState::new(vec![1.0, 5.0])],
+        valid_goal.clone(),
+    ));
+
+    let mut planner = RRT::new(0.5, 0.1);
+    planner.set_seed(0);
+    planner.setup(problem_definition, checker.clone());
+
+    let path = planner
+        .solve(Duration::from_secs(5))
+        .expect("A collision-free goal state is reachable");
+    let last = path.0.last().unwrap();
+    assert!(checker.is_valid(last));
+    assert!(valid_goal.is_satisfied(last));
+}